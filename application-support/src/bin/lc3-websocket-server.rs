@@ -0,0 +1,36 @@
+//! Hosts a simulator that browsers (and other clients) can connect to over a
+//! WebSocket.
+//!
+//! Usage: `lc3-websocket-server [ADDR] [--json | --postcard]`
+//!
+//! `ADDR` defaults to `127.0.0.1:9001` and messages are JSON encoded (sent as
+//! text frames) by default.
+
+use lc3_application_support::init::websocket::{
+    serve, WebSocketServerConfig, WireFormat,
+};
+
+use std::process::exit;
+
+fn main() {
+    let mut config = WebSocketServerConfig::default();
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => config.format = WireFormat::Json,
+            "--postcard" => config.format = WireFormat::Postcard,
+            "-h" | "--help" => {
+                eprintln!("Usage: lc3-websocket-server [ADDR] [--json | --postcard]");
+                exit(0);
+            },
+            addr => config.addr = addr.to_string(),
+        }
+    }
+
+    eprintln!("Listening on ws://{}/ ({:?})", config.addr, config.format);
+
+    if let Err(e) = serve(config) {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+}
@@ -208,10 +208,39 @@ impl Backoff {
         T: Transport<<E as Encode<Resp>>::Encoded, <D as Decode<Req>>::Encoded>,
         C: Control,
         <C as Control>::EventFuture: Unpin, // TODO: use `pin_utils::pin_mut!` and relax this requirement. (see rpc::device)
-   {
+    {
+        loop {
+            self.run_step_while(sim, &mut device, |_| true)
+        }
+    }
+
+    /// Just like [`run_step`], but stops once `cond` returns false.
+    ///
+    /// `cond` is checked once every `num_iters` steps. This is useful for
+    /// devices whose transports can go away (i.e. a network connection that
+    /// gets closed); the [`Device`] can be dropped once this returns and the
+    /// [`Control`] impl can be reused with a new [`Device`].
+    ///
+    /// [`run_step`]: `Backoff::run_step`
+    /// [`Control`]: `lc3_traits::control::Control`
+    /// [`Device`]: `lc3_traits::control::rpc::Device`
+    #[inline]
+    pub fn run_step_while<C, Req, Resp, D, E, T, F>(&self, sim: &mut C, device: &mut Device<T, C, Req, Resp, D, E>, mut cond: F)
+    where
+        Req: Debug,
+        Resp: Debug,
+        Req: Into<RequestMessage>,
+        ResponseMessage: Into<Resp>,
+        D: Decode<Req>,
+        E: Encode<Resp>,
+        T: Transport<<E as Encode<Resp>>::Encoded, <D as Decode<Req>>::Encoded>,
+        C: Control,
+        <C as Control>::EventFuture: Unpin, // TODO: use `pin_utils::pin_mut!` and relax this requirement. (see rpc::device)
+        F: FnMut(&Device<T, C, Req, Resp, D, E>) -> bool,
+    {
         let mut idle_count = 0;
 
-        loop {
+        while cond(device) {
             let count: usize = (0..self.num_iters).map(|_| {
                 let (msgs, insns) = device.step(sim);
                 msgs + insns
@@ -14,6 +14,7 @@ use lc3_device_support::{
     rpc::{
         transport::uart_host::{HostUartTransport, SerialPortSettings},
         encoding::{PostcardEncode, PostcardDecode, Cobs},
+        reliability::{ReliabilityConfig, ReliableTransport, StdTime},
     },
     util::Fifo,
 };
@@ -34,9 +35,12 @@ lazy_static::lazy_static! {
         SyncEventFutureSharedState::new();
 }
 
+// Note: the firmware on the board has to be using the reliability layer too.
+type Transport = ReliableTransport<HostUartTransport, StdTime>;
+
 type Cont<'ss, EncFunc: FnMut() -> Cobs<Fifo<u8>>> = Controller<
     'ss,
-    HostUartTransport,
+    Transport,
     SyncEventFutureSharedState,
     RequestMessage,
     ResponseMessage,
@@ -64,6 +68,9 @@ pub enum SerialSettings {
 pub struct BoardConfig<P: AsRef<Path>> {
     pub path: P,
     pub serial_settings: SerialSettings,
+    /// Timeouts and retransmits for the link (the timeout is in
+    /// milliseconds).
+    pub reliability: ReliabilityConfig,
 }
 
 // TODO: use Strings instead?
@@ -127,6 +134,7 @@ impl<P: AsRef<Path>> BoardConfig<P> {
         Self {
             path,
             serial_settings: SerialSettings::DefaultsWithBaudRate { baud_rate },
+            reliability: ReliabilityConfig::default(),
         }
     }
 
@@ -134,15 +142,20 @@ impl<P: AsRef<Path>> BoardConfig<P> {
         Self {
             path,
             serial_settings: SerialSettings::Custom(config),
+            reliability: ReliabilityConfig::default(),
         }
     }
+
+    pub fn with_reliability(self, reliability: ReliabilityConfig) -> Self {
+        Self { reliability, ..self }
+    }
 }
 
 impl<P: AsRef<Path>> BoardConfig<P> {
-    fn new_transport(self) -> HostUartTransport {
+    fn new_transport(self) -> Transport {
         // Note: we unwrap here! This is probably not great!
         // (TODO)
-        match self.serial_settings {
+        let uart = match self.serial_settings {
             SerialSettings::DefaultsWithBaudRate { baud_rate } => {
                 HostUartTransport::new(self.path, baud_rate).unwrap()
             },
@@ -150,7 +163,9 @@ impl<P: AsRef<Path>> BoardConfig<P> {
             SerialSettings::Custom(config) => {
                 HostUartTransport::new_with_config(self.path, config).unwrap()
             },
-        }
+        };
+
+        ReliableTransport::new(uart, StdTime::default(), self.reliability)
     }
 }
 
@@ -182,6 +197,7 @@ where
         Option<&'s Self::Output>,
     ) {
         let func: Box<dyn FnMut() -> Cobs<Fifo<u8>>> = Box::new(|| Cobs::try_new(Fifo::new()).unwrap());
+        let path = config.path.as_ref().display().to_string();
 
         let controller = Controller::new(
             PostcardEncode::new(func),
@@ -190,6 +206,13 @@ where
             &*EVENT_FUTURE_SHARED_STATE_CONT
         );
 
+        // Make sure we're speaking the same language as the board. Firmware
+        // that predates the handshake won't answer; the reliability layer
+        // turns that into an error instead of us waiting forever.
+        if let Err(e) = controller.handshake() {
+            panic!("Couldn't connect to the board at `{}`: {}", path, e);
+        }
+
         let storage: &'s mut _ = b.put(BoardDevice::<_, P> { controller, _p: PhantomData });
 
         (
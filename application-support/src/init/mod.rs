@@ -37,17 +37,17 @@ use lc3_traits::control::Control;
 use std::any::Any;
 
 pub mod sim;
-pub mod websocket;
 
 pub use sim::*;
-pub use websocket::*;
 
 not_wasm! {
     pub mod board;
     pub mod sim_rpc;
+    pub mod websocket;
 
     pub use board::*;
     pub use sim_rpc::*;
+    pub use websocket::*;
 }
 
 #[derive(Debug)]
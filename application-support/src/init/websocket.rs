@@ -1,26 +1,274 @@
-//! TODO!
+//! A [`Controller`] that talks to a simulator over a WebSocket and the
+//! server that hosts said simulator.
+//!
+//! The server side ([`serve`]) runs a [`Simulator`] behind a [`Device`] and
+//! accepts connections (one at a time) from browsers or from the
+//! [`WebSocketDevice`] [`Init`] impl below. The simulator persists across
+//! connections so clients can reconnect without losing state.
+//!
+//! [`Controller`]: lc3_traits::control::rpc::Controller
+//! [`Device`]: lc3_traits::control::rpc::Device
+//! [`Simulator`]: lc3_baseline_sim::sim::Simulator
+//! [`Init`]: super::Init
 
-use super::{BlackBox, Init};
-use crate::shim_support::Shims;
+use super::{sim::new_sim, BlackBox, Init};
+use crate::{
+    event_loop::Backoff,
+    shim_support::{new_shim_peripherals_set, Shims},
+};
+
+use lc3_shims::peripherals::SourceShim;
+use lc3_traits::control::{
+    Control,
+    rpc::{
+        encoding::JsonEncoding, futures::{EventFuture, SyncEventFutureSharedState},
+        Controller, Device, RequestMessage, ResponseMessage,
+    },
+};
+use lc3_device_support::{
+    rpc::{
+        transport::websocket::WebSocketTransport,
+        encoding::{PostcardEncode, PostcardDecode, Cobs},
+    },
+    util::Fifo,
+};
+
+use std::{
+    fmt::{self, Debug},
+    io::Result as IoResult,
+    net::TcpListener,
+    sync::Mutex,
+};
+
+// Static data that we need:
+// TODO: note that, like sim and sim_rpc, this will cause problems if more than
+// 1 instance of this simulator is instantiated.
+lazy_static::lazy_static! {
+    pub static ref EVENT_FUTURE_SHARED_STATE_CONT: SyncEventFutureSharedState =
+        SyncEventFutureSharedState::new();
+}
+
+/// The address [`WebSocketConfig`] and [`WebSocketServerConfig`] use by
+/// default.
+pub const DEFAULT_WEBSOCKET_ADDR: &str = "127.0.0.1:9001";
+
+type Cont<'ss, Enc, Dec> = Controller<
+    'ss,
+    WebSocketTransport,
+    SyncEventFutureSharedState,
+    RequestMessage,
+    ResponseMessage,
+    Enc,
+    Dec,
+>;
+
+type JsonCont<'ss> = Cont<'ss, JsonEncoding, JsonEncoding>;
+type PostcardCont<'ss> = Cont<
+    'ss,
+    PostcardEncode<RequestMessage, Cobs<Fifo<u8>>, fn() -> Cobs<Fifo<u8>>>,
+    PostcardDecode<ResponseMessage, Cobs<Fifo<u8>>>,
+>;
+
+// The controller's type depends on the wire format so `Init` hands out a trait
+// object (both kinds have the same event future).
+type DynControl<'ss> = dyn Control<EventFuture = EventFuture<'ss, SyncEventFutureSharedState>> + 'ss;
+
+enum WebSocketController<'ss> {
+    Json(JsonCont<'ss>),
+    Postcard(PostcardCont<'ss>),
+}
+
+// The postcard controller isn't `Debug` (its encoder isn't).
+impl Debug for WebSocketController<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebSocketController::Json(c) => fmt.debug_tuple("Json").field(c).finish(),
+            WebSocketController::Postcard(_) => fmt.debug_tuple("Postcard").finish(),
+        }
+    }
+}
 
 #[derive(Debug)]
-pub struct WebSocketDevice {}
-
-// impl<'s> Init<'s> for WebSocketDevice {
-//     type Config = ();
-
-//     type ControlImpl = !;
-//     type Input = !;
-//     type Output = !;
-
-//     fn init(
-//         b: &'s mut BlackBox,
-//     ) -> (
-//         &'s mut Self::ControlImpl,
-//         Option<Shims<'static>>,
-//         Option<&'s Self::Input>,
-//         Option<&'s Self::Output>,
-//     ) {
-//         unimplemented!();
-//     }
-// }
+pub struct WebSocketDevice<'ss> {
+    controller: WebSocketController<'ss>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebSocketConfig {
+    /// Address of the server to connect to (i.e. `"127.0.0.1:9001"`).
+    pub addr: String,
+    /// Has to match the [server's](WebSocketServerConfig::format).
+    pub format: WireFormat,
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_WEBSOCKET_ADDR)
+    }
+}
+
+impl WebSocketConfig {
+    pub fn new<A: Into<String>>(addr: A) -> Self {
+        Self { addr: addr.into(), format: WireFormat::default() }
+    }
+
+    pub fn with_format(self, format: WireFormat) -> Self {
+        Self { format, ..self }
+    }
+}
+
+fn new_cobs() -> Cobs<Fifo<u8>> {
+    Cobs::try_new(Fifo::new()).unwrap()
+}
+
+impl<'s> Init<'s> for WebSocketDevice<'static> {
+    type Config = WebSocketConfig;
+
+    type ControlImpl = DynControl<'static>;
+    type Input = SourceShim; // TODO
+    type Output = Mutex<Vec<u8>>; // TODO
+
+    fn init_with_config(
+        b: &'s mut BlackBox,
+        config: Self::Config,
+    ) -> (
+        &'s mut Self::ControlImpl,
+        Option<Shims<'static>>,
+        Option<&'s Self::Input>,
+        Option<&'s Self::Output>,
+    ) {
+        let transport = WebSocketTransport::connect(config.addr.as_str())
+            .unwrap_or_else(|e| panic!("Couldn't open a WebSocket to `{}`: {:?}", config.addr, e));
+
+        let shared_state = &*EVENT_FUTURE_SHARED_STATE_CONT;
+        let controller = match config.format {
+            WireFormat::Json => WebSocketController::Json(
+                Controller::new(JsonEncoding, JsonEncoding, transport, shared_state),
+            ),
+            WireFormat::Postcard => WebSocketController::Postcard(Controller::new(
+                PostcardEncode::new(new_cobs),
+                PostcardDecode::new(),
+                transport,
+                shared_state,
+            )),
+        };
+
+        // Make sure we're speaking the same language as the server:
+        let handshake = match &controller {
+            WebSocketController::Json(c) => c.handshake().map_err(|e| e.to_string()),
+            WebSocketController::Postcard(c) => c.handshake().map_err(|e| e.to_string()),
+        };
+        if let Err(e) = handshake {
+            panic!("Couldn't connect to `{}`: {}", config.addr, e);
+        }
+
+        let storage: &'s mut _ = b.put(WebSocketDevice { controller });
+        let controller: &'s mut Self::ControlImpl = match &mut storage.controller {
+            WebSocketController::Json(c) => c,
+            WebSocketController::Postcard(c) => c,
+        };
+
+        (
+            controller,
+            None,
+            None, // TODO
+            None, // TODO
+        )
+    }
+}
+
+/// How messages are encoded on the wire.
+///
+/// Postcard encoded messages are sent as binary frames and JSON encoded
+/// messages are sent as text frames.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WireFormat {
+    Postcard,
+    Json,
+}
+
+impl Default for WireFormat {
+    fn default() -> Self {
+        // Browsers are the primary users of the server and JSON is much easier
+        // for them to deal with.
+        WireFormat::Json
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebSocketServerConfig {
+    /// Address to listen on (i.e. `"127.0.0.1:9001"`).
+    pub addr: String,
+    pub format: WireFormat,
+    pub backoff: Backoff,
+}
+
+impl Default for WebSocketServerConfig {
+    fn default() -> Self {
+        Self {
+            addr: DEFAULT_WEBSOCKET_ADDR.to_string(),
+            format: WireFormat::default(),
+            backoff: Backoff::default(),
+        }
+    }
+}
+
+/// Hosts a [`Simulator`] (set up with the shim peripherals, just like
+/// [`SimDevice`]) for clients to connect to.
+///
+/// Connections are served one at a time; once a client disconnects, the next
+/// pending connection is accepted. This only returns if we fail to bind to the
+/// address given.
+///
+/// [`Simulator`]: lc3_baseline_sim::sim::Simulator
+/// [`SimDevice`]: super::SimDevice
+pub fn serve(config: WebSocketServerConfig) -> IoResult<()> {
+    let listener = TcpListener::bind(config.addr.as_str())?;
+
+    // See the notes in `src/init/sim.rs` about why these are leaked.
+    let input: &'static SourceShim = Box::leak(Box::new(SourceShim::new()));
+    let output: &'static Mutex<Vec<u8>> =
+        Box::leak(Box::new(Mutex::new(Vec::new())));
+
+    let (shims, _, _) =
+        new_shim_peripherals_set::<'static, 'static, _, _>(input, output);
+    let mut sim = new_sim(shims);
+
+    for stream in listener.incoming() {
+        let transport = match stream.map_err(Into::into).and_then(WebSocketTransport::accept) {
+            Ok(t) => t,
+            Err(e) => {
+                log::warn!("Failed to accept a WebSocket connection: {:?}", e);
+                continue;
+            },
+        };
+
+        log::info!("Accepted a connection: {:?}", transport);
+
+        match config.format {
+            WireFormat::Json => {
+                let mut device = Device::<_, _, RequestMessage, ResponseMessage, _, _>::new(
+                    JsonEncoding,
+                    JsonEncoding,
+                    transport,
+                );
+
+                config.backoff.run_step_while(&mut sim, &mut device, |d| !d.transport.is_closed());
+            },
+
+            WireFormat::Postcard => {
+                let mut device = Device::<_, _, RequestMessage, ResponseMessage, _, _>::new(
+                    PostcardEncode::new(new_cobs),
+                    PostcardDecode::<RequestMessage, Cobs<Fifo<u8>>>::new(),
+                    transport,
+                );
+
+                config.backoff.run_step_while(&mut sim, &mut device, |d| !d.transport.is_closed());
+            },
+        }
+
+        log::info!("Connection closed.");
+    }
+
+    Ok(())
+}
@@ -3,8 +3,8 @@
 use crate::io_peripherals::{InputSink, OutputSource};
 
 use lc3_traits::peripherals::PeripheralSet;
-use lc3_shims::peripherals::{Source, Sink, ShareablePeripheralsShim};
-use lc3_shims::peripherals::{GpioShim, AdcShim, PwmShim, TimersShim, ClockShim, InputShim, OutputShim};
+use lc3_shims::peripherals::{Source, Sink, ShareablePeripheralsShim, TimeSource};
+use lc3_shims::peripherals::{GpioShim, AdcShim, PwmShim, TimersShim, ClockShim, InputShim, OutputShim, DisplayShim, DiskShim, UartShim, RngShim, WatchdogShim};
 
 use std::sync::{Arc, Mutex, RwLock};
 
@@ -16,6 +16,11 @@ pub struct Shims<'int> {
     pub pwm: Arc<Mutex<PwmShim>>,
     pub timers: Arc<Mutex<TimersShim<'int>>>,
     pub clock: Arc<RwLock<ClockShim>>,
+    pub display: Arc<RwLock<DisplayShim>>,
+    pub disk: Arc<RwLock<DiskShim>>,
+    pub uart: Arc<RwLock<UartShim>>,
+    pub rng: Arc<RwLock<RngShim>>,
+    pub watchdog: Arc<RwLock<WatchdogShim>>,
 }
 
 pub fn new_shim_peripherals_set<'int, 'io, I, O>(input: &'io I, output: &'io O)
@@ -24,16 +29,34 @@ where
     I: InputSink + Source + Send + Sync + 'io,
     O: OutputSource + Sink + Send + Sync + 'io,
 {
-    let gpio_shim = Arc::new(RwLock::new(GpioShim::default()));
-    let adc_shim = Arc::new(RwLock::new(AdcShim::default()));
-    let pwm_shim = Arc::new(Mutex::new(PwmShim::default()));
-    let timer_shim = Arc::new(Mutex::new(TimersShim::default()));
-    let clock_shim = Arc::new(RwLock::new(ClockShim::default()));
+    new_shim_peripherals_set_with_time_source(input, output, TimeSource::Real)
+}
+
+/// Like [`new_shim_peripherals_set`] but with the GPIO, ADC, clock, timers, PWM,
+/// and watchdog shims using the given [`TimeSource`].
+///
+/// Use [`TimeSource::Virtual`] to make programs that use time reproducible.
+pub fn new_shim_peripherals_set_with_time_source<'int, 'io, I, O>(input: &'io I, output: &'io O, time: TimeSource)
+        -> (ShimPeripheralSet<'int, 'io>, &'io impl InputSink, &'io impl OutputSource)
+where
+    I: InputSink + Source + Send + Sync + 'io,
+    O: OutputSource + Sink + Send + Sync + 'io,
+{
+    let gpio_shim = Arc::new(RwLock::new(GpioShim::with_time_source(time.clone())));
+    let adc_shim = Arc::new(RwLock::new(AdcShim::with_time_source(time.clone())));
+    let pwm_shim = Arc::new(Mutex::new(PwmShim::with_time_source(time.clone())));
+    let timer_shim = Arc::new(Mutex::new(TimersShim::with_time_source(time.clone())));
+    let clock_shim = Arc::new(RwLock::new(ClockShim::with_time_source(time.clone())));
+    let display_shim = Arc::new(RwLock::new(DisplayShim::default()));
+    let disk_shim = Arc::new(RwLock::new(DiskShim::default()));
+    let uart_shim = Arc::new(RwLock::new(UartShim::default()));
+    let rng_shim = Arc::new(RwLock::new(RngShim::default()));
+    let watchdog_shim = Arc::new(RwLock::new(WatchdogShim::with_time_source(time)));
 
     let input_shim = Arc::new(Mutex::new(InputShim::with_ref(input)));
     let output_shim = Arc::new(Mutex::new(OutputShim::with_ref(output)));
 
-    (PeripheralSet::new(gpio_shim, adc_shim, pwm_shim, timer_shim, clock_shim, input_shim, output_shim),
+    (PeripheralSet::new(gpio_shim, adc_shim, pwm_shim, timer_shim, clock_shim, input_shim, output_shim, display_shim, disk_shim, uart_shim, rng_shim, watchdog_shim),
         input,
         output,
     )
@@ -48,6 +71,11 @@ impl<'int> Shims<'int> {
             pwm: p.get_pwm().clone(),
             timers: p.get_timers().clone(),
             clock: p.get_clock().clone(),
+            display: p.get_display().clone(),
+            disk: p.get_disk().clone(),
+            uart: p.get_uart().clone(),
+            rng: p.get_rng().clone(),
+            watchdog: p.get_watchdog().clone(),
         }
     }
 }
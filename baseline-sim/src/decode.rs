@@ -0,0 +1,119 @@
+//! A cache of decoded instructions, so that [`step`] doesn't have to decode
+//! the same words over and over again (i.e. in loops).
+//!
+//! The cache holds a few [pages](lc3_traits::control::load::PAGE_SIZE_IN_WORDS)
+//! of memory; each page can only go in one spot so a page that's fetched from
+//! evicts whatever page was in its spot before. Entries are filled in as
+//! instructions are fetched and are dropped when the word they came from is
+//! written to. All writes to memory go through
+//! [`set_word_force_memory_backed`] (checked and unchecked writes, the
+//! debugger's writes, the disk's DMA, etc.) so self-modifying code works as
+//! usual. Committed pages and resets drop entries too.
+//!
+//! Instructions in the memory mapped region are never cached; the words there
+//! aren't necessarily what was last written.
+//!
+//! The interpreter still fetches (including the access control check) and
+//! executes one instruction at a time, so interrupts, breakpoints, and
+//! watchpoints aren't affected.
+//!
+//! [`step`]: crate::interp::InstructionInterpreter::step
+//! [`set_word_force_memory_backed`]: crate::interp::InstructionInterpreter::set_word_force_memory_backed
+
+use lc3_isa::{Addr, Instruction, MEM_MAPPED_START_ADDR};
+use lc3_traits::control::load::{PageIndex, PAGE_SIZE_IN_WORDS};
+
+/// The number of pages the cache holds.
+const NUM_CACHED_PAGES: usize = 4;
+
+#[derive(Debug, Clone)]
+struct CachedPage {
+    page_idx: Option<PageIndex>,
+    insns: [Option<Instruction>; PAGE_SIZE_IN_WORDS as usize],
+}
+
+impl CachedPage {
+    const fn new() -> Self {
+        Self {
+            page_idx: None,
+            insns: [None; PAGE_SIZE_IN_WORDS as usize],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DecodeCache {
+    pages: [CachedPage; NUM_CACHED_PAGES],
+}
+
+impl Default for DecodeCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DecodeCache {
+    pub(crate) const fn new() -> Self {
+        Self {
+            pages: [CachedPage::new(), CachedPage::new(), CachedPage::new(), CachedPage::new()],
+        }
+    }
+
+    // The page an address is in, where that page goes in the cache, and where
+    // the address is in the page.
+    fn locate(addr: Addr) -> (PageIndex, usize, usize) {
+        let page_idx = (addr / PAGE_SIZE_IN_WORDS) as PageIndex;
+        let slot = (page_idx as usize) % NUM_CACHED_PAGES;
+
+        (page_idx, slot, (addr % PAGE_SIZE_IN_WORDS) as usize)
+    }
+
+    pub(crate) fn get(&self, addr: Addr) -> Option<Instruction> {
+        let (page_idx, slot, offset) = Self::locate(addr);
+        let page = &self.pages[slot];
+
+        if page.page_idx == Some(page_idx) {
+            page.insns[offset]
+        } else {
+            None
+        }
+    }
+
+    /// Does nothing for addresses in the memory mapped region.
+    pub(crate) fn insert(&mut self, addr: Addr, insn: Instruction) {
+        if addr >= MEM_MAPPED_START_ADDR {
+            return;
+        }
+
+        let (page_idx, slot, offset) = Self::locate(addr);
+        let page = &mut self.pages[slot];
+
+        if page.page_idx != Some(page_idx) {
+            *page = CachedPage::new();
+            page.page_idx = Some(page_idx);
+        }
+
+        page.insns[offset] = Some(insn);
+    }
+
+    pub(crate) fn invalidate(&mut self, addr: Addr) {
+        let (page_idx, slot, offset) = Self::locate(addr);
+        let page = &mut self.pages[slot];
+
+        if page.page_idx == Some(page_idx) {
+            page.insns[offset] = None;
+        }
+    }
+
+    pub(crate) fn invalidate_page(&mut self, page_idx: PageIndex) {
+        let page = &mut self.pages[(page_idx as usize) % NUM_CACHED_PAGES];
+
+        if page.page_idx == Some(page_idx) {
+            *page = CachedPage::new();
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        *self = Self::new();
+    }
+}
@@ -0,0 +1,79 @@
+//! Executing [custom instructions](lc3_isa::ext) in the reserved opcode.
+//!
+//! An [`InstructionExtension`] is an [`Extension`] that also knows how to run
+//! its instructions against an interpreter: it has full access to the
+//! registers, memory (through the usual checked accesses, so access control
+//! violations still happen), and the condition codes (through the
+//! [`PSR`](crate::mem_mapped::PSR)).
+//!
+//! Extensions are attached to an interpreter with
+//! [`InstructionInterpreter::set_extension`](crate::interp::InstructionInterpreter::set_extension).
+//! They're held by (shared, `'static`) reference so any state they have needs
+//! interior mutability; most extensions are unit structs anyways.
+//!
+//! Words in the reserved opcode that the extension doesn't decode (or all of
+//! them, when there's no extension) raise the illegal opcode exception as
+//! usual. This module is only available with the `extensions` feature.
+
+use crate::interp::{Acv, InstructionInterpreter};
+
+use lc3_isa::ext::{decode, Decoded, Extension};
+use lc3_isa::Word;
+
+use core::any::Any;
+use core::fmt::{self, Debug};
+
+/// An [`Extension`] that can be executed by `I`.
+pub trait InstructionExtension<I: InstructionInterpreter>: Extension {
+    /// Executes `insn`.
+    ///
+    /// The PC has already been incremented (as with any other instruction).
+    fn execute(&self, insn: Self::Insn, interp: &mut I) -> Result<(), Acv>;
+}
+
+// The extension attached to an interpreter. The extension's type is erased
+// (the interpreter can't be generic over it) but it's paired with a function
+// that knows what it is.
+pub(crate) struct ExtensionRef<I> {
+    ext: &'static (dyn Any + Send + Sync),
+    step: fn(&'static (dyn Any + Send + Sync), Word, &mut I) -> Option<Result<(), Acv>>,
+}
+
+impl<I: InstructionInterpreter> ExtensionRef<I> {
+    pub(crate) fn new<X: InstructionExtension<I> + Send + Sync>(ext: &'static X) -> Self {
+        fn step<I: InstructionInterpreter, X: InstructionExtension<I> + 'static>(
+            ext: &'static (dyn Any + Send + Sync),
+            word: Word,
+            interp: &mut I,
+        ) -> Option<Result<(), Acv>> {
+            let ext = ext.downcast_ref::<X>().unwrap(); // Always made with an `X` (see below).
+
+            // Only words in the reserved opcode are the extension's:
+            match decode(ext, word) {
+                Ok(Decoded::Ext(insn)) => Some(ext.execute(insn, interp)),
+                Ok(Decoded::Base(_)) | Err(_) => None,
+            }
+        }
+
+        Self { ext, step: step::<I, X> }
+    }
+
+    /// Decodes and executes `word`; `None` if the extension doesn't decode it.
+    pub(crate) fn step(&self, word: Word, interp: &mut I) -> Option<Result<(), Acv>> {
+        (self.step)(self.ext, word, interp)
+    }
+}
+
+impl<I> Clone for ExtensionRef<I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I> Copy for ExtensionRef<I> { }
+
+impl<I> Debug for ExtensionRef<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtensionRef").finish()
+    }
+}
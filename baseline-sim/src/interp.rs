@@ -7,24 +7,36 @@ use lc3_isa::{
     Word, ACCESS_CONTROL_VIOLATION_EXCEPTION_VECTOR, ILLEGAL_OPCODE_EXCEPTION_VECTOR,
     INTERRUPT_VECTOR_TABLE_START_ADDR, MEM_MAPPED_START_ADDR,
     PRIVILEGE_MODE_VIOLATION_EXCEPTION_VECTOR, TRAP_VECTOR_TABLE_START_ADDR,
-    USER_PROGRAM_START_ADDR,
+    OS_START_ADDR, RESET_VECTOR_ADDR,
+    util::AssembledProgram,
 };
+use lc3_traits::control::boot::{BootConfig, BootEntry, ResetKind};
+use lc3_traits::control::Idx;
+use lc3_traits::control::mpu::{MpuError, MpuRegion};
 use lc3_traits::control::metadata::{Identifier, ProgramMetadata, Version, version_from_crate};
 use lc3_traits::control::load::{PageIndex, PAGE_SIZE_IN_WORDS};
 use lc3_traits::control::control::MAX_CALL_STACK_DEPTH;
-use lc3_traits::peripherals::{gpio::GpioPinArr, timers::TimerArr};
+use lc3_traits::peripherals::{gpio::{GpioPin, GpioPinArr}, timers::{TimerArr, TimerId, TIMERS}};
 use lc3_traits::{memory::Memory, peripherals::Peripherals};
-use lc3_traits::peripherals::{gpio::Gpio, input::Input, output::Output, timers::Timers};
-use lc3_traits::error::Error;
+use lc3_traits::peripherals::{clock::Clock, gpio::Gpio, input::Input, output::Output, timers::Timers, watchdog::Watchdog};
+use lc3_traits::error::{Error, Location};
 use crate::mem_mapped::Interrupt;
+use crate::decode::DecodeCache;
+use crate::mpu::{Access, Mpu};
+use crate::shadow::{self, Shadow};
+use crate::timing::{Timing, TimingModel};
+use crate::mmio::{MmioDevice, MmioInterrupt, MmioRegistry, MmioRegistrationError};
+#[cfg(feature = "extensions")]
+use crate::ext::{ExtensionRef, InstructionExtension};
 
 use core::any::TypeId;
-use core::convert::TryInto;
+use core::convert::{TryFrom, TryInto};
 use core::marker::PhantomData;
 use core::ops::{Index, IndexMut};
 use core::sync::atomic::AtomicBool;
 use core::ops::{Deref, DerefMut};
 use core::cell::Cell;
+use core::ops::RangeInclusive;
 
 // TODO: Break up this file!
 
@@ -70,8 +82,10 @@ where
         use lc3_traits::peripherals::gpio::{GPIO_PINS, GpioPin, GpioState};
         use lc3_traits::peripherals::adc::{Adc, ADC_PINS, AdcPin, AdcState};
         use lc3_traits::peripherals::pwm::{Pwm, PWM_PINS, PwmPin, PwmState};
-        use lc3_traits::peripherals::timers::{TIMERS, TimerId, TimerMode, TimerState};
+        use lc3_traits::peripherals::timers::{TimerMode, TimerState};
         use lc3_traits::peripherals::clock::Clock;
+        use lc3_traits::peripherals::display::Display;
+        use lc3_traits::peripherals::uart::Uart;
 
         for pin in GPIO_PINS.iter() {
             Gpio::set_state(self.get_peripherals_mut(), *pin, GpioState::Disabled);
@@ -90,12 +104,24 @@ where
         for id in TIMERS.iter() {
             Timers::set_mode(self.get_peripherals_mut(), *id, TimerMode::SingleShot);
             Timers::set_state(self.get_peripherals_mut(), *id, TimerState::Disabled);
+            Timers::set_capture(self.get_peripherals_mut(), *id, None);
             Timers::reset_interrupt_flag(self.get_peripherals_mut(), *id);
         }
 
         Clock::set_milliseconds(self.get_peripherals_mut(), 0);
         Input::reset_interrupt_flag(self.get_peripherals_mut());
         Output::reset_interrupt_flag(self.get_peripherals_mut());
+        Display::fill(self.get_peripherals_mut(), 0);
+
+        Uart::set_rx_interrupt_enable_bit(self.get_peripherals_mut(), false);
+        Uart::set_tx_interrupt_enable_bit(self.get_peripherals_mut(), false);
+        Uart::clear_overrun(self.get_peripherals_mut());
+
+        // Whether the watchdog caused this reset is left alone so the program
+        // can check.
+        Watchdog::set_timeout(self.get_peripherals_mut(), 0);
+        Watchdog::set_interrupt_enable_bit(self.get_peripherals_mut(), false);
+        Watchdog::reset_interrupt_flag(self.get_peripherals_mut());
     }
 }
 
@@ -125,8 +151,74 @@ pub trait InstructionInterpreter:
     fn set_register(&mut self, reg: Reg, word: Word) { self[reg] = word; }
 
     fn get_machine_state(&self) -> MachineState;
-    fn reset(&mut self);
-    fn halt(&mut self); // TODO: have the MCR set this, etc.
+    /// A [cold](ResetKind::Cold) reset with the current
+    /// [boot config](InstructionInterpreter::get_boot_config).
+    fn reset(&mut self) {
+        let boot = self.get_boot_config();
+        self.reset_with(ResetKind::Cold, boot)
+    }
+    /// Resets the machine and then boots it as `boot` describes. `boot` is
+    /// used for all later resets as well.
+    fn reset_with(&mut self, kind: ResetKind, boot: BootConfig);
+    fn get_boot_config(&self) -> BootConfig;
+    /// Makes [`BRK`](Instruction::Brk) a software breakpoint instead of an
+    /// illegal instruction: executing one leaves the PC on it (so it's hit
+    /// again until it's swapped out) and the
+    /// [simulator](crate::sim::Simulator) pauses when it reaches one.
+    ///
+    /// Off by default; not affected by resets.
+    fn set_break_instructions_enabled(&mut self, enabled: bool);
+    fn get_break_instructions_enabled(&self) -> bool;
+    /// Attaches the [extension](crate::ext) that runs words in the reserved
+    /// opcode. Replaces the current extension, if there is one.
+    ///
+    /// None by default (words in the reserved opcode other than `BRK` are
+    /// illegal instructions); not affected by resets.
+    #[cfg(feature = "extensions")]
+    fn set_extension<X: InstructionExtension<Self> + Send + Sync>(&mut self, ext: &'static X);
+    /// Detaches the current [extension](crate::ext), if there is one.
+    #[cfg(feature = "extensions")]
+    fn remove_extension(&mut self);
+    /// Reports reads of registers and memory that haven't been set yet as
+    /// [`Error::UninitializedRead`]s.
+    ///
+    /// Memory is set once it's loaded (with
+    /// [`Interpreter::load_program`], which only counts the words the program
+    /// sets, or with the load API, which counts whole pages) or written; the
+    /// registers (other than R6) are set once they're written. Instruction
+    /// fetches, loads, and source registers are checked, except for the
+    /// source register of a store (so that saving registers is fine) and
+    /// `AND` with `#0`. Only the first such read is reported until the error
+    /// is taken (i.e. for an instruction that reads two registers that aren't
+    /// set, the first one).
+    ///
+    /// Off by default; not affected by resets.
+    fn set_uninitialized_read_detection(&mut self, enabled: bool);
+    fn get_uninitialized_read_detection(&self) -> bool;
+    /// Sets (or, with `None`, clears) region `idx` of the memory protection
+    /// table (see [`lc3_traits::control::mpu`]). Programs can also change the
+    /// table through the [`MPUNR`](crate::mem_mapped::MPUNR) registers.
+    ///
+    /// Fetches need execute permission, loads need read permission, and
+    /// stores need write permission; addresses that aren't in a region get
+    /// the usual user/supervisor check. Fetches from data (read but no
+    /// execute) raise the
+    /// [execute from data](lc3_isa::EXECUTE_FROM_DATA_EXCEPTION_VECTOR)
+    /// exception, writes to code (execute but no write) raise the
+    /// [write to code](lc3_isa::WRITE_TO_CODE_EXCEPTION_VECTOR) exception,
+    /// and anything else that isn't allowed is an access control violation.
+    /// Reading the vector tables and pushing onto the supervisor stack when
+    /// taking a trap, exception, or interrupt aren't checked.
+    ///
+    /// Empty by default; not affected by resets.
+    fn set_mpu_region(&mut self, idx: Idx, region: Option<MpuRegion>) -> Result<(), MpuError>;
+    fn get_mpu_region(&self, idx: Idx) -> Result<Option<MpuRegion>, MpuError>;
+    /// The number of cycles that have gone by since the last reset, if
+    /// there's a [timing model](crate::timing).
+    fn get_cycle_count(&self) -> Option<u64>;
+    /// Stops the clock; see [`MCR`](crate::mem_mapped::MCR). Programs halt
+    /// the machine by clearing the MCR's clock enable bit, which ends up here.
+    fn halt(&mut self);
 
     fn set_error(&self, err: Error);
     fn get_error(&self) -> Option<Error>;
@@ -136,10 +228,16 @@ pub trait InstructionInterpreter:
 
     // Taken straight from Memory:
     fn commit_page(&mut self, page_idx: PageIndex, page: &[Word; PAGE_SIZE_IN_WORDS as usize]);
+    fn read_committed_word(&self, addr: Addr) -> Word;
 
     fn get_program_metadata(&self) -> ProgramMetadata;
     fn set_program_metadata(&mut self, metadata: ProgramMetadata);
 
+    /// Whether every word in the page counts as loaded (see
+    /// [`set_uninitialized_read_detection`](InstructionInterpreter::set_uninitialized_read_detection)),
+    /// i.e. whether the page was last replaced by committing the whole thing.
+    fn page_is_loaded(&self, page_idx: PageIndex) -> bool;
+
     // Until TypeId::of is a const function, this can't be an associated const:
     fn type_id() -> TypeId { core::any::TypeId::of::<Instruction>() }
 }
@@ -183,16 +281,12 @@ pub struct PeripheralInterruptFlags {
 
 impl PeripheralInterruptFlags {
     pub const fn new() -> Self {
-        macro_rules! b {
-            () => {
-                AtomicBool::new(false)
-            };
-        }
+        #[allow(clippy::declare_interior_mutable_const)] // Only used to fill the arrays.
+        const B: AtomicBool = AtomicBool::new(false);
 
-        // TODO: make this less gross..
         Self {
-            gpio: GpioPinArr([b!(), b!(), b!(), b!(), b!(), b!(), b!(), b!()]),
-            timers: TimerArr([b!(), b!()]),
+            gpio: GpioPinArr([B; GpioPin::NUM_PINS]),
+            timers: TimerArr([B; TimerId::NUM_TIMERS]),
             input: AtomicBool::new(false),
             output: AtomicBool::new(false),
         }
@@ -306,10 +400,26 @@ pub struct Interpreter<'per, M: Memory, P: Peripherals<'per>> {
     // flags: OwnedOrRef<'a, PeripheralInterruptFlags>,
     flags: PhantomData<OwnedOrRef<'per, PeripheralInterruptFlags>>,
     regs: [Word; Reg::NUM_REGS],
-    pc: Word, //TODO: what should the default for this be
+    pc: Word,
     state: MachineState,
+    boot: BootConfig,
+    break_instructions: bool,
     error: Cell<Option<Error>>,
     call_stack: CallStack,
+    mmio: MmioRegistry<'per>,
+    decode_cache: DecodeCache,
+    timing: Option<Timing>,
+    shadow: Shadow,
+    uninitialized_read_detection: bool,
+    mpu: Mpu,
+    // The exception the last access the protection table turned down should
+    // raise (`Acv` doesn't say which).
+    protection_fault: Cell<Option<u8>>,
+    #[cfg(feature = "extensions")]
+    extension: Option<ExtensionRef<Interpreter<'per, M, P>>>,
+    // The last level we saw on the GPIO pin each timer captures from (if any);
+    // see `update_timer_captures`.
+    capture_levels: TimerArr<Option<bool>>,
 }
 
 impl<'a, M: Memory + Default, P: Peripherals<'a>> Default for Interpreter<'a, M, P> {
@@ -333,9 +443,15 @@ where
     memory: Option<M>,
     peripherals: Option<P>,
     flags: Option<OwnedOrRef<'a, PeripheralInterruptFlags>>,
+    // If set, these override what the boot sequence would otherwise give the
+    // registers and the PC (only for the first boot).
     regs: Option<[Word; Reg::NUM_REGS]>,
     pc: Option<Word>,
     state: Option<MachineState>,
+    boot: BootConfig,
+    break_instructions: bool,
+    timing: Option<TimingModel>,
+    uninitialized_read_detection: bool,
 }
 
 #[derive(Debug)]
@@ -392,6 +508,10 @@ where
                 regs: None,
                 pc: None,
                 state: None,
+                boot: BootConfig::default(),
+                break_instructions: false,
+                timing: None,
+                uninitialized_read_detection: false,
             },
             _mem: PhantomData,
             _perip: PhantomData,
@@ -413,9 +533,13 @@ where
             memory: Some(Default::default()),
             peripherals: Some(Default::default()),
             flags: Some(Default::default()),
-            regs: Some(Default::default()),
-            pc: Some(Default::default()),
+            regs: None,
+            pc: None,
             state: Some(Default::default()),
+            boot: self.data.boot,
+            break_instructions: self.data.break_instructions,
+            timing: self.data.timing,
+            uninitialized_read_detection: self.data.uninitialized_read_detection,
         })
     }
 }
@@ -506,7 +630,6 @@ where
     }
 }
 
-// TODO: do we want to allow people to set the starting register values?
 // impl<'a, M: Memory, P, Mem, Perip, Flags, Pc, State> InterpreterBuilder<'a, M, P, Mem, Perip, Flags, NotSet, Pc, State>
 impl<'a, M: Memory, P, Mem, Perip, Flags, Regs, Pc, State>
     InterpreterBuilder<'a, M, P, Mem, Perip, Flags, Regs, Pc, State>
@@ -523,14 +646,17 @@ where
         })
     }
 
+    /// Leaves the registers to the boot sequence (see [`BootConfig`]).
     pub fn with_default_regs(
         self,
     ) -> InterpreterBuilder<'a, M, P, Mem, Perip, Flags, Set, Pc, State> {
-        self.with_regs(Default::default())
+        InterpreterBuilder::with_data(InterpreterBuilderData {
+            regs: None,
+            ..self.data
+        })
     }
 }
 
-// TODO: do we want to allow people to set the starting pc?
 // impl<'a, M: Memory, P, Mem, Perip, Flags, Regs, State> InterpreterBuilder<'a, M, P, Mem, Perip, Flags, Regs, NotSet, State>
 impl<'a, M: Memory, P, Mem, Perip, Flags, Regs, Pc, State>
     InterpreterBuilder<'a, M, P, Mem, Perip, Flags, Regs, Pc, State>
@@ -547,10 +673,58 @@ where
         })
     }
 
+    /// Leaves the PC to the boot sequence (see [`BootConfig`]).
     pub fn with_default_pc(
         self,
     ) -> InterpreterBuilder<'a, M, P, Mem, Perip, Flags, Regs, Set, State> {
-        self.with_pc(Default::default())
+        InterpreterBuilder::with_data(InterpreterBuilderData {
+            pc: None,
+            ..self.data
+        })
+    }
+}
+
+impl<'a, M: Memory, P, Mem, Perip, Flags, Regs, Pc, State>
+    InterpreterBuilder<'a, M, P, Mem, Perip, Flags, Regs, Pc, State>
+where
+    P: Peripherals<'a>,
+{
+    /// How the interpreter starts up (on every reset, including the first);
+    /// defaults to [booting the OS](BootConfig::os).
+    pub fn with_boot_config(self, boot: BootConfig) -> Self {
+        InterpreterBuilder::with_data(InterpreterBuilderData {
+            boot,
+            ..self.data
+        })
+    }
+
+    /// Whether [`BRK`](Instruction::Brk) instructions are software
+    /// breakpoints (see
+    /// [`set_break_instructions_enabled`](InstructionInterpreter::set_break_instructions_enabled));
+    /// off by default.
+    pub fn with_break_instructions(self, enabled: bool) -> Self {
+        InterpreterBuilder::with_data(InterpreterBuilderData {
+            break_instructions: enabled,
+            ..self.data
+        })
+    }
+
+    /// Attaches a [timing model](crate::timing); there isn't one by default.
+    pub fn with_timing_model(self, model: TimingModel) -> Self {
+        InterpreterBuilder::with_data(InterpreterBuilderData {
+            timing: Some(model),
+            ..self.data
+        })
+    }
+
+    /// Whether reads of uninitialized state are reported (see
+    /// [`set_uninitialized_read_detection`](InstructionInterpreter::set_uninitialized_read_detection));
+    /// off by default.
+    pub fn with_uninitialized_read_detection(self, enabled: bool) -> Self {
+        InterpreterBuilder::with_data(InterpreterBuilderData {
+            uninitialized_read_detection: enabled,
+            ..self.data
+        })
     }
 }
 
@@ -587,9 +761,13 @@ where
             self.data.memory.unwrap(),
             self.data.peripherals.unwrap(),
             self.data.flags.unwrap(),
-            self.data.regs.unwrap(),
-            self.data.pc.unwrap(),
+            self.data.regs,
+            self.data.pc,
             self.data.state.unwrap(),
+            self.data.boot,
+            self.data.break_instructions,
+            self.data.timing,
+            self.data.uninitialized_read_detection,
         )
     }
 }
@@ -599,9 +777,13 @@ impl<'a, M: Memory, P: Peripherals<'a>> Interpreter<'a, M, P> {
         memory: M,
         peripherals: P,
         flags: OwnedOrRef<'a, PeripheralInterruptFlags>,
-        regs: [Word; Reg::NUM_REGS],
-        pc: Word,
+        regs: Option<[Word; Reg::NUM_REGS]>,
+        pc: Option<Word>,
         state: MachineState,
+        boot: BootConfig,
+        break_instructions: bool,
+        timing: Option<TimingModel>,
+        uninitialized_read_detection: bool,
     ) -> Self {
         // TODO: propagate flags to the peripherals!
         // TODO: maybe eventually don't even hold flags; just pass it along
@@ -610,11 +792,23 @@ impl<'a, M: Memory, P: Peripherals<'a>> Interpreter<'a, M, P> {
             memory,
             peripherals,
             flags: PhantomData,
-            regs,
-            pc,
+            regs: [0; Reg::NUM_REGS],
+            pc: 0,
             state,
+            boot,
+            break_instructions,
             error: Cell::new(None),
             call_stack: CallStack::new(),
+            mmio: MmioRegistry::new(),
+            decode_cache: DecodeCache::new(),
+            timing: timing.map(Timing::new),
+            shadow: Shadow::new(),
+            uninitialized_read_detection,
+            mpu: Mpu::new(),
+            protection_fault: Cell::new(None),
+            #[cfg(feature = "extensions")]
+            extension: None,
+            capture_levels: TimerArr([None; TimerId::NUM_TIMERS]),
         };
 
         // TODO: we can't call this.
@@ -639,7 +833,14 @@ impl<'a, M: Memory, P: Peripherals<'a>> Interpreter<'a, M, P> {
             interp.init(&INTERNAL_INACCESSIBLE_PERIPHERAL_FLAGS);
         }
 
-        interp.reset(); // TODO: remove pc/regs options from the interpreter builder
+        interp.reset();
+
+        if let Some(regs) = regs {
+            interp.regs = regs;
+            interp.shadow.write_all_regs();
+        }
+        if let Some(pc) = pc { interp.pc = pc; }
+
         interp
     }
 }
@@ -654,6 +855,7 @@ impl<'a, M: Memory, P: Peripherals<'a>> Index<Reg> for Interpreter<'a, M, P> {
 
 impl<'a, M: Memory, P: Peripherals<'a>> IndexMut<Reg> for Interpreter<'a, M, P> {
     fn index_mut(&mut self, reg: Reg) -> &mut Self::Output {
+        self.shadow.write_reg(reg);
         &mut self.regs[TryInto::<usize>::try_into(Into::<u8>::into(reg)).unwrap()]
     }
 }
@@ -683,9 +885,161 @@ impl<'a, M: Memory, P: Peripherals<'a>> Interpreter<'a, M, P> {
         Input::<'a>::register_interrupt_flag(&mut self.peripherals, &flags.input);
         Output::<'a>::register_interrupt_flag(&mut self.peripherals, &flags.output);
     }
+
+    /// Attaches a memory mapped device to `addrs` (see the
+    /// [`mmio` module](crate::mmio)).
+    ///
+    /// `addrs` must be within the memory mapped region and cannot overlap with
+    /// the simulator's built-in device registers or other registered devices.
+    pub fn register_mmio_device(
+        &mut self,
+        addrs: RangeInclusive<Addr>,
+        device: &'a (dyn MmioDevice + Sync + 'a),
+        interrupt: Option<MmioInterrupt>,
+    ) -> Result<(), MmioRegistrationError> {
+        self.mmio.register(addrs, device, interrupt, BUILT_IN_DEVICE_ADDRS)
+    }
+
+    /// Attaches a [timing model](crate::timing) (or detaches the current one,
+    /// for `None`). Starts the cycle count over.
+    pub fn set_timing_model(&mut self, model: Option<TimingModel>) {
+        self.timing = model.map(Timing::new);
+    }
+
+    /// The attached [timing model](crate::timing), if there is one.
+    pub fn get_timing_model(&self) -> Option<TimingModel> {
+        self.timing.as_ref().map(Timing::model)
+    }
+
+    /// Loads the words `program` sets into memory and marks them as
+    /// initialized (see
+    /// [`set_uninitialized_read_detection`](InstructionInterpreter::set_uninitialized_read_detection));
+    /// the other words in the pages `program` touches are zeroed and are
+    /// uninitialized.
+    ///
+    /// Like the load API, this replaces the memory's image (what it goes back
+    /// to on cold resets). Pages in the memory mapped region are skipped.
+    pub fn load_program(&mut self, program: &AssembledProgram) {
+        for (page_idx, words) in program.chunks_exact(PAGE_SIZE_IN_WORDS as usize).enumerate() {
+            let (page_idx, start) = (page_idx as PageIndex, (page_idx as Addr) * PAGE_SIZE_IN_WORDS);
+
+            if start >= MEM_MAPPED_START_ADDR || !words.iter().any(|(_, set)| *set) {
+                continue;
+            }
+
+            let mut page = [0; PAGE_SIZE_IN_WORDS as usize];
+            page.iter_mut().zip(words.iter()).for_each(|(p, (word, _))| *p = *word);
+            self.commit_page(page_idx, &page);
+
+            // Update the current copy too and only count the words that were
+            // actually set:
+            for (addr, (word, set)) in (start..).zip(words.iter()) {
+                self.memory.write_word(addr, *word);
+                self.shadow.load(addr, *set);
+            }
+        }
+    }
 }
 
 impl<'a, M: Memory, P: Peripherals<'a>> Interpreter<'a, M, P> {
+    // Timers that capture (see `Timers::set_capture`) can't see the GPIO pins
+    // so we watch the pins for them and pass along the edges.
+    fn update_timer_captures(&mut self) {
+        for timer in TIMERS.iter() {
+            let capture = match Timers::get_capture(self.get_peripherals(), *timer) {
+                Some(c) => c,
+                None => {
+                    self.capture_levels[*timer] = None;
+                    continue;
+                }
+            };
+
+            // Pins that can't be read don't have edges:
+            let level = Gpio::read(self.get_peripherals(), capture.pin).ok();
+            if let (Some(prev), Some(new)) = (self.capture_levels[*timer], level) {
+                if capture.edge.triggered_by(prev, new) {
+                    let clock = Clock::get_milliseconds(self.get_peripherals());
+                    Timers::capture(self.get_peripherals_mut(), *timer, clock);
+                }
+            }
+
+            self.capture_levels[*timer] = level;
+        }
+    }
+
+    // The watchdog only counts how many times its timeout has elapsed; it's up
+    // to us to reset the machine once the program has missed its last chance
+    // (the watchdog's interrupt, if it's enabled).
+    fn check_watchdog(&mut self) {
+        let expirations = Watchdog::expirations(self.get_peripherals());
+        let interrupt_enabled = Watchdog::interrupt_enabled(self.get_peripherals());
+
+        if expirations >= 2 || (expirations == 1 && !interrupt_enabled) {
+            // Like a real watchdog, this doesn't touch memory:
+            self.reset_with(ResetKind::Warm, self.boot);
+            Watchdog::set_caused_reset(self.get_peripherals_mut(), true);
+            self.set_error(Error::WatchdogExpired);
+        }
+    }
+
+    // Like `get_word` but decodes the word too, using the decode cache (see
+    // the `decode` module) when it can.
+    fn fetch(&mut self, addr: Addr) -> Result<Result<Instruction, Word>, Acv> {
+        if self.is_acv(addr, Access::Execute) {
+            return Err(Acv);
+        }
+
+        if let Some(timing) = &self.timing {
+            timing.read(addr);
+        }
+        self.check_initialized(addr);
+
+        if let Some(insn) = self.decode_cache.get(addr) {
+            return Ok(Ok(insn));
+        }
+
+        let decoded = Instruction::try_from(self.get_word_unchecked(addr));
+        if let Ok(insn) = decoded {
+            self.decode_cache.insert(addr, insn);
+        }
+
+        Ok(decoded)
+    }
+
+    // Reports reads of memory that hasn't been set, if we're doing that (see
+    // the `shadow` module).
+    fn check_initialized(&self, addr: Addr) {
+        if self.uninitialized_read_detection && !self.shadow.is_initialized(addr) {
+            self.report_uninitialized_read(Location::Memory(addr));
+        }
+    }
+
+    // Only the first uninitialized read is reported (until the error is taken)
+    // since it's usually the cause of the ones that come after it.
+    fn report_uninitialized_read(&self, location: Location) {
+        let err = match self.error.take() {
+            Some(err @ Error::UninitializedRead { .. }) => err,
+            _ => Error::UninitializedRead { pc: self.shadow.pc(), location },
+        };
+
+        self.set_error(err);
+    }
+
+    // Words in the reserved opcode that aren't instructions go to the
+    // extension, if there is one.
+    #[cfg_attr(not(feature = "extensions"), allow(unused_variables))]
+    fn illegal_instruction_step(&mut self, word: Word) -> Result<(), Acv> {
+        #[cfg(feature = "extensions")]
+        if let Some(ext) = self.extension {
+            if let Some(res) = ext.step(word, self) {
+                return res;
+            }
+        }
+
+        self.handle_exception(ILLEGAL_OPCODE_EXCEPTION_VECTOR);
+        Ok(())
+    }
+
     fn set_cc(&mut self, word: Word) {
         <PSR as MemMapped>::from(self).unwrap().set_cc(self, word)
     }
@@ -704,7 +1058,8 @@ impl<'a, M: Memory, P: Peripherals<'a>> Interpreter<'a, M, P> {
         }
 
         self[R6] -= 1;
-        self.set_word(self[R6], word)
+        self.write_unprotected(self[R6], word);
+        Ok(())
     }
 
     // Take notice! This will not modify R6 if the read fails!
@@ -777,10 +1132,7 @@ impl<'a, M: Memory, P: Peripherals<'a>> Interpreter<'a, M, P> {
         self.prep_for_execution_event();
 
         // Go to the trap routine:
-        // (this should also not panic)
-        self.pc = self
-            .get_word(TRAP_VECTOR_TABLE_START_ADDR | (Into::<Word>::into(trap_vec)))
-            .unwrap();
+        self.pc = self.read_unprotected(TRAP_VECTOR_TABLE_START_ADDR | (Into::<Word>::into(trap_vec)));
 
         self.push_call_stack(self.pc, self.get_special_reg::<PSR>().in_user_mode());
     }
@@ -791,10 +1143,7 @@ impl<'a, M: Memory, P: Peripherals<'a>> Interpreter<'a, M, P> {
         self.prep_for_execution_event();
 
         // Go to the exception routine:
-        // (this should also not panic)
-        self.pc = self
-            .get_word(INTERRUPT_VECTOR_TABLE_START_ADDR | (Into::<Word>::into(ex_vec)))
-            .unwrap();
+        self.pc = self.read_unprotected(INTERRUPT_VECTOR_TABLE_START_ADDR | (Into::<Word>::into(ex_vec)));
 
         self.push_call_stack(self.pc, self.get_special_reg::<PSR>().in_user_mode());
     }
@@ -828,51 +1177,109 @@ impl<'a, M: Memory, P: Peripherals<'a>> Interpreter<'a, M, P> {
     }
 
     fn check_interrupts(&mut self) -> bool {
-        macro_rules! assert_in_priority_order {
-            ($dev1: ty, $dev2: ty, $($rest:ty),*) => {
-                sa::const_assert!(<$dev1>::PRIORITY >= <$dev2>::PRIORITY);
-
-                assert_in_priority_order!($dev2, $($rest),*);
-            };
-            ($dev1: ty, $dev2: ty) => {
-                sa::const_assert!(<$dev1>::PRIORITY >= <$dev2>::PRIORITY);
+        const fn in_priority_order(priorities: &[u8]) -> bool {
+            let mut i = 1;
+            while i < priorities.len() {
+                if priorities[i - 1] < priorities[i] { return false; }
+                i += 1;
             }
+
+            true
         }
 
         macro_rules! int_devices {
-            ($($dev:ty),* $(,)?) => {
+            ($($(#[$attr:meta])* $dev:ty),* $(,)?) => {
                 let cur_priority: u8 = self.get_special_reg::<PSR>().get_priority();
-                $(
+
+                // Registered devices go before built-in devices with a lower
+                // priority (but not before ones with the same priority):
+                let registered = self.mmio.pending_interrupt(cur_priority);
+                $($(#[$attr])* {
+                    if let Some((idx, int)) = registered {
+                        if int.priority > <$dev>::PRIORITY {
+                            return self.handle_registered_interrupt(idx, int);
+                        }
+                    }
+
                     if <$dev>::PRIORITY <= cur_priority { return false; }
                     else if <$dev as Interrupt>::interrupt(self) {
                         <$dev as Interrupt>::reset_interrupt_flag(self);
                         return self.handle_interrupt(<$dev>::INT_VEC, <$dev>::PRIORITY);
                     }
-                )*
+                })*
+
+                if let Some((idx, int)) = registered {
+                    return self.handle_registered_interrupt(idx, int);
+                }
 
-                assert_in_priority_order!($($dev),*);
+                sa::const_assert!(in_priority_order(&[$($(#[$attr])* <$dev>::PRIORITY),*]));
             }
         }
 
         int_devices!(
-            KBSR, DSR, G0CR, G1CR, G2CR, G3CR, G4CR, G5CR, G6CR, G7CR, T0CR, T1CR
+            WDCR, KBSR, DSR, DKSR, USR, G0CR, G1CR, G2CR, G3CR, G4CR, G5CR, G6CR, G7CR,
+            #[cfg(feature = "gpio_pins_16")] G8CR,
+            #[cfg(feature = "gpio_pins_16")] G9CR,
+            #[cfg(feature = "gpio_pins_16")] G10CR,
+            #[cfg(feature = "gpio_pins_16")] G11CR,
+            #[cfg(feature = "gpio_pins_16")] G12CR,
+            #[cfg(feature = "gpio_pins_16")] G13CR,
+            #[cfg(feature = "gpio_pins_16")] G14CR,
+            #[cfg(feature = "gpio_pins_16")] G15CR,
+            T0CR, T1CR,
+            #[cfg(feature = "timers_4")] T2CR,
+            #[cfg(feature = "timers_4")] T3CR,
         );
         false
     }
 
-    fn is_acv(&self, addr: Word) -> bool {
+    fn handle_registered_interrupt(&mut self, idx: usize, int: MmioInterrupt) -> bool {
+        self.mmio.reset_interrupt_flag(idx);
+        self.handle_interrupt(int.vector, int.priority)
+    }
+
+    // Checks `access` against the protection table (see the `mpu` module),
+    // noting which exception to raise if it isn't allowed.
+    fn is_acv(&self, addr: Word, access: Access) -> bool {
         // TODO: is `PSR::from_special(self).in_user_mode()` clearer?
+        let in_user_mode = self.get_special_reg::<PSR>().in_user_mode();
 
-        if self.get_special_reg::<PSR>().in_user_mode() {
-            (addr < USER_PROGRAM_START_ADDR) | (addr >= MEM_MAPPED_START_ADDR)
-        } else {
-            false
+        match self.mpu.check(addr, access, in_user_mode) {
+            Some(vec) => { self.protection_fault.set(Some(vec)); true }
+            None => false,
+        }
+    }
+
+    // Checked access without the protection check, for the machine's own
+    // accesses (the vector tables and the supervisor stack).
+    fn read_unprotected(&self, addr: Addr) -> Word {
+        if let Some(timing) = &self.timing {
+            timing.read(addr);
+        }
+        self.check_initialized(addr);
+
+        self.get_word_unchecked(addr)
+    }
+
+    fn write_unprotected(&mut self, addr: Addr, word: Word) {
+        if let Some(timing) = &self.timing {
+            timing.write(addr);
         }
+
+        self.set_word_unchecked(addr, word)
     }
 
     fn instruction_step_inner(&mut self, insn: Instruction) -> Result<(), Acv> {
         use Instruction::*;
 
+        if self.uninitialized_read_detection {
+            for reg in shadow::source_regs(&insn).iter().filter_map(|r| *r) {
+                if !self.shadow.reg_is_initialized(reg) {
+                    self.report_uninitialized_read(Location::Register(reg));
+                }
+            }
+        }
+
         macro_rules! i {
             (PC <- $expr:expr) => {
                 self.set_pc($expr);
@@ -1035,6 +1442,14 @@ impl<'a, M: Memory, P: Peripherals<'a>> Interpreter<'a, M, P> {
             Sti { sr, offset9 } => I!(mem[mem[PC + offset9]] <- R[sr]),
             Str { sr, base, offset6 } => I!(mem[R[base] + offset6] <- R[sr]),
             Trap { trapvec } => self.handle_trap(trapvec),
+            Brk { .. } => {
+                if self.break_instructions {
+                    // Stay on the BRK (the PC has already been incremented):
+                    self.set_pc(self.get_pc().wrapping_sub(1))
+                } else {
+                    self.handle_exception(ILLEGAL_OPCODE_EXCEPTION_VECTOR)
+                }
+            }
         }
 
         Ok(())
@@ -1057,11 +1472,92 @@ use super::mem_mapped::{
     A0CR, A0DR, A1CR, A1DR, A2CR, A2DR, A3CR, A3DR, A4CR, A4DR, A5CR, A5DR,
     P0CR, P0DR, P1CR, P1DR,
     CLKR,
-    T0CR, T0DR, T1CR, T1DR
+    T0CR, T0DR, T1CR, T1DR,
+    T0CCR, T0CNTR, T0CAPR, T1CCR, T1CNTR, T1CAPR,
+    VXR, VYR, VPR, VFR,
+    DKSR, DKCR, DKAR, DKBR, DKNR,
+    USR, UDR, UCNR,
+    RNGSR, RNGDR,
+    WDCR, WDTR, WDKR,
+    MPUNR, MPUBR, MPUER, MPUAR,
 };
+#[cfg(feature = "gpio_pins_16")]
+use super::mem_mapped::{G8CR, G8DR, G9CR, G9DR, G10CR, G10DR, G11CR, G11DR, G12CR, G12DR, G13CR, G13DR, G14CR, G14DR, G15CR, G15DR};
+#[cfg(feature = "adc_pins_8")]
+use super::mem_mapped::{A6CR, A6DR, A7CR, A7DR};
+#[cfg(feature = "pwm_pins_4")]
+use super::mem_mapped::{P2CR, P2DR, P3CR, P3DR};
+#[cfg(feature = "timers_4")]
+use super::mem_mapped::{T2CR, T2DR, T3CR, T3DR, T2CCR, T2CNTR, T2CAPR, T3CCR, T3CNTR, T3CAPR};
 use lc3_traits::error::Error::SystemStackOverflow;
 use lc3_traits::control::ProcessorMode;
 
+// Calls the given macro with the list of the simulator's built-in device
+// registers.
+macro_rules! built_in_devices {
+    ($m:ident) => {
+        $m!(
+            KBSR, KBDR,
+            DSR, DDR,
+            BSP, PSR, MCR,
+            G0CR, G0DR, G1CR, G1DR, G2CR, G2DR, G3CR, G3DR, G4CR, G4DR, G5CR, G5DR, G6CR, G6DR, G7CR, G7DR,
+            A0CR, A0DR, A1CR, A1DR, A2CR, A2DR, A3CR, A3DR, A4CR, A4DR, A5CR, A5DR,
+            P0CR, P0DR, P1CR, P1DR,
+            CLKR,
+            T0CR, T0DR, T1CR, T1DR,
+            T0CCR, T0CNTR, T0CAPR, T1CCR, T1CNTR, T1CAPR,
+            VXR, VYR, VPR, VFR,
+            DKSR, DKCR, DKAR, DKBR, DKNR,
+            USR, UDR, UCNR,
+            RNGSR, RNGDR,
+            WDCR, WDTR, WDKR,
+            MPUNR, MPUBR, MPUER, MPUAR,
+            #[cfg(feature = "gpio_pins_16")] G8CR,
+            #[cfg(feature = "gpio_pins_16")] G8DR,
+            #[cfg(feature = "gpio_pins_16")] G9CR,
+            #[cfg(feature = "gpio_pins_16")] G9DR,
+            #[cfg(feature = "gpio_pins_16")] G10CR,
+            #[cfg(feature = "gpio_pins_16")] G10DR,
+            #[cfg(feature = "gpio_pins_16")] G11CR,
+            #[cfg(feature = "gpio_pins_16")] G11DR,
+            #[cfg(feature = "gpio_pins_16")] G12CR,
+            #[cfg(feature = "gpio_pins_16")] G12DR,
+            #[cfg(feature = "gpio_pins_16")] G13CR,
+            #[cfg(feature = "gpio_pins_16")] G13DR,
+            #[cfg(feature = "gpio_pins_16")] G14CR,
+            #[cfg(feature = "gpio_pins_16")] G14DR,
+            #[cfg(feature = "gpio_pins_16")] G15CR,
+            #[cfg(feature = "gpio_pins_16")] G15DR,
+            #[cfg(feature = "adc_pins_8")] A6CR,
+            #[cfg(feature = "adc_pins_8")] A6DR,
+            #[cfg(feature = "adc_pins_8")] A7CR,
+            #[cfg(feature = "adc_pins_8")] A7DR,
+            #[cfg(feature = "pwm_pins_4")] P2CR,
+            #[cfg(feature = "pwm_pins_4")] P2DR,
+            #[cfg(feature = "pwm_pins_4")] P3CR,
+            #[cfg(feature = "pwm_pins_4")] P3DR,
+            #[cfg(feature = "timers_4")] T2CR,
+            #[cfg(feature = "timers_4")] T2DR,
+            #[cfg(feature = "timers_4")] T3CR,
+            #[cfg(feature = "timers_4")] T3DR,
+            #[cfg(feature = "timers_4")] T2CCR,
+            #[cfg(feature = "timers_4")] T2CNTR,
+            #[cfg(feature = "timers_4")] T2CAPR,
+            #[cfg(feature = "timers_4")] T3CCR,
+            #[cfg(feature = "timers_4")] T3CNTR,
+            #[cfg(feature = "timers_4")] T3CAPR,
+        )
+    };
+}
+
+macro_rules! addrs {
+    ($($(#[$attr:meta])* $dev:ty),* $(,)?) => { &[$($(#[$attr])* <$dev as MemMapped>::ADDR),*] };
+}
+
+/// Addresses that belong to the simulator's built-in devices (these can't be
+/// used by [registered devices](crate::mmio)).
+pub const BUILT_IN_DEVICE_ADDRS: &[Addr] = built_in_devices!(addrs);
+
 impl<'a, M: Memory, P: Peripherals<'a>> InstructionInterpreter for Interpreter<'a, M, P> {
     const ID: Identifier = Identifier::new_from_str_that_crashes_on_invalid_inputs("Base");
     const VER: Version = version_from_crate!();
@@ -1074,23 +1570,40 @@ impl<'a, M: Memory, P: Peripherals<'a>> InstructionInterpreter for Interpreter<'
         // Increment PC (state 18):
         let mut current_pc = self.get_pc();
         self.set_pc(current_pc.wrapping_add(1)); // TODO: ???
+        self.shadow.set_pc(current_pc);
+        self.protection_fault.set(None);
 
         if self.check_interrupts() {
             return self.get_machine_state();
         };
 
-        match self.get_word(current_pc).and_then(|w| match w.try_into() {
-            Ok(insn) => self.instruction_step_inner(insn),
-            Err(_) => {
-                self.handle_exception(ILLEGAL_OPCODE_EXCEPTION_VECTOR);
-                Ok(())
+        match self.fetch(current_pc).and_then(|insn| {
+            if let Some(timing) = &self.timing {
+                timing.opcode(match insn { Ok(insn) => insn.into(), Err(w) => w });
+            }
+
+            match insn {
+                Ok(insn) => self.instruction_step_inner(insn),
+                Err(w) => self.illegal_instruction_step(w),
             }
         }) {
             Ok(()) => {}
             // Access control violation: triggered when getting the current instruction or when executing it
-            Err(Acv) => self.handle_exception(ACCESS_CONTROL_VIOLATION_EXCEPTION_VECTOR),
+            // (the protection table can make it a more specific exception)
+            Err(Acv) => {
+                let vec = self.protection_fault.take();
+                self.handle_exception(vec.unwrap_or(ACCESS_CONTROL_VIOLATION_EXCEPTION_VECTOR))
+            }
         }
 
+        // Let the clock know (for peripherals that keep virtual time); this
+        // is in cycles if the timing model says so:
+        let elapsed = self.timing.as_ref().and_then(Timing::unclocked).unwrap_or(1);
+        Clock::instructions_executed(self.get_peripherals(), elapsed);
+
+        self.update_timer_captures();
+        self.check_watchdog();
+
         self.get_machine_state()
     }
 
@@ -1104,18 +1617,18 @@ impl<'a, M: Memory, P: Peripherals<'a>> InstructionInterpreter for Interpreter<'
 
     // Checked access:
     fn set_word(&mut self, addr: Addr, word: Word) -> WriteAttempt {
-        if self.is_acv(addr) {
+        if self.is_acv(addr, Access::Write) {
             Err(Acv)
         } else {
-            Ok(self.set_word_unchecked(addr, word))
+            Ok(self.write_unprotected(addr, word))
         }
     }
 
     fn get_word(&self, addr: Addr) -> ReadAttempt {
-        if self.is_acv(addr) {
+        if self.is_acv(addr, Access::Read) {
             Err(Acv)
         } else {
-            Ok(self.get_word_unchecked(addr))
+            Ok(self.read_unprotected(addr))
         }
     }
 
@@ -1124,24 +1637,15 @@ impl<'a, M: Memory, P: Peripherals<'a>> InstructionInterpreter for Interpreter<'
     fn set_word_unchecked(&mut self, addr: Addr, word: Word) {
         if addr >= MEM_MAPPED_START_ADDR {
             macro_rules! devices {
-                ($($dev:ty),*) => {
+                ($($(#[$attr:meta])* $dev:ty),* $(,)?) => {
                     match addr {
-                        $(<$dev as MemMapped>::ADDR => self.set_device_reg::<$dev>(word).unwrap(),)*
-                        _ => (), // unimplemented!() // TODO: make a sane handler?
+                        $($(#[$attr])* <$dev as MemMapped>::ADDR => self.set_device_reg::<$dev>(word).unwrap(),)*
+                        _ => { let _ = self.mmio.write(addr, word); }
                     }
                 };
             }
 
-            devices!(
-                KBSR, KBDR,
-                DSR, DDR,
-                BSP, PSR, MCR,
-                G0CR, G0DR, G1CR, G1DR, G2CR, G2DR, G3CR, G3DR, G4CR, G4DR, G5CR, G5DR, G6CR, G6DR, G7CR, G7DR,
-                A0CR, A0DR, A1CR, A1DR, A2CR, A2DR, A3CR, A3DR, A4CR, A4DR, A5CR, A5DR,
-                P0CR, P0DR, P1CR, P1DR,
-                CLKR,
-                T0CR, T0DR, T1CR, T1DR
-            )
+            built_in_devices!(devices)
         } else {
             self.set_word_force_memory_backed(addr, word)
         }
@@ -1152,31 +1656,24 @@ impl<'a, M: Memory, P: Peripherals<'a>> InstructionInterpreter for Interpreter<'
         if addr >= MEM_MAPPED_START_ADDR {
             // TODO: mem mapped peripherals!
             macro_rules! devices {
-                ($($dev:ty),*) => {
+                ($($(#[$attr:meta])* $dev:ty),* $(,)?) => {
                     match addr {
-                        $(<$dev as MemMapped>::ADDR => *self.get_device_reg::<$dev>().unwrap(),)*
+                        $($(#[$attr])* <$dev as MemMapped>::ADDR => *self.get_device_reg::<$dev>().unwrap(),)*
                         // $(devices!( $($special_access)? $dev ))*
-                        _ => 0, // unimplemented!() // TODO: make a sane handler?
+                        _ => self.mmio.read(addr).unwrap_or(0),
                     }
                 };
             }
 
-            devices!(
-                KBSR, KBDR,
-                DSR, DDR,
-                BSP, PSR, MCR,
-                G0CR, G0DR, G1CR, G1DR, G2CR, G2DR, G3CR, G3DR, G4CR, G4DR, G5CR, G5DR, G6CR, G6DR, G7CR, G7DR,
-                A0CR, A0DR, A1CR, A1DR, A2CR, A2DR, A3CR, A3DR, A4CR, A4DR, A5CR, A5DR,
-                P0CR, P0DR, P1CR, P1DR,
-                CLKR,
-                T0CR, T0DR, T1CR, T1DR
-            )
+            built_in_devices!(devices)
         } else {
             self.get_word_force_memory_backed(addr)
         }
     }
 
     fn set_word_force_memory_backed(&mut self, addr: Addr, word: Word) {
+        self.decode_cache.invalidate(addr);
+        self.shadow.write(addr);
         self.memory.write_word(addr, word)
     }
 
@@ -1188,31 +1685,91 @@ impl<'a, M: Memory, P: Peripherals<'a>> InstructionInterpreter for Interpreter<'
         self.state
     }
 
-    fn reset(&mut self) {
-        // TODO: Reset Vector
-        // On start!
-        // PC = 0x200
-        // All regs are 0
-        // cc = z
-        // pri = 7
-        // MCR = 0;
-        self.pc = lc3_isa::OS_START_ADDR;
-
-        // Reset memory _before_ setting the PSR and MCR so we don't wipe out
-        // their values.
-        self.memory.reset();
-
-        self.get_special_reg::<PSR>().set_priority(self, 7);
-        self.get_special_reg::<MCR>().run(self);
-        self.set_cc(0);
+    fn reset_with(&mut self, kind: ResetKind, boot: BootConfig) {
+        self.boot = boot;
+
+        // Reset memory _before_ setting the PSR, BSP, and MCR so we don't wipe
+        // out their values.
+        if let ResetKind::Cold = kind {
+            self.memory.reset();
+            self.decode_cache.clear();
+        }
 
+        self.shadow.reset(matches!(kind, ResetKind::Cold));
         self.regs = [0; Reg::NUM_REGS];
+        self.set_special_reg::<PSR>(boot.psr);
+        self.set_special_reg::<MCR>(MCR::DEFAULT);
+
+        // R6 gets the stack pointer for the mode we're starting in:
+        let (sp, backup_sp) = if boot.in_user_mode() {
+            (boot.usp, boot.ssp)
+        } else {
+            (boot.ssp, boot.usp)
+        };
+        self[R6] = sp;
+        self.set_special_reg::<BSP>(backup_sp);
+
+        self.pc = match boot.entry {
+            BootEntry::Addr(addr) => addr,
+            BootEntry::ResetVector => match self.get_word_force_memory_backed(RESET_VECTOR_ADDR) {
+                0 => OS_START_ADDR,
+                addr => addr,
+            },
+        };
 
         self.reset_peripherals();
+        self.mmio.reset_devices();
         self.state = MachineState::Running;
 
         self.error.set(None);
         self.call_stack = CallStack::new();
+        self.capture_levels = TimerArr([None; TimerId::NUM_TIMERS]);
+
+        if let Some(timing) = &mut self.timing {
+            timing.reset();
+        }
+    }
+
+    fn get_boot_config(&self) -> BootConfig {
+        self.boot
+    }
+
+    fn set_break_instructions_enabled(&mut self, enabled: bool) {
+        self.break_instructions = enabled;
+    }
+
+    fn get_break_instructions_enabled(&self) -> bool {
+        self.break_instructions
+    }
+
+    #[cfg(feature = "extensions")]
+    fn set_extension<X: InstructionExtension<Self> + Send + Sync>(&mut self, ext: &'static X) {
+        self.extension = Some(ExtensionRef::new(ext));
+    }
+
+    #[cfg(feature = "extensions")]
+    fn remove_extension(&mut self) {
+        self.extension = None;
+    }
+
+    fn set_uninitialized_read_detection(&mut self, enabled: bool) {
+        self.uninitialized_read_detection = enabled;
+    }
+
+    fn get_uninitialized_read_detection(&self) -> bool {
+        self.uninitialized_read_detection
+    }
+
+    fn set_mpu_region(&mut self, idx: Idx, region: Option<MpuRegion>) -> Result<(), MpuError> {
+        self.mpu.set(idx, region)
+    }
+
+    fn get_mpu_region(&self, idx: Idx) -> Result<Option<MpuRegion>, MpuError> {
+        self.mpu.get(idx)
+    }
+
+    fn get_cycle_count(&self) -> Option<u64> {
+        self.timing.as_ref().map(Timing::cycles)
     }
 
     fn halt(&mut self) {
@@ -1240,9 +1797,15 @@ impl<'a, M: Memory, P: Peripherals<'a>> InstructionInterpreter for Interpreter<'
     }
 
     fn commit_page(&mut self, page_idx: PageIndex, page: &[Word; PAGE_SIZE_IN_WORDS as usize]) {
+        self.decode_cache.invalidate_page(page_idx);
+        self.shadow.load_page(page_idx);
         self.memory.commit_page(page_idx, page)
     }
 
+    fn read_committed_word(&self, addr: Addr) -> Word {
+        self.memory.read_committed_word(addr)
+    }
+
     fn get_program_metadata(&self) -> ProgramMetadata {
         self.memory.get_program_metadata()
     }
@@ -1251,6 +1814,10 @@ impl<'a, M: Memory, P: Peripherals<'a>> InstructionInterpreter for Interpreter<'
         self.memory.set_program_metadata(metadata)
     }
 
+    fn page_is_loaded(&self, page_idx: PageIndex) -> bool {
+        self.shadow.page_is_loaded(page_idx)
+    }
+
     fn type_id() -> TypeId {
         TypeId::of::<Interpreter<'static, lc3_traits::memory::MemoryStub, lc3_traits::peripherals::stubs::PeripheralsStub<'static>>>()
     }
@@ -56,8 +56,15 @@ extern crate static_assertions as sa;
 #[allow(unused_extern_crates)]
 extern crate core; // makes rls actually look into the standard library (hack)
 
+#[cfg(feature = "extensions")]
+pub mod ext;
 pub mod interp;
+mod decode;
+mod mpu;
+mod shadow;
 pub mod mem_mapped;
+pub mod mmio;
 pub mod sim;
+pub mod timing;
 
 pub use mem_mapped::*;
@@ -15,9 +15,43 @@ pub const DDR_ADDR: Addr = 0xFE06;
 pub const DISPLAY_INT_VEC: u8 = 0x81; // TODO: What is this actually?
 pub const DISPLAY_INT_PRIORITY: u8 = 4;
 
+// The GPIO, ADC, PWM, and timer registers are laid out according to how many
+// of each there are (see the peripheral count features in `lc3-traits`). Each
+// group of registers starts where it usually does (`MEM_MAPPED_START_ADDR +
+// <PERIPHERAL>_OFFSET`) unless the group before it has grown into that space,
+// in which case it starts at the next multiple of 16 after the previous group
+// instead. With the default counts this is the usual layout (noted next to
+// each address below).
+//
+// The offsets themselves (which are also the TRAP vector groups and the
+// interrupt vector groups) don't move.
+const fn group_base(offset: u8, prev_end: Addr) -> Addr {
+    let usual = MEM_MAPPED_START_ADDR + (offset as Addr);
+
+    if prev_end <= usual { usual } else { (prev_end + 0xF) & !0xF }
+}
+
+// The configured counts; these have to match the ones `lc3-traits` was built
+// with (i.e. the features on this crate have to match the ones on
+// `lc3-traits`).
+const NUM_GPIO_PINS: Addr = if cfg!(feature = "gpio_pins_16") { 16 } else { 8 };
+const NUM_ADC_PINS: Addr = if cfg!(feature = "adc_pins_8") { 8 } else { 6 };
+const NUM_PWM_PINS: Addr = if cfg!(feature = "pwm_pins_4") { 4 } else { 2 };
+const NUM_TIMERS: Addr = if cfg!(feature = "timers_4") { 4 } else { 2 };
+
+sa::const_assert_eq!(NUM_GPIO_PINS as usize, GpioPin::NUM_PINS);
+sa::const_assert_eq!(NUM_ADC_PINS as usize, lc3_traits::peripherals::adc::AdcPin::NUM_PINS);
+sa::const_assert_eq!(NUM_PWM_PINS as usize, lc3_traits::peripherals::pwm::PwmPin::NUM_PINS);
+sa::const_assert_eq!(NUM_TIMERS as usize, lc3_traits::peripherals::timers::TimerId::NUM_TIMERS);
+
+// Each interrupt vector group only has room for 16 vectors.
+sa::const_assert!(NUM_GPIO_PINS <= 16);
+sa::const_assert!(NUM_TIMERS <= 16);
+
 pub const GPIO_OFFSET: u8 = 0x30;
-const GPIO_MEM_MAPPED_BASE: Addr = MEM_MAPPED_START_ADDR + (GPIO_OFFSET as Addr);
+const GPIO_MEM_MAPPED_BASE: Addr = group_base(GPIO_OFFSET, 0);
 const GPIO_PIN_ADDRS: Addr = 2;
+const GPIO_MEM_MAPPED_END: Addr = GPIO_MEM_MAPPED_BASE + GPIO_PIN_ADDRS * NUM_GPIO_PINS;
 
 pub const G0CR_ADDR: Addr = gpio_cr_addr(0); // xFE30
 pub const G0DR_ADDR: Addr = gpio_dr_addr(0); // xFE31
@@ -35,75 +69,265 @@ pub const G6CR_ADDR: Addr = gpio_cr_addr(6); // xFE3C
 pub const G6DR_ADDR: Addr = gpio_dr_addr(6); // xFE3D
 pub const G7CR_ADDR: Addr = gpio_cr_addr(7); // xFE3E
 pub const G7DR_ADDR: Addr = gpio_dr_addr(7); // xFE3F
-
-const fn gpio_cr_addr(i: u16) -> Addr {
+#[cfg(feature = "gpio_pins_16")] pub const G8CR_ADDR: Addr = gpio_cr_addr(8);
+#[cfg(feature = "gpio_pins_16")] pub const G8DR_ADDR: Addr = gpio_dr_addr(8);
+#[cfg(feature = "gpio_pins_16")] pub const G9CR_ADDR: Addr = gpio_cr_addr(9);
+#[cfg(feature = "gpio_pins_16")] pub const G9DR_ADDR: Addr = gpio_dr_addr(9);
+#[cfg(feature = "gpio_pins_16")] pub const G10CR_ADDR: Addr = gpio_cr_addr(10);
+#[cfg(feature = "gpio_pins_16")] pub const G10DR_ADDR: Addr = gpio_dr_addr(10);
+#[cfg(feature = "gpio_pins_16")] pub const G11CR_ADDR: Addr = gpio_cr_addr(11);
+#[cfg(feature = "gpio_pins_16")] pub const G11DR_ADDR: Addr = gpio_dr_addr(11);
+#[cfg(feature = "gpio_pins_16")] pub const G12CR_ADDR: Addr = gpio_cr_addr(12);
+#[cfg(feature = "gpio_pins_16")] pub const G12DR_ADDR: Addr = gpio_dr_addr(12);
+#[cfg(feature = "gpio_pins_16")] pub const G13CR_ADDR: Addr = gpio_cr_addr(13);
+#[cfg(feature = "gpio_pins_16")] pub const G13DR_ADDR: Addr = gpio_dr_addr(13);
+#[cfg(feature = "gpio_pins_16")] pub const G14CR_ADDR: Addr = gpio_cr_addr(14);
+#[cfg(feature = "gpio_pins_16")] pub const G14DR_ADDR: Addr = gpio_dr_addr(14);
+#[cfg(feature = "gpio_pins_16")] pub const G15CR_ADDR: Addr = gpio_cr_addr(15);
+#[cfg(feature = "gpio_pins_16")] pub const G15DR_ADDR: Addr = gpio_dr_addr(15);
+
+pub const fn gpio_cr_addr(i: u16) -> Addr {
     GPIO_MEM_MAPPED_BASE + GPIO_PIN_ADDRS * i
 }
-const fn gpio_dr_addr(i: u16) -> Addr {
+pub const fn gpio_dr_addr(i: u16) -> Addr {
     gpio_cr_addr(i) + 1
 }
 
-pub const GPIODR_ADDR: Addr = GPIO_MEM_MAPPED_BASE + GPIO_PIN_ADDRS * 8 + 0;
+pub const GPIODR_ADDR: Addr = GPIO_MEM_MAPPED_END;
 
 pub const GPIO_BASE_INT_VEC: Addr = INTERRUPT_SERVICE_ROUTINES_START_ADDR + (GPIO_OFFSET as Addr); // x1B0
-pub const G0_INT_VEC: u8 = 128 + GPIO_OFFSET + 0; // xB0
-pub const G1_INT_VEC: u8 = 128 + GPIO_OFFSET + 1; // xB1
-pub const G2_INT_VEC: u8 = 128 + GPIO_OFFSET + 2; // xB2
-pub const G3_INT_VEC: u8 = 128 + GPIO_OFFSET + 3; // xB3
-pub const G4_INT_VEC: u8 = 128 + GPIO_OFFSET + 4; // xB4
-pub const G5_INT_VEC: u8 = 128 + GPIO_OFFSET + 5; // xB5
-pub const G6_INT_VEC: u8 = 128 + GPIO_OFFSET + 6; // xB6
-pub const G7_INT_VEC: u8 = 128 + GPIO_OFFSET + 7; // xB7
+pub const G0_INT_VEC: u8 = gpio_int_vec(0); // xB0
+pub const G1_INT_VEC: u8 = gpio_int_vec(1); // xB1
+pub const G2_INT_VEC: u8 = gpio_int_vec(2); // xB2
+pub const G3_INT_VEC: u8 = gpio_int_vec(3); // xB3
+pub const G4_INT_VEC: u8 = gpio_int_vec(4); // xB4
+pub const G5_INT_VEC: u8 = gpio_int_vec(5); // xB5
+pub const G6_INT_VEC: u8 = gpio_int_vec(6); // xB6
+pub const G7_INT_VEC: u8 = gpio_int_vec(7); // xB7
+#[cfg(feature = "gpio_pins_16")] pub const G8_INT_VEC: u8 = gpio_int_vec(8);
+#[cfg(feature = "gpio_pins_16")] pub const G9_INT_VEC: u8 = gpio_int_vec(9);
+#[cfg(feature = "gpio_pins_16")] pub const G10_INT_VEC: u8 = gpio_int_vec(10);
+#[cfg(feature = "gpio_pins_16")] pub const G11_INT_VEC: u8 = gpio_int_vec(11);
+#[cfg(feature = "gpio_pins_16")] pub const G12_INT_VEC: u8 = gpio_int_vec(12);
+#[cfg(feature = "gpio_pins_16")] pub const G13_INT_VEC: u8 = gpio_int_vec(13);
+#[cfg(feature = "gpio_pins_16")] pub const G14_INT_VEC: u8 = gpio_int_vec(14);
+#[cfg(feature = "gpio_pins_16")] pub const G15_INT_VEC: u8 = gpio_int_vec(15);
 pub const GPIO_INT_PRIORITY: u8 = 4;
 
+pub const fn gpio_int_vec(i: u8) -> u8 {
+    128 + GPIO_OFFSET + i
+}
+
 pub const ADC_OFFSET: u8 = 0x40;
-const ADC_MEM_MAPPED_BASE: Addr = MEM_MAPPED_START_ADDR + (ADC_OFFSET as Addr);
+const ADC_MEM_MAPPED_BASE: Addr = group_base(ADC_OFFSET, GPIO_MEM_MAPPED_END);
 const ADC_PIN_ADDRS: Addr = 2;
-
-pub const A0CR_ADDR: Addr = ADC_MEM_MAPPED_BASE + ADC_PIN_ADDRS * 0 + 0; // xFE40
-pub const A0DR_ADDR: Addr = ADC_MEM_MAPPED_BASE + ADC_PIN_ADDRS * 0 + 1; // xFE41
-pub const A1CR_ADDR: Addr = ADC_MEM_MAPPED_BASE + ADC_PIN_ADDRS * 1 + 0; // xFE42
-pub const A1DR_ADDR: Addr = ADC_MEM_MAPPED_BASE + ADC_PIN_ADDRS * 1 + 1; // xFE43
-pub const A2CR_ADDR: Addr = ADC_MEM_MAPPED_BASE + ADC_PIN_ADDRS * 2 + 0; // xFE44
-pub const A2DR_ADDR: Addr = ADC_MEM_MAPPED_BASE + ADC_PIN_ADDRS * 2 + 1; // xFE45
-pub const A3CR_ADDR: Addr = ADC_MEM_MAPPED_BASE + ADC_PIN_ADDRS * 3 + 0; // xFE46
-pub const A3DR_ADDR: Addr = ADC_MEM_MAPPED_BASE + ADC_PIN_ADDRS * 3 + 1; // xFE47
-pub const A4CR_ADDR: Addr = ADC_MEM_MAPPED_BASE + ADC_PIN_ADDRS * 4 + 0; // xFE48
-pub const A4DR_ADDR: Addr = ADC_MEM_MAPPED_BASE + ADC_PIN_ADDRS * 4 + 1; // xFE49
-pub const A5CR_ADDR: Addr = ADC_MEM_MAPPED_BASE + ADC_PIN_ADDRS * 5 + 0; // xFE4A
-pub const A5DR_ADDR: Addr = ADC_MEM_MAPPED_BASE + ADC_PIN_ADDRS * 5 + 1; // xFE4B
+const ADC_MEM_MAPPED_END: Addr = ADC_MEM_MAPPED_BASE + ADC_PIN_ADDRS * NUM_ADC_PINS;
+
+pub const A0CR_ADDR: Addr = adc_cr_addr(0); // xFE40
+pub const A0DR_ADDR: Addr = adc_dr_addr(0); // xFE41
+pub const A1CR_ADDR: Addr = adc_cr_addr(1); // xFE42
+pub const A1DR_ADDR: Addr = adc_dr_addr(1); // xFE43
+pub const A2CR_ADDR: Addr = adc_cr_addr(2); // xFE44
+pub const A2DR_ADDR: Addr = adc_dr_addr(2); // xFE45
+pub const A3CR_ADDR: Addr = adc_cr_addr(3); // xFE46
+pub const A3DR_ADDR: Addr = adc_dr_addr(3); // xFE47
+pub const A4CR_ADDR: Addr = adc_cr_addr(4); // xFE48
+pub const A4DR_ADDR: Addr = adc_dr_addr(4); // xFE49
+pub const A5CR_ADDR: Addr = adc_cr_addr(5); // xFE4A
+pub const A5DR_ADDR: Addr = adc_dr_addr(5); // xFE4B
+#[cfg(feature = "adc_pins_8")] pub const A6CR_ADDR: Addr = adc_cr_addr(6);
+#[cfg(feature = "adc_pins_8")] pub const A6DR_ADDR: Addr = adc_dr_addr(6);
+#[cfg(feature = "adc_pins_8")] pub const A7CR_ADDR: Addr = adc_cr_addr(7);
+#[cfg(feature = "adc_pins_8")] pub const A7DR_ADDR: Addr = adc_dr_addr(7);
+
+pub const fn adc_cr_addr(i: u16) -> Addr {
+    ADC_MEM_MAPPED_BASE + ADC_PIN_ADDRS * i
+}
+pub const fn adc_dr_addr(i: u16) -> Addr {
+    adc_cr_addr(i) + 1
+}
 
 pub const PWM_OFFSET: u8 = 0x50;
-const PWM_MEM_MAPPED_BASE: Addr = MEM_MAPPED_START_ADDR + (PWM_OFFSET as Addr);
+const PWM_MEM_MAPPED_BASE: Addr = group_base(PWM_OFFSET, ADC_MEM_MAPPED_END);
 const PWM_PIN_ADDRS: Addr = 2;
-
-pub const P0CR_ADDR: Addr = PWM_MEM_MAPPED_BASE + PWM_PIN_ADDRS * 0 + 0; // xFE50
-pub const P0DR_ADDR: Addr = PWM_MEM_MAPPED_BASE + PWM_PIN_ADDRS * 0 + 1; // xFE51
-pub const P1CR_ADDR: Addr = PWM_MEM_MAPPED_BASE + PWM_PIN_ADDRS * 1 + 0; // xFE52
-pub const P1DR_ADDR: Addr = PWM_MEM_MAPPED_BASE + PWM_PIN_ADDRS * 1 + 1; // xFE53
+const PWM_MEM_MAPPED_END: Addr = PWM_MEM_MAPPED_BASE + PWM_PIN_ADDRS * NUM_PWM_PINS;
+
+pub const P0CR_ADDR: Addr = pwm_cr_addr(0); // xFE50
+pub const P0DR_ADDR: Addr = pwm_dr_addr(0); // xFE51
+pub const P1CR_ADDR: Addr = pwm_cr_addr(1); // xFE52
+pub const P1DR_ADDR: Addr = pwm_dr_addr(1); // xFE53
+#[cfg(feature = "pwm_pins_4")] pub const P2CR_ADDR: Addr = pwm_cr_addr(2);
+#[cfg(feature = "pwm_pins_4")] pub const P2DR_ADDR: Addr = pwm_dr_addr(2);
+#[cfg(feature = "pwm_pins_4")] pub const P3CR_ADDR: Addr = pwm_cr_addr(3);
+#[cfg(feature = "pwm_pins_4")] pub const P3DR_ADDR: Addr = pwm_dr_addr(3);
+
+pub const fn pwm_cr_addr(i: u16) -> Addr {
+    PWM_MEM_MAPPED_BASE + PWM_PIN_ADDRS * i
+}
+pub const fn pwm_dr_addr(i: u16) -> Addr {
+    pwm_cr_addr(i) + 1
+}
 
 pub const TIMER_OFFSET: u8 = 0x60;
-const TIMER_MEM_MAPPED_BASE: Addr = MEM_MAPPED_START_ADDR + (TIMER_OFFSET as Addr);
+const TIMER_MEM_MAPPED_BASE: Addr = group_base(TIMER_OFFSET, PWM_MEM_MAPPED_END);
 const TIMER_PIN_ADDRS: Addr = 2;
 
-pub const T0CR_ADDR: Addr = TIMER_MEM_MAPPED_BASE + TIMER_PIN_ADDRS * 0 + 0; // xFE60
-pub const T0DR_ADDR: Addr = TIMER_MEM_MAPPED_BASE + TIMER_PIN_ADDRS * 0 + 1; // xFE61
-pub const T1CR_ADDR: Addr = TIMER_MEM_MAPPED_BASE + TIMER_PIN_ADDRS * 1 + 0; // xFE62
-pub const T1DR_ADDR: Addr = TIMER_MEM_MAPPED_BASE + TIMER_PIN_ADDRS * 1 + 1; // xFE63
+pub const T0CR_ADDR: Addr = timer_cr_addr(0); // xFE60
+pub const T0DR_ADDR: Addr = timer_dr_addr(0); // xFE61
+pub const T1CR_ADDR: Addr = timer_cr_addr(1); // xFE62
+pub const T1DR_ADDR: Addr = timer_dr_addr(1); // xFE63
+#[cfg(feature = "timers_4")] pub const T2CR_ADDR: Addr = timer_cr_addr(2);
+#[cfg(feature = "timers_4")] pub const T2DR_ADDR: Addr = timer_dr_addr(2);
+#[cfg(feature = "timers_4")] pub const T3CR_ADDR: Addr = timer_cr_addr(3);
+#[cfg(feature = "timers_4")] pub const T3DR_ADDR: Addr = timer_dr_addr(3);
+
+pub const fn timer_cr_addr(i: u16) -> Addr {
+    TIMER_MEM_MAPPED_BASE + TIMER_PIN_ADDRS * i
+}
+pub const fn timer_dr_addr(i: u16) -> Addr {
+    timer_cr_addr(i) + 1
+}
+
+// The capture and count registers come after the control and period registers
+// of all the timers.
+const TIMER_CAPTURE_MEM_MAPPED_BASE: Addr = TIMER_MEM_MAPPED_BASE + TIMER_PIN_ADDRS * NUM_TIMERS;
+const TIMER_CAPTURE_ADDRS: Addr = 3;
+const TIMER_MEM_MAPPED_END: Addr = TIMER_CAPTURE_MEM_MAPPED_BASE + TIMER_CAPTURE_ADDRS * NUM_TIMERS;
+
+pub const T0CCR_ADDR: Addr = timer_ccr_addr(0); // xFE64
+pub const T0CNTR_ADDR: Addr = timer_cntr_addr(0); // xFE65
+pub const T0CAPR_ADDR: Addr = timer_capr_addr(0); // xFE66
+pub const T1CCR_ADDR: Addr = timer_ccr_addr(1); // xFE67
+pub const T1CNTR_ADDR: Addr = timer_cntr_addr(1); // xFE68
+pub const T1CAPR_ADDR: Addr = timer_capr_addr(1); // xFE69
+#[cfg(feature = "timers_4")] pub const T2CCR_ADDR: Addr = timer_ccr_addr(2);
+#[cfg(feature = "timers_4")] pub const T2CNTR_ADDR: Addr = timer_cntr_addr(2);
+#[cfg(feature = "timers_4")] pub const T2CAPR_ADDR: Addr = timer_capr_addr(2);
+#[cfg(feature = "timers_4")] pub const T3CCR_ADDR: Addr = timer_ccr_addr(3);
+#[cfg(feature = "timers_4")] pub const T3CNTR_ADDR: Addr = timer_cntr_addr(3);
+#[cfg(feature = "timers_4")] pub const T3CAPR_ADDR: Addr = timer_capr_addr(3);
+
+pub const fn timer_ccr_addr(i: u16) -> Addr {
+    TIMER_CAPTURE_MEM_MAPPED_BASE + TIMER_CAPTURE_ADDRS * i
+}
+pub const fn timer_cntr_addr(i: u16) -> Addr {
+    timer_ccr_addr(i) + 1
+}
+pub const fn timer_capr_addr(i: u16) -> Addr {
+    timer_ccr_addr(i) + 2
+}
 
 pub const TIMER_BASE_INT_VEC: Addr = INTERRUPT_SERVICE_ROUTINES_START_ADDR + (TIMER_OFFSET as Addr); // x1E0;       // TODO: do this in a better way
-pub const T0_INT_VEC: u8 = 128 + TIMER_OFFSET + 0; // xE0
-pub const T1_INT_VEC: u8 = 128 + TIMER_OFFSET + 1; // xE1;
+pub const T0_INT_VEC: u8 = timer_int_vec(0); // xE0
+pub const T1_INT_VEC: u8 = timer_int_vec(1); // xE1;
+#[cfg(feature = "timers_4")] pub const T2_INT_VEC: u8 = timer_int_vec(2);
+#[cfg(feature = "timers_4")] pub const T3_INT_VEC: u8 = timer_int_vec(3);
 pub const TIMER_INT_PRIORITY: u8 = 4;
 
+pub const fn timer_int_vec(i: u8) -> u8 {
+    128 + TIMER_OFFSET + i
+}
+
 // (For one off peripherals like the clock and the display, etc.)
 pub const MISC_OFFSET: u8 = 0x70;
-const MISC_MEM_MAPPED_BASE: Addr = MEM_MAPPED_START_ADDR + (MISC_OFFSET as Addr);
+const MISC_MEM_MAPPED_BASE: Addr = group_base(MISC_OFFSET, TIMER_MEM_MAPPED_END);
+const MISC_MEM_MAPPED_END: Addr = MISC_MEM_MAPPED_BASE + 0x10;
 
 pub const CLKR_ADDR: Addr = MISC_MEM_MAPPED_BASE + 0; // xFE70
 
+pub const DISPLAY_OFFSET: u8 = 0x80;
+const DISPLAY_MEM_MAPPED_BASE: Addr = group_base(DISPLAY_OFFSET, MISC_MEM_MAPPED_END);
+const DISPLAY_MEM_MAPPED_END: Addr = DISPLAY_MEM_MAPPED_BASE + 0x10;
+
+pub const VXR_ADDR: Addr = DISPLAY_MEM_MAPPED_BASE + 0; // xFE80
+pub const VYR_ADDR: Addr = DISPLAY_MEM_MAPPED_BASE + 1; // xFE81
+pub const VPR_ADDR: Addr = DISPLAY_MEM_MAPPED_BASE + 2; // xFE82
+pub const VFR_ADDR: Addr = DISPLAY_MEM_MAPPED_BASE + 3; // xFE83
+
+pub const DISK_OFFSET: u8 = 0x90;
+const DISK_MEM_MAPPED_BASE: Addr = group_base(DISK_OFFSET, DISPLAY_MEM_MAPPED_END);
+const DISK_MEM_MAPPED_END: Addr = DISK_MEM_MAPPED_BASE + 0x10;
+
+pub const DKSR_ADDR: Addr = DISK_MEM_MAPPED_BASE + 0; // xFE90
+pub const DKCR_ADDR: Addr = DISK_MEM_MAPPED_BASE + 1; // xFE91
+pub const DKAR_ADDR: Addr = DISK_MEM_MAPPED_BASE + 2; // xFE92
+pub const DKBR_ADDR: Addr = DISK_MEM_MAPPED_BASE + 3; // xFE93
+pub const DKNR_ADDR: Addr = DISK_MEM_MAPPED_BASE + 4; // xFE94
+
+// `128 + DISK_OFFSET` doesn't fit so the disk goes next to the keyboard and
+// the console display instead.
+pub const DISK_INT_VEC: u8 = 0x82;
+pub const DISK_INT_PRIORITY: u8 = 4;
+
+/// [`DKCR`] command: copy sector [`DKAR`] into memory at [`DKBR`].
+pub const DISK_READ_CMD: Word = 1;
+/// [`DKCR`] command: copy the sector's worth of memory at [`DKBR`] into sector
+/// [`DKAR`].
+pub const DISK_WRITE_CMD: Word = 2;
+
+pub const UART_OFFSET: u8 = 0xA0;
+const UART_MEM_MAPPED_BASE: Addr = group_base(UART_OFFSET, DISK_MEM_MAPPED_END);
+const UART_MEM_MAPPED_END: Addr = UART_MEM_MAPPED_BASE + 0x10;
+
+pub const USR_ADDR: Addr = UART_MEM_MAPPED_BASE + 0; // xFEA0
+pub const UDR_ADDR: Addr = UART_MEM_MAPPED_BASE + 1; // xFEA1
+pub const UCNR_ADDR: Addr = UART_MEM_MAPPED_BASE + 2; // xFEA2
+
+// Like the disk's, the UART's interrupt vector goes next to the keyboard and
+// the console display.
+pub const UART_INT_VEC: u8 = 0x83;
+pub const UART_INT_PRIORITY: u8 = 4;
+
+pub const RNG_OFFSET: u8 = 0xB0;
+const RNG_MEM_MAPPED_BASE: Addr = group_base(RNG_OFFSET, UART_MEM_MAPPED_END);
+const RNG_MEM_MAPPED_END: Addr = RNG_MEM_MAPPED_BASE + 0x10;
+
+pub const RNGSR_ADDR: Addr = RNG_MEM_MAPPED_BASE + 0; // xFEB0
+pub const RNGDR_ADDR: Addr = RNG_MEM_MAPPED_BASE + 1; // xFEB1
+
+pub const WATCHDOG_OFFSET: u8 = 0xC0;
+const WATCHDOG_MEM_MAPPED_BASE: Addr = group_base(WATCHDOG_OFFSET, RNG_MEM_MAPPED_END);
+
+pub const WDCR_ADDR: Addr = WATCHDOG_MEM_MAPPED_BASE + 0; // xFEC0
+pub const WDTR_ADDR: Addr = WATCHDOG_MEM_MAPPED_BASE + 1; // xFEC1
+pub const WDKR_ADDR: Addr = WATCHDOG_MEM_MAPPED_BASE + 2; // xFEC2
+
+pub const MPU_OFFSET: u8 = 0xD0;
+const WATCHDOG_MEM_MAPPED_END: Addr = WATCHDOG_MEM_MAPPED_BASE + 0x10;
+const MPU_MEM_MAPPED_BASE: Addr = group_base(MPU_OFFSET, WATCHDOG_MEM_MAPPED_END);
+const MPU_MEM_MAPPED_END: Addr = MPU_MEM_MAPPED_BASE + 0x10;
+
+pub const MPUNR_ADDR: Addr = MPU_MEM_MAPPED_BASE + 0; // xFED0
+pub const MPUBR_ADDR: Addr = MPU_MEM_MAPPED_BASE + 1; // xFED1
+pub const MPUER_ADDR: Addr = MPU_MEM_MAPPED_BASE + 2; // xFED2
+pub const MPUAR_ADDR: Addr = MPU_MEM_MAPPED_BASE + 3; // xFED3
+
+// The watchdog's warning should get through even if the program is busy
+// handling other interrupts.
+pub const WATCHDOG_INT_VEC: u8 = 0x84;
+pub const WATCHDOG_INT_PRIORITY: u8 = 6;
+
 pub const BSP_ADDR: Addr = 0xFFFA;
 
+// `group_base` shouldn't ever let a group run into the next one but since the
+// groups grow with the peripheral counts, check anyways (for whatever counts
+// this is being built with). The last group has to stay clear of the
+// registers at the top of memory.
+//
+// (`GPIODR_ADDR` sits just past the GPIO group and isn't counted here; with
+// the default counts it's the same address as `A0CR_ADDR`.)
+sa::const_assert!(GPIO_MEM_MAPPED_END <= ADC_MEM_MAPPED_BASE);
+sa::const_assert!(ADC_MEM_MAPPED_END <= PWM_MEM_MAPPED_BASE);
+sa::const_assert!(PWM_MEM_MAPPED_END <= TIMER_MEM_MAPPED_BASE);
+sa::const_assert!(TIMER_MEM_MAPPED_END <= MISC_MEM_MAPPED_BASE);
+sa::const_assert!(MISC_MEM_MAPPED_END <= DISPLAY_MEM_MAPPED_BASE);
+sa::const_assert!(DISPLAY_MEM_MAPPED_END <= DISK_MEM_MAPPED_BASE);
+sa::const_assert!(DISK_MEM_MAPPED_END <= UART_MEM_MAPPED_BASE);
+sa::const_assert!(UART_MEM_MAPPED_END <= RNG_MEM_MAPPED_BASE);
+sa::const_assert!(RNG_MEM_MAPPED_END <= WATCHDOG_MEM_MAPPED_BASE);
+sa::const_assert!(WATCHDOG_MEM_MAPPED_END <= MPU_MEM_MAPPED_BASE);
+sa::const_assert!(MPU_MEM_MAPPED_END <= BSP_ADDR);
+
 use crate::interp::InstructionInterpreterPeripheralAccess;
 use core::ops::Deref;
 use lc3_isa::{Addr, Bits, SignedWord, Word, MCR as MCR_ADDR, PSR as PSR_ADDR, WORD_MAX_VAL};
@@ -554,10 +778,42 @@ impl MemMapped for DDR {
     }
 }
 
+// Fields in the G*CR registers:
+//   [1:0]  -> state (00 Disabled, 01 Output, 10 Input, 11 Interrupt)
+//   [4:2]  -> interrupt mode (see `gpio_interrupt_mode_from_bits`)
+//   [15:8] -> debounce time in milliseconds (0 is off)
+fn gpio_interrupt_mode_from_bits(bits: Word) -> Option<GpioInterruptMode> {
+    use GpioInterruptMode::*;
+
+    Some(match bits {
+        0 => RisingEdge,
+        1 => FallingEdge,
+        2 => BothEdges,
+        3 => LevelHigh,
+        4 => LevelLow,
+        _ => return None,
+    })
+}
+
+fn gpio_interrupt_mode_to_bits(mode: GpioInterruptMode) -> Word {
+    use GpioInterruptMode::*;
+
+    match mode {
+        RisingEdge => 0,
+        FallingEdge => 1,
+        BothEdges => 2,
+        LevelHigh => 3,
+        LevelLow => 4,
+    }
+}
+
 macro_rules! gpio_mem_mapped {
     ($pin:expr, $pin_name:literal, $cr:ident, $dr:ident, $cr_addr:expr, $dr_addr:expr, $int_vec:expr) => {
         #[doc=$pin_name]
         #[doc="GPIO Pin Control Register"] // TODO: format correctly
+        #[doc="\n\nBits 0 and 1 are the pin's state, bits 2 through 4 are its \
+        interrupt mode (rising edge, falling edge, both edges, level high, level \
+        low), and bits 8 through 15 are its debounce time in milliseconds."]
         #[derive(Copy, Clone, Debug, PartialEq)]
         pub struct $cr(Word);
 
@@ -578,6 +834,8 @@ macro_rules! gpio_mem_mapped {
                 <I as Deref>::Target: Peripherals<'a>,
             {
                 let state = Gpio::get_state(interp.get_peripherals(), $pin);
+                let mode = Gpio::get_interrupt_mode(interp.get_peripherals(), $pin);
+                let debounce = Gpio::get_debounce(interp.get_peripherals(), $pin);
 
                 use lc3_traits::peripherals::gpio::GpioState::*;
                 let word: Word = match state {
@@ -587,6 +845,10 @@ macro_rules! gpio_mem_mapped {
                     Interrupt => 3,
                 };
 
+                let word = word
+                    | (gpio_interrupt_mode_to_bits(mode) << 2)
+                    | ((debounce as Word) << 8);
+
                 Ok(Self::with_value(word))
             }
 
@@ -604,7 +866,15 @@ macro_rules! gpio_mem_mapped {
                     _ => unreachable!()
                 };
 
-                match Gpio::set_state(interp.get_peripherals_mut(), $pin, state) {
+                let res = match gpio_interrupt_mode_from_bits(value.u16(2..4)) {
+                    Some(mode) => Gpio::set_interrupt_mode(interp.get_peripherals_mut(), $pin, mode),
+                    None => Err(GpioMiscError),
+                };
+                let res = res
+                    .and(Gpio::set_debounce(interp.get_peripherals_mut(), $pin, value.u8(8..15)))
+                    .and(Gpio::set_state(interp.get_peripherals_mut(), $pin, state));
+
+                match res {
                     Ok(()) => Ok(()),
                     Err(err) => {
                         interp.set_error(Error::from(err));
@@ -700,7 +970,7 @@ macro_rules! gpio_mem_mapped {
     };
 }
 
-use lc3_traits::peripherals::gpio::{Gpio, GpioPin::*, GpioPinArr, GpioPin, GPIO_PINS};
+use lc3_traits::peripherals::gpio::{Gpio, GpioPin::*, GpioPinArr, GpioPin, GPIO_PINS, GpioInterruptMode, GpioMiscError};
 
 gpio_mem_mapped!(G0, "G0", G0CR, G0DR, G0CR_ADDR, G0DR_ADDR, G0_INT_VEC);
 gpio_mem_mapped!(G1, "G1", G1CR, G1DR, G1CR_ADDR, G1DR_ADDR, G1_INT_VEC);
@@ -710,6 +980,14 @@ gpio_mem_mapped!(G4, "G4", G4CR, G4DR, G4CR_ADDR, G4DR_ADDR, G4_INT_VEC);
 gpio_mem_mapped!(G5, "G5", G5CR, G5DR, G5CR_ADDR, G5DR_ADDR, G5_INT_VEC);
 gpio_mem_mapped!(G6, "G6", G6CR, G6DR, G6CR_ADDR, G6DR_ADDR, G6_INT_VEC);
 gpio_mem_mapped!(G7, "G7", G7CR, G7DR, G7CR_ADDR, G7DR_ADDR, G7_INT_VEC);
+#[cfg(feature = "gpio_pins_16")] gpio_mem_mapped!(G8, "G8", G8CR, G8DR, G8CR_ADDR, G8DR_ADDR, G8_INT_VEC);
+#[cfg(feature = "gpio_pins_16")] gpio_mem_mapped!(G9, "G9", G9CR, G9DR, G9CR_ADDR, G9DR_ADDR, G9_INT_VEC);
+#[cfg(feature = "gpio_pins_16")] gpio_mem_mapped!(G10, "G10", G10CR, G10DR, G10CR_ADDR, G10DR_ADDR, G10_INT_VEC);
+#[cfg(feature = "gpio_pins_16")] gpio_mem_mapped!(G11, "G11", G11CR, G11DR, G11CR_ADDR, G11DR_ADDR, G11_INT_VEC);
+#[cfg(feature = "gpio_pins_16")] gpio_mem_mapped!(G12, "G12", G12CR, G12DR, G12CR_ADDR, G12DR_ADDR, G12_INT_VEC);
+#[cfg(feature = "gpio_pins_16")] gpio_mem_mapped!(G13, "G13", G13CR, G13DR, G13CR_ADDR, G13DR_ADDR, G13_INT_VEC);
+#[cfg(feature = "gpio_pins_16")] gpio_mem_mapped!(G14, "G14", G14CR, G14DR, G14CR_ADDR, G14DR_ADDR, G14_INT_VEC);
+#[cfg(feature = "gpio_pins_16")] gpio_mem_mapped!(G15, "G15", G15CR, G15DR, G15CR_ADDR, G15DR_ADDR, G15_INT_VEC);
 
 pub struct GPIODR(Word);
 impl Deref for GPIODR {
@@ -891,6 +1169,8 @@ adc_mem_mapped!(A2, "A2", A2CR, A2DR, A2CR_ADDR, A2DR_ADDR);
 adc_mem_mapped!(A3, "A3", A3CR, A3DR, A3CR_ADDR, A3DR_ADDR);
 adc_mem_mapped!(A4, "A4", A4CR, A4DR, A4CR_ADDR, A4DR_ADDR);
 adc_mem_mapped!(A5, "A5", A5CR, A5DR, A5CR_ADDR, A5DR_ADDR);
+#[cfg(feature = "adc_pins_8")] adc_mem_mapped!(A6, "A6", A6CR, A6DR, A6CR_ADDR, A6DR_ADDR);
+#[cfg(feature = "adc_pins_8")] adc_mem_mapped!(A7, "A7", A7CR, A7DR, A7CR_ADDR, A7DR_ADDR);
 
 use lc3_traits::peripherals::clock::Clock;
 #[doc = "Clock Register"]
@@ -931,161 +1211,1035 @@ impl MemMapped for CLKR {
     }
 }
 
-macro_rules! pwm_mem_mapped {
-    ($pin:expr, $pin_name:literal, $cr:ident, $dr:ident, $cr_addr:expr, $dr_addr:expr) => {
-        #[doc=$pin_name]
-        #[doc="PWM Pin Control Register"] // TODO: format correctly
-        #[derive(Copy, Clone, Debug, PartialEq)]
-        pub struct $cr(Word);
-
-        impl Deref for $cr {
-            type Target = Word;
+use lc3_traits::peripherals::display::Display;
 
-            fn deref(&self) -> &Self::Target { &self.0 }
-        }
+mem_mapped!(special: VXR, VXR_ADDR, "Display X Register. The column of the pixel that [`VPR`] accesses.");
+mem_mapped!(special: VYR, VYR_ADDR, "Display Y Register. The row of the pixel that [`VPR`] accesses.");
 
-        impl MemMapped for $cr {
-            const ADDR: Addr = $cr_addr;
+#[doc = "Display Pixel Register. The color of the pixel at ([`VXR`], [`VYR`])."]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct VPR(Word);
+impl Deref for VPR {
+    type Target = Word;
 
-            fn with_value(value: Word) -> Self { Self(value) }
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl MemMapped for VPR {
+    const ADDR: Addr = VPR_ADDR;
 
-            fn from<'a, I> (interp: &I) -> Result<Self, Acv>
-            where
-                I: InstructionInterpreterPeripheralAccess<'a>,
-                <I as Deref>::Target: Peripherals<'a>,
-            {
-                let state = Pwm::get_state(interp.get_peripherals(), $pin);
+    fn with_value(value: Word) -> Self {
+        Self(value)
+    }
 
-                use lc3_traits::peripherals::pwm::PwmState::*;
-                let word: Word = match state {
-                    Disabled => 0,
-                    Enabled(ref nzu8) => nzu8.get() as Word,
-                };
+    fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let (x, y) = (*interp.get_special_reg::<VXR>(), *interp.get_special_reg::<VYR>());
 
-                Ok(Self::with_value(word))
+        let color = match Display::get_pixel(interp.get_peripherals(), x, y) {
+            Ok(color) => color,
+            Err(err) => {
+                interp.set_error(Error::from(err));
+                0
             }
+        };
 
-            fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
-            where
-                I: InstructionInterpreterPeripheralAccess<'a>,
-                <I as Deref>::Target: Peripherals<'a>,
-            {
-                use lc3_traits::peripherals::pwm::PwmState::*;
-                use core::num::NonZeroU8;
-
-                let state_val: u8 = value as u8;
-                let state = match state_val {
-                    0 => Disabled,
-                    _ => Enabled(NonZeroU8::new(state_val).unwrap()),
-                };
+        Ok(Self::with_value(color))
+    }
 
-                Pwm::set_state(interp.get_peripherals_mut(), $pin, state);
+    fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let (x, y) = (*interp.get_special_reg::<VXR>(), *interp.get_special_reg::<VYR>());
 
-                Ok(())
-            }
+        if let Err(err) = Display::set_pixel(interp.get_peripherals_mut(), x, y, value) {
+            interp.set_error(Error::from(err));
         }
 
-        #[doc=$pin_name]
-        #[doc="PWM Pin Duty Cycle Register"] // TODO: format correctly
-        #[derive(Copy, Clone, Debug, PartialEq)]
-        pub struct $dr(Word);
+        Ok(())
+    }
+}
 
-        impl Deref for $dr {
-            type Target = Word;
+#[doc = "Display Fill Register. Writing a color fills the display with it; reads return 0."]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct VFR(Word);
+impl Deref for VFR {
+    type Target = Word;
 
-            fn deref(&self) -> &Self::Target { &self.0 }
-        }
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl MemMapped for VFR {
+    const ADDR: Addr = VFR_ADDR;
 
-        impl MemMapped for $dr {
-            const ADDR: Addr = $dr_addr;
+    fn with_value(value: Word) -> Self {
+        Self(value)
+    }
 
-            fn with_value(value: Word) -> Self { Self(value) }
+    fn from<'a, I>(_interp: &I) -> Result<Self, Acv>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        Ok(Self::with_value(0))
+    }
 
-            fn from<'a, I> (interp: &I) -> Result<Self, Acv> // TODO: change all these to some other kind of error since we already check for ACVs in read_word, etc.
-            where
-                I: InstructionInterpreterPeripheralAccess<'a>,
-                <I as Deref>::Target: Peripherals<'a>,
-            {
-                use lc3_traits::peripherals::pwm::PwmState::*;
+    fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        Display::fill(interp.get_peripherals_mut(), value);
 
-                let word = Pwm::get_duty_cycle(interp.get_peripherals(), $pin) as Word;
+        Ok(())
+    }
+}
 
-                Ok(Self::with_value(word))
-            }
+use lc3_traits::peripherals::disk::{Disk, DiskError, SECTOR_SIZE_IN_WORDS};
 
-            fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
-            where
-                I: InstructionInterpreterPeripheralAccess<'a>,
-                <I as Deref>::Target: Peripherals<'a>,
-            {
-                let duty_val: u8 = value as u8;
+// Bits in DKSR:
+const DISK_READY_BIT: u32 = 15;
+const DISK_INT_ENABLE_BIT: u32 = 14;
+const DISK_DONE_BIT: u32 = 1;
+const DISK_ERROR_BIT: u32 = 0;
 
-                Pwm::set_duty_cycle(interp.get_peripherals_mut(), $pin, duty_val);
+#[doc = "Disk Status Register.\n\n\
+Bit 15 is set when the disk is ready for a command (always, since commands \
+finish immediately), bit 14 enables the completion interrupt, bit 1 is set \
+when a command finishes (and cleared when the completion interrupt is taken), \
+and bit 0 is set if the last command failed.\n\n\
+Only bit 14 can be written."]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DKSR(Word);
+impl Deref for DKSR {
+    type Target = Word;
 
-                Ok(())
-            }
-        }
-    };
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
 }
+impl MemMapped for DKSR {
+    const ADDR: Addr = DKSR_ADDR;
 
-use lc3_traits::peripherals::pwm::{Pwm, PwmPin::*};
+    fn with_value(value: Word) -> Self {
+        Self(value)
+    }
 
-pwm_mem_mapped!(P0, "P0", P0CR, P0DR, P0CR_ADDR, P0DR_ADDR);
-pwm_mem_mapped!(P1, "P1", P1CR, P1DR, P1CR_ADDR, P1DR_ADDR);
+    fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        // Everything but the ready bit lives in memory:
+        let status = interp.get_word_force_memory_backed(DKSR_ADDR);
 
-macro_rules! timer_mem_mapped {
-    ($id:expr, $id_name:literal, $cr:ident, $dr:ident, $cr_addr:expr, $dr_addr:expr, $int_vec:expr) => {
-        #[doc=$id_name]
-        #[doc="Timer Control Register"] // TODO: format correctly
-        #[derive(Copy, Clone, Debug, PartialEq)]
-        pub struct $cr(Word);
+        Ok(Self::with_value(status | (1 << DISK_READY_BIT)))
+    }
 
-        impl Deref for $cr {
-            type Target = Word;
+    fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let status = interp.get_word_force_memory_backed(DKSR_ADDR);
+        let status = (status & !(1 << DISK_INT_ENABLE_BIT))
+            | ((value.bit(DISK_INT_ENABLE_BIT) as Word) << DISK_INT_ENABLE_BIT);
 
-            fn deref(&self) -> &Self::Target { &self.0 }
-        }
+        interp.set_word_force_memory_backed(DKSR_ADDR, status);
+        Ok(())
+    }
+}
 
-        impl MemMapped for $cr {
-            const ADDR: Addr = $cr_addr;
+impl Interrupt for DKSR {
+    const INT_VEC: u8 = DISK_INT_VEC;
+    const PRIORITY: u8 = DISK_INT_PRIORITY;
 
-            fn with_value(value: Word) -> Self { Self(value) }
+    fn interrupt_ready<'a, I>(interp: &I) -> bool
+        where
+            I: InstructionInterpreterPeripheralAccess<'a>,
+            <I as Deref>::Target: Peripherals<'a>,
+    {
+        interp.get_word_force_memory_backed(DKSR_ADDR).bit(DISK_DONE_BIT)
+    }
 
-            fn from<'a, I> (interp: &I) -> Result<Self, Acv>
-            where
-                I: InstructionInterpreterPeripheralAccess<'a>,
-                <I as Deref>::Target: Peripherals<'a>,
-            {
-                let mode = Timers::get_mode(interp.get_peripherals(), $id);
+    fn interrupt_enabled<'a, I>(interp: &I) -> bool
+        where
+            I: InstructionInterpreterPeripheralAccess<'a>,
+            <I as Deref>::Target: Peripherals<'a>
+    {
+        interp.get_word_force_memory_backed(DKSR_ADDR).bit(DISK_INT_ENABLE_BIT)
+    }
 
-                use lc3_traits::peripherals::timers::TimerMode::*;
-                let word: Word = match mode {
-                    SingleShot => 0,
-                    Repeated => 1,
-                };
+    fn reset_interrupt_flag<'a, I>(interp: &mut I)
+        where
+            I: InstructionInterpreterPeripheralAccess<'a>,
+            <I as Deref>::Target: Peripherals<'a>
+    {
+        let status = interp.get_word_force_memory_backed(DKSR_ADDR);
+        interp.set_word_force_memory_backed(DKSR_ADDR, status & !(1 << DISK_DONE_BIT));
+    }
+}
 
-                Ok(Self::with_value(word))
-            }
+#[doc = "Disk Command Register. Writing a command ([`DISK_READ_CMD`] or \
+[`DISK_WRITE_CMD`]) runs it; reads return 0.\n\n\
+Commands finish immediately and update [`DKSR`]."]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DKCR(Word);
+impl Deref for DKCR {
+    type Target = Word;
 
-            fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
-            where
-                I: InstructionInterpreterPeripheralAccess<'a>,
-                <I as Deref>::Target: Peripherals<'a>,
-            {
-                use lc3_traits::peripherals::timers::TimerMode::*;
-                let mode = if value.bit(0) {
-                    Repeated
-                } else {
-                    SingleShot
-                };
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl DKCR {
+    fn run<'a, I>(interp: &mut I, cmd: Word) -> Result<(), DiskError>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let (sector, buf_addr) = (*interp.get_special_reg::<DKAR>(), *interp.get_special_reg::<DKBR>());
+        let mut buf = [0; SECTOR_SIZE_IN_WORDS];
 
-                Timers::set_mode(interp.get_peripherals_mut(), $id, mode);
+        // DMA into the memory mapped region is not allowed:
+        if (buf_addr as usize) + SECTOR_SIZE_IN_WORDS > (MEM_MAPPED_START_ADDR as usize) {
+            return Err(DiskError::InvalidBuffer(buf_addr));
+        }
 
-                Ok(())
+        match cmd {
+            DISK_READ_CMD => {
+                Disk::read_sector(interp.get_peripherals(), sector, &mut buf)?;
+                for (addr, word) in (buf_addr..).zip(buf.iter()) {
+                    interp.set_word_unchecked(addr, *word);
+                }
+            }
+            DISK_WRITE_CMD => {
+                for (addr, word) in (buf_addr..).zip(buf.iter_mut()) {
+                    *word = interp.get_word_unchecked(addr);
+                }
+                Disk::write_sector(interp.get_peripherals_mut(), sector, &buf)?;
             }
+            _ => return Err(DiskError::InvalidCommand(cmd)),
         }
 
-        impl Interrupt for $cr {
+        Ok(())
+    }
+}
+impl MemMapped for DKCR {
+    const ADDR: Addr = DKCR_ADDR;
+
+    fn with_value(value: Word) -> Self {
+        Self(value)
+    }
+
+    fn from<'a, I>(_interp: &I) -> Result<Self, Acv>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        Ok(Self::with_value(0))
+    }
+
+    fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let res = Self::run(interp, value);
+
+        let status = interp.get_word_force_memory_backed(DKSR_ADDR)
+            & !(1 << DISK_ERROR_BIT);
+        let status = status | (1 << DISK_DONE_BIT) | ((res.is_err() as Word) << DISK_ERROR_BIT);
+        interp.set_word_force_memory_backed(DKSR_ADDR, status);
+
+        if let Err(err) = res {
+            interp.set_error(Error::from(err));
+        }
+
+        Ok(())
+    }
+}
+
+mem_mapped!(special: DKAR, DKAR_ADDR, "Disk Address Register. The sector that [`DKCR`] commands operate on.");
+mem_mapped!(special: DKBR, DKBR_ADDR, "Disk Buffer Register. The address of the sector's worth of memory that [`DKCR`] commands copy to or from.");
+
+#[doc = "Disk Number of sectors Register. The number of sectors on the disk \
+(0 if there's no disk). Writes are ignored."]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DKNR(Word);
+impl Deref for DKNR {
+    type Target = Word;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl MemMapped for DKNR {
+    const ADDR: Addr = DKNR_ADDR;
+
+    fn with_value(value: Word) -> Self {
+        Self(value)
+    }
+
+    fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        Ok(Self::with_value(Disk::num_sectors(interp.get_peripherals())))
+    }
+
+    fn set<'a, I>(_interp: &mut I, _value: Word) -> WriteAttempt
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        Ok(())
+    }
+}
+
+use lc3_traits::peripherals::uart::{Uart, UART_FIFO_DEPTH};
+
+// Bits in USR:
+const UART_RX_READY_BIT: u32 = 15;
+const UART_RX_INT_ENABLE_BIT: u32 = 14;
+const UART_TX_READY_BIT: u32 = 13;
+const UART_TX_INT_ENABLE_BIT: u32 = 12;
+const UART_TX_EMPTY_BIT: u32 = 11;
+const UART_PRESENT_BIT: u32 = 8;
+const UART_OVERRUN_BIT: u32 = 0;
+
+#[doc = "UART Status Register.\n\n\
+Bit 15 is set when there's a byte to read from [`UDR`], bit 14 enables the \
+receive interrupt, bit 13 is set when there's room in the transmit FIFO, bit \
+12 enables the transmit interrupt, bit 11 is set once everything that was \
+transmitted has been sent, bit 8 is set if there's a UART at all, and bit 0 is \
+set when bytes were dropped because the receive FIFO was full.\n\n\
+Bits 14 and 12 can be written; writing a 1 to bit 0 clears it.\n\n\
+The UART's interrupt (which is level triggered) is pending while the receive \
+interrupt is enabled and bit 15 is set or while the transmit interrupt is \
+enabled and bit 13 is set."]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct USR(Word);
+impl Deref for USR {
+    type Target = Word;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl USR {
+    fn rx_ready<'a, P: Peripherals<'a>>(p: &P) -> bool {
+        Uart::rx_count(p) > 0
+    }
+
+    fn tx_ready<'a, P: Peripherals<'a>>(p: &P) -> bool {
+        Uart::is_present(p) && Uart::tx_count(p) < UART_FIFO_DEPTH
+    }
+}
+impl MemMapped for USR {
+    const ADDR: Addr = USR_ADDR;
+
+    fn with_value(value: Word) -> Self {
+        Self(value)
+    }
+
+    fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let p = interp.get_peripherals();
+        let word = ((Self::rx_ready(p) as Word) << UART_RX_READY_BIT)
+            | ((Uart::rx_interrupt_enabled(p) as Word) << UART_RX_INT_ENABLE_BIT)
+            | ((Self::tx_ready(p) as Word) << UART_TX_READY_BIT)
+            | ((Uart::tx_interrupt_enabled(p) as Word) << UART_TX_INT_ENABLE_BIT)
+            | (((Uart::tx_count(p) == 0) as Word) << UART_TX_EMPTY_BIT)
+            | ((Uart::is_present(p) as Word) << UART_PRESENT_BIT)
+            | ((Uart::overrun(p) as Word) << UART_OVERRUN_BIT);
+
+        Ok(Self::with_value(word))
+    }
+
+    fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let p = interp.get_peripherals_mut();
+        Uart::set_rx_interrupt_enable_bit(p, value.bit(UART_RX_INT_ENABLE_BIT));
+        Uart::set_tx_interrupt_enable_bit(p, value.bit(UART_TX_INT_ENABLE_BIT));
+
+        if value.bit(UART_OVERRUN_BIT) {
+            Uart::clear_overrun(p);
+        }
+
+        Ok(())
+    }
+}
+
+impl Interrupt for USR {
+    const INT_VEC: u8 = UART_INT_VEC;
+    const PRIORITY: u8 = UART_INT_PRIORITY;
+
+    fn interrupt_ready<'a, I>(interp: &I) -> bool
+        where
+            I: InstructionInterpreterPeripheralAccess<'a>,
+            <I as Deref>::Target: Peripherals<'a>,
+    {
+        let p = interp.get_peripherals();
+
+        (Uart::rx_interrupt_enabled(p) && Self::rx_ready(p))
+            || (Uart::tx_interrupt_enabled(p) && Self::tx_ready(p))
+    }
+
+    fn interrupt_enabled<'a, I>(interp: &I) -> bool
+        where
+            I: InstructionInterpreterPeripheralAccess<'a>,
+            <I as Deref>::Target: Peripherals<'a>
+    {
+        let p = interp.get_peripherals();
+        Uart::rx_interrupt_enabled(p) || Uart::tx_interrupt_enabled(p)
+    }
+
+    fn reset_interrupt_flag<'a, I>(_interp: &mut I)
+        where
+            I: InstructionInterpreterPeripheralAccess<'a>,
+            <I as Deref>::Target: Peripherals<'a>
+    {
+        // Level triggered; nothing to reset.
+    }
+}
+
+#[doc = "UART Data Register.\n\n\
+Reading takes the next byte out of the receive FIFO (or returns 0 if it's \
+empty). Writing puts the low byte of the value in the transmit FIFO; if the \
+FIFO is full the byte is dropped (check bit 13 of [`USR`] first)."]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UDR(Word);
+impl Deref for UDR {
+    type Target = Word;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl MemMapped for UDR {
+    const ADDR: Addr = UDR_ADDR;
+    const HAS_STATEFUL_READS: bool = true;
+
+    fn with_value(value: Word) -> Self {
+        Self(value)
+    }
+
+    fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let byte = Uart::receive(interp.get_peripherals()).unwrap_or(0);
+
+        Ok(Self::with_value(byte as Word))
+    }
+
+    fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let _ = Uart::transmit(interp.get_peripherals_mut(), value as u8);
+
+        Ok(())
+    }
+}
+
+#[doc = "UART Count Register. The low byte is the number of bytes in the \
+receive FIFO and the high byte is the number of bytes in the transmit FIFO \
+that haven't been sent yet. Writes are ignored."]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UCNR(Word);
+impl Deref for UCNR {
+    type Target = Word;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl MemMapped for UCNR {
+    const ADDR: Addr = UCNR_ADDR;
+
+    fn with_value(value: Word) -> Self {
+        Self(value)
+    }
+
+    fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let p = interp.get_peripherals();
+        let word = ((Uart::tx_count(p) as Word) << 8) | (Uart::rx_count(p) as Word);
+
+        Ok(Self::with_value(word))
+    }
+
+    fn set<'a, I>(_interp: &mut I, _value: Word) -> WriteAttempt
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        Ok(())
+    }
+}
+
+// The counts have to fit in a byte each.
+sa::const_assert!(UART_FIFO_DEPTH <= 0xFF);
+
+use lc3_traits::peripherals::rng::Rng;
+
+#[doc = "Random Number Generator Status Register. Bit 15 is set if there's a \
+random number generator and bit 0 is set if it has been seeded (i.e. if \
+[`RNGDR`] is producing a fixed sequence). Writes are ignored."]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RNGSR(Word);
+impl Deref for RNGSR {
+    type Target = Word;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl MemMapped for RNGSR {
+    const ADDR: Addr = RNGSR_ADDR;
+
+    fn with_value(value: Word) -> Self {
+        Self(value)
+    }
+
+    fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let p = interp.get_peripherals();
+        let word = ((Rng::is_present(p) as Word) << 15) | (Rng::is_seeded(p) as Word);
+
+        Ok(Self::with_value(word))
+    }
+
+    fn set<'a, I>(_interp: &mut I, _value: Word) -> WriteAttempt
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        Ok(())
+    }
+}
+
+#[doc = "Random Number Generator Data Register.\n\n\
+Reading gives the next random word (or 0 if there's no random number \
+generator or it can't get any entropy). Writing seeds the generator with the value written; from then on \
+reads produce the sequence determined by that seed."]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RNGDR(Word);
+impl Deref for RNGDR {
+    type Target = Word;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl MemMapped for RNGDR {
+    const ADDR: Addr = RNGDR_ADDR;
+    const HAS_STATEFUL_READS: bool = true;
+
+    fn with_value(value: Word) -> Self {
+        Self(value)
+    }
+
+    fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let word = Rng::next_word(interp.get_peripherals()).unwrap_or(0);
+
+        Ok(Self::with_value(word))
+    }
+
+    fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let _ = Rng::set_seed(interp.get_peripherals_mut(), value as u64);
+
+        Ok(())
+    }
+}
+
+use lc3_traits::peripherals::watchdog::Watchdog;
+
+// Bits in WDCR:
+const WATCHDOG_EXPIRED_BIT: u32 = 15;
+const WATCHDOG_INT_ENABLE_BIT: u32 = 14;
+const WATCHDOG_PRESENT_BIT: u32 = 8;
+const WATCHDOG_DEBUGGER_DISABLED_BIT: u32 = 1;
+const WATCHDOG_CAUSED_RESET_BIT: u32 = 0;
+
+#[doc = "Watchdog Control Register.\n\n\
+Bit 15 is set once the timeout has elapsed without a kick (it's cleared by \
+kicking the watchdog), bit 14 is the interrupt enable bit, bit 8 is set if \
+there's a watchdog at all, bit 1 is set while a debugger has the watchdog \
+disabled, and bit 0 is set if the last reset was caused by the watchdog \
+(write a 1 to clear it). Only bits 14 and 0 can be written.\n\n\
+If the interrupt is enabled, the first time the timeout elapses the \
+watchdog's interrupt fires; if the timeout then elapses again without a kick \
+(or if the interrupt isn't enabled) the machine is reset."]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WDCR(Word);
+impl Deref for WDCR {
+    type Target = Word;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl MemMapped for WDCR {
+    const ADDR: Addr = WDCR_ADDR;
+
+    fn with_value(value: Word) -> Self {
+        Self(value)
+    }
+
+    fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let p = interp.get_peripherals();
+        let word = (((Watchdog::expirations(p) > 0) as Word) << WATCHDOG_EXPIRED_BIT)
+            | ((Watchdog::interrupt_enabled(p) as Word) << WATCHDOG_INT_ENABLE_BIT)
+            | ((Watchdog::is_present(p) as Word) << WATCHDOG_PRESENT_BIT)
+            | ((Watchdog::disabled_by_debugger(p) as Word) << WATCHDOG_DEBUGGER_DISABLED_BIT)
+            | ((Watchdog::caused_reset(p) as Word) << WATCHDOG_CAUSED_RESET_BIT);
+
+        Ok(Self::with_value(word))
+    }
+
+    fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let p = interp.get_peripherals_mut();
+        Watchdog::set_interrupt_enable_bit(p, value.bit(WATCHDOG_INT_ENABLE_BIT));
+
+        if value.bit(WATCHDOG_CAUSED_RESET_BIT) {
+            Watchdog::set_caused_reset(p, false);
+        }
+
+        Ok(())
+    }
+}
+
+impl Interrupt for WDCR {
+    const INT_VEC: u8 = WATCHDOG_INT_VEC;
+    const PRIORITY: u8 = WATCHDOG_INT_PRIORITY;
+
+    fn interrupt_ready<'a, I>(interp: &I) -> bool
+        where
+            I: InstructionInterpreterPeripheralAccess<'a>,
+            <I as Deref>::Target: Peripherals<'a>,
+    {
+        Watchdog::interrupt_occurred(interp.get_peripherals())
+    }
+
+    fn interrupt_enabled<'a, I>(interp: &I) -> bool
+        where
+            I: InstructionInterpreterPeripheralAccess<'a>,
+            <I as Deref>::Target: Peripherals<'a>
+    {
+        Watchdog::interrupt_enabled(interp.get_peripherals())
+    }
+
+    fn reset_interrupt_flag<'a, I>(interp: &mut I)
+        where
+            I: InstructionInterpreterPeripheralAccess<'a>,
+            <I as Deref>::Target: Peripherals<'a>
+    {
+        Watchdog::reset_interrupt_flag(interp.get_peripherals_mut())
+    }
+}
+
+#[doc = "Watchdog Timeout Register. The number of milliseconds the program has \
+between kicks; 0 (the default) turns the watchdog off. Writing this register \
+also kicks the watchdog."]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WDTR(Word);
+impl Deref for WDTR {
+    type Target = Word;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl MemMapped for WDTR {
+    const ADDR: Addr = WDTR_ADDR;
+
+    fn with_value(value: Word) -> Self {
+        Self(value)
+    }
+
+    fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        Ok(Self::with_value(Watchdog::get_timeout(interp.get_peripherals())))
+    }
+
+    fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        Watchdog::set_timeout(interp.get_peripherals_mut(), value);
+
+        Ok(())
+    }
+}
+
+#[doc = "Watchdog Kick Register. Writing anything to this register kicks the \
+watchdog; reading it gives the number of milliseconds left until the timeout \
+next elapses."]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WDKR(Word);
+impl Deref for WDKR {
+    type Target = Word;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl MemMapped for WDKR {
+    const ADDR: Addr = WDKR_ADDR;
+
+    fn with_value(value: Word) -> Self {
+        Self(value)
+    }
+
+    fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        Ok(Self::with_value(Watchdog::remaining(interp.get_peripherals())))
+    }
+
+    fn set<'a, I>(interp: &mut I, _value: Word) -> WriteAttempt
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        Watchdog::kick(interp.get_peripherals_mut());
+
+        Ok(())
+    }
+}
+
+use lc3_traits::control::mpu::{MpuRegion, Permissions, NUM_MPU_REGIONS};
+
+// Bits in MPUAR (the ranges are inclusive, as with all the `Bits` functions):
+const MPU_REGION_VALID_BIT: u32 = 15;
+const MPU_SUPERVISOR_PERMISSIONS: core::ops::Range<u32> = 4..6;
+const MPU_USER_PERMISSIONS: core::ops::Range<u32> = 0..2;
+
+// So that region numbers can be masked:
+sa::const_assert!(NUM_MPU_REGIONS.is_power_of_two());
+
+#[doc = "Memory Protection Region Number Register. Selects the region of the \
+memory protection table (see \
+[`set_mpu_region`](crate::interp::InstructionInterpreter::set_mpu_region)) \
+that [`MPUBR`], [`MPUER`], and [`MPUAR`] describe; only the low bits (enough \
+for the number of regions) are kept.\n\n\
+Writing this register also loads the selected region into the other three \
+registers (all zeros if the region isn't set)."]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MPUNR(Word);
+impl Deref for MPUNR {
+    type Target = Word;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl MemMapped for MPUNR {
+    const ADDR: Addr = MPUNR_ADDR;
+
+    fn with_value(value: Word) -> Self {
+        Self(value)
+    }
+
+    fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        Ok(Self::with_value(interp.get_word_force_memory_backed(Self::ADDR)))
+    }
+
+    fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        let idx = value & (NUM_MPU_REGIONS as Word - 1);
+        interp.set_word_force_memory_backed(Self::ADDR, idx);
+
+        let (start, end, attrs) = match interp.get_mpu_region(idx as u8) {
+            Ok(Some(r)) => {
+                let attrs = (1 << MPU_REGION_VALID_BIT)
+                    | ((r.supervisor.to_bits() as Word) << MPU_SUPERVISOR_PERMISSIONS.start)
+                    | ((r.user.to_bits() as Word) << MPU_USER_PERMISSIONS.start);
+
+                (r.start, r.end, attrs)
+            }
+            _ => (0, 0, 0),
+        };
+
+        interp.set_word_force_memory_backed(MPUBR::ADDR, start);
+        interp.set_word_force_memory_backed(MPUER::ADDR, end);
+        interp.set_word_force_memory_backed(MPUAR::ADDR, attrs);
+
+        Ok(())
+    }
+}
+
+mem_mapped!(special: MPUBR, MPUBR_ADDR, "Memory Protection Region Base Register. The first address in the region [`MPUNR`] selects.");
+mem_mapped!(special: MPUER, MPUER_ADDR, "Memory Protection Region End Register. The last address in the region [`MPUNR`] selects.");
+
+#[doc = "Memory Protection Region Attribute Register.\n\n\
+Bit 15 is set if the region [`MPUNR`] selects is in use, bits 6-4 are the \
+supervisor mode permissions, and bits 2-0 are the user mode permissions (for \
+both: bit 0 is read, bit 1 is write, and bit 2 is execute).\n\n\
+Writing this register updates the selected region of the table with the \
+values in [`MPUBR`], [`MPUER`], and this register (or clears the region if \
+bit 15 isn't set); writes that describe a region that ends before it starts \
+don't change the table."]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MPUAR(Word);
+impl Deref for MPUAR {
+    type Target = Word;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+impl MemMapped for MPUAR {
+    const ADDR: Addr = MPUAR_ADDR;
+
+    fn with_value(value: Word) -> Self {
+        Self(value)
+    }
+
+    fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        Ok(Self::with_value(interp.get_word_force_memory_backed(Self::ADDR)))
+    }
+
+    fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        interp.set_word_force_memory_backed(Self::ADDR, value);
+
+        let region = if value.bit(MPU_REGION_VALID_BIT) {
+            Some(MpuRegion::new(
+                interp.get_word_force_memory_backed(MPUBR::ADDR),
+                interp.get_word_force_memory_backed(MPUER::ADDR),
+                Permissions::from_bits(value.u8(MPU_USER_PERMISSIONS)),
+                Permissions::from_bits(value.u8(MPU_SUPERVISOR_PERMISSIONS)),
+            ))
+        } else {
+            None
+        };
+
+        let idx = interp.get_word_force_memory_backed(MPUNR::ADDR) as u8;
+        let _ = interp.set_mpu_region(idx, region);
+
+        Ok(())
+    }
+}
+
+macro_rules! pwm_mem_mapped {
+    ($pin:expr, $pin_name:literal, $cr:ident, $dr:ident, $cr_addr:expr, $dr_addr:expr) => {
+        #[doc=$pin_name]
+        #[doc="PWM Pin Control Register"] // TODO: format correctly
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        pub struct $cr(Word);
+
+        impl Deref for $cr {
+            type Target = Word;
+
+            fn deref(&self) -> &Self::Target { &self.0 }
+        }
+
+        impl MemMapped for $cr {
+            const ADDR: Addr = $cr_addr;
+
+            fn with_value(value: Word) -> Self { Self(value) }
+
+            fn from<'a, I> (interp: &I) -> Result<Self, Acv>
+            where
+                I: InstructionInterpreterPeripheralAccess<'a>,
+                <I as Deref>::Target: Peripherals<'a>,
+            {
+                let state = Pwm::get_state(interp.get_peripherals(), $pin);
+
+                use lc3_traits::peripherals::pwm::PwmState::*;
+                let word: Word = match state {
+                    Disabled => 0,
+                    Enabled(ref nzu8) => nzu8.get() as Word,
+                };
+
+                Ok(Self::with_value(word))
+            }
+
+            fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
+            where
+                I: InstructionInterpreterPeripheralAccess<'a>,
+                <I as Deref>::Target: Peripherals<'a>,
+            {
+                use lc3_traits::peripherals::pwm::PwmState::*;
+                use core::num::NonZeroU8;
+
+                let state_val: u8 = value as u8;
+                let state = match state_val {
+                    0 => Disabled,
+                    _ => Enabled(NonZeroU8::new(state_val).unwrap()),
+                };
+
+                Pwm::set_state(interp.get_peripherals_mut(), $pin, state);
+
+                Ok(())
+            }
+        }
+
+        #[doc=$pin_name]
+        #[doc="PWM Pin Duty Cycle Register"] // TODO: format correctly
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        pub struct $dr(Word);
+
+        impl Deref for $dr {
+            type Target = Word;
+
+            fn deref(&self) -> &Self::Target { &self.0 }
+        }
+
+        impl MemMapped for $dr {
+            const ADDR: Addr = $dr_addr;
+
+            fn with_value(value: Word) -> Self { Self(value) }
+
+            fn from<'a, I> (interp: &I) -> Result<Self, Acv> // TODO: change all these to some other kind of error since we already check for ACVs in read_word, etc.
+            where
+                I: InstructionInterpreterPeripheralAccess<'a>,
+                <I as Deref>::Target: Peripherals<'a>,
+            {
+                use lc3_traits::peripherals::pwm::PwmState::*;
+
+                let word = Pwm::get_duty_cycle(interp.get_peripherals(), $pin) as Word;
+
+                Ok(Self::with_value(word))
+            }
+
+            fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
+            where
+                I: InstructionInterpreterPeripheralAccess<'a>,
+                <I as Deref>::Target: Peripherals<'a>,
+            {
+                let duty_val: u8 = value as u8;
+
+                Pwm::set_duty_cycle(interp.get_peripherals_mut(), $pin, duty_val);
+
+                Ok(())
+            }
+        }
+    };
+}
+
+use lc3_traits::peripherals::pwm::{Pwm, PwmPin::*};
+
+pwm_mem_mapped!(P0, "P0", P0CR, P0DR, P0CR_ADDR, P0DR_ADDR);
+pwm_mem_mapped!(P1, "P1", P1CR, P1DR, P1CR_ADDR, P1DR_ADDR);
+#[cfg(feature = "pwm_pins_4")] pwm_mem_mapped!(P2, "P2", P2CR, P2DR, P2CR_ADDR, P2DR_ADDR);
+#[cfg(feature = "pwm_pins_4")] pwm_mem_mapped!(P3, "P3", P3CR, P3DR, P3CR_ADDR, P3DR_ADDR);
+
+macro_rules! timer_mem_mapped {
+    ($id:expr, $id_name:literal, $cr:ident, $dr:ident, $cr_addr:expr, $dr_addr:expr, $int_vec:expr) => {
+        #[doc=$id_name]
+        #[doc="Timer Control Register"] // TODO: format correctly
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        pub struct $cr(Word);
+
+        impl Deref for $cr {
+            type Target = Word;
+
+            fn deref(&self) -> &Self::Target { &self.0 }
+        }
+
+        impl MemMapped for $cr {
+            const ADDR: Addr = $cr_addr;
+
+            fn with_value(value: Word) -> Self { Self(value) }
+
+            fn from<'a, I> (interp: &I) -> Result<Self, Acv>
+            where
+                I: InstructionInterpreterPeripheralAccess<'a>,
+                <I as Deref>::Target: Peripherals<'a>,
+            {
+                let mode = Timers::get_mode(interp.get_peripherals(), $id);
+
+                use lc3_traits::peripherals::timers::TimerMode::*;
+                let word: Word = match mode {
+                    SingleShot => 0,
+                    Repeated => 1,
+                };
+
+                Ok(Self::with_value(word))
+            }
+
+            fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
+            where
+                I: InstructionInterpreterPeripheralAccess<'a>,
+                <I as Deref>::Target: Peripherals<'a>,
+            {
+                use lc3_traits::peripherals::timers::TimerMode::*;
+                let mode = if value.bit(0) {
+                    Repeated
+                } else {
+                    SingleShot
+                };
+
+                Timers::set_mode(interp.get_peripherals_mut(), $id, mode);
+
+                Ok(())
+            }
+        }
+
+        impl Interrupt for $cr {
             const INT_VEC: u8 = $int_vec;
             const PRIORITY: u8 = TIMER_INT_PRIORITY;
 
@@ -1172,6 +2326,197 @@ use lc3_traits::peripherals::timers::{Timers, TimerId::*};
 
 timer_mem_mapped!(T0, "T0", T0CR, T0DR, T0CR_ADDR, T0DR_ADDR, T0_INT_VEC);
 timer_mem_mapped!(T1, "T1", T1CR, T1DR, T1CR_ADDR, T1DR_ADDR, T1_INT_VEC);
+#[cfg(feature = "timers_4")] timer_mem_mapped!(T2, "T2", T2CR, T2DR, T2CR_ADDR, T2DR_ADDR, T2_INT_VEC);
+#[cfg(feature = "timers_4")] timer_mem_mapped!(T3, "T3", T3CR, T3DR, T3CR_ADDR, T3DR_ADDR, T3_INT_VEC);
+
+// Bits in TnCCR:
+//   [2:0] -> the GPIO pin to capture from
+//   [4:3] -> the edges to capture on (see `capture_edge_from_bits`)
+//   [5]   -> whether captures raise the timer's interrupt
+//   [6]   -> the high bit of the GPIO pin (for G8 - G15, when there are 16)
+//   [15]  -> capture enable
+const TIMER_CAPTURE_ENABLE_BIT: u32 = 15;
+const TIMER_CAPTURE_INT_BIT: u32 = 5;
+const TIMER_CAPTURE_PIN_HIGH_BIT: u32 = 6;
+
+fn capture_pin_from_bits(value: Word) -> Option<GpioPin> {
+    let idx = value.u16(0..2) | ((value.bit(TIMER_CAPTURE_PIN_HIGH_BIT) as Word) << 3);
+
+    GPIO_PINS.get(idx as usize).copied()
+}
+
+fn capture_pin_to_bits(pin: GpioPin) -> Word {
+    let idx = usize::from(pin) as Word;
+
+    idx.u16(0..2) | (((idx >> 3) & 1) << TIMER_CAPTURE_PIN_HIGH_BIT)
+}
+
+fn capture_edge_from_bits(bits: Word) -> Option<CaptureEdge> {
+    use CaptureEdge::*;
+
+    Some(match bits {
+        0 => Rising,
+        1 => Falling,
+        2 => Both,
+        _ => return None,
+    })
+}
+
+fn capture_edge_to_bits(edge: CaptureEdge) -> Word {
+    use CaptureEdge::*;
+
+    match edge {
+        Rising => 0,
+        Falling => 1,
+        Both => 2,
+    }
+}
+
+macro_rules! timer_capture_mem_mapped {
+    ($id:expr, $id_name:literal, $ccr:ident, $cntr:ident, $capr:ident, $ccr_addr:expr, $cntr_addr:expr, $capr_addr:expr) => {
+        #[doc=$id_name]
+        #[doc="Timer Capture Control Register.\n\n\
+        Bits 2:0 are the GPIO pin to capture from (with bit 6 as the pin \
+        number's high bit when there are 16 pins), bits 4:3 are the edges to \
+        capture on (00 rising, 01 falling, 10 both), bit 5 makes captures raise \
+        the timer's interrupt, and bit 15 enables capturing. Writes with an \
+        invalid edge or pin are ignored."]
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        pub struct $ccr(Word);
+
+        impl Deref for $ccr {
+            type Target = Word;
+
+            fn deref(&self) -> &Self::Target { &self.0 }
+        }
+
+        impl MemMapped for $ccr {
+            const ADDR: Addr = $ccr_addr;
+
+            fn with_value(value: Word) -> Self { Self(value) }
+
+            fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+            where
+                I: InstructionInterpreterPeripheralAccess<'a>,
+                <I as Deref>::Target: Peripherals<'a>,
+            {
+                let word = match Timers::get_capture(interp.get_peripherals(), $id) {
+                    Some(TimerCapture { pin, edge, interrupt }) => {
+                        (1 << TIMER_CAPTURE_ENABLE_BIT)
+                            | ((interrupt as Word) << TIMER_CAPTURE_INT_BIT)
+                            | (capture_edge_to_bits(edge) << 3)
+                            | capture_pin_to_bits(pin)
+                    }
+                    None => 0,
+                };
+
+                Ok(Self::with_value(word))
+            }
+
+            fn set<'a, I>(interp: &mut I, value: Word) -> WriteAttempt
+            where
+                I: InstructionInterpreterPeripheralAccess<'a>,
+                <I as Deref>::Target: Peripherals<'a>,
+            {
+                let capture = if value.bit(TIMER_CAPTURE_ENABLE_BIT) {
+                    let (pin, edge) = match (capture_pin_from_bits(value), capture_edge_from_bits(value.u16(3..4))) {
+                        (Some(pin), Some(edge)) => (pin, edge),
+                        _ => return Ok(()),
+                    };
+
+                    Some(TimerCapture {
+                        pin,
+                        edge,
+                        interrupt: value.bit(TIMER_CAPTURE_INT_BIT),
+                    })
+                } else {
+                    None
+                };
+
+                Timers::set_capture(interp.get_peripherals_mut(), $id, capture);
+
+                Ok(())
+            }
+        }
+
+        #[doc=$id_name]
+        #[doc="Timer Count Register.\n\n\
+        The number of milliseconds left until the timer next fires (0 if the \
+        timer isn't running). Read-only."]
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        pub struct $cntr(Word);
+
+        impl Deref for $cntr {
+            type Target = Word;
+
+            fn deref(&self) -> &Self::Target { &self.0 }
+        }
+
+        impl MemMapped for $cntr {
+            const ADDR: Addr = $cntr_addr;
+
+            fn with_value(value: Word) -> Self { Self(value) }
+
+            fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+            where
+                I: InstructionInterpreterPeripheralAccess<'a>,
+                <I as Deref>::Target: Peripherals<'a>,
+            {
+                Ok(Self::with_value(Timers::get_remaining(interp.get_peripherals(), $id).unwrap_or(0)))
+            }
+
+            fn set<'a, I>(_interp: &mut I, _value: Word) -> WriteAttempt
+            where
+                I: InstructionInterpreterPeripheralAccess<'a>,
+                <I as Deref>::Target: Peripherals<'a>,
+            {
+                Ok(())
+            }
+        }
+
+        #[doc=$id_name]
+        #[doc="Timer Capture Register.\n\n\
+        The clock value (in milliseconds) the timer last captured (0 if it \
+        hasn't captured anything). Read-only."]
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        pub struct $capr(Word);
+
+        impl Deref for $capr {
+            type Target = Word;
+
+            fn deref(&self) -> &Self::Target { &self.0 }
+        }
+
+        impl MemMapped for $capr {
+            const ADDR: Addr = $capr_addr;
+
+            fn with_value(value: Word) -> Self { Self(value) }
+
+            fn from<'a, I>(interp: &I) -> Result<Self, Acv>
+            where
+                I: InstructionInterpreterPeripheralAccess<'a>,
+                <I as Deref>::Target: Peripherals<'a>,
+            {
+                Ok(Self::with_value(Timers::get_captured(interp.get_peripherals(), $id).unwrap_or(0)))
+            }
+
+            fn set<'a, I>(_interp: &mut I, _value: Word) -> WriteAttempt
+            where
+                I: InstructionInterpreterPeripheralAccess<'a>,
+                <I as Deref>::Target: Peripherals<'a>,
+            {
+                Ok(())
+            }
+        }
+    };
+}
+
+use lc3_traits::peripherals::timers::{CaptureEdge, TimerCapture};
+
+timer_capture_mem_mapped!(T0, "T0", T0CCR, T0CNTR, T0CAPR, T0CCR_ADDR, T0CNTR_ADDR, T0CAPR_ADDR);
+timer_capture_mem_mapped!(T1, "T1", T1CCR, T1CNTR, T1CAPR, T1CCR_ADDR, T1CNTR_ADDR, T1CAPR_ADDR);
+#[cfg(feature = "timers_4")] timer_capture_mem_mapped!(T2, "T2", T2CCR, T2CNTR, T2CAPR, T2CCR_ADDR, T2CNTR_ADDR, T2CAPR_ADDR);
+#[cfg(feature = "timers_4")] timer_capture_mem_mapped!(T3, "T3", T3CCR, T3CNTR, T3CAPR, T3CCR_ADDR, T3CNTR_ADDR, T3CAPR_ADDR);
 
 mem_mapped!(special: BSP, BSP_ADDR, "Backup Stack Pointer.");
 
@@ -1281,7 +2626,25 @@ impl PSR {
 
 // mem_mapped!(special: MCR, MCR_ADDRESS, "Machine Control Register.");
 
-/// Machine Control Register
+/// Machine Control Register.
+///
+/// Bit 15 is the clock enable bit: clearing it halts the machine (this is how
+/// the OS's `HALT` TRAP works and is the only way the machine halts). Only a
+/// reset starts the clock again.
+///
+/// Setting bit 14, the debug break bit, asks the simulator to pause as if it
+/// had hit a breakpoint (see [`Event::SoftwareBreak`]); the simulator clears
+/// the bit once it has done so. This lets programs place breakpoints in
+/// themselves.
+///
+/// Bits 7 through 0 throttle the simulator: when nonzero, they're the number of
+/// instructions the simulator runs each time it's
+/// [ticked](lc3_traits::control::Control::tick). 0 (the default) lets the
+/// simulator pick.
+///
+/// The MCR goes back to [`MCR::DEFAULT`] on every reset.
+///
+/// [`Event::SoftwareBreak`]: lc3_traits::control::Event::SoftwareBreak
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct MCR(Word);
 
@@ -1315,7 +2678,7 @@ impl MemMapped for MCR {
     {
         interp.set_word_force_memory_backed(Self::ADDR, value);
 
-        if !value.bit(15) {
+        if !value.bit(Self::CLOCK_ENABLE_BIT) {
             interp.halt();
         }
 
@@ -1326,19 +2689,46 @@ impl MemMapped for MCR {
 impl MemMappedSpecial for MCR {}
 
 impl MCR {
+    pub const CLOCK_ENABLE_BIT: u32 = 15;
+    pub const DEBUG_BREAK_BIT: u32 = 14;
+
+    /// Clock enabled, no debug break, and no throttle.
+    pub const DEFAULT: Word = 1 << Self::CLOCK_ENABLE_BIT;
+
     fn set_running_bit<'a, I>(&mut self, interp: &mut I, bit: bool)
     where
         I: InstructionInterpreterPeripheralAccess<'a>,
         <I as Deref>::Target: Peripherals<'a>,
     {
-        self.0 = (self.0 & (!WORD_MAX_VAL.select(15..15))) | ((bit as Word) << 15);
+        self.0 = (self.0 & (!WORD_MAX_VAL.select(15..15))) | ((bit as Word) << Self::CLOCK_ENABLE_BIT);
 
         // Don't return a `WriteAttempt` since MCR accesses don't produce ACVs (and are hence infallible).
         self.write_current_value(interp).unwrap();
     }
 
     pub fn is_running(&self) -> bool {
-        self.0.bit(15)
+        self.0.bit(Self::CLOCK_ENABLE_BIT)
+    }
+
+    pub fn debug_break_requested(&self) -> bool {
+        self.0.bit(Self::DEBUG_BREAK_BIT)
+    }
+
+    pub fn clear_debug_break<'a, I>(&mut self, interp: &mut I)
+    where
+        I: InstructionInterpreterPeripheralAccess<'a>,
+        <I as Deref>::Target: Peripherals<'a>,
+    {
+        self.0 &= !(1 << Self::DEBUG_BREAK_BIT);
+
+        // Doesn't go through `set` so there's no chance of halting.
+        interp.set_word_force_memory_backed(Self::ADDR, self.0);
+    }
+
+    /// The number of instructions to run per tick (0 if the simulator should
+    /// pick).
+    pub fn throttle(&self) -> u8 {
+        self.0.u8(0..7)
     }
 
     pub fn halt<'a, I>(&mut self, interp: &mut I)
@@ -0,0 +1,211 @@
+//! A registry for memory mapped devices that aren't built into the simulator.
+//!
+//! The simulator's own device registers (the keyboard and display registers,
+//! the GPIO, ADC, PWM, timer and clock registers, the PSR/MCR, etc.) are
+//! spelled out in [`mem_mapped`](crate::mem_mapped). Other devices (i.e. custom
+//! lab peripherals) can be attached to the rest of the memory mapped region
+//! (`0xFE00` to `0xFFFF`) at runtime using
+//! [`Interpreter::register_mmio_device`](crate::interp::Interpreter::register_mmio_device).
+//!
+//! A device is given a contiguous (inclusive) range of addresses; reads and
+//! writes to addresses in that range are passed along to the device's
+//! [`read`](MmioDevice::read) and [`write`](MmioDevice::write) functions along
+//! with the offset of the address within the range.
+//!
+//! Devices can optionally raise interrupts. Each device that does is given an
+//! interrupt vector and a priority; as with the built-in devices, the interrupt
+//! is taken when the device [says](MmioDevice::interrupt_pending) it has one
+//! pending and the priority is greater than the priority the processor is
+//! currently running at. When a built-in device and a registered device both
+//! have interrupts pending at the same priority level, the built-in device
+//! goes first.
+//!
+//! Devices are held by (shared) reference so that whoever registered the
+//! device can still get at it; this also means devices have to use interior
+//! mutability (i.e. atomics or a `Mutex`) for their state.
+
+use lc3_isa::{Addr, Word, MEM_MAPPED_START_ADDR};
+
+use core::fmt::{self, Debug};
+use core::ops::RangeInclusive;
+
+/// The maximum number of devices that can be registered with a single
+/// interpreter.
+pub const MAX_MMIO_DEVICES: usize = 8;
+
+/// The highest priority an interrupt can have (priorities are 3 bits).
+pub const MAX_INTERRUPT_PRIORITY: u8 = 7;
+
+/// A memory mapped device.
+pub trait MmioDevice {
+    /// Reads the register at `offset` (relative to the start of the address
+    /// range the device was registered with).
+    fn read(&self, offset: Addr) -> Word;
+
+    /// Writes to the register at `offset` (relative to the start of the
+    /// address range the device was registered with).
+    fn write(&self, offset: Addr, word: Word);
+
+    /// Returns true if the device has an interrupt pending (and interrupts for
+    /// the device are enabled).
+    ///
+    /// Only called for devices that were registered with an
+    /// [`MmioInterrupt`].
+    fn interrupt_pending(&self) -> bool { false }
+
+    /// Called once the device's interrupt has been taken.
+    fn reset_interrupt_flag(&self) { }
+
+    /// Called when the interpreter is reset.
+    fn reset(&self) { }
+}
+
+/// The interrupt vector and priority of a device's interrupt.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MmioInterrupt {
+    pub vector: u8,
+    /// Must be in [1, 7]; an interrupt with a priority of 0 could never fire.
+    pub priority: u8,
+}
+
+/// Reasons a device can't be registered.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MmioRegistrationError {
+    /// The given range is empty or isn't entirely within the memory mapped
+    /// region.
+    InvalidRange(RangeInclusive<Addr>),
+    /// The given range includes this address which belongs to one of the
+    /// simulator's built-in device registers.
+    OverlapsBuiltInDevice(Addr),
+    /// The given range includes this address which belongs to an already
+    /// registered device.
+    OverlapsRegisteredDevice(Addr),
+    /// Interrupt priorities must be in [1, 7].
+    InvalidPriority(u8),
+    /// There's no room for another device (see [`MAX_MMIO_DEVICES`]).
+    RegistryFull,
+}
+
+struct MmioDeviceEntry<'d> {
+    start: Addr,
+    end: Addr,
+    device: &'d (dyn MmioDevice + Sync + 'd),
+    interrupt: Option<MmioInterrupt>,
+}
+
+impl Debug for MmioDeviceEntry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MmioDeviceEntry")
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .field("interrupt", &self.interrupt)
+            .finish()
+    }
+}
+
+/// The devices that have been attached to an interpreter.
+#[derive(Debug)]
+pub struct MmioRegistry<'d> {
+    devices: [Option<MmioDeviceEntry<'d>>; MAX_MMIO_DEVICES],
+}
+
+impl Default for MmioRegistry<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'d> MmioRegistry<'d> {
+    pub const fn new() -> Self {
+        Self {
+            devices: [None, None, None, None, None, None, None, None],
+        }
+    }
+
+    /// Attaches `device` to `addrs`.
+    ///
+    /// `reserved` is the list of addresses that are already taken by built-in
+    /// devices.
+    pub(crate) fn register(
+        &mut self,
+        addrs: RangeInclusive<Addr>,
+        device: &'d (dyn MmioDevice + Sync + 'd),
+        interrupt: Option<MmioInterrupt>,
+        reserved: &[Addr],
+    ) -> Result<(), MmioRegistrationError> {
+        use MmioRegistrationError::*;
+
+        let (start, end) = (*addrs.start(), *addrs.end());
+        if start > end || start < MEM_MAPPED_START_ADDR {
+            return Err(InvalidRange(addrs));
+        }
+
+        if let Some(addr) = reserved.iter().find(|a| addrs.contains(a)) {
+            return Err(OverlapsBuiltInDevice(*addr));
+        }
+
+        if let Some(other) = self.entries().find(|d| d.start <= end && start <= d.end) {
+            return Err(OverlapsRegisteredDevice(start.max(other.start)));
+        }
+
+        if let Some(MmioInterrupt { priority, .. }) = interrupt {
+            if priority == 0 || priority > MAX_INTERRUPT_PRIORITY {
+                return Err(InvalidPriority(priority));
+            }
+        }
+
+        let slot = self.devices.iter_mut().find(|d| d.is_none()).ok_or(RegistryFull)?;
+        *slot = Some(MmioDeviceEntry { start, end, device, interrupt });
+
+        Ok(())
+    }
+
+    fn entries(&self) -> impl Iterator<Item = &MmioDeviceEntry<'d>> {
+        self.devices.iter().filter_map(|d| d.as_ref())
+    }
+
+    fn find(&self, addr: Addr) -> Option<&MmioDeviceEntry<'d>> {
+        self.entries().find(|d| d.start <= addr && addr <= d.end)
+    }
+
+    /// Returns `None` if no device is attached to `addr`.
+    pub fn read(&self, addr: Addr) -> Option<Word> {
+        self.find(addr).map(|d| d.device.read(addr - d.start))
+    }
+
+    /// Returns false if no device is attached to `addr`.
+    pub fn write(&self, addr: Addr, word: Word) -> bool {
+        self.find(addr).map(|d| d.device.write(addr - d.start, word)).is_some()
+    }
+
+    /// Finds the device with the highest priority pending interrupt whose
+    /// priority is greater than `current_priority`.
+    ///
+    /// Returns the index of the device (for use with
+    /// [`reset_interrupt_flag`](MmioRegistry::reset_interrupt_flag)) and the
+    /// device's interrupt.
+    pub fn pending_interrupt(&self, current_priority: u8) -> Option<(usize, MmioInterrupt)> {
+        self.devices
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, d)| d.as_ref().map(|d| (idx, d)))
+            .filter_map(|(idx, d)| d.interrupt.map(|int| (idx, d, int)))
+            .filter(|(_, _, int)| int.priority > current_priority)
+            .filter(|(_, d, _)| d.device.interrupt_pending())
+            // `max_by_key` returns the last max; we want the first.
+            .fold(None, |best: Option<(usize, MmioInterrupt)>, (idx, _, int)| match best {
+                Some((_, b)) if b.priority >= int.priority => best,
+                _ => Some((idx, int)),
+            })
+    }
+
+    pub fn reset_interrupt_flag(&self, idx: usize) {
+        if let Some(d) = &self.devices[idx] {
+            d.device.reset_interrupt_flag()
+        }
+    }
+
+    pub fn reset_devices(&self) {
+        self.entries().for_each(|d| d.device.reset())
+    }
+}
@@ -0,0 +1,80 @@
+//! The memory protection table (see [`lc3_traits::control::mpu`]).
+//!
+//! Instruction fetches need execute permission, loads need read permission,
+//! and stores need write permission; addresses that aren't in any region fall
+//! back to the usual user/supervisor rule. Fetches from data (read but no
+//! execute) and writes to code (execute but no write) raise their own
+//! exceptions; everything else that isn't allowed is an access control
+//! violation.
+//!
+//! The table only applies to the accesses instructions make: the machine's
+//! own accesses when it takes a trap, exception, or interrupt (reading the
+//! vector tables and pushing onto the supervisor stack) aren't checked.
+
+use lc3_isa::{
+    Addr, ACCESS_CONTROL_VIOLATION_EXCEPTION_VECTOR, EXECUTE_FROM_DATA_EXCEPTION_VECTOR,
+    MEM_MAPPED_START_ADDR, USER_PROGRAM_START_ADDR, WRITE_TO_CODE_EXCEPTION_VECTOR,
+};
+use lc3_traits::control::Idx;
+use lc3_traits::control::mpu::{MpuError, MpuRegion, NUM_MPU_REGIONS};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Access {
+    Read,
+    Write,
+    Execute,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Mpu {
+    regions: [Option<MpuRegion>; NUM_MPU_REGIONS],
+}
+
+impl Mpu {
+    pub(crate) const fn new() -> Self {
+        Self { regions: [None; NUM_MPU_REGIONS] }
+    }
+
+    pub(crate) fn get(&self, idx: Idx) -> Result<Option<MpuRegion>, MpuError> {
+        self.regions.get(idx as usize).copied().ok_or(MpuError::InvalidRegion)
+    }
+
+    pub(crate) fn set(&mut self, idx: Idx, region: Option<MpuRegion>) -> Result<(), MpuError> {
+        match (self.regions.get_mut(idx as usize), region) {
+            (Some(_), Some(r)) if r.end < r.start => Err(MpuError::InvalidRegion),
+            (Some(slot), region) => {
+                *slot = region;
+                Ok(())
+            }
+            (None, _) => Err(MpuError::InvalidRegion),
+        }
+    }
+
+    /// The exception `access` to `addr` raises, if it isn't allowed.
+    pub(crate) fn check(&self, addr: Addr, access: Access, in_user_mode: bool) -> Option<u8> {
+        use Access::*;
+
+        let region = self.regions.iter().rev().flatten().find(|r| r.contains(addr));
+        let perms = match region {
+            Some(region) => region.permissions(in_user_mode),
+            None => {
+                let outside_user_space = !(USER_PROGRAM_START_ADDR..MEM_MAPPED_START_ADDR).contains(&addr);
+
+                return if in_user_mode && outside_user_space {
+                    Some(ACCESS_CONTROL_VIOLATION_EXCEPTION_VECTOR)
+                } else {
+                    None
+                };
+            }
+        };
+
+        match access {
+            Read if perms.read => None,
+            Write if perms.write => None,
+            Execute if perms.execute => None,
+            Execute if perms.read => Some(EXECUTE_FROM_DATA_EXCEPTION_VECTOR),
+            Write if perms.execute => Some(WRITE_TO_CODE_EXCEPTION_VECTOR),
+            _ => Some(ACCESS_CONTROL_VIOLATION_EXCEPTION_VECTOR),
+        }
+    }
+}
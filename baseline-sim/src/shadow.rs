@@ -0,0 +1,142 @@
+//! Shadow "initialized" bits for memory and the registers, for catching reads
+//! of state that the program never set (i.e. a variable that was never given
+//! a value or a register that's used before anything was put in it).
+//!
+//! A word of memory is initialized if it was _loaded_ (part of a program
+//! loaded with [`load_program`], which only counts the words the program
+//! actually sets, or part of a page committed with the load API, which counts
+//! the whole page) or if it has been _written_ since the last cold reset. A
+//! register is initialized once it's been written; only R6 (the stack
+//! pointer) starts out initialized. The memory mapped region is always
+//! initialized.
+//!
+//! The bits are always kept up to date. With
+//! [detection](crate::interp::InstructionInterpreter::set_uninitialized_read_detection)
+//! on, the interpreter reports reads of uninitialized state as
+//! [`Error::UninitializedRead`]. Reads are instruction fetches, the loads
+//! that instructions and exceptions/interrupts make, and instructions'
+//! source registers, with two exceptions that keep the usual idioms quiet:
+//! the source register of a store (so saving registers that haven't been set
+//! yet, like the OS's trap routines do, is fine) and `AND` with `#0`.
+//!
+//! [`load_program`]: crate::interp::Interpreter::load_program
+//! [`Error::UninitializedRead`]: lc3_traits::error::Error::UninitializedRead
+
+use lc3_isa::{Addr, Instruction, Reg, ADDR_SPACE_SIZE_IN_WORDS, MEM_MAPPED_START_ADDR};
+use lc3_traits::control::load::{PageIndex, PAGE_SIZE_IN_WORDS};
+
+const WORDS: usize = ADDR_SPACE_SIZE_IN_WORDS / 64;
+
+#[derive(Debug, Clone)]
+struct Bits([u64; WORDS]);
+
+impl Bits {
+    const fn new() -> Self {
+        Self([0; WORDS])
+    }
+
+    fn get(&self, addr: Addr) -> bool {
+        (self.0[addr as usize / 64] >> (addr % 64)) & 1 == 1
+    }
+
+    fn set(&mut self, addr: Addr, bit: bool) {
+        let (word, mask) = (&mut self.0[addr as usize / 64], 1 << (addr % 64));
+
+        if bit { *word |= mask } else { *word &= !mask }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Shadow {
+    loaded: Bits,
+    written: Bits,
+    regs: u8,
+    // The instruction that's running (for reports).
+    pc: Addr,
+}
+
+impl Shadow {
+    pub(crate) const fn new() -> Self {
+        Self {
+            loaded: Bits::new(),
+            written: Bits::new(),
+            regs: Self::INITIAL_REGS,
+            pc: 0,
+        }
+    }
+
+    const INITIAL_REGS: u8 = 1 << 6; // R6
+
+    pub(crate) fn is_initialized(&self, addr: Addr) -> bool {
+        addr >= MEM_MAPPED_START_ADDR || self.loaded.get(addr) || self.written.get(addr)
+    }
+
+    pub(crate) fn write(&mut self, addr: Addr) {
+        self.written.set(addr, true)
+    }
+
+    /// Replaces whatever was at `addr`; `set` is whether the new word counts
+    /// as initialized.
+    pub(crate) fn load(&mut self, addr: Addr, set: bool) {
+        self.loaded.set(addr, set);
+        self.written.set(addr, false);
+    }
+
+    pub(crate) fn load_page(&mut self, page_idx: PageIndex) {
+        let start = (page_idx as Addr) * PAGE_SIZE_IN_WORDS;
+        for offset in 0..PAGE_SIZE_IN_WORDS {
+            self.load(start + offset, true);
+        }
+    }
+
+    /// Whether every word in the page counts as loaded.
+    pub(crate) fn page_is_loaded(&self, page_idx: PageIndex) -> bool {
+        let start = (page_idx as Addr) * PAGE_SIZE_IN_WORDS;
+        (start..start + PAGE_SIZE_IN_WORDS).all(|addr| self.loaded.get(addr))
+    }
+
+    pub(crate) fn pc(&self) -> Addr {
+        self.pc
+    }
+
+    pub(crate) fn set_pc(&mut self, pc: Addr) {
+        self.pc = pc;
+    }
+
+    pub(crate) fn reg_is_initialized(&self, reg: Reg) -> bool {
+        (self.regs >> u8::from(reg)) & 1 == 1
+    }
+
+    pub(crate) fn write_reg(&mut self, reg: Reg) {
+        self.regs |= 1 << u8::from(reg)
+    }
+
+    pub(crate) fn write_all_regs(&mut self) {
+        self.regs = !0;
+    }
+
+    /// Forgets the registers (and, for cold resets, what's been written to
+    /// memory).
+    pub(crate) fn reset(&mut self, cold: bool) {
+        if cold {
+            self.written = Bits::new();
+        }
+
+        self.regs = Self::INITIAL_REGS;
+    }
+}
+
+/// The registers `insn` reads, as far as detection goes (see the module docs).
+pub(crate) fn source_regs(insn: &Instruction) -> [Option<Reg>; 2] {
+    use Instruction::*;
+
+    match *insn {
+        AddReg { sr1, sr2, .. } | AndReg { sr1, sr2, .. } => [Some(sr1), Some(sr2)],
+        AndImm { imm5: 0, .. } => [None, None],
+        AddImm { sr1, .. } | AndImm { sr1, .. } => [Some(sr1), None],
+        Not { sr, .. } => [Some(sr), None],
+        Jmp { base } | Jsrr { base } | Ldr { base, .. } | Str { base, .. } => [Some(base), None],
+        Ret => [Some(Reg::R7), None],
+        _ => [None, None],
+    }
+}
@@ -1,29 +1,41 @@
 //! TODO!
 
 use crate::interp::{InstructionInterpreter, InstructionInterpreterPeripheralAccess, MachineState};
-use crate::mem_mapped::{MemMapped, KBDR};
+use crate::mem_mapped::{MemMapped, KBDR, MCR};
 
-use lc3_isa::{Addr, Reg, Word};
+use lc3_isa::{Addr, Instruction, Reg, Word, MEM_MAPPED_START_ADDR};
 use lc3_traits::control::{Control, Event, State, UnifiedRange, Idx, ProcessorMode};
+use lc3_traits::control::boot::{BootConfig, ResetKind, ResetError};
 use lc3_traits::control::control::{MAX_BREAKPOINTS, MAX_MEMORY_WATCHPOINTS, MAX_CALL_STACK_DEPTH};
-use lc3_traits::control::metadata::{Identifier, ProgramMetadata, DeviceInfo, Version};
+use lc3_traits::control::mpu::{MpuRegion, MpuError};
+use lc3_traits::control::metadata::{Identifier, ProgramMetadata, DeviceInfo, Version, Capabilities};
 use lc3_traits::control::load::{
     PageIndex, PageWriteStart, StartPageWriteError, PageChunkError,
     FinishPageWriteError, LoadApiSession, Offset, CHUNK_SIZE_IN_WORDS,
-    PAGE_SIZE_IN_WORDS, Index, hash_page, PageAccess,
+    PAGE_SIZE_IN_WORDS, Index, hash_page, PageAccess, CompressedChunk,
+    ChunkDecodeError, COMPRESSED_CHUNK_CAPACITY, PAGE_HASHES_PER_MESSAGE, PageOffset,
 };
 use lc3_traits::control::rpc::{
     EventFutureSharedStatePorcelain, SimpleEventFutureSharedState, EventFuture
 };
 use lc3_traits::error::Error;
-use lc3_traits::peripherals::adc::{Adc, AdcPinArr, AdcReadError, AdcState};
+use lc3_traits::peripherals::adc::{Adc, AdcMiscError, AdcPin, AdcPinArr, AdcReadError, AdcSource, AdcState};
 use lc3_traits::peripherals::clock::Clock;
+use lc3_traits::peripherals::display::{
+    Display, Color, DISPLAY_PIXELS, DISPLAY_PIXELS_PER_MESSAGE, DISPLAY_WIDTH,
+};
+use lc3_traits::peripherals::disk::{
+    Disk, DiskError, SectorIdx, DISK_WORDS_PER_MESSAGE, SECTOR_SIZE_IN_WORDS,
+};
 use lc3_traits::peripherals::gpio::{Gpio, GpioPinArr, GpioReadError, GpioState};
-use lc3_traits::peripherals::pwm::{Pwm, PwmPinArr, PwmState};
-use lc3_traits::peripherals::timers::{Timers, TimerArr, TimerMode, TimerState};
+use lc3_traits::peripherals::pwm::{Pwm, PwmConfig, PwmPinArr, PwmState};
+use lc3_traits::peripherals::rng::{Rng, RngError};
+use lc3_traits::peripherals::watchdog::{Watchdog, WatchdogError};
+use lc3_traits::peripherals::timers::{Timers, TimerArr, TimerMode, TimerStatus};
 use lc3_traits::peripherals::Peripherals;
 
 // use core::future::Future;
+use core::convert::TryFrom;
 use core::marker::PhantomData;
 use core::ops::Deref;
 use core::fmt::{self, Debug};
@@ -221,6 +233,59 @@ where
 
     }
 
+    fn get_max_compressed_chunk_size(&self) -> u8 {
+        COMPRESSED_CHUNK_CAPACITY as u8
+    }
+
+    // Pages that weren't loaded as a whole get a hash of 0 so that they're
+    // committed (and the words in them count as initialized) the same way
+    // they would be with a full load.
+    fn get_page_hashes(&self, first: PageIndex) -> [u64; PAGE_HASHES_PER_MESSAGE] {
+        let mut hashes = [0; PAGE_HASHES_PER_MESSAGE];
+        let mut page = [0; PAGE_SIZE_IN_WORDS as usize];
+
+        for (idx, hash) in hashes.iter_mut().enumerate() {
+            let p_idx = (first as usize) + idx;
+            if p_idx >= MEM_MAPPED_START_ADDR.page_idx() as usize { break; }
+            if !self.interp.page_is_loaded(p_idx as PageIndex) { continue; }
+
+            page.iter_mut().enumerate().for_each(|(offset, w)| {
+                *w = self.interp.read_committed_word(Index(p_idx as PageIndex).with_offset(offset as PageOffset))
+            });
+
+            *hash = hash_page(&page);
+        }
+
+        hashes
+    }
+
+    fn send_compressed_page_chunk(
+        &mut self,
+        offset: LoadApiSession<Offset>,
+        chunk: CompressedChunk,
+    ) -> Result<(), PageChunkError> {
+        use {LoadApiState::*, PageChunkError::*};
+
+        // Skipped words come from the committed copy of the page (that's what
+        // `get_page_hashes` describes):
+        let interp = &self.interp;
+
+        match self.load_api_state {
+            NoSession => Err(NoCurrentSession),
+            Session { page_idx, ref mut page, .. } => {
+                let received_address = Index(page_idx).with_offset(offset.get().0);
+
+                chunk
+                    .decompress(offset.get().0, page, |o| interp.read_committed_word(Index(page_idx).with_offset(o)))
+                    .map(|_| ())
+                    .map_err(|err| match err {
+                        ChunkDecodeError::Malformed => MalformedChunk { page: page_idx, received_address },
+                        ChunkDecodeError::CrossesPageBoundary => ChunkCrossesPageBoundary { page: page_idx, received_address },
+                    })
+            }
+        }
+    }
+
     fn finish_page_write(
         &mut self,
         page_token: LoadApiSession<PageIndex>,
@@ -381,6 +446,12 @@ where
         // lower values will improve response times.
         const STEPS_IN_A_TICK: usize = 100; // TODO: tune!
 
+        // Unless the program has asked to be throttled (see `MCR`):
+        let steps = match self.interp.get_special_reg::<MCR>().throttle() {
+            0 => STEPS_IN_A_TICK,
+            n => n as usize,
+        };
+
         use State::*;
 
         if let RunningUntilEvent = self.get_state() {
@@ -395,10 +466,10 @@ where
 //                return STEPS_IN_A_TICK;
 //            }
 
-            for _ in 0..STEPS_IN_A_TICK {
+            for _ in 0..steps {
                 if let Some(e) = self.step() {
                     // If we produced some event, we're no longer `RunningUntilEvent`.
-                    return STEPS_IN_A_TICK; // this is not accurate but this is allowed
+                    return steps; // this is not accurate but this is allowed
                 }
             }
         }
@@ -432,6 +503,21 @@ where
                     }
                 }
 
+                // And BRK instructions:
+                if self.interp.get_break_instructions_enabled() {
+                    let pc = self.get_pc();
+                    if let Ok(Instruction::Brk { code }) = Instruction::try_from(self.interp.get_word_force_memory_backed(pc)) {
+                        return (Paused, Some(Event::BreakInstruction { addr: pc, code }));
+                    }
+                }
+
+                // And software breaks (these wait for any breakpoint at the
+                // same spot to be reported first):
+                if self.interp.get_special_reg::<MCR>().debug_break_requested() {
+                    self.interp.get_special_reg::<MCR>().clear_debug_break(&mut self.interp);
+                    return (Paused, Some(Event::SoftwareBreak { addr: self.get_pc() }));
+                }
+
                 // And watchpoints:
                 if self.num_set_watchpoints > 0 {
                     for i in 0..self.watchpoints.len() {
@@ -566,6 +652,13 @@ where
     }
 
     fn reset(&mut self) {
+        let boot = self.interp.get_boot_config();
+        let _ = self.reset_with(ResetKind::Cold, boot);
+    }
+
+    // The simulator supports every kind of reset and any boot config so this
+    // always succeeds.
+    fn reset_with(&mut self, kind: ResetKind, boot: BootConfig) -> Result<(), ResetError> {
         self.interp.halt();
 
         self.unset_depth_condition();
@@ -576,7 +669,9 @@ where
 
         // TODO: unset + reset watchpoints here
 
-        InstructionInterpreter::reset(&mut self.interp);
+        InstructionInterpreter::reset_with(&mut self.interp, kind, boot);
+        // Unlike a reset by the watchdog, this one wasn't its fault:
+        Watchdog::set_caused_reset(self.interp.get_peripherals_mut(), false);
         self.state = State::Paused;
 
         // For now, we won't force all futures to have resolved on a reset.
@@ -586,6 +681,8 @@ where
         if let Some(s) = self.shared_state {
             s.reset()
         }
+
+        Ok(())
     }
 
     fn get_error(&self) -> Option<Error> {
@@ -608,32 +705,105 @@ where
         Adc::read_all(self.interp.get_peripherals())
     }
 
+    fn set_adc_source(&mut self, pin: AdcPin, source: AdcSource) -> Result<(), AdcMiscError> {
+        Adc::set_source(self.interp.get_peripherals_mut(), pin, source)
+    }
+
     fn get_timer_modes(&self) -> TimerArr<TimerMode> {
         Timers::get_modes(self.interp.get_peripherals())
     }
 
-    fn get_timer_states(&self) -> TimerArr<TimerState> {
-        Timers::get_states(self.interp.get_peripherals())
+    fn get_timer_states(&self) -> TimerArr<TimerStatus> {
+        Timers::get_statuses(self.interp.get_peripherals())
     }
 
     fn get_pwm_states(&self) -> PwmPinArr<PwmState> {
         Pwm::get_states(self.interp.get_peripherals())
     }
 
-    fn get_pwm_config(&self) -> PwmPinArr<u8> {
-        Pwm::get_duty_cycles(self.interp.get_peripherals())
+    fn get_pwm_config(&self) -> PwmPinArr<PwmConfig> {
+        Pwm::get_configs(self.interp.get_peripherals())
     }
 
     fn get_clock(&self) -> Word {
         Clock::get_milliseconds(self.interp.get_peripherals())
     }
 
+    fn get_display_pixels(&self, first: u16) -> [Color; DISPLAY_PIXELS_PER_MESSAGE] {
+        let mut pixels = [0; DISPLAY_PIXELS_PER_MESSAGE];
+        let display = self.interp.get_peripherals();
+
+        for (idx, p) in (first as usize..DISPLAY_PIXELS).zip(pixels.iter_mut()) {
+            let (x, y) = ((idx as Word) % DISPLAY_WIDTH, (idx as Word) / DISPLAY_WIDTH);
+            *p = Display::get_pixel(display, x, y).unwrap_or(0);
+        }
+
+        pixels
+    }
+
+    fn get_disk_num_sectors(&self) -> SectorIdx {
+        Disk::num_sectors(self.interp.get_peripherals())
+    }
+
+    fn get_disk_words(&self, sector: SectorIdx, first: u16) -> Result<[Word; DISK_WORDS_PER_MESSAGE], DiskError> {
+        let mut buf = [0; SECTOR_SIZE_IN_WORDS];
+        Disk::read_sector(self.interp.get_peripherals(), sector, &mut buf)?;
+
+        let mut words = [0; DISK_WORDS_PER_MESSAGE];
+        for (w, b) in words.iter_mut().zip(buf.iter().skip(first as usize)) {
+            *w = *b;
+        }
+
+        Ok(words)
+    }
+
+    fn set_rng_seed(&mut self, seed: u64) -> Result<(), RngError> {
+        Rng::set_seed(self.interp.get_peripherals_mut(), seed)
+    }
+
+    fn set_watchdog_disabled(&mut self, disabled: bool) -> Result<(), WatchdogError> {
+        if !Watchdog::is_present(self.interp.get_peripherals()) {
+            return Err(WatchdogError::NoWatchdog);
+        }
+
+        Watchdog::set_disabled_by_debugger(self.interp.get_peripherals_mut(), disabled);
+        Ok(())
+    }
+
+    fn set_break_instructions_enabled(&mut self, enabled: bool) -> Result<(), ()> {
+        self.interp.set_break_instructions_enabled(enabled);
+        Ok(())
+    }
+
+    fn get_cycle_count(&self) -> Result<u64, ()> {
+        self.interp.get_cycle_count().ok_or(())
+    }
+
+    fn set_uninitialized_read_detection(&mut self, enabled: bool) -> Result<(), ()> {
+        self.interp.set_uninitialized_read_detection(enabled);
+        Ok(())
+    }
+
+    fn set_mpu_region(&mut self, idx: Idx, region: Option<MpuRegion>) -> Result<(), MpuError> {
+        self.interp.set_mpu_region(idx, region)
+    }
+
+    fn get_mpu_region(&self, idx: Idx) -> Result<Option<MpuRegion>, MpuError> {
+        self.interp.get_mpu_region(idx)
+    }
+
     fn get_device_info(&self) -> DeviceInfo {
+        let capabilities = Capabilities {
+            display: Display::is_present(self.interp.get_peripherals()),
+            disk: Disk::is_present(self.interp.get_peripherals()),
+            ..Default::default()
+        };
+
         DeviceInfo::new(
             self.id(),
             I::VER,
             I::type_id(),
-            Default::default(), // TODO: when we add other capabilities
+            capabilities,
             Default::default(), // no proxies (yet)
         )
     }
@@ -0,0 +1,221 @@
+//! An (optional) model of how long instructions take, in cycles.
+//!
+//! The interpreter normally only counts instructions. With a [`TimingModel`]
+//! attached (see
+//! [`Interpreter::set_timing_model`](crate::interp::Interpreter::set_timing_model))
+//! it also keeps a cycle count: every instruction costs a fixed number of
+//! cycles for its opcode plus the cost of each memory access it makes (the
+//! fetch, loads and stores, stack pushes and pops for traps and interrupts,
+//! etc.). Accesses to memory mapped devices have their own cost and accesses
+//! to regular memory can optionally go through a [cache](CacheConfig).
+//!
+//! The count is available through
+//! [`InstructionInterpreter::get_cycle_count`](crate::interp::InstructionInterpreter::get_cycle_count)
+//! (and [`Control::get_cycle_count`](lc3_traits::control::Control::get_cycle_count))
+//! and goes back to 0 when the machine is reset.
+//!
+//! Normally the interpreter tells the [`Clock`] that one instruction went by
+//! for every instruction it executes; with
+//! [`drive_clock`](TimingModel::drive_clock) set it passes along the number of
+//! cycles instead, so that peripherals that keep virtual time (timers, the
+//! clock, etc.) run on cycles (i.e. `instructions_per_ms` becomes cycles per
+//! millisecond).
+//!
+//! [`Clock`]: lc3_traits::peripherals::clock::Clock
+
+use lc3_isa::{Addr, Word, MEM_MAPPED_START_ADDR};
+
+use core::cell::Cell;
+
+/// The most lines a [cache](CacheConfig) can have.
+pub const MAX_CACHE_LINES: usize = 64;
+
+/// A direct mapped, write-through cache in front of memory (but not in front
+/// of memory mapped devices).
+///
+/// Reads (including instruction fetches) that hit cost
+/// [`hit_cost`](CacheConfig::hit_cost) cycles; reads that miss cost a
+/// [memory access](TimingModel::memory_access) and fill in the line. Writes
+/// always cost a memory access and don't fill in lines.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CacheConfig {
+    lines: usize,
+    line_size: Word,
+    hit_cost: u64,
+}
+
+impl CacheConfig {
+    /// Panics if `lines` isn't between 1 and [`MAX_CACHE_LINES`] or if
+    /// `line_size` (in words) isn't a power of two.
+    pub const fn new(lines: usize, line_size: Word, hit_cost: u64) -> Self {
+        assert!(lines > 0 && lines <= MAX_CACHE_LINES, "unsupported number of cache lines");
+        assert!(line_size.is_power_of_two(), "cache lines must be a power of two words long");
+
+        Self { lines, line_size, hit_cost }
+    }
+
+    /// The number of lines in the cache.
+    pub const fn lines(&self) -> usize { self.lines }
+    /// The number of words in a line.
+    pub const fn line_size(&self) -> Word { self.line_size }
+    /// The number of cycles a read that hits takes.
+    pub const fn hit_cost(&self) -> u64 { self.hit_cost }
+}
+
+/// How many cycles things take.
+///
+/// The [default](TimingModel::new) is loosely based on the LC-3's state
+/// machine (appendix C of Patt and Patel): opcodes cost the number of states
+/// they go through (including fetch and decode) other than the ones that wait
+/// on memory and memory accesses take 5 cycles.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TimingModel {
+    /// The cycles each opcode takes (indexed by opcode), not counting memory
+    /// accesses.
+    pub opcode_costs: [u64; 16],
+    /// The cycles an access to memory takes (when there's no cache or when
+    /// the cache misses).
+    pub memory_access: u64,
+    /// The cycles an access to a memory mapped device takes.
+    pub mmio_access: u64,
+    /// The cache, if there is one.
+    pub cache: Option<CacheConfig>,
+    /// Whether time (for [peripherals that keep virtual time](lc3_traits::peripherals::clock::Clock::instructions_executed))
+    /// moves forward by cycles instead of by instructions.
+    pub drive_clock: bool,
+}
+
+impl Default for TimingModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimingModel {
+    /// The default costs (see above); no cache, and the clock isn't driven.
+    pub const fn new() -> Self {
+        Self {
+            opcode_costs: [
+                4, // BR
+                4, // ADD
+                5, // LD
+                5, // ST
+                5, // JSR(R)
+                4, // AND
+                5, // LDR
+                5, // STR
+                9, // RTI
+                4, // NOT
+                6, // LDI
+                6, // STI
+                4, // JMP
+                4, // (reserved)
+                4, // LEA
+                5, // TRAP
+            ],
+            memory_access: 5,
+            mmio_access: 5,
+            cache: None,
+            drive_clock: false,
+        }
+    }
+
+    /// The same model with `cache` in front of memory.
+    pub const fn with_cache(self, cache: CacheConfig) -> Self {
+        Self { cache: Some(cache), ..self }
+    }
+
+    /// The same model, driving the clock (see
+    /// [`drive_clock`](TimingModel::drive_clock)).
+    pub const fn driving_clock(self) -> Self {
+        Self { drive_clock: true, ..self }
+    }
+}
+
+// The model and its state. Memory accesses (`get_word`) only get `&self` so
+// the state lives in `Cell`s.
+#[derive(Debug)]
+pub(crate) struct Timing {
+    model: TimingModel,
+    cycles: Cell<u64>,
+    // How many of `cycles` the clock has been told about.
+    clocked: Cell<u64>,
+    // The tag of the block in each line.
+    lines: [Cell<Option<Word>>; MAX_CACHE_LINES],
+}
+
+impl Timing {
+    pub(crate) const fn new(model: TimingModel) -> Self {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const EMPTY: Cell<Option<Word>> = Cell::new(None);
+
+        Self {
+            model,
+            cycles: Cell::new(0),
+            clocked: Cell::new(0),
+            lines: [EMPTY; MAX_CACHE_LINES],
+        }
+    }
+
+    pub(crate) fn model(&self) -> TimingModel {
+        self.model
+    }
+
+    pub(crate) fn cycles(&self) -> u64 {
+        self.cycles.get()
+    }
+
+    fn charge(&self, cycles: u64) {
+        self.cycles.set(self.cycles.get().wrapping_add(cycles))
+    }
+
+    pub(crate) fn opcode(&self, word: Word) {
+        self.charge(self.model.opcode_costs[(word >> 12) as usize])
+    }
+
+    pub(crate) fn read(&self, addr: Addr) {
+        if addr >= MEM_MAPPED_START_ADDR {
+            return self.charge(self.model.mmio_access);
+        }
+
+        let cost = match self.model.cache {
+            Some(cache) => {
+                let tag = addr / cache.line_size;
+                let line = &self.lines[tag as usize % cache.lines];
+
+                if line.replace(Some(tag)) == Some(tag) {
+                    cache.hit_cost
+                } else {
+                    self.model.memory_access
+                }
+            }
+            None => self.model.memory_access,
+        };
+
+        self.charge(cost)
+    }
+
+    pub(crate) fn write(&self, addr: Addr) {
+        self.charge(if addr >= MEM_MAPPED_START_ADDR {
+            self.model.mmio_access
+        } else {
+            self.model.memory_access
+        })
+    }
+
+    /// The cycles that have gone by since the last time this was called, if
+    /// the clock is being driven.
+    pub(crate) fn unclocked(&self) -> Option<u64> {
+        if !self.model.drive_clock {
+            return None;
+        }
+
+        let cycles = self.cycles.get();
+        Some(cycles.wrapping_sub(self.clocked.replace(cycles)))
+    }
+
+    /// Zeroes the count and empties the cache.
+    pub(crate) fn reset(&mut self) {
+        *self = Self::new(self.model);
+    }
+}
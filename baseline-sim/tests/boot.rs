@@ -0,0 +1,110 @@
+use lc3_baseline_sim::interp::{Interpreter, InterpreterBuilder, InstructionInterpreter};
+use lc3_baseline_sim::sim::Simulator;
+use lc3_isa::{Reg::*, Word, MEM_MAPPED_START_ADDR, OS_START_ADDR, PSR, RESET_VECTOR_ADDR};
+use lc3_traits::control::Control;
+use lc3_traits::control::boot::{BootConfig, BootEntry, ResetKind};
+use lc3_traits::control::rpc::SimpleEventFutureSharedState;
+use lc3_test_infrastructure::{assert_eq, with_larger_stack, MemoryShim, PeripheralsShim};
+
+type Interp = Interpreter<'static, MemoryShim, PeripheralsShim<'static>>;
+type Sim<I> = Simulator<'static, 'static, I, SimpleEventFutureSharedState>;
+
+const BSP: Word = 0xFFFA;
+
+fn interp() -> Interp {
+    InterpreterBuilder::new()
+        .with_defaults()
+        .build()
+}
+
+#[test]
+fn defaults() { with_larger_stack(None, || {
+    let interp = interp();
+
+    // No reset vector, so we start at the OS:
+    assert_eq!(interp.get_pc(), OS_START_ADDR);
+    assert_eq!(interp.get_word_unchecked(PSR), BootConfig::OS_PSR);
+    assert_eq!(interp.get_register(R6), 0);
+    assert_eq!(interp.get_boot_config(), BootConfig::default());
+})}
+
+#[test]
+fn reset_vector() { with_larger_stack(None, || {
+    let mut interp = interp();
+    interp.set_word_unchecked(RESET_VECTOR_ADDR, 0x1234);
+
+    // Warm resets keep memory (and thus the reset vector) around:
+    interp.reset_with(ResetKind::Warm, BootConfig::default());
+    assert_eq!(interp.get_pc(), 0x1234);
+    assert_eq!(interp.get_word_unchecked(RESET_VECTOR_ADDR), 0x1234);
+
+    // Cold resets don't:
+    interp.reset_with(ResetKind::Cold, BootConfig::default());
+    assert_eq!(interp.get_pc(), OS_START_ADDR);
+    assert_eq!(interp.get_word_unchecked(RESET_VECTOR_ADDR), 0);
+
+    // An explicit entry point skips the vector:
+    interp.set_word_unchecked(RESET_VECTOR_ADDR, 0x1234);
+    interp.reset_with(ResetKind::Warm, BootConfig { entry: BootEntry::Addr(0x0400), ..BootConfig::default() });
+    assert_eq!(interp.get_pc(), 0x0400);
+})}
+
+#[test]
+fn user_program() { with_larger_stack(None, || {
+    let mut interp = interp();
+    interp.set_register(R3, 7);
+
+    interp.reset_with(ResetKind::Warm, BootConfig::user_program(0x3000));
+    assert_eq!(interp.get_pc(), 0x3000);
+    assert_eq!(interp.get_word_unchecked(PSR), BootConfig::USER_PSR);
+    assert_eq!(interp.get_register(R6), MEM_MAPPED_START_ADDR);
+    assert_eq!(interp.get_word_unchecked(BSP), 0x3000);
+    assert_eq!(interp.get_register(R3), 0);
+
+    // The boot config sticks around for later resets:
+    interp.set_pc(0x4000);
+    interp.reset();
+    assert_eq!(interp.get_pc(), 0x3000);
+    assert_eq!(interp.get_word_unchecked(PSR), BootConfig::USER_PSR);
+})}
+
+#[test]
+fn builder() { with_larger_stack(None, || {
+    let interp: Interp = InterpreterBuilder::new()
+        .with_defaults()
+        .with_boot_config(BootConfig { ssp: 0x2FFF, ..BootConfig::os() })
+        .build();
+    assert_eq!(interp.get_register(R6), 0x2FFF);
+
+    // The starting PC and registers override the boot config (only for the
+    // first boot):
+    let mut interp: Interp = InterpreterBuilder::new()
+        .with_defaults()
+        .with_pc(0x3000)
+        .with_regs([1, 2, 3, 4, 5, 6, 7, 8])
+        .build();
+    assert_eq!(interp.get_pc(), 0x3000);
+    assert_eq!(interp.get_register(R7), 8);
+
+    interp.reset();
+    assert_eq!(interp.get_pc(), OS_START_ADDR);
+    assert_eq!(interp.get_register(R7), 0);
+})}
+
+#[test]
+fn control() { with_larger_stack(None, || {
+    let state = Box::leak(Box::new(SimpleEventFutureSharedState::new()));
+    let mut sim = Sim::<Interp>::new_with_state(interp(), state);
+
+    sim.write_word(0x3000, 0xABCD);
+    assert_eq!(sim.reset_with(ResetKind::Warm, BootConfig::user_program(0x3000)), Ok(()));
+    assert_eq!(sim.get_pc(), 0x3000);
+    assert_eq!(sim.read_word(0x3000), 0xABCD);
+
+    sim.reset();
+    assert_eq!(sim.get_pc(), 0x3000);
+    assert_eq!(sim.read_word(0x3000), 0);
+
+    assert_eq!(sim.reset_with(ResetKind::Cold, BootConfig::default()), Ok(()));
+    assert_eq!(sim.get_pc(), OS_START_ADDR);
+})}
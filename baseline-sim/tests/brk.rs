@@ -0,0 +1,113 @@
+use lc3_baseline_sim::interp::{Interpreter, InterpreterBuilder, InstructionInterpreter};
+use lc3_baseline_sim::sim::Simulator;
+use lc3_isa::{Instruction, Reg::*, Word, ILLEGAL_OPCODE_EXCEPTION_VECTOR};
+use lc3_traits::control::{Control, Event, State};
+use lc3_traits::control::rpc::SimpleEventFutureSharedState;
+use lc3_test_infrastructure::{assert_eq, with_larger_stack, MemoryShim, PeripheralsShim};
+
+type Interp = Interpreter<'static, MemoryShim, PeripheralsShim<'static>>;
+type Sim = Simulator<'static, 'static, Interp, SimpleEventFutureSharedState>;
+
+// Counts in R0, forever.
+const PROGRAM: [Instruction; 3] = [
+    Instruction::AddImm { dr: R0, sr1: R0, imm5: 1 },
+    Instruction::AddImm { dr: R0, sr1: R0, imm5: 1 },
+    Instruction::Br { n: true, z: true, p: true, offset9: -3 },
+];
+
+fn sim() -> Sim {
+    let state = Box::leak(Box::new(SimpleEventFutureSharedState::new()));
+    let mut sim = Sim::new_with_state(Default::default(), state);
+
+    for (addr, insn) in (0x3000..).zip(PROGRAM.iter()) {
+        sim.write_word(addr, Word::from(*insn));
+    }
+    sim.set_pc(0x3000);
+
+    sim
+}
+
+#[test]
+fn illegal_by_default() { with_larger_stack(None, || {
+    let mut interp: Interp = InterpreterBuilder::new()
+        .with_defaults()
+        .build();
+    assert!(!interp.get_break_instructions_enabled());
+
+    let handler = 0x0100 + ILLEGAL_OPCODE_EXCEPTION_VECTOR as Word;
+    interp.set_word_unchecked(handler, 0x1000);
+    interp.set_word_unchecked(0x3000, Word::from(Instruction::new_brk(0)));
+    interp.set_pc(0x3000);
+    interp[R6] = 0x2FF0;
+
+    let _ = interp.step();
+    assert_eq!(interp.get_pc(), 0x1000);
+})}
+
+#[test]
+fn builder() { with_larger_stack(None, || {
+    let mut interp: Interp = InterpreterBuilder::new()
+        .with_defaults()
+        .with_break_instructions(true)
+        .build();
+    assert!(interp.get_break_instructions_enabled());
+
+    // Executing a BRK doesn't go anywhere:
+    interp.set_word_unchecked(0x3000, Word::from(Instruction::new_brk(0)));
+    interp.set_pc(0x3000);
+    let _ = interp.step();
+    let _ = interp.step();
+    assert_eq!(interp.get_pc(), 0x3000);
+
+    // And the setting survives resets:
+    interp.reset();
+    assert!(interp.get_break_instructions_enabled());
+})}
+
+#[test]
+fn patching() { with_larger_stack(None, || {
+    let mut sim = sim();
+    assert_eq!(sim.set_break_instructions_enabled(true), Ok(()));
+
+    // Patch in more breakpoints than the simulator's breakpoint slots:
+    let addrs = [0x3001, 0x3002];
+    let originals: Vec<Word> = addrs.iter().map(|a| sim.read_word(*a)).collect();
+    for (code, addr) in addrs.iter().enumerate() {
+        sim.write_word(*addr, Word::from(Instruction::new_brk(code as u8)));
+    }
+
+    assert_eq!(sim.step(), Some(Event::BreakInstruction { addr: 0x3001, code: 0 }));
+    assert_eq!(sim.get_state(), State::Paused);
+
+    // A BRK that's left in place stops the machine again:
+    assert_eq!(sim.step(), Some(Event::BreakInstruction { addr: 0x3001, code: 0 }));
+    assert_eq!(sim.get_register(R0), 1);
+
+    // Swapping the original back in lets execution continue:
+    sim.write_word(addrs[0], originals[0]);
+    assert_eq!(sim.step(), Some(Event::BreakInstruction { addr: 0x3002, code: 1 }));
+    assert_eq!(sim.get_register(R0), 2);
+
+    sim.write_word(addrs[1], originals[1]);
+    let _ = sim.set_breakpoint(0x3001).unwrap();
+    let _ = sim.run_until_event();
+    let _ = sim.tick();
+    assert_eq!(sim.get_pc(), 0x3001);
+    assert_eq!(sim.get_register(R0), 3);
+})}
+
+#[test]
+fn disabled() { with_larger_stack(None, || {
+    let mut sim = sim();
+
+    // Without the mode BRK is an illegal instruction, which the debugger
+    // doesn't hear about:
+    let handler = 0x0100 + ILLEGAL_OPCODE_EXCEPTION_VECTOR as Word;
+    sim.write_word(handler, 0x1000);
+    sim.write_word(0x3001, Word::from(Instruction::new_brk(0)));
+    sim.set_register(R6, 0x2FF0);
+
+    assert_eq!(sim.step(), None);
+    assert_eq!(sim.step(), None);
+    assert_eq!(sim.get_pc(), 0x1000);
+})}
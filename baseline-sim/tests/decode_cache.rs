@@ -0,0 +1,106 @@
+use lc3_baseline_sim::interp::{Interpreter, InterpreterBuilder, InstructionInterpreter};
+use lc3_isa::{Instruction, Reg::*, Word, ADDR_SPACE_SIZE_IN_WORDS};
+use lc3_traits::control::boot::ResetKind;
+use lc3_traits::control::load::PAGE_SIZE_IN_WORDS;
+use lc3_test_infrastructure::{assert_eq, with_larger_stack, MemoryShim, PeripheralsShim};
+
+type Interp = Interpreter<'static, MemoryShim, PeripheralsShim<'static>>;
+
+const INC_1: Instruction = Instruction::AddImm { dr: R0, sr1: R0, imm5: 1 };
+const INC_2: Instruction = Instruction::AddImm { dr: R0, sr1: R0, imm5: 2 };
+
+// Runs the instruction at 0x3000 and returns what it added to R0.
+fn run_once(interp: &mut Interp) -> Word {
+    let before = interp[R0];
+    interp.set_pc(0x3000);
+    let _ = interp.step();
+
+    interp[R0].wrapping_sub(before)
+}
+
+fn interp() -> Interp {
+    let mut mem = [0; ADDR_SPACE_SIZE_IN_WORDS];
+    mem[0x3000] = Word::from(INC_1);
+
+    let mut interp: Interp = InterpreterBuilder::new()
+        .with_defaults()
+        .with_memory(MemoryShim::new(mem))
+        .build();
+    interp.reset();
+
+    interp
+}
+
+#[test]
+fn writes() { with_larger_stack(None, || {
+    let mut interp = interp();
+    assert_eq!(run_once(&mut interp), 1);
+    assert_eq!(run_once(&mut interp), 1);
+
+    interp.set_word_unchecked(0x3000, Word::from(INC_2));
+    assert_eq!(run_once(&mut interp), 2);
+
+    let _ = interp.set_word(0x3000, Word::from(INC_1));
+    assert_eq!(run_once(&mut interp), 1);
+
+    interp.set_word_force_memory_backed(0x3000, Word::from(INC_2));
+    assert_eq!(run_once(&mut interp), 2);
+})}
+
+#[test]
+fn self_modifying() { with_larger_stack(None, || {
+    let mut interp = interp();
+
+    // Turns the `ADD R0, R0, #1` at the top of the loop into an
+    // `ADD R0, R0, #2` the first time around:
+    let program = [
+        INC_1,
+        Instruction::new_ld(R1, 3),
+        Instruction::new_st(R1, -3),
+        Instruction::new_br(true, true, true, -4),
+    ];
+    for (addr, insn) in (0x3000..).zip(program.iter()) {
+        interp.set_word_unchecked(addr, Word::from(*insn));
+    }
+    interp.set_word_unchecked(0x3005, Word::from(INC_2));
+
+    interp.set_pc(0x3000);
+    for _ in 0..(program.len() * 2) {
+        let _ = interp.step();
+    }
+    assert_eq!(interp[R0], 3);
+})}
+
+#[test]
+fn pages_and_resets() { with_larger_stack(None, || {
+    let mut interp = interp();
+    assert_eq!(run_once(&mut interp), 1);
+
+    // Other pages that share the cached page's spot evict it:
+    for page in 1..8 {
+        let addr = 0x3000 + page * PAGE_SIZE_IN_WORDS;
+        interp.set_word_unchecked(addr, Word::from(INC_2));
+        interp.set_pc(addr);
+        let _ = interp.step();
+    }
+    assert_eq!(run_once(&mut interp), 1);
+
+    // Cold resets go back to the memory image (under the cache):
+    interp.set_word_unchecked(0x3000, Word::from(INC_2));
+    assert_eq!(run_once(&mut interp), 2);
+    interp.reset();
+    assert_eq!(run_once(&mut interp), 1);
+
+    // Warm resets don't touch memory (or the cache):
+    interp.set_word_unchecked(0x3000, Word::from(INC_2));
+    let boot = interp.get_boot_config();
+    interp.reset_with(ResetKind::Warm, boot);
+    assert_eq!(run_once(&mut interp), 2);
+
+    // Committing a page replaces the image:
+    let mut page = [0; PAGE_SIZE_IN_WORDS as usize];
+    page[0] = Word::from(INC_2);
+    interp.commit_page(0x30, &page);
+    interp.reset();
+    assert_eq!(run_once(&mut interp), 2);
+})}
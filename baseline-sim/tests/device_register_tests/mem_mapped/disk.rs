@@ -0,0 +1,205 @@
+use super::*;
+
+use lc3_traits::error::Error;
+use lc3_traits::peripherals::disk::{Disk, DiskError, SECTOR_SIZE_IN_WORDS};
+use lc3_baseline_sim::mem_mapped::{
+    MemMapped, Interrupt,
+    DKSR, DKSR_ADDR, DKCR_ADDR, DKAR_ADDR, DKBR_ADDR, DKNR_ADDR,
+    DISK_READ_CMD, DISK_WRITE_CMD, DISK_INT_VEC,
+    PSR,
+};
+use lc3_isa::INTERRUPT_VECTOR_TABLE_START_ADDR;
+
+use std::sync::RwLock;
+
+single_test! {
+    present,
+    prefill: {
+        0x3010: DKNR_ADDR,
+        0x3011: DKSR_ADDR,
+    },
+    insns: [
+        { LDI R0, #0xF },   // Number of sectors
+        { LDI R1, #0xF },   // Status: present
+    ],
+    steps: 2,
+    regs: { R0: 4, R1: 0x8000 },
+    memory: { },
+    pre: |p| { *RwLock::write(p.get_disk()).unwrap() = DiskShim::in_memory(4); }
+}
+
+single_test! {
+    write_then_read,
+    prefill: {
+        0x3010: DKAR_ADDR,
+        0x3011: DKBR_ADDR,
+        0x3012: DKCR_ADDR,
+        0x3013: DKSR_ADDR,
+        0x3014: 0x4000,
+        0x3015: 0x5000,
+        0x3016: DISK_WRITE_CMD,
+        0x3017: DISK_READ_CMD,
+        0x4000: 1,
+        0x40FF: 256,
+    },
+    insns: [
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #2 },
+        { STI R0, #0xD },   // Sector 2
+        { LD R0, #0x10 },
+        { STI R0, #0xC },   // Buffer: x4000
+        { LD R0, #0x10 },
+        { STI R0, #0xB },   // Write
+        { LDI R1, #0xB },   // Status: last command was a write
+        { LDI R2, #0x9 },   // Command: reads as 0
+        { LD R0, #0xB },
+        { STI R0, #0x6 },   // Buffer: x5000
+        { LD R0, #0xB },
+        { STI R0, #0x5 },   // Read
+    ],
+    steps: 13,
+    regs: { R1: 0x8002, R2: 0 },
+    memory: { 0x5000: 1, 0x50FF: 256, 0x5100: 0 },
+    pre: |p| { *RwLock::write(p.get_disk()).unwrap() = DiskShim::in_memory(4); },
+    post: |i| {
+        eq!(i.get_error(), None);
+
+        let mut sector = [0; SECTOR_SIZE_IN_WORDS];
+        Disk::read_sector(i.get_peripherals(), 2, &mut sector).unwrap();
+        eq!((sector[0], sector[255]), (1, 256));
+    }
+}
+
+// Errors set bit 0 and are cleared by the next command that works:
+single_test! {
+    sector_out_of_range,
+    prefill: {
+        0x3010: DKAR_ADDR,
+        0x3011: DKBR_ADDR,
+        0x3012: DKCR_ADDR,
+        0x3013: DKSR_ADDR,
+        0x3014: 0x4000,
+        0x3015: DISK_READ_CMD,
+    },
+    insns: [
+        { LD R0, #0x13 },
+        { STI R0, #0xF },   // Buffer: x4000
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #4 },
+        { STI R0, #0xB },   // Sector 4 (one past the end)
+        { LD R0, #0xF },
+        { STI R0, #0xB },   // Read
+        { LDI R1, #0xB },   // Status: error
+        { AND R0, R0, #0 },
+        { STI R0, #0x6 },   // Sector 0
+        { LD R0, #0xA },
+        { STI R0, #0x6 },   // Read
+        { LDI R2, #0x6 },   // Status: no error
+    ],
+    steps: 13,
+    regs: { R1: 0x8003, R2: 0x8002 },
+    memory: { },
+    pre: |p| { *RwLock::write(p.get_disk()).unwrap() = DiskShim::in_memory(4); },
+    post: |i| { eq!(i.get_error(), Some(Error::DiskError(DiskError::SectorOutOfRange(4)))); }
+}
+
+single_test! {
+    invalid_buffer,
+    prefill: {
+        0x3010: DKBR_ADDR,
+        0x3011: DKCR_ADDR,
+        0x3012: 0xFE00 - 255,
+        0x3013: DISK_READ_CMD,
+    },
+    insns: [
+        { LD R0, #0x11 },
+        { STI R0, #0xE },   // Buffer: runs into the memory mapped registers
+        { LD R0, #0x10 },
+        { STI R0, #0xD },   // Read
+    ],
+    steps: 4,
+    regs: { },
+    memory: { },
+    pre: |p| { *RwLock::write(p.get_disk()).unwrap() = DiskShim::in_memory(4); },
+    post: |i| { eq!(i.get_error(), Some(Error::DiskError(DiskError::InvalidBuffer(0xFE00 - 255)))); }
+}
+
+single_test! {
+    invalid_command,
+    prefill: {
+        0x3010: DKBR_ADDR,
+        0x3011: DKCR_ADDR,
+        0x3012: 0x4000,
+    },
+    insns: [
+        { LD R0, #0x11 },
+        { STI R0, #0xE },   // Buffer: x4000
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #7 },
+        { STI R0, #0xC },   // Command 7
+    ],
+    steps: 5,
+    regs: { },
+    memory: { },
+    pre: |p| { *RwLock::write(p.get_disk()).unwrap() = DiskShim::in_memory(4); },
+    post: |i| { eq!(i.get_error(), Some(Error::DiskError(DiskError::InvalidCommand(7)))); }
+}
+
+single_test! {
+    only_interrupt_enable_is_writeable,
+    prefill: {
+        0x3010: DKSR_ADDR,
+    },
+    insns: [
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #-1 },
+        { STI R0, #0xD },   // Write every bit
+        { LDI R1, #0xC },
+        { AND R0, R0, #0 },
+        { STI R0, #0xA },   // Clear every bit
+        { LDI R2, #0x9 },
+    ],
+    steps: 7,
+    regs: { R1: 0xC000, R2: 0x8000 },
+    memory: { },
+    pre: |p| { *RwLock::write(p.get_disk()).unwrap() = DiskShim::in_memory(4); }
+}
+
+single_test! {
+    interrupt,
+    prefill: {
+        0x3010: DKSR_ADDR,
+        0x3011: DKBR_ADDR,
+        0x3012: DKCR_ADDR,
+        0x3013: 1 << 14,
+        0x3014: 0x4000,
+        0x3015: DISK_READ_CMD,
+        0x3016: 0x2FF0,
+    },
+    prefill_expr: {
+        (INTERRUPT_VECTOR_TABLE_START_ADDR + DISK_INT_VEC as Word): 0x3009,
+        (<PSR as MemMapped>::ADDR): 0x0002,
+    },
+    insns: [
+        { LD R6, #0x15 },   // Set nonzero R6
+        { LD R0, #0x11 },
+        { STI R0, #0xD },   // Enable the interrupt
+        { LD R0, #0x10 },
+        { STI R0, #0xC },   // Buffer: x4000
+        { LD R0, #0xF },
+        { STI R0, #0xB },   // Read
+        { ADD R1, R1, #1 }, // (skipped; the interrupt is taken first)
+        { BRnzp #-1 },
+        { ADD R2, R2, #1 }, // Handler
+        { BRnzp #-1 },
+    ],
+    steps: 12,
+    regs: { R1: 0, R2: 1 },
+    memory: { },
+    pre: |p| { *RwLock::write(p.get_disk()).unwrap() = DiskShim::in_memory(4); },
+    post: |i| {
+        eq!(i.get_pc(), 0x300A);
+        assert!(!DKSR::interrupt(i));
+        assert!(DKSR::interrupt_enabled(i));
+    }
+}
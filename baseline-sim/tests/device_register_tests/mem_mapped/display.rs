@@ -0,0 +1,68 @@
+use super::*;
+
+use lc3_traits::error::Error;
+use lc3_traits::peripherals::display::{Display, DisplayError};
+use lc3_baseline_sim::mem_mapped::{VXR_ADDR, VYR_ADDR, VPR_ADDR, VFR_ADDR};
+
+single_test! {
+    pixel,
+    prefill: {
+        0x3010: VXR_ADDR,
+        0x3011: VYR_ADDR,
+        0x3012: VPR_ADDR,
+        0x3013: 0x7C00,
+    },
+    insns: [
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #5 },
+        { STI R0, #0xD },   // X: 5
+        { ADD R0, R0, #2 },
+        { STI R0, #0xC },   // Y: 7
+        { LD R1, #0xD },
+        { STI R1, #0xB },   // Red
+        { LDI R2, #0xA },   // Read it back
+    ],
+    steps: 8,
+    regs: { R2: 0x7C00 },
+    memory: { },
+    post: |i| { eq!(Display::get_pixel(i.get_peripherals(), 5, 7), Ok(0x7C00)); }
+}
+
+single_test! {
+    fill,
+    prefill: {
+        0x3010: VFR_ADDR,
+        0x3011: 0x001F,
+    },
+    insns: [
+        { LD R0, #0x10 },
+        { STI R0, #0xE },   // Fill with blue
+        { LDI R1, #0xD },   // Reads as 0
+    ],
+    steps: 3,
+    regs: { R1: 0 },
+    memory: { },
+    post: |i| {
+        eq!(Display::get_pixel(i.get_peripherals(), 0, 0), Ok(0x001F));
+        eq!(Display::get_pixel(i.get_peripherals(), 127, 123), Ok(0x001F));
+    }
+}
+
+single_test! {
+    off_the_display,
+    prefill: {
+        0x3010: VXR_ADDR,
+        0x3011: VPR_ADDR,
+        0x3012: 128,
+    },
+    insns: [
+        { ADD R1, R1, #-1 },
+        { LD R0, #0x10 },
+        { STI R0, #0xD },   // X: 128
+        { LDI R1, #0xD },   // Reads as 0 and is an error
+    ],
+    steps: 4,
+    regs: { R1: 0 },
+    memory: { },
+    post: |i| { eq!(i.get_error(), Some(Error::InvalidDisplayAccess(DisplayError((128, 0))))); }
+}
@@ -1,6 +1,6 @@
 use super::*;
 
-use lc3_traits::peripherals::gpio::{Gpio, GpioPin, GpioState, GPIO_PINS};
+use lc3_traits::peripherals::gpio::{Gpio, GpioInterruptMode, GpioMiscError, GpioPin, GpioState, GPIO_PINS};
 use lc3_baseline_sim::interp::InstructionInterpreter;
 use lc3_baseline_sim::mem_mapped::{
     G0CR_ADDR, G0DR_ADDR, G0_INT_VEC,
@@ -20,13 +20,17 @@ use std::sync::mpsc::channel;
 use std::thread;
 use std::time::Duration;
 
+// The assembly in these tests only covers the first 8 pins (the default count);
+// with the `gpio_pins_16` feature the other pins are left alone.
+const TESTED_PINS: usize = 8;
+
 
 
 mod states {
     use super::*;
 
     // The idea is to test all the valid configurations ((4 states) ^
-    // GpioPin::NUM_PINS -> 65536) for the set of Gpio Pins.
+    // TESTED_PINS -> 65536) for the set of Gpio Pins.
     //
     // And then to individually test some specific edge cases (everything but
     // the first function below).
@@ -34,9 +38,9 @@ mod states {
 
     #[test]
     fn exhaustive_state_testing() { with_larger_stack(None, || {
-        // The actual assembly assumes that there are 8 pins and needs to be
-        // updated if this changes.
-        assert_eq!(GpioPin::NUM_PINS, 8, "Number of Gpio Pins has changed!");
+        // The actual assembly assumes that there are at least 8 pins and needs
+        // to be updated if this changes.
+        assert!(GpioPin::NUM_PINS >= TESTED_PINS, "Number of Gpio Pins has shrunk!");
 
         // We're also assuming that the states in GpioState won't change:
         // TODO: make this a `From` impl on the GpioState type so we don't have
@@ -52,7 +56,7 @@ mod states {
 
         let state_iter = [Disabled, Output, Input, Interrupt].iter();
 
-        let permutations = GPIO_PINS.iter()
+        let permutations = GPIO_PINS.iter().take(TESTED_PINS)
             .map(|_| state_iter.clone())
             .multi_cartesian_product();
 
@@ -79,7 +83,7 @@ mod states {
                     { LDI R6, #0xF }, // G6
                     { LDI R7, #0xF }, // G7
                 ],
-                steps: GpioPin::NUM_PINS,
+                steps: TESTED_PINS,
                 regs: {
                     R0: state_to_word(*states[0]) as Word,
                     R1: state_to_word(*states[1]) as Word,
@@ -91,7 +95,7 @@ mod states {
                     R7: state_to_word(*states[7]) as Word,
                 },
                 pre: |p| {
-                    for (pin, state) in GPIO_PINS.iter().zip(states.clone()) {
+                    for (pin, state) in GPIO_PINS.iter().take(TESTED_PINS).zip(states.clone()) {
                         Gpio::set_state(p, *pin, *state).unwrap();
                     }
                 },
@@ -142,9 +146,9 @@ mod states {
                     { ADD R7, R7, #(state_to_word(*states[7])) },
                     { STI R7, #0x1F }, // G7
                 ],
-                steps: GpioPin::NUM_PINS * 3,
+                steps: TESTED_PINS * 3,
                 post: |i| {
-                    for (pin, state) in GPIO_PINS.iter().zip(states.clone()) {
+                    for (pin, state) in GPIO_PINS.iter().take(TESTED_PINS).zip(states.clone()) {
                         let got = Gpio::get_state(i.get_peripherals(), *pin);
                         eq!(
                             *state, got,
@@ -178,8 +182,9 @@ mod states {
         post: |i| { eq!(Output, Gpio::get_state(i.get_peripherals(), G0)); }
     }
 
-    // We should also test that we actually only use the lower two bits of the
-    // value we're given in the G*_CR registers.
+    // We should also test that only the lower two bits of the value we're
+    // given in the G*_CR registers affect the state (the rest are the interrupt
+    // mode and debounce time).
     single_test! {
         gpio_cr_pin0_set_output_invalid,
         prefill: { 0x3010: G0CR_ADDR },
@@ -189,6 +194,36 @@ mod states {
         post: |i| { eq!(Output, Gpio::get_state(i.get_peripherals(), G0)); }
     }
 
+    // Bits 2 through 4 are the interrupt mode and the upper byte is the
+    // debounce time:
+    single_test! {
+        gpio_cr_interrupt_mode_and_debounce,
+        prefill: { 0x3010: G2CR_ADDR, 0x3011: 0x0A0B },
+        insns: [ { LD R0, #0x10 }, { STI R0, #0xE }, { LDI R1, #0xD } ],
+        steps: 3,
+        regs: { R0: 0x0A0B, R1: 0x0A0B },
+        post: |i| {
+            let p = i.get_peripherals();
+            eq!(Interrupt, Gpio::get_state(p, G2));
+            eq!(GpioInterruptMode::BothEdges, Gpio::get_interrupt_mode(p, G2));
+            eq!(10, Gpio::get_debounce(p, G2));
+        }
+    }
+
+    // Modes past level low aren't valid; the state should still change.
+    single_test! {
+        gpio_cr_invalid_interrupt_mode,
+        prefill: { 0x3010: G0CR_ADDR, 0x3011: 0b1_0110 },
+        insns: [ { LD R0, #0x10 }, { STI R0, #0xE } ],
+        steps: 2,
+        regs: { R0: 0b1_0110 },
+        post: |i| {
+            eq!(Input, Gpio::get_state(i.get_peripherals(), G0));
+            eq!(GpioInterruptMode::RisingEdge, Gpio::get_interrupt_mode(i.get_peripherals(), G0));
+            eq!(Some(lc3_traits::error::Error::from(GpioMiscError)), InstructionInterpreter::get_error(i));
+        }
+    }
+
 }
 
 mod read {
@@ -208,7 +243,7 @@ mod read {
 
         let valid_state_iter = [Input, Interrupt].iter(); // test when set to input or output
 
-        let permutations = GPIO_PINS.iter()
+        let permutations = GPIO_PINS.iter().take(TESTED_PINS)
             .map(|_| valid_state_iter.clone())
             .multi_cartesian_product();
 
@@ -244,7 +279,7 @@ mod read {
                         { LDI R6, #0xF }, // G6
                         { LDI R7, #0xF }, // G7
                     ],
-                    steps: GpioPin::NUM_PINS,
+                    steps: TESTED_PINS,
                     regs: {
                         R0: gpio_bools[0],
                         R1: gpio_bools[1],
@@ -257,7 +292,7 @@ mod read {
                     },
                     pre: |p| {
 
-                        for (num,  pin) in GPIO_PINS.iter().enumerate() {
+                        for (num,  pin) in GPIO_PINS.iter().take(TESTED_PINS).enumerate() {
                             let _set = Gpio::set_state(p, *pin, Output).unwrap();
                             let state = Gpio::get_state(p, *pin);
                             eq!(state, Output);
@@ -311,7 +346,7 @@ mod read {
         let state_iter = [Disabled, Output, Input, Interrupt].iter();
 
 
-        let permutations = GPIO_PINS.iter()
+        let permutations = GPIO_PINS.iter().take(TESTED_PINS)
             .map(|_| state_iter.clone())
             .multi_cartesian_product();
 
@@ -353,7 +388,7 @@ mod read {
                         { LDI R6, #0xF }, // G6
                         { LDI R7, #0xF }, // G7
                     ],
-                    steps: GpioPin::NUM_PINS,
+                    steps: TESTED_PINS,
                     regs: {
                         R0: match_state(*states[0]),
                         R1: match_state(*states[1]),
@@ -365,7 +400,7 @@ mod read {
                         R7: match_state(*states[7]),
                     },
                     pre: |p| {
-                        for (pin, state) in GPIO_PINS.iter().zip(states.clone()) {
+                        for (pin, state) in GPIO_PINS.iter().take(TESTED_PINS).zip(states.clone()) {
                             let _set = Gpio::set_state(p, *pin, *state);
 
                         }
@@ -492,15 +527,15 @@ mod write {
                     { ADD R7, R7, #(state_to_word(Input))},
                     { STI  R7, #0x4D },
                 ],
-                steps: GpioPin::NUM_PINS * 6,
+                steps: TESTED_PINS * 6,
                 pre : |p| {
-                    for pin in GPIO_PINS.iter() {
+                    for pin in GPIO_PINS.iter().take(TESTED_PINS) {
                         let _set = Gpio::set_state(p, *pin, Output);
                     }
 
                 }
                 post: |i| {
-                    for (pin, pin_val) in GPIO_PINS.iter().zip(gpio_bools.iter()) {
+                    for (pin, pin_val) in GPIO_PINS.iter().take(TESTED_PINS).zip(gpio_bools.iter()) {
                         let exp_pin_val = pin_val != &0;
                         let actual_pin_val = Gpio::read(i.get_peripherals(), *pin).unwrap();
                         eq!(actual_pin_val, exp_pin_val, "Gpio Pin {:?}\nExpected {}, got {}\nTest Case {:?}", *pin, exp_pin_val, actual_pin_val, gpio_vals);
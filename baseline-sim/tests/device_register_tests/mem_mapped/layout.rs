@@ -0,0 +1,48 @@
+//! With every peripheral count feature on, the GPIO, ADC, PWM, and timer
+//! groups outgrow their usual spots and push everything after them up.
+
+use super::*;
+
+use lc3_baseline_sim::mem_mapped::*;
+
+#[test]
+fn shifted_addresses() {
+    // GPIO doesn't move; it just gets longer:
+    eq!(G0CR_ADDR, 0xFE30);
+    eq!(G15CR_ADDR, 0xFE4E);
+    eq!(G15DR_ADDR, 0xFE4F);
+    eq!(GPIODR_ADDR, 0xFE50);
+
+    eq!(A0CR_ADDR, 0xFE50);
+    eq!(A7DR_ADDR, 0xFE5F);
+
+    eq!(P0CR_ADDR, 0xFE60);
+    eq!(P3DR_ADDR, 0xFE67);
+
+    eq!(T0CR_ADDR, 0xFE70);
+    eq!(T3DR_ADDR, 0xFE77);
+    eq!(T0CCR_ADDR, 0xFE78);
+    eq!(T3CCR_ADDR, 0xFE81);
+    eq!(T3CAPR_ADDR, 0xFE83);
+
+    // Everything after the timers moves up a group:
+    eq!(CLKR_ADDR, 0xFE90);
+    eq!(VXR_ADDR, 0xFEA0);
+    eq!(DKSR_ADDR, 0xFEB0);
+    eq!(USR_ADDR, 0xFEC0);
+    eq!(RNGSR_ADDR, 0xFED0);
+    eq!(WDCR_ADDR, 0xFEE0);
+    eq!(MPUNR_ADDR, 0xFEF0);
+    eq!(MPUAR_ADDR, 0xFEF3);
+}
+
+#[test]
+fn vectors_dont_move() {
+    eq!(GPIO_BASE_INT_VEC, 0x1B0);
+    eq!(G0_INT_VEC, 0xB0);
+    eq!(G15_INT_VEC, 0xBF);
+
+    eq!(TIMER_BASE_INT_VEC, 0x1E0);
+    eq!(T0_INT_VEC, 0xE0);
+    eq!(T3_INT_VEC, 0xE3);
+}
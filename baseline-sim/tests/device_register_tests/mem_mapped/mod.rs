@@ -10,12 +10,25 @@ use Reg::*;
 
 use itertools::Itertools;
 use lc3_test_infrastructure::assert_eq as eq;
+use lc3_traits::peripherals::stubs::PeripheralsStub;
+
+// `single_test!` wants peripherals that take two lifetimes:
+#[allow(unused_lifetimes)]
+type PeripheralsStubAlias<'int, 'io> = PeripheralsStub<'int>;
 
 mod adc;
 mod clock;
+mod display;
+mod disk;
 mod gpio;
 mod pwm;
+mod rng;
 mod timers;
+mod uart;
+mod watchdog;
+
+#[cfg(all(feature = "gpio_pins_16", feature = "adc_pins_8", feature = "pwm_pins_4", feature = "timers_4"))]
+mod layout;
 
 mod input;
 mod output;
@@ -0,0 +1,58 @@
+use super::*;
+
+use lc3_traits::peripherals::rng::Rng;
+use lc3_baseline_sim::mem_mapped::{RNGSR_ADDR, RNGDR_ADDR};
+
+#[test]
+fn seeded() { with_larger_stack(None, || {
+    let rng = RngShim::seeded(0xBEEF);
+    let expected: Vec<Word> = (0..4).map(|_| rng.next_word().unwrap()).collect();
+
+    single_test_inner! {
+        prefill: {
+            0x3010: RNGSR_ADDR,
+            0x3011: RNGDR_ADDR,
+            0x3012: 0xBEEF,
+        },
+        insns: [
+            { LDI R0, #0xF },   // Status: present but not seeded
+            { LD R1, #0x10 },
+            { STI R1, #0xE },   // Seed
+            { LDI R1, #0xC },   // Status: seeded
+            { LDI R2, #0xC },
+            { LDI R3, #0xB },
+            { LDI R4, #0xA },
+            { LDI R5, #0x9 },
+            { AND R6, R6, #0 },
+            { STI R6, #0x6 },   // Writes to the status register are ignored
+            { LDI R7, #0x5 },
+        ],
+        steps: 11,
+        regs: {
+            R0: 0x8000, R1: 0x8001,
+            R2: expected[0], R3: expected[1], R4: expected[2], R5: expected[3],
+            R7: 0x8001,
+        },
+        memory: { },
+    }
+})}
+
+single_test! {
+    no_rng,
+    prefill: {
+        0x3010: RNGSR_ADDR,
+        0x3011: RNGDR_ADDR,
+    },
+    insns: [
+        { LDI R0, #0xF },   // Status: not present
+        { AND R1, R1, #0 },
+        { ADD R1, R1, #12 },
+        { STI R1, #0xD },   // Seeding does nothing
+        { LDI R2, #0xB },
+        { LDI R3, #0xB },   // Reads as 0
+    ],
+    steps: 6,
+    regs: { R0: 0, R2: 0, R3: 0 },
+    memory: { },
+    with custom peripherals: { PeripheralsStub::default() } -> [PeripheralsStubAlias]
+}
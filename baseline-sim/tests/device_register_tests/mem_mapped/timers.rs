@@ -1,15 +1,20 @@
 use super::*;
 
-use lc3_traits::peripherals::timers::{Timers, TimerId, TIMERS};
+use lc3_traits::peripherals::gpio::{Gpio, GpioPin::*, GpioState};
+use lc3_traits::peripherals::timers::{CaptureEdge, TimerCapture, Timers, TimerId};
+use lc3_baseline_sim::interp::InterpreterBuilder;
 use lc3_baseline_sim::mem_mapped::{
     MemMapped,
     T0CR_ADDR, T0DR_ADDR,
     T1CR_ADDR, T1DR_ADDR,
+    T0CCR_ADDR, T0CNTR_ADDR, T0CAPR_ADDR,
     TIMER_BASE_INT_VEC, T0_INT_VEC,
     PSR,
     MCR
 };
 
+use std::sync::RwLock;
+
 use TimerId::*;
 
 single_test! {
@@ -79,3 +84,124 @@ single_test! {
         { RTI } // 0x300D
     ],
 }
+
+single_test! {
+    count,
+    prefill: {
+        0x3010: T0CNTR_ADDR,
+        0x3011: T0CR_ADDR,
+        0x3012: T0DR_ADDR,
+        0x3013: 50,
+    },
+    insns: [
+        { LDI R0, #0xF },   // Count before the timer starts
+        { AND R1, R1, #0 }, // Mode: singleshot
+        { STI R1, #0xE },   // Set to singleshot
+        { LD R1, #0xF },    // Load period (50ms)
+        { STI R1, #0xD },   // Set period to 50ms (at 4ms)
+        { LDI R2, #0xA },   // Count (at 5ms)
+        { AND R4, R4, #0 },
+        { ADD R4, R4, #5 },
+        { STI R4, #0x7 },   // Try to set the count
+        { LDI R3, #0x6 },   // Count (at 9ms)
+    ],
+    steps: 10,
+    regs: { R0: 0, R2: 49, R3: 45 },
+    with custom peripherals: { virtual_time_peripherals() } -> [ShareablePeripheralsShim],
+}
+
+single_test! {
+    capture_register,
+    prefill: {
+        0x3010: T0CCR_ADDR,
+        0x3011: 0x8000 | (1 << 5) | (1 << 3) | 4, // Falling edges on G4, interrupt
+        0x3012: 0x8000 | (3 << 3),                // Not a valid edge
+    },
+    insns: [
+        { LD R0, #0x10 },
+        { STI R0, #0xE },   // Set up the capture
+        { LDI R1, #0xD },   // Read it back
+        { LD R2, #0xE },
+        { STI R2, #0xB },   // Invalid; should be ignored
+        { LDI R3, #0xA },   // Read it back again
+    ],
+    steps: 6,
+    regs: {
+        R1: 0x8000 | (1 << 5) | (1 << 3) | 4,
+        R3: 0x8000 | (1 << 5) | (1 << 3) | 4,
+    },
+    post: |i| {
+        eq!(Timers::get_capture(i.get_peripherals(), T0), Some(TimerCapture {
+            pin: G4,
+            edge: CaptureEdge::Falling,
+            interrupt: true,
+        }));
+    }
+}
+
+single_test! {
+    capture_register_off,
+    prefill: {
+        0x3010: T0CCR_ADDR,
+        0x3011: 0x8000 | 4,
+    },
+    insns: [
+        { LD R0, #0x10 },
+        { STI R0, #0xE },   // Set up the capture
+        { AND R0, R0, #0 },
+        { STI R0, #0xC },   // And turn it off again
+        { LDI R1, #0xB },
+    ],
+    steps: 5,
+    regs: { R1: 0 },
+    post: |i| { eq!(Timers::get_capture(i.get_peripherals(), T0), None); }
+}
+
+// The pin has to change while the program is running, which `single_test!`
+// can't do; so this one steps the interpreter itself.
+#[test]
+fn capture_edges() { with_larger_stack(None, || {
+    type Interp = Interpreter<'static, MemoryShim, ShareablePeripheralsShim<'static, 'static>>;
+
+    fn step(interp: &mut Interp, steps: usize) {
+        for _ in 0..steps {
+            let _ = interp.step();
+        }
+    }
+
+    let mut interp: Interp = InterpreterBuilder::new()
+        .with_defaults()
+        .with_peripherals(virtual_time_peripherals())
+        .build();
+
+    // An infinite loop:
+    interp.set_pc(0x3000);
+    interp.set_word_unchecked(0x3000, Word::from(Instruction::new_br(true, true, true, -1)));
+
+    let gpio = interp.get_peripherals().get_gpio().clone();
+
+    Gpio::set_state(interp.get_peripherals_mut(), G2, GpioState::Input).unwrap();
+    interp.set_word_unchecked(T0CCR_ADDR, 0x8000 | 2); // Rising edges on G2
+    step(&mut interp, 10);
+    eq!(interp.get_word_unchecked(T0CAPR_ADDR), 0);
+
+    let _ = RwLock::write(&gpio).unwrap().set_pin(G2, true);
+    step(&mut interp, 1);
+    eq!(interp.get_word_unchecked(T0CAPR_ADDR), 11);
+
+    // Falling edges don't count:
+    step(&mut interp, 4);
+    let _ = RwLock::write(&gpio).unwrap().set_pin(G2, false);
+    step(&mut interp, 1);
+    eq!(interp.get_word_unchecked(T0CAPR_ADDR), 11);
+
+    step(&mut interp, 4);
+    let _ = RwLock::write(&gpio).unwrap().set_pin(G2, true);
+    step(&mut interp, 1);
+    eq!(interp.get_word_unchecked(T0CAPR_ADDR), 21);
+
+    // Resetting turns capture off:
+    InstructionInterpreter::reset(&mut interp);
+    eq!(interp.get_word_unchecked(T0CCR_ADDR), 0);
+    eq!(interp.get_word_unchecked(T0CAPR_ADDR), 0);
+})}
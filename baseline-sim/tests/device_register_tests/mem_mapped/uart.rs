@@ -0,0 +1,261 @@
+use super::*;
+
+use lc3_traits::peripherals::uart::UART_FIFO_DEPTH;
+use lc3_baseline_sim::interp::InterpreterBuilder;
+use lc3_baseline_sim::mem_mapped::{
+    MemMapped, Interrupt,
+    USR, USR_ADDR, UDR_ADDR, UCNR_ADDR,
+    UART_INT_VEC,
+    PSR,
+};
+use lc3_isa::INTERRUPT_VECTOR_TABLE_START_ADDR;
+
+use std::sync::RwLock;
+
+// The UART's other end is a pipe that these tests hold on to, so they use
+// `single_test_inner!` to get at it.
+
+#[test]
+fn receive() { with_larger_stack(None, || {
+    let (uart, pipe) = UartShim::pipe();
+    let _ = pipe.send_all(b"hi");
+
+    single_test_inner! {
+        prefill: {
+            0x3010: USR_ADDR,
+            0x3011: UCNR_ADDR,
+            0x3012: UDR_ADDR,
+        },
+        insns: [
+            { LDI R0, #0xF },   // Status: RX ready, present, TX ready, TX empty
+            { LDI R1, #0xF },   // 2 bytes to read
+            { LDI R2, #0xF },
+            { LDI R3, #0xE },
+            { LDI R4, #0xD },   // Nothing left; reads as 0
+            { LDI R5, #0xA },   // Status: RX not ready
+        ],
+        steps: 6,
+        regs: { R0: 0xA900, R1: 0x0002, R2: b'h' as Word, R3: b'i' as Word, R4: 0, R5: 0x2900 },
+        memory: { },
+        pre: |p| { *RwLock::write(p.get_uart()).unwrap() = uart; },
+    }
+})}
+
+#[test]
+fn transmit() { with_larger_stack(None, || {
+    let (uart, pipe) = UartShim::pipe();
+
+    single_test_inner! {
+        prefill: {
+            0x3010: USR_ADDR,
+            0x3011: UCNR_ADDR,
+            0x3012: UDR_ADDR,
+            0x3013: 0x1221,
+        },
+        insns: [
+            { LDI R0, #0xF },   // Status: present, TX ready, TX empty
+            { LD R1, #0x11 },
+            { STI R1, #0xF },   // Only the low byte is sent
+            { LDI R2, #0xD },   // 1 byte waiting to go out
+            { LDI R3, #0xB },   // Status: TX not empty
+        ],
+        steps: 5,
+        regs: { R0: 0x2900, R2: 0x0100, R3: 0x2100 },
+        memory: { },
+        pre: |p| { *RwLock::write(p.get_uart()).unwrap() = uart; },
+        post: |i| { eq!(pipe.recv(), Some(0x21)); },
+    }
+})}
+
+#[test]
+fn tx_fifo_full() { with_larger_stack(None, || {
+    let (uart, pipe) = UartShim::pipe();
+
+    single_test_inner! {
+        prefill: {
+            0x3010: UDR_ADDR,
+            0x3011: USR_ADDR,
+            0x3012: UART_FIFO_DEPTH as Word,
+        },
+        insns: [
+            { LD R0, #0x11 },
+            { STI R1, #0xE },   // Fill the TX FIFO
+            { ADD R0, R0, #-1 },
+            { BRp #-3 },
+            { LDI R2, #0xC },   // Status: TX not ready
+        ],
+        steps: 2 + 3 * UART_FIFO_DEPTH,
+        regs: { R2: 0x0100 },
+        memory: { },
+        pre: |p| { *RwLock::write(p.get_uart()).unwrap() = uart; },
+        post: |i| { eq!(pipe.recv_all().len(), UART_FIFO_DEPTH); },
+    }
+})}
+
+// Overruns stick around until a 1 is written to bit 0:
+#[test]
+fn overrun() { with_larger_stack(None, || {
+    let (uart, pipe) = UartShim::pipe();
+    let _ = pipe.send_all(&[0; UART_FIFO_DEPTH + 1]);
+
+    single_test_inner! {
+        prefill: {
+            0x3010: USR_ADDR,
+        },
+        insns: [
+            { LDI R0, #0xF },   // Status: overrun
+            { AND R1, R1, #0 },
+            { STI R1, #0xD },   // Writing a 0 does nothing
+            { LDI R2, #0xC },
+            { ADD R1, R1, #1 },
+            { STI R1, #0xA },   // Writing a 1 clears it
+            { LDI R3, #0x9 },
+        ],
+        steps: 7,
+        regs: { R0: 0xA901, R2: 0xA901, R3: 0xA900 },
+        memory: { },
+        pre: |p| { *RwLock::write(p.get_uart()).unwrap() = uart; },
+    }
+})}
+
+#[test]
+fn rx_interrupt() { with_larger_stack(None, || {
+    let (uart, pipe) = UartShim::pipe();
+    let _ = pipe.send(b'a');
+
+    single_test_inner! {
+        prefill: {
+            0x3010: USR_ADDR,
+            0x3011: UDR_ADDR,
+            0x3012: 1 << 14,
+            0x3013: 0x2FF0,
+        },
+        prefill_expr: {
+            (INTERRUPT_VECTOR_TABLE_START_ADDR + UART_INT_VEC as Word): 0x3005,
+            (<PSR as MemMapped>::ADDR): 0x0002,
+        },
+        insns: [
+            { LD R6, #0x12 },   // Set nonzero R6
+            { LD R0, #0x10 },
+            { STI R0, #0xD },   // Enable the RX interrupt
+            { ADD R1, R1, #1 }, // (skipped; the interrupt is taken first)
+            { BRnzp #-1 },
+            { LDI R2, #0xB },   // Handler: read the byte
+            { LDI R3, #0x9 },   //   Status: RX not ready
+            { BRnzp #-1 },
+        ],
+        steps: 10,
+        regs: { R1: 0, R2: b'a' as Word, R3: 0x6900 },
+        memory: { },
+        pre: |p| { *RwLock::write(p.get_uart()).unwrap() = uart; },
+        post: |i| {
+            eq!(i.get_pc(), 0x3007);
+            assert!(USR::interrupt_enabled(i));
+            assert!(!USR::interrupt(i));
+        },
+    }
+})}
+
+// Level triggered; only draining the RX FIFO makes the interrupt go away:
+#[test]
+fn rx_interrupt_is_level_triggered() { with_larger_stack(None, || {
+    let (uart, pipe) = UartShim::pipe();
+    let _ = pipe.send(b'a');
+
+    single_test_inner! {
+        prefill: {
+            0x3010: USR_ADDR,
+            0x3011: 1 << 14,
+            0x3012: 0x2FF0,
+        },
+        prefill_expr: {
+            (INTERRUPT_VECTOR_TABLE_START_ADDR + UART_INT_VEC as Word): 0x3004,
+            (<PSR as MemMapped>::ADDR): 0x0002,
+        },
+        insns: [
+            { LD R6, #0x11 },   // Set nonzero R6
+            { LD R0, #0xF },
+            { STI R0, #0xD },   // Enable the RX interrupt
+            { BRnzp #-1 },
+            { BRnzp #-1 },      // Handler: doesn't read the byte
+        ],
+        steps: 6,
+        regs: { },
+        memory: { },
+        pre: |p| { *RwLock::write(p.get_uart()).unwrap() = uart; },
+        post: |i| {
+            eq!(i.get_pc(), 0x3004);
+            assert!(USR::interrupt(i));
+        },
+    }
+})}
+
+// The TX interrupt is pending whenever there's room:
+#[test]
+fn tx_interrupt() { with_larger_stack(None, || {
+    let (uart, _pipe) = UartShim::pipe();
+
+    single_test_inner! {
+        prefill: {
+            0x3010: USR_ADDR,
+            0x3011: 1 << 12,
+            0x3012: 0x2FF0,
+        },
+        prefill_expr: {
+            (INTERRUPT_VECTOR_TABLE_START_ADDR + UART_INT_VEC as Word): 0x3005,
+            (<PSR as MemMapped>::ADDR): 0x0002,
+        },
+        insns: [
+            { LD R6, #0x11 },   // Set nonzero R6
+            { LD R0, #0xF },
+            { STI R0, #0xD },   // Enable the TX interrupt
+            { ADD R1, R1, #1 }, // (skipped; the interrupt is taken first)
+            { BRnzp #-1 },
+            { ADD R2, R2, #1 }, // Handler
+            { BRnzp #-1 },
+        ],
+        steps: 8,
+        regs: { R1: 0, R2: 1 },
+        memory: { },
+        pre: |p| { *RwLock::write(p.get_uart()).unwrap() = uart; },
+        post: |i| { eq!(i.get_pc(), 0x3006); },
+    }
+})}
+
+// Resets aren't something `single_test!` can do partway through a program:
+#[test]
+fn reset_disables_interrupts() { with_larger_stack(None, || {
+    let mut interp: Interpreter<'static, MemoryShim, ShareablePeripheralsShim<'static, 'static>> =
+        InterpreterBuilder::new()
+            .with_defaults()
+            .build();
+
+    let (uart, _pipe) = UartShim::pipe();
+    *RwLock::write(interp.get_peripherals().get_uart()).unwrap() = uart;
+
+    interp.set_word_unchecked(USR_ADDR, 1 << 14);
+    assert!(USR::interrupt_enabled(&interp));
+
+    InstructionInterpreter::reset(&mut interp);
+    assert!(!USR::interrupt_enabled(&interp));
+})}
+
+single_test! {
+    no_uart,
+    prefill: {
+        0x3010: USR_ADDR,
+        0x3011: UDR_ADDR,
+        0x3012: UCNR_ADDR,
+        0x3013: 0x41,
+    },
+    insns: [
+        { LDI R0, #0xF },   // Status: not present
+        { LD R1, #0x11 },
+        { STI R1, #0xE },   // Writes are dropped
+        { LDI R2, #0xE },
+        { LDI R3, #0xC },   // Nothing to read
+    ],
+    steps: 5,
+    regs: { R0: 0x0800, R2: 0, R3: 0 },
+    memory: { }
+}
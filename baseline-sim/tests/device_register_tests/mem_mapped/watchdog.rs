@@ -0,0 +1,234 @@
+use super::*;
+
+use lc3_traits::error::Error;
+use lc3_traits::peripherals::watchdog::Watchdog;
+use lc3_baseline_sim::mem_mapped::{
+    MemMapped,
+    WDCR_ADDR, WDTR_ADDR, WDKR_ADDR,
+    WATCHDOG_INT_VEC,
+    PSR,
+};
+use lc3_isa::{INTERRUPT_VECTOR_TABLE_START_ADDR, OS_START_ADDR};
+
+// The tests that count on timing use virtual time: every instruction takes
+// exactly 1ms, so the nth instruction (starting from 0) runs at n ms.
+
+single_test! {
+    starts_off,
+    prefill: {
+        0x3010: WDCR_ADDR,
+        0x3011: WDTR_ADDR,
+        0x3012: WDKR_ADDR,
+    },
+    insns: [
+        { LDI R0, #0xF },   // Control: present, not enabled
+        { LDI R1, #0xF },   // Timeout: off
+        { LDI R2, #0xF },   // Remaining: 0
+    ],
+    steps: 3,
+    regs: { R0: 0x0100, R1: 0, R2: 0 },
+    memory: { }
+}
+
+single_test! {
+    timeout_and_kick,
+    prefill: {
+        0x3010: WDTR_ADDR,
+        0x3011: WDKR_ADDR,
+    },
+    insns: [
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #10 },
+        { ADD R0, R0, #10 },
+        { STI R0, #0xC },   // Timeout of 20ms, at 3ms
+        { LDI R1, #0xB },   // Read back the timeout
+        { LDI R2, #0xB },   // 2ms later: 18ms left
+        { AND R3, R3, #0 },
+        { STI R3, #0x9 },   // Kick (any value will do)
+        { LDI R4, #0x8 },   // 1ms after the kick: 19ms left
+    ],
+    steps: 9,
+    regs: { R1: 20, R2: 18, R4: 19 },
+    memory: { },
+    with custom peripherals: { virtual_time_peripherals() } -> [ShareablePeripheralsShim]
+}
+
+single_test! {
+    only_interrupt_enable_sticks,
+    prefill: {
+        0x3010: WDCR_ADDR,
+        0x3011: 0xFFFE,
+    },
+    insns: [
+        { LD R0, #0x10 },
+        { STI R0, #0xE },   // Write every bit but the caused reset bit
+        { LDI R1, #0xD },
+    ],
+    steps: 3,
+    regs: { R1: 0x4100 },
+    memory: { }
+}
+
+single_test! {
+    kicking_keeps_it_going,
+    prefill: {
+        0x3010: WDTR_ADDR,
+        0x3011: WDKR_ADDR,
+    },
+    insns: [
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #10 },
+        { STI R0, #0xD },   // Timeout of 10ms
+        { AND R1, R1, #0 }, // Loop:
+        { STI R1, #0xC },   //   Kick
+        { ADD R1, R1, #2 },
+        { ADD R1, R1, #-1 },//   Burn some time (8ms between kicks)
+        { BRp #-2 },
+        { BRnzp #-6 },
+    ],
+    steps: 150,
+    regs: { },
+    memory: { },
+    with custom peripherals: { virtual_time_peripherals() } -> [ShareablePeripheralsShim],
+    post: |i| {
+        eq!(i.get_error(), None);
+        assert!((0x3003..=0x3008).contains(&i.get_pc()), "PC: {:#06X}", i.get_pc());
+    },
+}
+
+single_test! {
+    interrupt,
+    prefill: {
+        0x3010: WDCR_ADDR,
+        0x3011: WDTR_ADDR,
+        0x3012: 1 << 14,
+        0x3013: 0x2FF0,
+        0x3014: WDKR_ADDR,
+    },
+    prefill_expr: {
+        (INTERRUPT_VECTOR_TABLE_START_ADDR + WATCHDOG_INT_VEC as Word): 0x3007,
+        (<PSR as MemMapped>::ADDR): 0x0002,
+    },
+    insns: [
+        { LD R6, #0x12 },   // Set nonzero R6
+        { LD R0, #0x10 },
+        { STI R0, #0xD },   // Enable the interrupt
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #10 },
+        { STI R0, #0xB },   // Timeout of 10ms, at 5ms
+        { BRnzp #-1 },      // Wait
+        { LDI R1, #0x8 },   // Handler: the interrupt flag is set
+        { AND R2, R2, #0 },
+        { STI R2, #0xA },   //   Kick
+        { LDI R3, #0x5 },   //   The flag is cleared
+        { BRnzp #-1 },
+    ],
+    steps: 25,
+    regs: { R1: 0xC100, R3: 0x4100 },
+    memory: { },
+    with custom peripherals: { virtual_time_peripherals() } -> [ShareablePeripheralsShim],
+    post: |i| {
+        eq!(i.get_pc(), 0x300B);
+        eq!(i.get_error(), None);
+    },
+}
+
+// The interrupt is the program's last chance; the next timeout resets the
+// machine (and taking the interrupt doesn't take any time):
+single_test! {
+    reset_after_interrupt,
+    prefill: {
+        0x3010: WDCR_ADDR,
+        0x3011: WDTR_ADDR,
+        0x3012: 1 << 14,
+        0x3013: 0x2FF0,
+    },
+    prefill_expr: {
+        (INTERRUPT_VECTOR_TABLE_START_ADDR + WATCHDOG_INT_VEC as Word): 0x3007,
+        (<PSR as MemMapped>::ADDR): 0x0002,
+    },
+    insns: [
+        { LD R6, #0x12 },   // Set nonzero R6
+        { LD R0, #0x10 },
+        { STI R0, #0xD },   // Enable the interrupt
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #10 },
+        { STI R0, #0xB },   // Timeout of 10ms, at 5ms
+        { BRnzp #-1 },      // Wait
+        { BRnzp #-1 },      // Handler: doesn't kick
+    ],
+    steps: 26,
+    regs: { },
+    memory: { },
+    with custom peripherals: { virtual_time_peripherals() } -> [ShareablePeripheralsShim],
+    post: |i| {
+        eq!(i.get_pc(), OS_START_ADDR);
+        eq!(i.get_error(), Some(Error::WatchdogExpired));
+
+        // The watchdog is turned off by the reset but remembers that it caused it:
+        eq!(i.get_word_unchecked(WDCR_ADDR), 0x0101);
+        eq!(i.get_word_unchecked(WDTR_ADDR), 0);
+    },
+}
+
+// Without the interrupt, the first timeout resets the machine:
+single_test! {
+    reset_without_interrupt,
+    prefill: {
+        0x3010: WDTR_ADDR,
+    },
+    insns: [
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #10 },
+        { STI R0, #0xD },   // Timeout of 10ms, at 2ms
+        { BRnzp #-1 },      // Wait
+    ],
+    steps: 12,
+    regs: { },
+    memory: { },
+    with custom peripherals: { virtual_time_peripherals() } -> [ShareablePeripheralsShim],
+    post: |i| {
+        eq!(i.get_pc(), OS_START_ADDR);
+        eq!(i.get_error(), Some(Error::WatchdogExpired));
+        assert!(Watchdog::caused_reset(i.get_peripherals()));
+    },
+}
+
+single_test! {
+    clear_caused_reset,
+    prefill: {
+        0x3010: WDCR_ADDR,
+    },
+    insns: [
+        { LDI R0, #0xF },   // The caused reset bit is set
+        { AND R1, R1, #0 },
+        { ADD R1, R1, #1 },
+        { STI R1, #0xC },   // Writing a 1 clears it
+        { LDI R2, #0xB },
+    ],
+    steps: 5,
+    regs: { R0: 0x0101, R2: 0x0100 },
+    memory: { },
+    pre: |p| { Watchdog::set_caused_reset(p, true); },
+}
+
+single_test! {
+    no_watchdog,
+    prefill: {
+        0x3010: WDCR_ADDR,
+        0x3011: WDTR_ADDR,
+        0x3012: WDKR_ADDR,
+    },
+    insns: [
+        { LDI R0, #0xF },   // Not present
+        { AND R1, R1, #0 },
+        { ADD R1, R1, #10 },
+        { STI R1, #0xD },   // Writes are ignored
+        { LDI R2, #0xC },
+        { LDI R3, #0xC },
+    ],
+    steps: 6,
+    regs: { R0: 0, R2: 0, R3: 0 },
+    memory: { },
+    with custom peripherals: { PeripheralsStub::default() } -> [PeripheralsStubAlias]
+}
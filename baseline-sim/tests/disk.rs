@@ -0,0 +1,68 @@
+use lc3_baseline_sim::interp::{Interpreter, InterpreterBuilder};
+use lc3_baseline_sim::mem_mapped::{DKSR_ADDR, DKCR_ADDR, DKAR_ADDR, DKBR_ADDR, DISK_WRITE_CMD};
+use lc3_baseline_sim::sim::Simulator;
+use lc3_traits::control::{Control, DiskControl};
+use lc3_traits::control::rpc::SimpleEventFutureSharedState;
+use lc3_traits::peripherals::PeripheralSet;
+use lc3_traits::peripherals::disk::{DiskError, SECTOR_SIZE_IN_WORDS};
+use lc3_traits::peripherals::stubs::PeripheralsStub;
+use lc3_test_infrastructure::{assert_eq, with_larger_stack, DiskShim, MemoryShim, PeripheralsShim};
+
+// The register level tests are in `device_register_tests/mem_mapped/disk.rs`;
+// these are for the things that go through `Control`.
+
+type Interp = Interpreter<'static, MemoryShim, PeripheralsShim<'static>>;
+type Sim<I> = Simulator<'static, 'static, I, SimpleEventFutureSharedState>;
+
+fn interp_with_disk() -> Interp {
+    let peripherals: PeripheralsShim<'static> = PeripheralSet::new(
+        Default::default(), Default::default(), Default::default(), Default::default(),
+        Default::default(), Default::default(), Default::default(), Default::default(),
+        DiskShim::in_memory(4), Default::default(), Default::default(), Default::default(),
+    );
+
+    InterpreterBuilder::new()
+        .with_defaults()
+        .with_peripherals(peripherals)
+        .build()
+}
+
+#[test]
+fn control() { with_larger_stack(None, || {
+    let state = Box::leak(Box::new(SimpleEventFutureSharedState::new()));
+    let mut sim = Sim::new_with_state(interp_with_disk(), state);
+    assert_eq!(sim.get_device_info().capabilities.disk, true);
+    assert_eq!(sim.get_disk_num_sectors(), 4);
+
+    sim.write_word(0x4010, 0x1234);
+    sim.write_word(DKAR_ADDR, 3);
+    sim.write_word(DKBR_ADDR, 0x4000);
+    sim.write_word(DKCR_ADDR, DISK_WRITE_CMD);
+
+    let words = sim.get_disk_words(3, 16).unwrap();
+    assert_eq!(words[0], 0x1234);
+
+    let mut sector = [0xFFFF; SECTOR_SIZE_IN_WORDS];
+    sim.get_disk_sector(3, &mut sector).unwrap();
+    assert_eq!(sector.iter().filter(|w| **w != 0).count(), 1);
+    assert_eq!(sector[16], 0x1234);
+
+    // Inspecting the disk doesn't count as a command:
+    assert_eq!(sim.read_word(DKSR_ADDR), 0x8002);
+    sim.write_word(DKSR_ADDR, 0);
+    sim.get_disk_sector(3, &mut sector).unwrap();
+    assert_eq!(sim.read_word(DKSR_ADDR), 0x8002);
+
+    assert_eq!(sim.get_disk_words(4, 0), Err(DiskError::SectorOutOfRange(4)));
+})}
+
+#[test]
+fn no_disk() { with_larger_stack(None, || {
+    let sim = Sim::<Interpreter<'static, MemoryShim, PeripheralsStub<'static>>>::default();
+    assert_eq!(sim.get_device_info().capabilities.disk, false);
+    assert_eq!(sim.get_disk_num_sectors(), 0);
+    assert_eq!(sim.get_disk_words(0, 0), Err(DiskError::NoDisk));
+
+    let sim = Sim::<Interp>::default();
+    assert_eq!(sim.get_device_info().capabilities.disk, false);
+})}
@@ -0,0 +1,46 @@
+use lc3_baseline_sim::interp::Interpreter;
+use lc3_baseline_sim::mem_mapped::{VXR_ADDR, VYR_ADDR, VPR_ADDR};
+use lc3_baseline_sim::sim::Simulator;
+use lc3_traits::control::{Control, DisplayControl};
+use lc3_traits::control::rpc::SimpleEventFutureSharedState;
+use lc3_traits::peripherals::display::DISPLAY_PIXELS;
+use lc3_traits::peripherals::stubs::PeripheralsStub;
+use lc3_test_infrastructure::{assert_eq, with_larger_stack, MemoryShim, PeripheralsShim};
+
+// The register level tests are in `device_register_tests/mem_mapped/display.rs`;
+// these are for the things that go through `Control`.
+
+type Interp = Interpreter<'static, MemoryShim, PeripheralsShim<'static>>;
+type Sim<I> = Simulator<'static, 'static, I, SimpleEventFutureSharedState>;
+
+#[test]
+fn control() { with_larger_stack(None, || {
+    let mut sim = Sim::<Interp>::default();
+    assert_eq!(sim.get_device_info().capabilities.display, true);
+
+    sim.write_word(VXR_ADDR, 17);
+    sim.write_word(VYR_ADDR, 1);
+    sim.write_word(VPR_ADDR, 0x1234);
+
+    let pixels = sim.get_display_pixels(128 + 16);
+    assert_eq!(pixels[0], 0);
+    assert_eq!(pixels[1], 0x1234);
+
+    let mut frame = [0; DISPLAY_PIXELS];
+    sim.get_display_frame(&mut frame);
+    assert_eq!(frame.iter().filter(|p| **p != 0).count(), 1);
+    assert_eq!(frame[128 + 17], 0x1234);
+
+    // Resetting clears the display:
+    sim.reset();
+    assert_eq!(sim.get_display_pixels(128 + 16)[1], 0);
+
+    // Past the end of the display:
+    assert_eq!(sim.get_display_pixels(DISPLAY_PIXELS as u16), [0; 16]);
+})}
+
+#[test]
+fn no_display() { with_larger_stack(None, || {
+    let sim = Sim::<Interpreter<'static, MemoryShim, PeripheralsStub<'static>>>::default();
+    assert_eq!(sim.get_device_info().capabilities.display, false);
+})}
@@ -0,0 +1,166 @@
+use lc3_baseline_sim::ext::InstructionExtension;
+use lc3_baseline_sim::interp::{Acv, Interpreter, InterpreterBuilder, InstructionInterpreter, InstructionInterpreterPeripheralAccess};
+use lc3_baseline_sim::mem_mapped::PSR;
+use lc3_isa::ext::{self, Decoded, Extension, MNEMONIC_WIDTH};
+use lc3_isa::{Instruction, Reg::{self, *}, Word, ILLEGAL_OPCODE_EXCEPTION_VECTOR};
+use lc3_test_infrastructure::{assert_eq, with_larger_stack, MemoryShim, PeripheralsShim};
+
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+
+type Interp = Interpreter<'static, MemoryShim, PeripheralsShim<'static>>;
+
+const HANDLER: Word = 0x1000;
+
+// `1101 1 op(2) ddd sss ttt`; op 0 is MUL, 1 is PUSH, 2 is POP.
+#[derive(Debug)]
+struct Arith;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum ArithInsn {
+    Mul { dr: Reg, sr1: Reg, sr2: Reg },
+    Push { sr: Reg },
+    Pop { dr: Reg },
+}
+
+fn reg(word: Word, bit: u32) -> Reg {
+    Reg::try_from(((word >> bit) & 0b111) as u8).unwrap()
+}
+
+impl Extension for Arith {
+    type Insn = ArithInsn;
+
+    fn decode(&self, word: Word) -> Option<ArithInsn> {
+        if word & 0x0800 == 0 {
+            return None;
+        }
+
+        match (word >> 9) & 0b11 {
+            0 => Some(ArithInsn::Mul { dr: reg(word, 6), sr1: reg(word, 3), sr2: reg(word, 0) }),
+            1 => Some(ArithInsn::Push { sr: reg(word, 6) }),
+            2 => Some(ArithInsn::Pop { dr: reg(word, 6) }),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ArithInsn {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let w = MNEMONIC_WIDTH;
+        match self {
+            ArithInsn::Mul { dr, sr1, sr2 } => write!(fmt, "{:<w$}{}, {}, {}", "MUL", dr, sr1, sr2, w = w),
+            ArithInsn::Push { sr } => write!(fmt, "{:<w$}{}", "PUSH", sr, w = w),
+            ArithInsn::Pop { dr } => write!(fmt, "{:<w$}{}", "POP", dr, w = w),
+        }
+    }
+}
+
+impl InstructionExtension<Interp> for Arith {
+    fn execute(&self, insn: ArithInsn, interp: &mut Interp) -> Result<(), Acv> {
+        let (dr, word) = match insn {
+            ArithInsn::Mul { dr, sr1, sr2 } => (dr, interp[sr1].wrapping_mul(interp[sr2])),
+            ArithInsn::Push { sr } => {
+                interp[R6] = interp[R6].wrapping_sub(1);
+                return interp.set_word(interp[R6], interp[sr]);
+            }
+            ArithInsn::Pop { dr } => {
+                let word = interp.get_word(interp[R6])?;
+                interp[R6] = interp[R6].wrapping_add(1);
+                (dr, word)
+            }
+        };
+
+        interp[dr] = word;
+        interp.get_special_reg::<PSR>().set_cc(interp, word);
+        Ok(())
+    }
+}
+
+const fn mul(dr: Reg, sr1: Reg, sr2: Reg) -> Word {
+    0xD800 | ((dr as Word) << 6) | ((sr1 as Word) << 3) | (sr2 as Word)
+}
+
+const fn push(sr: Reg) -> Word { 0xDA00 | ((sr as Word) << 6) }
+const fn pop(dr: Reg) -> Word { 0xDC00 | ((dr as Word) << 6) }
+
+fn interp(program: &[Word]) -> Interp {
+    let mut interp: Interp = InterpreterBuilder::new()
+        .with_defaults()
+        .build();
+
+    interp.set_word_unchecked(0x0100 + ILLEGAL_OPCODE_EXCEPTION_VECTOR as Word, HANDLER);
+    for (addr, word) in (0x3000..).zip(program.iter()) {
+        interp.set_word_unchecked(addr, *word);
+    }
+    interp.set_pc(0x3000);
+    interp[R6] = 0x2FF0;
+
+    interp
+}
+
+#[test]
+fn strict_by_default() { with_larger_stack(None, || {
+    let mut interp = interp(&[mul(R0, R1, R2)]);
+
+    let _ = interp.step();
+    assert_eq!(interp.get_pc(), HANDLER);
+})}
+
+#[test]
+fn execute() { with_larger_stack(None, || {
+    let mut interp = interp(&[
+        mul(R0, R1, R2),
+        push(R0),
+        mul(R3, R1, R1),
+        pop(R4),
+        0xDE00, // Not part of the extension.
+    ]);
+    interp.set_extension(&Arith);
+
+    interp[R1] = 7;
+    interp[R2] = -3i16 as Word;
+
+    let _ = interp.step();
+    assert_eq!(interp[R0], -21i16 as Word);
+    assert_eq!(interp.get_special_reg::<PSR>().get_cc(), (true, false, false));
+
+    let _ = interp.step();
+    assert_eq!(interp[R6], 0x2FEF);
+    assert_eq!(interp.get_word_unchecked(0x2FEF), -21i16 as Word);
+
+    let _ = interp.step();
+    assert_eq!(interp[R3], 49);
+    let _ = interp.step();
+    assert_eq!(interp[R4], -21i16 as Word);
+    assert_eq!(interp[R6], 0x2FF0);
+    assert_eq!(interp.get_pc(), 0x3004);
+
+    let _ = interp.step();
+    assert_eq!(interp.get_pc(), HANDLER);
+
+    // BRK isn't the extension's:
+    interp.set_word_unchecked(0x3000, Word::from(Instruction::new_brk(0)));
+    interp.set_pc(0x3000);
+    let _ = interp.step();
+    assert_eq!(interp.get_pc(), HANDLER);
+
+    // Detaching the extension makes its instructions illegal again:
+    interp.remove_extension();
+    interp.set_pc(0x3002);
+    let _ = interp.step();
+    assert_eq!(interp.get_pc(), HANDLER);
+})}
+
+#[test]
+fn formatting() {
+    let fmt = |word| format!("{}", ext::decode(&Arith, word).unwrap());
+
+    assert_eq!(fmt(mul(R0, R1, R2)), "MUL   R0, R1, R2");
+    assert_eq!(fmt(push(R7)), "PUSH  R7");
+    assert_eq!(fmt(pop(R5)), "POP   R5");
+    assert_eq!(fmt(0x1262), "ADD   R1, R1, #2");
+    assert_eq!(fmt(0xD003), "BRK   x3");
+
+    assert_eq!(ext::decode(&Arith, 0xDE00), Err(0xDE00));
+    assert_eq!(ext::decode(&Arith, 0x5020), Ok(Decoded::Base(Instruction::new_and_imm(R0, R0, 0))));
+}
@@ -0,0 +1,107 @@
+use lc3_baseline_sim::interp::{Interpreter, InterpreterBuilder, InstructionInterpreter, MachineState};
+use lc3_baseline_sim::mem_mapped::MCR;
+use lc3_baseline_sim::sim::Simulator;
+use lc3_isa::{Instruction, Reg::*, Word, MCR as MCR_ADDR};
+use lc3_traits::control::{Control, Event, State};
+use lc3_traits::control::rpc::SimpleEventFutureSharedState;
+use lc3_test_infrastructure::{assert_eq, with_larger_stack, MemoryShim, PeripheralsShim};
+
+type Interp = Interpreter<'static, MemoryShim, PeripheralsShim<'static>>;
+type Sim = Simulator<'static, 'static, Interp, SimpleEventFutureSharedState>;
+
+fn sim(program: &[Instruction], data: &[Word]) -> Sim {
+    let state = Box::leak(Box::new(SimpleEventFutureSharedState::new()));
+    let mut sim = Sim::new_with_state(Default::default(), state);
+
+    let words = program.iter().map(|i| Word::from(*i)).chain(data.iter().copied());
+    for (addr, word) in (0x3000..).zip(words) {
+        sim.write_word(addr, word);
+    }
+    sim.set_pc(0x3000);
+
+    sim
+}
+
+#[test]
+fn clock_enable() { with_larger_stack(None, || {
+    let mut interp: Interp = InterpreterBuilder::new()
+        .with_defaults()
+        .build();
+    assert_eq!(interp.get_word_unchecked(MCR_ADDR), MCR::DEFAULT);
+
+    // Other bits don't stop the clock:
+    interp.set_word_unchecked(MCR_ADDR, MCR::DEFAULT | 0x00FF);
+    assert_eq!(interp.get_machine_state(), MachineState::Running);
+
+    interp.set_word_unchecked(MCR_ADDR, 0x00FF);
+    assert_eq!(interp.get_machine_state(), MachineState::Halted);
+
+    // Only a reset starts the clock again (and clears everything else):
+    interp.set_word_unchecked(MCR_ADDR, MCR::DEFAULT);
+    assert_eq!(interp.get_machine_state(), MachineState::Halted);
+    interp.reset();
+    assert_eq!(interp.get_machine_state(), MachineState::Running);
+    assert_eq!(interp.get_word_unchecked(MCR_ADDR), MCR::DEFAULT);
+})}
+
+#[test]
+fn halt() { with_larger_stack(None, || {
+    // Clear the clock enable bit:
+    let mut sim = sim(&[
+        Instruction::new_and_imm(R0, R0, 0),
+        Instruction::new_sti(R0, 1),
+        Instruction::new_br(true, true, true, -1),
+    ], &[MCR_ADDR]);
+
+    assert_eq!(sim.step(), None);
+    assert_eq!(sim.step(), Some(Event::Halted));
+    assert_eq!(sim.get_state(), State::Halted);
+})}
+
+#[test]
+fn debug_break() { with_larger_stack(None, || {
+    // Set the debug break bit:
+    let mut sim = sim(&[
+        Instruction::new_ldi(R0, 4),
+        Instruction::new_ld(R1, 4),
+        Instruction::new_add_reg(R0, R0, R1),
+        Instruction::new_sti(R0, 1),
+        Instruction::new_br(true, true, true, -1),
+    ], &[MCR_ADDR, 1 << MCR::DEBUG_BREAK_BIT]);
+
+    for _ in 0..3 { assert_eq!(sim.step(), None); }
+    assert_eq!(sim.step(), Some(Event::SoftwareBreak { addr: 0x3004 }));
+    assert_eq!(sim.get_state(), State::Paused);
+
+    // The bit is cleared for the program:
+    assert_eq!(sim.read_word(MCR_ADDR), MCR::DEFAULT);
+    assert_eq!(sim.step(), None);
+
+    // Breakpoints come first:
+    sim.set_pc(0x3000);
+    let idx = sim.set_breakpoint(0x3004).unwrap();
+    for _ in 0..3 { assert_eq!(sim.step(), None); }
+    assert_eq!(sim.step(), Some(Event::Breakpoint { addr: 0x3004 }));
+
+    let _ = sim.unset_breakpoint(idx);
+    assert_eq!(sim.step(), Some(Event::SoftwareBreak { addr: 0x3004 }));
+})}
+
+#[test]
+fn throttle() { with_larger_stack(None, || {
+    // Count instructions, two at a time:
+    let mut sim = sim(&[
+        Instruction::new_add_imm(R0, R0, 1),
+        Instruction::new_br(true, true, true, -2),
+    ], &[]);
+
+    sim.write_word(MCR_ADDR, MCR::DEFAULT | 10);
+    let _ = sim.run_until_event();
+    let _ = sim.tick();
+    assert_eq!(sim.get_register(R0), 5);
+
+    // Back to the default:
+    sim.write_word(MCR_ADDR, MCR::DEFAULT);
+    let _ = sim.tick();
+    assert_eq!(sim.get_register(R0), 55);
+})}
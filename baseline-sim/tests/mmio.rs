@@ -0,0 +1,131 @@
+use lc3_baseline_sim::interp::{Interpreter, InstructionInterpreter, InstructionInterpreterPeripheralAccess};
+use lc3_baseline_sim::mem_mapped::PSR;
+use lc3_baseline_sim::mmio::{MmioDevice, MmioInterrupt, MmioRegistrationError::*};
+use lc3_isa::{Addr, Word, Reg::*, INTERRUPT_VECTOR_TABLE_START_ADDR};
+use lc3_test_infrastructure::{assert_eq, MemoryShim, PeripheralsShim};
+
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering::SeqCst};
+
+type Interp = Interpreter<'static, MemoryShim, PeripheralsShim<'static>>;
+
+// Two registers and an interrupt that's pending whenever the first register is
+// non-zero.
+#[derive(Debug)]
+struct Device {
+    regs: [AtomicU16; 2],
+    resets: AtomicU16,
+    was_reset: AtomicBool,
+}
+
+impl Device {
+    const fn new() -> Self {
+        Self {
+            regs: [AtomicU16::new(0), AtomicU16::new(0)],
+            resets: AtomicU16::new(0),
+            was_reset: AtomicBool::new(false),
+        }
+    }
+}
+
+impl MmioDevice for Device {
+    fn read(&self, offset: Addr) -> Word {
+        self.regs[offset as usize].load(SeqCst)
+    }
+
+    fn write(&self, offset: Addr, word: Word) {
+        self.regs[offset as usize].store(word, SeqCst)
+    }
+
+    fn interrupt_pending(&self) -> bool {
+        self.regs[0].load(SeqCst) != 0
+    }
+
+    fn reset_interrupt_flag(&self) {
+        let _ = self.resets.fetch_add(1, SeqCst);
+        self.regs[0].store(0, SeqCst)
+    }
+
+    fn reset(&self) {
+        self.was_reset.store(true, SeqCst)
+    }
+}
+
+#[test]
+fn read_and_write() {
+    static DEV: Device = Device::new();
+    let mut interp = Interp::default();
+
+    interp.register_mmio_device(0xFEE0..=0xFEE1, &DEV, None).unwrap();
+
+    interp.set_word_unchecked(0xFEE1, 0xBEEF);
+    assert_eq!(DEV.regs[1].load(SeqCst), 0xBEEF);
+
+    DEV.regs[0].store(0x1234, SeqCst);
+    assert_eq!(interp.get_word_unchecked(0xFEE0), 0x1234);
+
+    // Unclaimed addresses still read as 0:
+    assert_eq!(interp.get_word_unchecked(0xFEE2), 0);
+
+    InstructionInterpreter::reset(&mut interp);
+    assert_eq!(DEV.was_reset.load(SeqCst), true);
+}
+
+#[test]
+fn registration_errors() {
+    static DEV: Device = Device::new();
+    let mut interp = Interp::default();
+
+    assert_eq!(interp.register_mmio_device(0x3000..=0x3001, &DEV, None), Err(InvalidRange(0x3000..=0x3001)));
+    assert_eq!(interp.register_mmio_device(0xFEE1..=0xFEE0, &DEV, None), Err(InvalidRange(0xFEE1..=0xFEE0)));
+    assert_eq!(interp.register_mmio_device(0xFDFF..=0xFE00, &DEV, None), Err(InvalidRange(0xFDFF..=0xFE00)));
+    assert_eq!(interp.register_mmio_device(0xFE01..=0xFE03, &DEV, None), Err(OverlapsBuiltInDevice(0xFE02)));
+    assert_eq!(interp.register_mmio_device(0xFFF0..=0xFFFF, &DEV, None), Err(OverlapsBuiltInDevice(0xFFFA)));
+
+    let int = |priority| Some(MmioInterrupt { vector: 0x90, priority });
+    assert_eq!(interp.register_mmio_device(0xFEE0..=0xFEE1, &DEV, int(0)), Err(InvalidPriority(0)));
+    assert_eq!(interp.register_mmio_device(0xFEE0..=0xFEE1, &DEV, int(8)), Err(InvalidPriority(8)));
+
+    interp.register_mmio_device(0xFEE0..=0xFEE1, &DEV, int(7)).unwrap();
+    assert_eq!(interp.register_mmio_device(0xFEDF..=0xFEE0, &DEV, None), Err(OverlapsRegisteredDevice(0xFEE0)));
+    assert_eq!(interp.register_mmio_device(0xFEE1..=0xFEF0, &DEV, None), Err(OverlapsRegisteredDevice(0xFEE1)));
+
+    for i in 1..8 {
+        interp.register_mmio_device(0xFEE0 + 2 * i..=0xFEE1 + 2 * i, &DEV, None).unwrap();
+    }
+    assert_eq!(interp.register_mmio_device(0xFEF0..=0xFEF0, &DEV, None), Err(RegistryFull));
+}
+
+#[test]
+fn interrupts() {
+    static LOW: Device = Device::new();
+    static HIGH: Device = Device::new();
+    let mut interp = Interp::default();
+
+    interp.register_mmio_device(0xFEE0..=0xFEE1, &LOW, Some(MmioInterrupt { vector: 0x90, priority: 2 })).unwrap();
+    interp.register_mmio_device(0xFEE2..=0xFEE3, &HIGH, Some(MmioInterrupt { vector: 0x91, priority: 5 })).unwrap();
+
+    interp.set_word_unchecked(INTERRUPT_VECTOR_TABLE_START_ADDR + 0x90, 0x4000);
+    interp.set_word_unchecked(INTERRUPT_VECTOR_TABLE_START_ADDR + 0x91, 0x5000);
+    interp[R6] = 0x3000;
+
+    // Nothing should fire at priority 7 (what the machine starts at):
+    LOW.regs[0].store(1, SeqCst);
+    HIGH.regs[0].store(1, SeqCst);
+    let pc = interp.get_pc();
+    let _ = interp.step();
+    assert_eq!(interp.get_pc(), pc + 1);
+
+    // At priority 3, only the high priority device can interrupt:
+    interp.get_special_reg::<PSR>().set_priority(&mut interp, 3);
+    let _ = interp.step();
+    assert_eq!(interp.get_pc(), 0x5000);
+    assert_eq!(HIGH.resets.load(SeqCst), 1);
+    assert_eq!(LOW.resets.load(SeqCst), 0);
+
+    // At priority 1, the low priority device can interrupt:
+    interp.get_special_reg::<PSR>().set_priority(&mut interp, 1);
+    let _ = interp.step();
+    assert_eq!(interp.get_pc(), 0x4000);
+    assert_eq!(LOW.resets.load(SeqCst), 1);
+    assert_eq!(interp.get_special_reg::<PSR>().get_priority(), 2);
+}
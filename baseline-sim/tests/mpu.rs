@@ -0,0 +1,188 @@
+use lc3_baseline_sim::interp::{Interpreter, InterpreterBuilder, InstructionInterpreter};
+use lc3_baseline_sim::mem_mapped::{BSP_ADDR, MPUNR_ADDR, MPUBR_ADDR, MPUER_ADDR, MPUAR_ADDR};
+use lc3_baseline_sim::sim::Simulator;
+use lc3_isa::{Addr, Instruction, Reg::*, Word, INTERRUPT_VECTOR_TABLE_START_ADDR, PSR};
+use lc3_traits::control::{BootConfig, Control};
+use lc3_traits::control::mpu::{MpuError, MpuRegion, Permissions};
+use lc3_traits::control::rpc::SimpleEventFutureSharedState;
+use lc3_test_infrastructure::{assert_eq, with_larger_stack, MemoryShim, PeripheralsShim};
+
+type Interp = Interpreter<'static, MemoryShim, PeripheralsShim<'static>>;
+type Sim = Simulator<'static, 'static, Interp, SimpleEventFutureSharedState>;
+
+const CODE: MpuRegion = MpuRegion::new(0x3000, 0x30FF, Permissions::READ_EXECUTE, Permissions::ALL);
+const DATA: MpuRegion = MpuRegion::new(0x3100, 0x31FF, Permissions::READ_WRITE, Permissions::ALL);
+
+// The handler for exception `vec` (each one just gets an address).
+const fn handler(vec: u8) -> Addr {
+    0x1000 + vec as Addr
+}
+
+// A user mode program at 0x3000 with `regions` in the table.
+fn interp(regions: &[MpuRegion], insns: &[Instruction]) -> Interp {
+    let mut interp: Interp = InterpreterBuilder::new()
+        .with_defaults()
+        .build();
+
+    for (idx, region) in regions.iter().enumerate() {
+        interp.set_mpu_region(idx as u8, Some(*region)).unwrap();
+    }
+    for (addr, insn) in (0x3000..).zip(insns.iter()) {
+        interp.set_word_unchecked(addr, Word::from(*insn));
+    }
+    for vec in 0..5 {
+        interp.set_word_unchecked(INTERRUPT_VECTOR_TABLE_START_ADDR + vec as Addr, handler(vec));
+    }
+
+    interp.set_word_unchecked(PSR, BootConfig::USER_PSR);
+    interp.set_word_unchecked(BSP_ADDR, 0x2FF0);
+    interp[R6] = 0xFD00;
+    interp.set_pc(0x3000);
+
+    interp
+}
+
+fn run(interp: &mut Interp, steps: usize) -> Addr {
+    for _ in 0..steps {
+        let _ = interp.step();
+    }
+
+    interp.get_pc()
+}
+
+#[test]
+fn exceptions() { with_larger_stack(None, || {
+    // Jumping into data:
+    let jump = [Instruction::new_br(true, true, true, 0xFF)];
+    assert_eq!(run(&mut interp(&[], &jump), 2), 0x3101);
+    assert_eq!(run(&mut interp(&[CODE, DATA], &jump), 2), handler(0x03));
+
+    // Writing to code:
+    let store = [Instruction::new_st(R0, -1)];
+    let mut i = interp(&[CODE, DATA], &store);
+    i[R0] = 7;
+    assert_eq!(run(&mut i, 1), handler(0x04));
+    assert_eq!(i.get_word_unchecked(0x3000), Word::from(store[0]));
+
+    // Anything else is an ACV:
+    let load = [Instruction::new_ld(R0, 0xFF)];
+    let no_access = MpuRegion::new(0x3100, 0x31FF, Permissions::NONE, Permissions::ALL);
+    assert_eq!(run(&mut interp(&[CODE, DATA], &load), 1), 0x3001);
+    assert_eq!(run(&mut interp(&[CODE, no_access], &load), 1), handler(0x02));
+})}
+
+#[test]
+fn regions() { with_larger_stack(None, || {
+    let program = [
+        Instruction::new_ldr(R0, R1, 0),
+        Instruction::new_ldr(R0, R2, 0),
+    ];
+
+    // Higher numbered regions win:
+    let hole = MpuRegion::new(0x3180, 0x3180, Permissions::NONE, Permissions::NONE);
+    let mut i = interp(&[CODE, DATA, hole], &program);
+    i[R1] = 0x3100;
+    i[R2] = 0x3180;
+    assert_eq!(run(&mut i, 2), handler(0x02));
+
+    // Regions can let user mode out of user space:
+    let os_data = MpuRegion::new(0x0200, 0x02FF, Permissions::READ_ONLY, Permissions::ALL);
+    let mut i = interp(&[CODE, os_data], &program);
+    i[R1] = 0x0200;
+    i[R2] = 0x0300;
+    assert_eq!(run(&mut i, 1), 0x3001);
+    assert_eq!(run(&mut i, 1), handler(0x02));
+
+    // Supervisor mode has its own permissions:
+    let sup_code = MpuRegion::new(0x3000, 0x30FF, Permissions::NONE, Permissions::READ_EXECUTE);
+    let mut i = interp(&[sup_code], &[Instruction::new_st(R0, -1)]);
+    i.set_word_unchecked(PSR, BootConfig::OS_PSR);
+    i[R6] = 0x2FF0;
+    assert_eq!(run(&mut i, 1), handler(0x04));
+
+    // But taking an exception doesn't need permission to use the stack:
+    let stack = MpuRegion::new(0x2F00, 0x2FFF, Permissions::NONE, Permissions::NONE);
+    let mut i = interp(&[CODE, stack], &[Instruction::new_st(R0, -1)]);
+    assert_eq!(run(&mut i, 1), handler(0x04));
+    assert_eq!(i[R6], 0x2FEE);
+})}
+
+#[test]
+fn registers() { with_larger_stack(None, || {
+    let mut interp = interp(&[], &[]);
+
+    interp.set_word_unchecked(MPUNR_ADDR, 2);
+    interp.set_word_unchecked(MPUBR_ADDR, 0x4000);
+    interp.set_word_unchecked(MPUER_ADDR, 0x4FFF);
+    interp.set_word_unchecked(MPUAR_ADDR, 0x8073);
+    assert_eq!(interp.get_mpu_region(2), Ok(Some(MpuRegion::new(
+        0x4000, 0x4FFF, Permissions::READ_WRITE, Permissions::ALL,
+    ))));
+
+    // Selecting a region loads it:
+    interp.set_word_unchecked(MPUNR_ADDR, 0);
+    assert_eq!(interp.get_word_unchecked(MPUAR_ADDR), 0);
+    interp.set_word_unchecked(MPUNR_ADDR, 8 + 2);
+    assert_eq!(interp.get_word_unchecked(MPUNR_ADDR), 2);
+    assert_eq!(interp.get_word_unchecked(MPUBR_ADDR), 0x4000);
+    assert_eq!(interp.get_word_unchecked(MPUER_ADDR), 0x4FFF);
+    assert_eq!(interp.get_word_unchecked(MPUAR_ADDR), 0x8073);
+
+    // Regions that end before they start are ignored:
+    interp.set_word_unchecked(MPUBR_ADDR, 0x5000);
+    interp.set_word_unchecked(MPUAR_ADDR, 0x8077);
+    assert_eq!(interp.get_mpu_region(2).unwrap().unwrap().start, 0x4000);
+
+    // And clearing bit 15 clears the region:
+    interp.set_word_unchecked(MPUAR_ADDR, 0x0073);
+    assert_eq!(interp.get_mpu_region(2), Ok(None));
+
+    // The registers are only for supervisor mode:
+    let mut interp = self::interp(&[CODE], &[Instruction::new_sti(R0, 1)]);
+    interp.set_word_unchecked(0x3002, MPUAR_ADDR);
+    assert_eq!(run(&mut interp, 1), handler(0x02));
+})}
+
+#[test]
+fn permission_bits() { with_larger_stack(None, || {
+    let mut interp = interp(&[], &[]);
+    let region = |user, supervisor| Ok(Some(MpuRegion::new(0x4000, 0x4FFF, user, supervisor)));
+
+    interp.set_word_unchecked(MPUNR_ADDR, 1);
+    interp.set_word_unchecked(MPUBR_ADDR, 0x4000);
+    interp.set_word_unchecked(MPUER_ADDR, 0x4FFF);
+
+    // Only the user bits:
+    interp.set_word_unchecked(MPUAR_ADDR, 0x8007);
+    assert_eq!(interp.get_mpu_region(1), region(Permissions::ALL, Permissions::NONE));
+    interp.set_word_unchecked(MPUAR_ADDR, 0x8005);
+    assert_eq!(interp.get_mpu_region(1), region(Permissions::READ_EXECUTE, Permissions::NONE));
+
+    // Only the supervisor bits:
+    interp.set_word_unchecked(MPUAR_ADDR, 0x8070);
+    assert_eq!(interp.get_mpu_region(1), region(Permissions::NONE, Permissions::ALL));
+    interp.set_word_unchecked(MPUAR_ADDR, 0x8030);
+    assert_eq!(interp.get_mpu_region(1), region(Permissions::NONE, Permissions::READ_WRITE));
+
+    // And reading the region back gives the same bits:
+    interp.set_word_unchecked(MPUNR_ADDR, 1);
+    assert_eq!(interp.get_word_unchecked(MPUAR_ADDR), 0x8030);
+})}
+
+#[test]
+fn sim() { with_larger_stack(None, || {
+    let state = Box::leak(Box::new(SimpleEventFutureSharedState::new()));
+    let mut sim = Sim::new_with_state(Default::default(), state);
+
+    assert_eq!(sim.set_mpu_region(0, Some(CODE)), Ok(()));
+    assert_eq!(sim.get_mpu_region(0), Ok(Some(CODE)));
+    assert_eq!(sim.get_mpu_region(1), Ok(None));
+
+    let backwards = MpuRegion::new(0x4000, 0x3000, Permissions::ALL, Permissions::ALL);
+    assert_eq!(sim.set_mpu_region(1, Some(backwards)), Err(MpuError::InvalidRegion));
+    assert_eq!(sim.set_mpu_region(8, Some(DATA)), Err(MpuError::InvalidRegion));
+
+    // Resets don't touch the table:
+    sim.reset();
+    assert_eq!(sim.get_mpu_region(0), Ok(Some(CODE)));
+})}
@@ -0,0 +1,30 @@
+use lc3_baseline_sim::interp::Interpreter;
+use lc3_baseline_sim::mem_mapped::{RNGSR_ADDR, RNGDR_ADDR};
+use lc3_baseline_sim::sim::Simulator;
+use lc3_traits::control::Control;
+use lc3_traits::control::rpc::SimpleEventFutureSharedState;
+use lc3_traits::peripherals::rng::{Rng, RngError};
+use lc3_traits::peripherals::stubs::PeripheralsStub;
+use lc3_test_infrastructure::{assert_eq, with_larger_stack, MemoryShim, PeripheralsShim, RngShim};
+
+// The register level tests are in `device_register_tests/mem_mapped/rng.rs`;
+// these are for the things that go through `Control`.
+
+type Interp = Interpreter<'static, MemoryShim, PeripheralsShim<'static>>;
+type Sim<I> = Simulator<'static, 'static, I, SimpleEventFutureSharedState>;
+
+#[test]
+fn control() { with_larger_stack(None, || {
+    let state = Box::leak(Box::new(SimpleEventFutureSharedState::new()));
+    let mut sim = Sim::<Interp>::new_with_state(Default::default(), state);
+
+    assert_eq!(sim.set_rng_seed(0x1234_5678_9ABC), Ok(()));
+    assert_eq!(sim.read_word(RNGSR_ADDR), 0x8001);
+    assert_eq!(sim.read_word(RNGDR_ADDR), RngShim::seeded(0x1234_5678_9ABC).next_word().unwrap());
+})}
+
+#[test]
+fn no_rng() { with_larger_stack(None, || {
+    let mut sim = Sim::<Interpreter<'static, MemoryShim, PeripheralsStub<'static>>>::default();
+    assert_eq!(sim.set_rng_seed(1), Err(RngError::NoRng));
+})}
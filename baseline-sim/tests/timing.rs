@@ -0,0 +1,117 @@
+use lc3_baseline_sim::interp::{Interpreter, InterpreterBuilder, InstructionInterpreter, InstructionInterpreterPeripheralAccess};
+use lc3_baseline_sim::timing::{CacheConfig, TimingModel};
+use lc3_isa::{Instruction, Reg::*, Word, MCR};
+use lc3_traits::peripherals::clock::Clock;
+use lc3_test_infrastructure::{
+    assert_eq, with_larger_stack, MemoryShim, ShareablePeripheralsShim, TimeSource,
+    new_shareable_peripherals_shim,
+};
+
+type Interp = Interpreter<'static, MemoryShim, ShareablePeripheralsShim<'static, 'static>>;
+
+// Ten instructions (or cycles) per millisecond.
+fn with_model(model: Option<TimingModel>, program: &[Instruction]) -> Interp {
+    let mut interp: Interp = InterpreterBuilder::new()
+        .with_defaults()
+        .with_peripherals(new_shareable_peripherals_shim(TimeSource::virtual_time(10)))
+        .build();
+    interp.set_timing_model(model);
+
+    for (addr, insn) in (0x3000..).zip(program.iter()) {
+        interp.set_word_unchecked(addr, Word::from(*insn));
+    }
+    interp.set_pc(0x3000);
+
+    interp
+}
+
+fn cycles_per_step(interp: &mut Interp, steps: usize) -> Vec<u64> {
+    (0..steps).map(|_| {
+        let before = interp.get_cycle_count().unwrap();
+        let _ = interp.step();
+        interp.get_cycle_count().unwrap() - before
+    }).collect()
+}
+
+#[test]
+fn no_model() { with_larger_stack(None, || {
+    let mut interp = with_model(None, &[Instruction::new_add_imm(R0, R0, 1)]);
+
+    let _ = interp.step();
+    assert_eq!(interp.get_cycle_count(), None);
+    assert_eq!(interp.get_timing_model(), None);
+})}
+
+#[test]
+fn opcodes_and_memory() { with_larger_stack(None, || {
+    let model = TimingModel { mmio_access: 7, ..TimingModel::new() };
+    let mut interp = with_model(Some(model), &[
+        Instruction::new_add_imm(R0, R0, 1),
+        Instruction::new_ld(R1, 3),
+        Instruction::new_st(R1, 3),
+        Instruction::new_ldi(R2, 2),
+        Instruction::new_br(true, true, true, -5),
+    ]);
+    interp.set_word_unchecked(0x3005, MCR); // (for the LDI)
+
+    // (fetch + opcode + reads and writes)
+    assert_eq!(cycles_per_step(&mut interp, 5), vec![
+        5 + 4,
+        5 + 5 + 5,
+        5 + 5 + 5,
+        5 + 6 + 5 + 7,
+        5 + 4,
+    ]);
+    assert_eq!(interp.get_cycle_count(), Some(9 + 15 + 15 + 23 + 9));
+    assert_eq!(interp.get_pc(), 0x3000);
+
+    // Resets start the count over:
+    interp.reset();
+    assert_eq!(interp.get_cycle_count(), Some(0));
+})}
+
+#[test]
+fn cache() { with_larger_stack(None, || {
+    let model = TimingModel::new().with_cache(CacheConfig::new(4, 4, 1));
+    let mut interp = with_model(Some(model), &[
+        Instruction::new_add_imm(R0, R0, 1),
+        Instruction::new_br(true, true, true, -2),
+    ]);
+
+    // The first fetch misses and brings in the line both instructions are in:
+    assert_eq!(cycles_per_step(&mut interp, 4), vec![5 + 4, 1 + 4, 1 + 4, 1 + 4]);
+
+    // Stores don't change the cache, loads do:
+    let mut interp = with_model(Some(model), &[
+        Instruction::new_st(R0, 6),
+        Instruction::new_ld(R0, 4),
+        Instruction::new_ld(R0, 4),
+        Instruction::new_ld(R0, -4), // Same line as the instructions.
+        Instruction::new_ld(R0, 0x0C), // Same spot as the instructions' line.
+        Instruction::new_br(true, true, true, -6),
+    ]);
+    assert_eq!(cycles_per_step(&mut interp, 7), vec![
+        5 + 5 + 5,
+        1 + 5 + 5,
+        1 + 5 + 1,
+        1 + 5 + 1,
+        1 + 5 + 5,
+        1 + 4,
+        5 + 5 + 5,
+    ]);
+})}
+
+#[test]
+fn drive_clock() { with_larger_stack(None, || {
+    let program = [Instruction::new_br(true, true, true, -1)];
+
+    let mut interp = with_model(Some(TimingModel::new()), &program);
+    for _ in 0..20 { let _ = interp.step(); }
+    assert_eq!(Clock::get_milliseconds(interp.get_peripherals()), 2);
+
+    // 9 cycles per BR:
+    let mut interp = with_model(Some(TimingModel::new().driving_clock()), &program);
+    for _ in 0..20 { let _ = interp.step(); }
+    assert_eq!(interp.get_cycle_count(), Some(180));
+    assert_eq!(Clock::get_milliseconds(interp.get_peripherals()), 18);
+})}
@@ -0,0 +1,174 @@
+use lc3_baseline_sim::interp::{Interpreter, InterpreterBuilder, InstructionInterpreter};
+use lc3_baseline_sim::sim::Simulator;
+use lc3_isa::{util::{AssembledProgram, MemoryDump}, Addr, Instruction, Reg::*, Word, ADDR_SPACE_SIZE_IN_WORDS};
+use lc3_traits::control::{Control, Event};
+use lc3_traits::control::boot::ResetKind;
+use lc3_traits::control::load::{load_memory_dump_compressed, Progress};
+use lc3_traits::control::rpc::SimpleEventFutureSharedState;
+use lc3_traits::error::{Error, Location};
+use lc3_test_infrastructure::{assert_eq, with_larger_stack, MemoryShim, PeripheralsShim};
+
+type Interp = Interpreter<'static, MemoryShim, PeripheralsShim<'static>>;
+type Sim = Simulator<'static, 'static, Interp, SimpleEventFutureSharedState>;
+
+// A program at 0x3000 followed by a word that's set (at 0x3010) and one that
+// isn't (0x3011), in the same page.
+fn program(insns: &[Instruction]) -> AssembledProgram {
+    let mut prog = AssembledProgram::new([(0, false); ADDR_SPACE_SIZE_IN_WORDS]);
+    for (addr, insn) in (0x3000..).zip(insns.iter()) {
+        prog[addr] = (Word::from(*insn), true);
+    }
+    prog[0x3010] = (1, true);
+
+    prog
+}
+
+fn interp(insns: &[Instruction]) -> Interp {
+    let mut interp: Interp = InterpreterBuilder::new()
+        .with_defaults()
+        .with_uninitialized_read_detection(true)
+        .build();
+    interp.load_program(&program(insns));
+    interp.set_pc(0x3000);
+
+    interp
+}
+
+fn uninit(pc: Addr, location: Location) -> Option<Error> {
+    Some(Error::UninitializedRead { pc, location })
+}
+
+#[test]
+fn off_by_default() { with_larger_stack(None, || {
+    let mut interp: Interp = InterpreterBuilder::new()
+        .with_defaults()
+        .build();
+    assert!(!interp.get_uninitialized_read_detection());
+
+    interp.load_program(&program(&[Instruction::new_ld(R0, 0x10)]));
+    interp.set_pc(0x3000);
+    let _ = interp.step();
+    assert_eq!(interp.get_error(), None);
+})}
+
+#[test]
+fn memory() { with_larger_stack(None, || {
+    let mut interp = interp(&[
+        Instruction::new_ld(R0, 0x0F),
+        Instruction::new_ld(R1, 0x0F),
+        Instruction::new_st(R0, 0x0E),
+        Instruction::new_ld(R1, 0x0D),
+    ]);
+
+    let expected = [None, uninit(0x3001, Location::Memory(0x3011)), None, None];
+    for e in expected.iter() {
+        let _ = interp.step();
+        assert_eq!(interp.get_error(), *e);
+    }
+    assert_eq!(interp[R1], 1);
+
+    // Running off the end of the program is a read too:
+    let _ = interp.step();
+    assert_eq!(interp.get_error(), uninit(0x3004, Location::Memory(0x3004)));
+})}
+
+#[test]
+fn registers() { with_larger_stack(None, || {
+    let mut interp = interp(&[
+        Instruction::new_and_imm(R0, R0, 0),
+        Instruction::new_add_reg(R1, R0, R2),
+        Instruction::new_st(R3, 0x0D),
+        Instruction::new_jsr(0),
+        Instruction::new_add_imm(R4, R7, 0),
+        Instruction::new_str(R4, R5, 0),
+    ]);
+
+    let expected = [
+        None,
+        uninit(0x3001, Location::Register(R2)),
+        None,
+        None,
+        None,
+        uninit(0x3005, Location::Register(R5)),
+    ];
+    for e in expected.iter() {
+        let _ = interp.step();
+        assert_eq!(interp.get_error(), *e);
+    }
+})}
+
+#[test]
+fn first_read_is_reported() { with_larger_stack(None, || {
+    let mut interp = interp(&[
+        Instruction::new_add_reg(R0, R1, R2),
+        Instruction::new_add_imm(R3, R4, 0),
+    ]);
+
+    // Both R1 and R2 are read before they're set:
+    let _ = interp.step();
+    assert_eq!(interp.get_error(), uninit(0x3000, Location::Register(R1)));
+
+    let _ = interp.step();
+    assert_eq!(interp.get_error(), uninit(0x3001, Location::Register(R4)));
+})}
+
+#[test]
+fn resets() { with_larger_stack(None, || {
+    let mut interp = interp(&[
+        Instruction::new_ld(R0, 0x10),
+        Instruction::new_add_imm(R0, R1, 0),
+    ]);
+
+    interp.set_word(0x3011, 5).unwrap();
+    interp.set_register(R1, 2);
+    let _ = interp.step();
+    let _ = interp.step();
+    assert_eq!(interp.get_error(), None);
+
+    // Warm resets forget the registers:
+    let boot = interp.get_boot_config();
+    interp.reset_with(ResetKind::Warm, boot);
+    interp.set_pc(0x3000);
+    let _ = interp.step();
+    assert_eq!(interp.get_error(), None);
+    let _ = interp.step();
+    assert_eq!(interp.get_error(), uninit(0x3001, Location::Register(R1)));
+
+    // And cold resets forget what was written (but not what was loaded):
+    interp.reset();
+    interp.set_pc(0x3000);
+    let _ = interp.step();
+    assert_eq!(interp.get_error(), uninit(0x3000, Location::Memory(0x3011)));
+    assert_eq!(interp.get_word_unchecked(0x3000), Word::from(Instruction::new_ld(R0, 0x10)));
+})}
+
+#[test]
+fn sim() { with_larger_stack(None, || {
+    let state = Box::leak(Box::new(SimpleEventFutureSharedState::new()));
+    let mut sim = Sim::new_with_state(Default::default(), state);
+    assert_eq!(sim.set_uninitialized_read_detection(true), Ok(()));
+
+    sim.write_word(0x3000, Word::from(Instruction::new_add_imm(R0, R0, 1)));
+    sim.set_pc(0x3000);
+
+    let err = Error::UninitializedRead { pc: 0x3000, location: Location::Register(R0) };
+    assert_eq!(sim.step(), Some(Event::Error { err }));
+    assert_eq!(err.to_string(), "The instruction at x3000 read R0 before it was set");
+})}
+
+#[test]
+fn compressed_loads() { with_larger_stack(None, || {
+    let state = Box::leak(Box::new(SimpleEventFutureSharedState::new()));
+    let mut sim = Sim::new_with_state(Default::default(), state);
+    assert_eq!(sim.set_uninitialized_read_detection(true), Ok(()));
+
+    // Blank pages still have to be sent (and committed) so that their words
+    // count as loaded:
+    let mut dump = MemoryDump::blank();
+    dump[0x3000] = Word::from(Instruction::new_ld(R0, 0xFF));
+    load_memory_dump_compressed(&mut sim, &dump, None, None::<&Progress>).unwrap();
+
+    sim.set_pc(0x3000);
+    assert_eq!(sim.step(), None);
+    assert_eq!(sim.get_error(), None);
+})}
@@ -0,0 +1,59 @@
+use lc3_baseline_sim::interp::{Interpreter, InterpreterBuilder, InstructionInterpreter, InstructionInterpreterPeripheralAccess};
+use lc3_baseline_sim::mem_mapped::{WDCR_ADDR, WDTR_ADDR, WDKR_ADDR};
+use lc3_baseline_sim::sim::Simulator;
+use lc3_isa::{Instruction, Word};
+use lc3_traits::control::Control;
+use lc3_traits::control::rpc::SimpleEventFutureSharedState;
+use lc3_traits::peripherals::stubs::PeripheralsStub;
+use lc3_traits::peripherals::watchdog::{Watchdog, WatchdogError};
+use lc3_test_infrastructure::{
+    assert_eq, with_larger_stack, virtual_time_peripherals, MemoryShim, ShareablePeripheralsShim,
+};
+
+// The register level tests are in `device_register_tests/mem_mapped/watchdog.rs`;
+// these are for the things that go through `Control`.
+
+type Interp = Interpreter<'static, MemoryShim, ShareablePeripheralsShim<'static, 'static>>;
+type Sim<I> = Simulator<'static, 'static, I, SimpleEventFutureSharedState>;
+
+#[test]
+fn debugger() { with_larger_stack(None, || {
+    let state = Box::leak(Box::new(SimpleEventFutureSharedState::new()));
+    let mut interp: Interp = InterpreterBuilder::new()
+        .with_defaults()
+        .with_peripherals(virtual_time_peripherals())
+        .build();
+
+    // An infinite loop:
+    interp.set_pc(0x3000);
+    interp.set_word_unchecked(0x3000, Word::from(Instruction::new_br(true, true, true, -1)));
+
+    let mut watchdog = interp.get_peripherals().get_watchdog().clone();
+    let mut sim = Sim::<Interp>::new_with_state(interp, state);
+
+    sim.write_word(WDTR_ADDR, 10);
+    assert_eq!(sim.set_watchdog_disabled(true), Ok(()));
+    assert_eq!(sim.read_word(WDCR_ADDR), 0x0102);
+
+    for _ in 0..50 {
+        let _ = sim.step();
+    }
+    assert_eq!(sim.get_pc(), 0x3000);
+    assert_eq!(sim.get_error(), None);
+
+    assert_eq!(sim.set_watchdog_disabled(false), Ok(()));
+    assert_eq!(sim.read_word(WDCR_ADDR), 0x0100);
+    assert_eq!(sim.read_word(WDKR_ADDR), 10);
+
+    // Resetting from the debugger isn't the watchdog's fault:
+    Watchdog::set_caused_reset(&mut watchdog, true);
+    assert_eq!(sim.read_word(WDCR_ADDR), 0x0101);
+    sim.reset();
+    assert_eq!(sim.read_word(WDCR_ADDR), 0x0100);
+})}
+
+#[test]
+fn no_watchdog() { with_larger_stack(None, || {
+    let mut sim = Sim::<Interpreter<'static, MemoryShim, PeripheralsStub<'static>>>::default();
+    assert_eq!(sim.set_watchdog_disabled(true), Err(WatchdogError::NoWatchdog));
+})}
@@ -37,7 +37,7 @@ impl<I: Iterator<Item = u8>> Source for BufferedInput<I> {
 }
 
 use lc3_baseline_sim::interp::{Interpreter, InterpreterBuilder};
-use lc3_traits::peripherals::{stubs::{GpioStub, AdcStub, PwmStub, TimersStub, ClockStub}, PeripheralSet};
+use lc3_traits::peripherals::{stubs::{GpioStub, AdcStub, PwmStub, TimersStub, ClockStub, DisplayStub, DiskStub, UartStub, RngStub, WatchdogStub}, PeripheralSet};
 use lc3_shims::peripherals::output::{OutputShim, Sink};
 use lc3_shims::memory::MemoryShim;
 use lc3_isa::util::MemoryDump;
@@ -59,6 +59,11 @@ pub fn interpreter<'b, 's>(
         ClockStub,
         InputShim<'s, 'b>,
         OutputShim<'s, 'b>,
+        DisplayStub,
+        DiskStub,
+        UartStub,
+        RngStub,
+        WatchdogStub,
     >
 > {
     let memory = MemoryShim::new(**program);
@@ -71,6 +76,11 @@ pub fn interpreter<'b, 's>(
         ClockStub,
         InputShim::using(Box::new(BufferedInput::new(inp))),
         OutputShim::using(Box::new(out)),
+        DisplayStub,
+        DiskStub,
+        UartStub,
+        RngStub,
+        WatchdogStub,
     );
 
     let mut interp: Interpreter::<'b, MemoryShim, _> = InterpreterBuilder::new()
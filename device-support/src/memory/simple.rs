@@ -187,6 +187,8 @@ impl IndexMut<Addr> for &'_ mut PartialMemory {
 
 #[deny(unconditional_recursion)]
 impl Memory for &'_ mut PartialMemory {
+    fn read_committed_word(&self, addr: Addr) -> Word { (&**self).read_committed_word(addr) }
+
     fn commit_page(&mut self, page_idx: PageIndex, page: &[Word; PAGE_SIZE_IN_WORDS as usize]) {
         (&mut **self).commit_page(page_idx, page)
     }
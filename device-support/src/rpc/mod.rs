@@ -861,3 +861,4 @@
 
 pub mod encoding;
 pub mod transport;
+pub mod reliability;
@@ -0,0 +1,679 @@
+//! An optional reliability layer that sits between the encoding layer and the
+//! transport layer.
+//!
+//! [`ReliableTransport`] wraps any [`Transport`] that moves `Fifo<u8>`s and
+//! makes it so that messages sent across it arrive exactly once and in order
+//! (or not at all, in which case the sender is told so). It does this with
+//! the machinery described in the [module docs](super):
+//!
+//!   - every message is put in a frame that has a _kind_ (data, ack, or nak),
+//!     a sequence number, and a CRC-16 (CCITT) of everything before it
+//!   - frames are COBS encoded (with 0 as the sentinel) so that they can go
+//!     over transports that look for sentinels, like the UART ones
+//!   - data frames that arrive intact are acknowledged; frames that fail the
+//!     COBS or CRC checks are answered with a NAK that asks the sender to
+//!     resend everything starting from the frame we were expecting (only
+//!     one NAK goes out per expected frame)
+//!   - the sender keeps up to [`WINDOW`] unacknowledged frames around and
+//!     resends them (go-back-N) when it gets a NAK or when the oldest one
+//!     hasn't been acknowledged within the configured timeout
+//!   - after [`ReliabilityConfig::max_retransmits`] failed retransmits
+//!     (whether they were caused by NAKs or by timeouts), the sender gives
+//!     up and reports [`ReliableTransportError::RetriesExhausted`] instead of
+//!     waiting forever
+//!   - data frames that have already been delivered (i.e. retransmits that
+//!     happened because an acknowledgement was lost) are acknowledged again
+//!     but are _not_ passed up again; this is what keeps the [`Device`] from
+//!     running a request twice
+//!
+//! Both sides of a connection use the same type; there's no real distinction
+//! between requester and responder at this layer.
+//!
+//! Because retransmits are driven by [`Transport::get`], both sides need to
+//! call `get` regularly. Both [`Controller`] and [`Device`] already do this.
+//!
+//! Note that both sides start at sequence number 0; if one side restarts and
+//! the other doesn't, the sequence numbers will no longer line up and sends
+//! will eventually fail with [`ReliableTransportError::RetriesExhausted`].
+//!
+//! [`Controller`]: lc3_traits::control::rpc::Controller
+//! [`Device`]: lc3_traits::control::rpc::Device
+
+use crate::util::fifo::{Fifo, CAPACITY};
+
+use lc3_traits::control::rpc::Transport;
+use lc3_traits::control::{Identifier, Version};
+
+use core::cell::{Cell, RefCell};
+use core::fmt::Debug;
+
+/// The maximum number of frames that can be unacknowledged at once.
+///
+/// Must divide 256 (the number of sequence numbers) evenly.
+pub const WINDOW: usize = 4;
+
+sa::const_assert!(256 % WINDOW == 0);
+
+/// Kind + sequence number + CRC.
+pub const FRAME_OVERHEAD: usize = 2 + 2;
+
+/// The size of the largest message that can be sent across a
+/// [`ReliableTransport`].
+///
+/// This accounts for the frame overhead, the worst case COBS overhead, and the
+/// sentinel.
+pub const MAX_MESSAGE_SIZE: usize = CAPACITY - FRAME_OVERHEAD - (CAPACITY / 254 + 1) - 1;
+
+mod kind {
+    pub const DATA: u8 = 0xD1;
+    pub const ACK: u8 = 0xAC;
+    pub const NAK: u8 = 0x4E;
+}
+
+/// Something that tells the time, in arbitrary (but consistent and monotonic)
+/// units.
+///
+/// [`ReliabilityConfig::timeout`] is in these same units.
+pub trait TimeSource {
+    fn now(&self) -> u64;
+}
+
+/// A [`TimeSource`] that advances by one every time it's asked for the time.
+///
+/// This is deterministic (which is great for tests) but the timeout ends up
+/// being a number of _polls_ rather than an amount of time; if one side
+/// polls much faster than the other responds, it'll retransmit needlessly.
+#[derive(Debug, Default)]
+pub struct PollCount(Cell<u64>);
+
+impl PollCount {
+    pub const fn new() -> Self {
+        Self(Cell::new(0))
+    }
+}
+
+impl TimeSource for PollCount {
+    fn now(&self) -> u64 {
+        let now = self.0.get();
+        self.0.set(now.wrapping_add(1));
+
+        now
+    }
+}
+
+using_std! {
+    use std::time::Instant;
+
+    /// A [`TimeSource`] in milliseconds, backed by [`Instant`].
+    #[derive(Debug, Clone)]
+    pub struct StdTime(Instant);
+
+    impl Default for StdTime {
+        fn default() -> Self {
+            Self(Instant::now())
+        }
+    }
+
+    impl TimeSource for StdTime {
+        fn now(&self) -> u64 {
+            self.0.elapsed().as_millis() as u64
+        }
+    }
+
+    impl<T> ReliableTransport<T, StdTime>
+    where
+        T: Transport<Fifo<u8>, Fifo<u8>>,
+    {
+        /// Uses [`StdTime`] and the default config (100ms timeouts).
+        pub fn with_std_time(inner: T) -> Self {
+            Self::new(inner, StdTime::default(), ReliabilityConfig::default())
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ReliabilityConfig {
+    /// How long to wait for an acknowledgement before retransmitting, in the
+    /// units of the [`TimeSource`] being used.
+    pub timeout: u64,
+    /// How many times to retransmit a frame before giving up.
+    pub max_retransmits: u8,
+}
+
+impl Default for ReliabilityConfig {
+    fn default() -> Self {
+        Self {
+            timeout: 100,
+            max_retransmits: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReliableTransportError<S: Debug, R: Debug> {
+    /// The transport underneath us failed to send.
+    Send(S),
+    /// The transport underneath us failed to receive.
+    Recv(R),
+    /// The frame with this sequence number was retransmitted
+    /// [`ReliabilityConfig::max_retransmits`] times without being
+    /// acknowledged. It (and any frames after it) have been dropped.
+    RetriesExhausted { seq: u8 },
+    /// The message was larger than [`MAX_MESSAGE_SIZE`].
+    MessageTooLarge(usize),
+}
+
+/// Why an incoming frame was rejected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FrameError {
+    BadCobs,
+    TooShort,
+    ChecksumMismatch { expected: u16, got: u16 },
+    UnknownKind(u8),
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ReliabilityStats {
+    pub frames_sent: u64,
+    pub retransmits: u64,
+    pub naks_sent: u64,
+    pub naks_received: u64,
+    pub bad_frames: u64,
+    pub duplicates: u64,
+    pub out_of_order: u64,
+}
+
+/// CRC-16/CCITT-FALSE.
+fn crc16<'a>(data: impl Iterator<Item = &'a u8>) -> u16 {
+    data.fold(0xFFFF, |mut crc: u16, byte| {
+        crc ^= (*byte as u16) << 8;
+
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+
+        crc
+    })
+}
+
+fn copy(fifo: &Fifo<u8>) -> Fifo<u8> {
+    let mut new = Fifo::new();
+    new.push_slice(fifo.as_slice()).expect("fifo: same capacity");
+
+    new
+}
+
+/// Builds a (COBS encoded, sentinel terminated) frame.
+fn frame(kind: u8, seq: u8, payload: &[u8]) -> Result<Fifo<u8>, usize> {
+    if payload.len() > MAX_MESSAGE_SIZE {
+        return Err(payload.len());
+    }
+
+    let mut raw = [0u8; CAPACITY];
+    let len = payload.len() + FRAME_OVERHEAD;
+
+    raw[0] = kind;
+    raw[1] = seq;
+    raw[2..(2 + payload.len())].copy_from_slice(payload);
+
+    let crc = crc16(raw[..(len - 2)].iter());
+    raw[len - 2..len].copy_from_slice(&crc.to_le_bytes());
+
+    let mut encoded = [0u8; CAPACITY];
+    let enc_len = postcard_cobs::encode(&raw[..len], &mut encoded);
+
+    let mut out = Fifo::new();
+    out.push_slice(&encoded[..enc_len]).map_err(|()| payload.len())?;
+    out.push(0).map_err(|()| payload.len())?;
+
+    Ok(out)
+}
+
+/// Checks and takes apart an incoming frame.
+///
+/// Returns the kind, the sequence number, and the payload (in `buf`).
+fn unframe<'b>(wire: &Fifo<u8>, buf: &'b mut [u8; CAPACITY]) -> Result<(u8, u8, &'b [u8]), FrameError> {
+    let mut bytes = wire.as_slice();
+
+    // Some transports hand us the sentinel; others don't.
+    if let Some((0, rest)) = bytes.split_last() {
+        bytes = rest;
+    }
+
+    // Note: `postcard_cobs::decode` panics on some kinds of malformed input so
+    // we decode in place instead.
+    buf[..bytes.len()].copy_from_slice(bytes);
+    let len = postcard_cobs::decode_in_place(&mut buf[..bytes.len()])
+        .map_err(|()| FrameError::BadCobs)?;
+
+    if len < FRAME_OVERHEAD {
+        return Err(FrameError::TooShort);
+    }
+
+    let expected = crc16(buf[..(len - 2)].iter());
+    let got = u16::from_le_bytes([buf[len - 2], buf[len - 1]]);
+
+    if expected != got {
+        return Err(FrameError::ChecksumMismatch { expected, got });
+    }
+
+    match buf[0] {
+        k @ kind::DATA | k @ kind::ACK | k @ kind::NAK => Ok((k, buf[1], &buf[2..(len - 2)])),
+        k => Err(FrameError::UnknownKind(k)),
+    }
+}
+
+#[derive(Debug)]
+struct Pending {
+    wire: Fifo<u8>,
+    sent_at: u64,
+    retransmits: u8,
+}
+
+#[derive(Debug)]
+struct State {
+    /// Oldest unacknowledged sequence number.
+    base: u8,
+    /// Sequence number the next data frame we send will get.
+    next_seq: u8,
+    /// Indexed by `seq % WINDOW`.
+    window: [Option<Pending>; WINDOW],
+    /// Sequence number of the next data frame we're willing to accept.
+    expected: u8,
+    /// Whether we've already asked for `expected` again (with a NAK).
+    nakd: bool,
+    /// A message that arrived while we were waiting for room in the window
+    /// (in `send`) that hasn't been handed out yet.
+    held: Option<Fifo<u8>>,
+    stats: ReliabilityStats,
+}
+
+impl State {
+    const fn new() -> Self {
+        Self {
+            base: 0,
+            next_seq: 0,
+            window: [None, None, None, None],
+            expected: 0,
+            nakd: false,
+            held: None,
+            stats: ReliabilityStats {
+                frames_sent: 0,
+                retransmits: 0,
+                naks_sent: 0,
+                naks_received: 0,
+                bad_frames: 0,
+                duplicates: 0,
+                out_of_order: 0,
+            },
+        }
+    }
+
+    fn outstanding(&self) -> usize {
+        self.next_seq.wrapping_sub(self.base) as usize
+    }
+
+    /// Marks everything up to and including `seq` as acknowledged.
+    fn acknowledge(&mut self, seq: u8) {
+        // Ignore acknowledgements for things that aren't in flight.
+        if (seq.wrapping_sub(self.base) as usize) >= self.outstanding() {
+            return;
+        }
+
+        let end = seq.wrapping_add(1);
+        while self.base != end {
+            self.window[self.base as usize % WINDOW] = None;
+            self.base = self.base.wrapping_add(1);
+        }
+    }
+}
+
+/// See the [module docs](self).
+#[derive(Debug)]
+pub struct ReliableTransport<T, C>
+where
+    T: Transport<Fifo<u8>, Fifo<u8>>,
+    C: TimeSource,
+{
+    pub inner: T,
+    clock: C,
+    config: ReliabilityConfig,
+    state: RefCell<State>,
+}
+
+type Error<T> = ReliableTransportError<
+    <T as Transport<Fifo<u8>, Fifo<u8>>>::SendErr,
+    <T as Transport<Fifo<u8>, Fifo<u8>>>::RecvErr,
+>;
+
+impl<T, C> ReliableTransport<T, C>
+where
+    T: Transport<Fifo<u8>, Fifo<u8>>,
+    C: TimeSource,
+{
+    pub /*const*/ fn new(inner: T, clock: C, config: ReliabilityConfig) -> Self {
+        Self {
+            inner,
+            clock,
+            config,
+            state: RefCell::new(State::new()),
+        }
+    }
+
+    pub fn stats(&self) -> ReliabilityStats {
+        self.state.borrow().stats
+    }
+
+    /// Number of data frames that have been sent but not acknowledged yet.
+    pub fn num_unacknowledged(&self) -> usize {
+        self.state.borrow().outstanding()
+    }
+
+    /// Asks for everything starting at `expected` again.
+    ///
+    /// Only one NAK is sent per expected frame; if it's lost (or if the
+    /// retransmits it causes are) the sender's timeout takes over. Answering
+    /// every bad frame with a NAK would mean that when NAKs themselves get
+    /// mangled, both sides would go back and forth NAKing each other's NAKs
+    /// forever.
+    fn send_nak(&self, state: &mut State) -> Result<(), Error<T>> {
+        if state.nakd { return Ok(()); }
+        state.nakd = true;
+
+        let expected = state.expected;
+        self.send_control(state, kind::NAK, expected)
+    }
+
+    fn send_control(&self, state: &mut State, kind: u8, seq: u8) -> Result<(), Error<T>> {
+        if kind == kind::NAK { state.stats.naks_sent += 1; }
+
+        let wire = frame(kind, seq, &[]).expect("control frames are small");
+        self.inner.send(wire).map_err(ReliableTransportError::Send)
+    }
+
+    /// Resends everything in the window (oldest first).
+    ///
+    /// If the oldest frame has already been retransmitted
+    /// [`ReliabilityConfig::max_retransmits`] times, this gives up on
+    /// everything in flight instead and returns
+    /// [`ReliableTransportError::RetriesExhausted`].
+    fn retransmit_all(&self, state: &mut State, now: u64) -> Result<(), Error<T>> {
+        let base = state.base;
+
+        if state.outstanding() != 0 {
+            let oldest = state.window[base as usize % WINDOW]
+                .as_ref()
+                .expect("in-flight frames are in the window");
+
+            if oldest.retransmits >= self.config.max_retransmits {
+                // Give up on everything in flight.
+                for slot in state.window.iter_mut() { *slot = None; }
+                state.base = state.next_seq;
+
+                return Err(ReliableTransportError::RetriesExhausted { seq: base });
+            }
+        }
+
+        let mut seq = base;
+
+        while seq != state.next_seq {
+            let pending = state.window[seq as usize % WINDOW]
+                .as_mut()
+                .expect("in-flight frames are in the window");
+
+            pending.sent_at = now;
+            pending.retransmits += 1;
+
+            let wire = copy(&pending.wire);
+            state.stats.retransmits += 1;
+            state.stats.frames_sent += 1;
+
+            self.inner.send(wire).map_err(ReliableTransportError::Send)?;
+            seq = seq.wrapping_add(1);
+        }
+
+        Ok(())
+    }
+
+    /// Processes any frames that have come in and does any retransmits that
+    /// are due.
+    ///
+    /// Returns the payload of the next data frame, if one arrived.
+    fn poll(&self) -> Result<Fifo<u8>, Option<Error<T>>> {
+        let mut state = self.state.borrow_mut();
+        let mut buf = [0u8; CAPACITY];
+
+        loop {
+            let wire = match self.inner.get() {
+                Ok(wire) => wire,
+                Err(None) => break,
+                Err(Some(err)) => return Err(Some(ReliableTransportError::Recv(err))),
+            };
+
+            let (kind, seq, payload) = match unframe(&wire, &mut buf) {
+                Ok(f) => f,
+                Err(err) => {
+                    log::trace!("Bad frame: {:?}", err);
+                    state.stats.bad_frames += 1;
+
+                    self.send_nak(&mut state).map_err(Some)?;
+                    continue;
+                },
+            };
+
+            match kind {
+                kind::ACK => state.acknowledge(seq),
+                kind::NAK => {
+                    // A NAK asks for everything starting at `seq`; so
+                    // everything before `seq` made it.
+                    state.stats.naks_received += 1;
+                    state.acknowledge(seq.wrapping_sub(1));
+
+                    let now = self.clock.now();
+                    self.retransmit_all(&mut state, now).map_err(Some)?;
+                },
+                kind::DATA => {
+                    let expected = state.expected;
+                    let behind = expected.wrapping_sub(seq);
+
+                    if seq == expected && state.held.is_some() {
+                        // No room for it; don't ack it so that it gets sent
+                        // again later.
+                        continue;
+                    } else if seq == expected {
+                        state.expected = expected.wrapping_add(1);
+                        state.nakd = false;
+                        self.send_control(&mut state, kind::ACK, seq).map_err(Some)?;
+
+                        let mut message = Fifo::new();
+                        message.push_slice(payload).expect("payload fits in a fifo");
+
+                        return Ok(message);
+                    } else if behind != 0 && behind <= 128 {
+                        // We've seen this one already; our ack must have been
+                        // lost. Ack again but don't pass the message up.
+                        state.stats.duplicates += 1;
+                        self.send_control(&mut state, kind::ACK, expected.wrapping_sub(1)).map_err(Some)?;
+                    } else {
+                        // We missed something; ask for it again.
+                        state.stats.out_of_order += 1;
+                        self.send_nak(&mut state).map_err(Some)?;
+                    }
+                },
+                _ => unreachable!(),
+            }
+        }
+
+        // Retransmit if the oldest frame has timed out:
+        if state.outstanding() != 0 {
+            let now = self.clock.now();
+            let base = state.base;
+            let oldest = state.window[base as usize % WINDOW].as_ref().unwrap();
+
+            if now.wrapping_sub(oldest.sent_at) >= self.config.timeout {
+                self.retransmit_all(&mut state, now).map_err(Some)?;
+            }
+        }
+
+        Err(None)
+    }
+}
+
+impl<T, C> Transport<Fifo<u8>, Fifo<u8>> for ReliableTransport<T, C>
+where
+    T: Transport<Fifo<u8>, Fifo<u8>>,
+    C: TimeSource,
+{
+    type RecvErr = Error<T>;
+    type SendErr = Error<T>;
+
+    const ID: Identifier = T::ID;
+    const VER: Version = {
+        let ver = T::VER;
+
+        let id = Identifier::new_from_str_that_crashes_on_invalid_inputs("rlbl");
+
+        Version::new(ver.major, ver.minor, ver.patch, Some(id))
+    };
+
+    /// If the window is full, this will poll (and process incoming
+    /// acknowledgements) until there's room. One data frame that arrives while
+    /// we're waiting is held on to (and returned by the next `get`); any
+    /// others are dropped without being acknowledged so that the other side
+    /// retransmits them later.
+    fn send(&self, message: Fifo<u8>) -> Result<(), Error<T>> {
+        let wire = frame(kind::DATA, self.state.borrow().next_seq, message.as_slice())
+            .map_err(ReliableTransportError::MessageTooLarge)?;
+
+        while self.num_unacknowledged() >= WINDOW {
+            match self.poll() {
+                Ok(message) => self.state.borrow_mut().held = Some(message),
+                Err(None) => {},
+                Err(Some(err)) => return Err(err),
+            }
+        }
+
+        let mut state = self.state.borrow_mut();
+        let seq = state.next_seq;
+
+        state.window[seq as usize % WINDOW] = Some(Pending {
+            wire: copy(&wire),
+            sent_at: self.clock.now(),
+            retransmits: 0,
+        });
+        state.next_seq = seq.wrapping_add(1);
+        state.stats.frames_sent += 1;
+
+        self.inner.send(wire).map_err(ReliableTransportError::Send)
+    }
+
+    fn get(&self) -> Result<Fifo<u8>, Option<Error<T>>> {
+        if let Some(message) = self.state.borrow_mut().held.take() {
+            return Ok(message);
+        }
+
+        self.poll()
+    }
+
+    fn num_get_errors(&self) -> u64 {
+        self.state.borrow().stats.bad_frames + self.inner.num_get_errors()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use lc3_traits::control::rpc::MpscTransport;
+
+    use pretty_assertions::assert_eq;
+
+    type Inner = MpscTransport<Fifo<u8>, Fifo<u8>>;
+
+    fn pair(config: ReliabilityConfig) -> (ReliableTransport<Inner, PollCount>, ReliableTransport<Inner, PollCount>) {
+        let (a, b) = MpscTransport::new();
+
+        (
+            ReliableTransport::new(a, PollCount::new(), config),
+            ReliableTransport::new(b, PollCount::new(), config),
+        )
+    }
+
+    fn msg(bytes: &[u8]) -> Fifo<u8> {
+        let mut f = Fifo::new();
+        f.push_slice(bytes).unwrap();
+        f
+    }
+
+    fn recv<T: Transport<Fifo<u8>, Fifo<u8>>>(t: &T) -> Vec<u8> where T::RecvErr: Debug {
+        for _ in 0..1000 {
+            match t.get() {
+                Ok(m) => return m.as_slice().to_vec(),
+                Err(None) => {},
+                Err(Some(e)) => panic!("{:?}", e),
+            }
+        }
+
+        panic!("no message")
+    }
+
+    #[test]
+    fn crc() {
+        // The check value for CRC-16/CCITT-FALSE:
+        assert_eq!(0x29B1, crc16(b"123456789".iter()));
+    }
+
+    #[test]
+    fn frame_roundtrip() {
+        let mut buf = [0; CAPACITY];
+        let f = frame(kind::DATA, 7, &[0, 1, 2, 0, 255]).unwrap();
+
+        assert!(f.as_slice()[..(f.length() - 1)].iter().all(|b| *b != 0));
+        assert_eq!(Ok((kind::DATA, 7, &[0, 1, 2, 0, 255][..])), unframe(&f, &mut buf));
+    }
+
+    #[test]
+    fn corrupted_frame() {
+        let mut buf = [0; CAPACITY];
+        let f = frame(kind::DATA, 7, &[1, 2, 3]).unwrap();
+
+        let mut bytes = f.as_slice().to_vec();
+        bytes[3] ^= 0x10;
+
+        assert!(unframe(&msg(&bytes), &mut buf).is_err());
+    }
+
+    #[test]
+    fn in_order_delivery() {
+        let (a, b) = pair(ReliabilityConfig::default());
+
+        for i in 0..20u8 {
+            a.send(msg(&[i, i, i])).unwrap();
+            assert_eq!(vec![i, i, i], recv(&b));
+
+            b.send(msg(&[i])).unwrap();
+            assert_eq!(vec![i], recv(&a));
+        }
+
+        assert_eq!(0, b.stats().duplicates);
+        assert_eq!(0, a.num_unacknowledged());
+    }
+
+    #[test]
+    fn gives_up_eventually() {
+        // `b` never calls `get` so nothing is ever acknowledged.
+        let (a, _b) = pair(ReliabilityConfig { timeout: 5, max_retransmits: 2 });
+
+        a.send(msg(&[1])).unwrap();
+
+        let err = loop {
+            match a.get() {
+                Err(Some(ReliableTransportError::RetriesExhausted { seq })) => break seq,
+                Err(_) => {},
+                Ok(_) => panic!(),
+            }
+        };
+
+        assert_eq!(0, err);
+    }
+}
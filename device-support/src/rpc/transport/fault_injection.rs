@@ -0,0 +1,283 @@
+//! A [`Transport`] wrapper that makes things go wrong, on purpose.
+//!
+//! [`FaultInjectingTransport`] wraps any [`Transport`] that moves `Fifo<u8>`s
+//! and, on the way out (i.e. in [`send`](Transport::send)), messes with the
+//! messages going across it. It can:
+//!   - flip bits in a message (corruption)
+//!   - chop off the end of a message (truncation)
+//!   - not send a message at all (drops)
+//!   - send a message twice (duplication)
+//!   - send a message before the one that was sent before it (reordering)
+//!   - hold on to a message for a while before sending it (latency)
+//!
+//! How often each of these things happen is set with a [`FaultConfig`]. All
+//! the randomness comes from a small PRNG that's seeded from the config so
+//! that failures are reproducible: the same seed with the same sequence of
+//! calls will produce the same faults.
+//!
+//! Latency is measured in _polls_ (calls to `send` or `get` on the wrapper)
+//! rather than in wall clock time, again so that things stay deterministic.
+//! Messages that are being held are sent along when the wrapper is polled, so
+//! the wrapper needs to be polled for delayed messages to make it out; both
+//! [`Controller`] and [`Device`] do this.
+//!
+//! This is meant for testing [`Controller`]/[`Device`] setups (and the
+//! [reliability layer](crate::rpc::reliability)) and, since it's
+//! on the sending side, both ends of a connection need to be wrapped for
+//! faults to happen in both directions.
+//!
+//! [`Controller`]: lc3_traits::control::rpc::Controller
+//! [`Device`]: lc3_traits::control::rpc::Device
+
+use crate::util::Fifo;
+
+use lc3_traits::control::rpc::Transport;
+use lc3_traits::control::{Identifier, Version};
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+/// How often each kind of fault should happen.
+///
+/// Probabilities are per message and are in `[0, 1]`; the defaults are all
+/// zero (no faults).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FaultConfig {
+    /// Seed for the PRNG.
+    pub seed: u64,
+    /// Probability that a byte in the message is flipped.
+    pub corrupt: f32,
+    /// Probability that the message is cut short.
+    pub truncate: f32,
+    /// Probability that the message isn't sent at all.
+    pub drop: f32,
+    /// Probability that the message is sent twice.
+    pub duplicate: f32,
+    /// Probability that the message jumps ahead of the message sent before
+    /// it (if that message hasn't gone out yet).
+    pub reorder: f32,
+    /// Minimum and maximum number of polls a message is held for before it's
+    /// sent (inclusive).
+    pub latency: (u32, u32),
+}
+
+impl Default for FaultConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0xDEAD_BEEF_CAFE_F00D,
+            corrupt: 0.0,
+            truncate: 0.0,
+            drop: 0.0,
+            duplicate: 0.0,
+            reorder: 0.0,
+            latency: (0, 0),
+        }
+    }
+}
+
+/// Counts of the faults that have been injected so far.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct FaultStats {
+    pub messages: u64,
+    pub corrupted: u64,
+    pub truncated: u64,
+    pub dropped: u64,
+    pub duplicated: u64,
+    pub reordered: u64,
+    pub delayed: u64,
+}
+
+/// xorshift64*; not cryptographically anything but it's small, fast, and
+/// deterministic which is all we want here.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // xorshift gets stuck at 0:
+        Rng(if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed })
+    }
+
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// In `[0, 1)`.
+    fn unit(&mut self) -> f32 {
+        (self.next() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn chance(&mut self, probability: f32) -> bool {
+        probability > 0.0 && self.unit() < probability
+    }
+
+    /// In `[lo, hi]`.
+    fn range(&mut self, lo: u64, hi: u64) -> u64 {
+        if hi <= lo { lo } else { lo + self.next() % (hi - lo + 1) }
+    }
+}
+
+#[derive(Debug)]
+struct Delayed {
+    release_at: u64,
+    message: Fifo<u8>,
+}
+
+/// See the [module docs](self).
+#[derive(Debug)]
+pub struct FaultInjectingTransport<T: Transport<Fifo<u8>, Fifo<u8>>> {
+    pub inner: T,
+    config: FaultConfig,
+    rng: RefCell<Rng>,
+    queue: RefCell<VecDeque<Delayed>>,
+    polls: Cell<u64>,
+    stats: Cell<FaultStats>,
+}
+
+impl<T: Transport<Fifo<u8>, Fifo<u8>>> FaultInjectingTransport<T> {
+    pub fn new(inner: T, config: FaultConfig) -> Self {
+        Self {
+            inner,
+            config,
+            rng: RefCell::new(Rng::new(config.seed)),
+            queue: RefCell::new(VecDeque::new()),
+            polls: Cell::new(0),
+            stats: Cell::new(FaultStats::default()),
+        }
+    }
+
+    pub fn config(&self) -> &FaultConfig {
+        &self.config
+    }
+
+    pub fn stats(&self) -> FaultStats {
+        self.stats.get()
+    }
+
+    /// Number of messages that are being held (because of latency) and
+    /// haven't been sent yet.
+    pub fn num_pending(&self) -> usize {
+        self.queue.borrow().len()
+    }
+
+    fn update_stats(&self, func: impl FnOnce(&mut FaultStats)) {
+        let mut stats = self.stats.get();
+        func(&mut stats);
+        self.stats.set(stats);
+    }
+
+    fn poll(&self) -> Result<(), T::SendErr> {
+        let now = self.polls.get().wrapping_add(1);
+        self.polls.set(now);
+
+        // Messages go out in queue order; a message that's ready has to wait
+        // for the messages in front of it to be ready too.
+        let mut queue = self.queue.borrow_mut();
+        while queue.front().map(|d| d.release_at <= now).unwrap_or(false) {
+            let Delayed { message, .. } = queue.pop_front().unwrap();
+            self.inner.send(message)?;
+        }
+
+        Ok(())
+    }
+
+    fn mangle(&self, rng: &mut Rng, message: &Fifo<u8>) -> Fifo<u8> {
+        let mut bytes = [0u8; crate::util::fifo::CAPACITY];
+        let mut len = message.length();
+        bytes[..len].copy_from_slice(message.as_slice());
+
+        if len > 0 && rng.chance(self.config.corrupt) {
+            let idx = rng.range(0, len as u64 - 1) as usize;
+            let mask = 1 << rng.range(0, 7);
+
+            bytes[idx] ^= mask;
+            self.update_stats(|s| s.corrupted += 1);
+        }
+
+        if len > 0 && rng.chance(self.config.truncate) {
+            len = rng.range(0, len as u64 - 1) as usize;
+            self.update_stats(|s| s.truncated += 1);
+        }
+
+        let mut out = Fifo::new();
+        out.push_slice(&bytes[..len]).expect("fifo: same capacity");
+
+        out
+    }
+
+    fn enqueue(&self, rng: &mut Rng, message: Fifo<u8>) {
+        let (lo, hi) = self.config.latency;
+        let delay = rng.range(lo as u64, hi as u64);
+        if delay > 0 { self.update_stats(|s| s.delayed += 1); }
+
+        let delayed = Delayed { release_at: self.polls.get() + delay, message };
+        let mut queue = self.queue.borrow_mut();
+
+        if !queue.is_empty() && rng.chance(self.config.reorder) {
+            let idx = queue.len() - 1;
+            queue.insert(idx, delayed);
+
+            self.update_stats(|s| s.reordered += 1);
+        } else {
+            queue.push_back(delayed);
+        }
+    }
+}
+
+impl<T: Transport<Fifo<u8>, Fifo<u8>>> Transport<Fifo<u8>, Fifo<u8>> for FaultInjectingTransport<T> {
+    type RecvErr = T::RecvErr;
+    type SendErr = T::SendErr;
+
+    const ID: Identifier = T::ID;
+    const VER: Version = {
+        let ver = T::VER;
+
+        let id = Identifier::new_from_str_that_crashes_on_invalid_inputs("flty");
+
+        Version::new(ver.major, ver.minor, ver.patch, Some(id))
+    };
+
+    fn send(&self, message: Fifo<u8>) -> Result<(), T::SendErr> {
+        self.update_stats(|s| s.messages += 1);
+        let mut rng = self.rng.borrow_mut();
+
+        if rng.chance(self.config.drop) {
+            self.update_stats(|s| s.dropped += 1);
+        } else {
+            let copies = if rng.chance(self.config.duplicate) {
+                self.update_stats(|s| s.duplicated += 1);
+                2
+            } else {
+                1
+            };
+
+            for _ in 0..copies {
+                let mangled = self.mangle(&mut rng, &message);
+                self.enqueue(&mut rng, mangled);
+            }
+        }
+
+        drop(rng);
+        self.poll()
+    }
+
+    fn get(&self) -> Result<Fifo<u8>, Option<T::RecvErr>> {
+        // We have no way to report send errors here; if the inner transport
+        // is broken, the next call to `send` will find out.
+        if let Err(err) = self.poll() {
+            log::warn!("Failed to send a delayed message: `{:?}`", err);
+        }
+
+        self.inner.get()
+    }
+
+    fn num_get_errors(&self) -> u64 {
+        self.inner.num_get_errors()
+    }
+}
@@ -14,6 +14,11 @@ sa::const_assert!(fifo::CAPACITY >= (3 * size_of::<ResponseMessage>()));
 pub mod uart_simple;
 
 using_std! {
+    pub mod fault_injection;
+
     #[cfg(all(feature = "host_transport", not(target_arch = "wasm32")))]
     pub mod uart_host;
+
+    #[cfg(all(feature = "websocket_transport", not(target_arch = "wasm32")))]
+    pub mod websocket;
 }
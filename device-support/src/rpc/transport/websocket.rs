@@ -0,0 +1,235 @@
+//! WebSocket transport for computers (and for talking to browsers).
+//!
+//! This works on both sides of a connection: [`WebSocketTransport::connect`]
+//! makes a client and [`WebSocketTransport::accept`] turns an incoming TCP
+//! connection into a server side transport.
+//!
+//! Two wire formats are supported:
+//!   - `Fifo<u8>` messages (i.e. the output of the postcard encoding layer)
+//!     are sent as _binary_ frames
+//!   - `String` messages (i.e. the output of the [`JsonEncoding`] layer) are
+//!     sent as _text_ frames
+//!
+//! Because WebSocket frames already tell us where messages start and end, we
+//! don't need to look for sentinels here like the UART transports do; each
+//! frame is exactly one message.
+//!
+//! [`JsonEncoding`]: lc3_traits::control::rpc::encoding::JsonEncoding
+
+use crate::util::Fifo;
+
+use lc3_traits::control::rpc::Transport;
+use lc3_traits::control::{Identifier, Version, version_from_crate};
+
+use tungstenite::{
+    Message, WebSocket,
+    error::Error as WsError,
+    handshake::HandshakeError,
+};
+
+use std::cell::{Cell, RefCell};
+use std::fmt::{self, Debug};
+use std::io::{Error as IoError, ErrorKind};
+use std::net::{TcpStream, ToSocketAddrs};
+
+/// Things that can go wrong when sending or receiving over a WebSocket.
+#[derive(Debug)]
+pub enum WebSocketTransportError {
+    /// Something went wrong in the WebSocket layer (or underneath it).
+    Ws(WsError),
+    /// We got a frame of the wrong kind (i.e. a text frame when we expected
+    /// a binary frame).
+    UnexpectedFrame(Message),
+    /// We got a binary frame that's too big to fit in a [`Fifo`].
+    ///
+    /// [`Fifo`]: crate::util::Fifo
+    FrameTooLarge(usize),
+}
+
+impl From<WsError> for WebSocketTransportError {
+    fn from(err: WsError) -> Self {
+        WebSocketTransportError::Ws(err)
+    }
+}
+
+impl From<IoError> for WebSocketTransportError {
+    fn from(err: IoError) -> Self {
+        WebSocketTransportError::Ws(WsError::Io(err))
+    }
+}
+
+/// A [`Transport`] over a (non-blocking) WebSocket connection.
+pub struct WebSocketTransport {
+    socket: RefCell<WebSocket<TcpStream>>,
+    closed: Cell<bool>,
+}
+
+impl Debug for WebSocketTransport {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("WebSocketTransport")
+            .field("peer", &self.socket.borrow().get_ref().peer_addr().ok())
+            .field("closed", &self.closed.get())
+            .finish()
+    }
+}
+
+fn handshake_err<R>(err: HandshakeError<R>) -> WebSocketTransportError
+where
+    R: tungstenite::handshake::HandshakeRole,
+{
+    match err {
+        HandshakeError::Failure(err) => err.into(),
+        // We do the handshake on a blocking stream so this shouldn't happen.
+        HandshakeError::Interrupted(_) => IoError::from(ErrorKind::WouldBlock).into(),
+    }
+}
+
+impl WebSocketTransport {
+    /// Connects to a WebSocket server at `addr` (i.e. `"127.0.0.1:9001"`).
+    ///
+    /// The handshake is done while the stream is still in blocking mode; once
+    /// it's finished the stream is switched to non-blocking mode so that
+    /// [`get`](Transport::get) doesn't block.
+    pub fn connect<A: ToSocketAddrs + AsRef<str>>(addr: A) -> Result<Self, WebSocketTransportError> {
+        let stream = TcpStream::connect(&addr)?;
+        let url = format!("ws://{}/", addr.as_ref());
+
+        let (socket, _resp) = tungstenite::client(url.as_str(), stream)
+            .map_err(handshake_err)?;
+
+        Self::from_socket(socket)
+    }
+
+    /// Performs the server side of the WebSocket handshake on an incoming
+    /// connection.
+    pub fn accept(stream: TcpStream) -> Result<Self, WebSocketTransportError> {
+        stream.set_nonblocking(false)?;
+
+        let socket = tungstenite::accept(stream)
+            .map_err(handshake_err)?;
+
+        Self::from_socket(socket)
+    }
+
+    fn from_socket(socket: WebSocket<TcpStream>) -> Result<Self, WebSocketTransportError> {
+        socket.get_ref().set_nonblocking(true)?;
+        socket.get_ref().set_nodelay(true)?;
+
+        Ok(Self {
+            socket: RefCell::new(socket),
+            closed: Cell::new(false),
+        })
+    }
+
+    /// Whether the other side has hung up (or the connection has otherwise
+    /// been lost).
+    ///
+    /// Once this is true, all sends and receives will fail.
+    pub fn is_closed(&self) -> bool {
+        self.closed.get()
+    }
+
+    fn write(&self, message: Message) -> Result<(), WebSocketTransportError> {
+        let mut socket = self.socket.borrow_mut();
+
+        // Since the stream is non-blocking, the message may only be queued
+        // (`WouldBlock`); that's fine — it'll go out on the next write or
+        // read.
+        match socket.write_message(message) {
+            Ok(()) => Ok(()),
+            Err(WsError::Io(ref e)) if e.kind() == ErrorKind::WouldBlock => Ok(()),
+            Err(err) => {
+                self.note_error(&err);
+                Err(err.into())
+            }
+        }
+    }
+
+    fn read(&self) -> Result<Message, Option<WebSocketTransportError>> {
+        let mut socket = self.socket.borrow_mut();
+
+        loop {
+            match socket.read_message() {
+                Ok(m @ Message::Binary(_)) | Ok(m @ Message::Text(_)) => break Ok(m),
+
+                // Pings are answered for us; pongs and close frames don't need
+                // anything from us (a close frame will result in a
+                // `ConnectionClosed` error on the next read).
+                Ok(_) => continue,
+
+                Err(WsError::Io(ref e)) if e.kind() == ErrorKind::WouldBlock => break Err(None),
+                Err(err) => {
+                    self.note_error(&err);
+                    break Err(Some(err.into()))
+                },
+            }
+        }
+    }
+
+    fn note_error(&self, err: &WsError) {
+        match err {
+            WsError::ConnectionClosed | WsError::AlreadyClosed => self.closed.set(true),
+            WsError::Io(e) if e.kind() != ErrorKind::WouldBlock => self.closed.set(true),
+            _ => {},
+        }
+    }
+}
+
+const ID: Identifier = Identifier::new_from_str_that_crashes_on_invalid_inputs("WEBS");
+
+impl Transport<Fifo<u8>, Fifo<u8>> for WebSocketTransport {
+    type RecvErr = WebSocketTransportError;
+    type SendErr = WebSocketTransportError;
+
+    const ID: Identifier = ID;
+    const VER: Version = {
+        let ver = version_from_crate!();
+
+        let id = Identifier::new_from_str_that_crashes_on_invalid_inputs("bin ");
+
+        Version::new(ver.major, ver.minor, ver.patch, Some(id))
+    };
+
+    fn send(&self, message: Fifo<u8>) -> Result<(), WebSocketTransportError> {
+        self.write(Message::Binary(message.as_slice().to_vec()))
+    }
+
+    fn get(&self) -> Result<Fifo<u8>, Option<WebSocketTransportError>> {
+        match self.read()? {
+            Message::Binary(bytes) => {
+                let mut fifo = Fifo::new();
+
+                fifo.push_slice(&bytes)
+                    .map_err(|()| Some(WebSocketTransportError::FrameTooLarge(bytes.len())))?;
+
+                Ok(fifo)
+            },
+            m => Err(Some(WebSocketTransportError::UnexpectedFrame(m))),
+        }
+    }
+}
+
+impl Transport<String, String> for WebSocketTransport {
+    type RecvErr = WebSocketTransportError;
+    type SendErr = WebSocketTransportError;
+
+    const ID: Identifier = ID;
+    const VER: Version = {
+        let ver = version_from_crate!();
+
+        let id = Identifier::new_from_str_that_crashes_on_invalid_inputs("text");
+
+        Version::new(ver.major, ver.minor, ver.patch, Some(id))
+    };
+
+    fn send(&self, message: String) -> Result<(), WebSocketTransportError> {
+        self.write(Message::Text(message))
+    }
+
+    fn get(&self) -> Result<String, Option<WebSocketTransportError>> {
+        match self.read()? {
+            Message::Text(s) => Ok(s),
+            m => Err(Some(WebSocketTransportError::UnexpectedFrame(m))),
+        }
+    }
+}
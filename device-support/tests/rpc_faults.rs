@@ -0,0 +1,460 @@
+//! Drives a [`Simulator`] through a [`Controller`]/[`Device`] pair that's
+//! connected by a transport that drops, corrupts, truncates, duplicates,
+//! reorders, and delays messages (with the reliability layer on top).
+//!
+//! From the perspective of the user of the [`Controller`] nothing should go
+//! wrong, other than things being slower.
+//!
+//! The controller and the device take turns (see [`TakeTurns`]) and time is
+//! counted in polls (see [`PollCount`]) so each test does exactly the same
+//! thing every time it runs.
+//!
+//! [`Simulator`]: lc3_baseline_sim::sim::Simulator
+//! [`Controller`]: lc3_traits::control::rpc::Controller
+//! [`Device`]: lc3_traits::control::rpc::Device
+//! [`PollCount`]: lc3_device_support::rpc::reliability::PollCount
+
+use lc3_baseline_sim::interp::{
+    InstructionInterpreter, Interpreter, InterpreterBuilder, PeripheralInterruptFlags,
+};
+use lc3_baseline_sim::sim::Simulator;
+use lc3_device_support::{
+    rpc::{
+        encoding::{Cobs, PostcardDecode, PostcardEncode},
+        reliability::{PollCount, ReliabilityConfig, ReliableTransport, ReliableTransportError},
+        transport::fault_injection::{FaultConfig, FaultInjectingTransport, FaultStats},
+    },
+    util::Fifo,
+};
+use lc3_isa::{util::MemoryDump, Addr, Instruction, Reg::*, Word};
+use lc3_shims::memory::MemoryShim;
+use lc3_traits::control::rpc::{
+    Controller, ControllerError, Decode, Device, MpscTransport, RequestMessage, ResponseMessage,
+    SyncEventFutureSharedState, Transport, PROTOCOL_VERSION,
+};
+use lc3_traits::control::load::{load_memory_dump_compressed, Progress};
+use lc3_traits::control::{Control, Identifier, Version};
+use lc3_traits::peripherals::stubs::PeripheralsStub;
+
+use pretty_assertions::assert_eq;
+
+use std::sync::atomic::{AtomicBool, Ordering::{self, SeqCst}};
+use std::sync::Arc;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread::{Builder as ThreadBuilder, JoinHandle};
+
+type Sim = Simulator<
+    'static,
+    'static,
+    Interpreter<'static, MemoryShim, PeripheralsStub<'static>>,
+    SyncEventFutureSharedState,
+>;
+
+type Transp = ReliableTransport<FaultInjectingTransport<TakeTurns>, PollCount>;
+
+type NewCobs = fn() -> Cobs<Fifo<u8>>;
+
+type Cont = Controller<
+    'static,
+    Transp,
+    SyncEventFutureSharedState,
+    RequestMessage,
+    ResponseMessage,
+    PostcardEncode<RequestMessage, Cobs<Fifo<u8>>, NewCobs>,
+    PostcardDecode<ResponseMessage, Cobs<Fifo<u8>>>,
+>;
+
+fn new_cobs() -> Cobs<Fifo<u8>> {
+    Cobs::try_new(Fifo::new()).unwrap()
+}
+
+const RELIABILITY: ReliabilityConfig = ReliabilityConfig {
+    timeout: 10, // polls
+    max_retransmits: 100,
+};
+
+type Inner = MpscTransport<Fifo<u8>, Fifo<u8>>;
+
+/// Only lets one side (controller or device) run at a time: checking for a
+/// message hands the turn to the other side and waits for it to come back.
+///
+/// Both sides still get their own thread (the device has to be able to block
+/// in the middle of a `step`, i.e. when its window is full) but the order in
+/// which things happen is fixed.
+struct TakeTurns {
+    inner: Inner,
+    give: Sender<()>,
+    take: Receiver<()>,
+    stop: Arc<AtomicBool>,
+}
+
+impl TakeTurns {
+    fn pair(stop: &Arc<AtomicBool>) -> (Self, Self) {
+        let (a, b) = MpscTransport::new();
+        let (a_give, b_take) = channel();
+        let (b_give, a_take) = channel();
+
+        (
+            TakeTurns { inner: a, give: a_give, take: a_take, stop: stop.clone() },
+            TakeTurns { inner: b, give: b_give, take: b_take, stop: stop.clone() },
+        )
+    }
+
+    fn wait(&self) {
+        let _ = self.take.recv();
+    }
+
+    // Once the harness is stopped, nobody waits.
+    fn pass(&self) {
+        if !self.stop.load(Ordering::SeqCst) {
+            let _ = self.give.send(());
+            self.wait();
+        }
+    }
+}
+
+impl Transport<Fifo<u8>, Fifo<u8>> for TakeTurns {
+    type RecvErr = <Inner as Transport<Fifo<u8>, Fifo<u8>>>::RecvErr;
+    type SendErr = <Inner as Transport<Fifo<u8>, Fifo<u8>>>::SendErr;
+
+    const ID: Identifier = Identifier::new_from_str_that_crashes_on_invalid_inputs("TURN");
+    const VER: Version = Version::new(0, 0, 0, None);
+
+    fn send(&self, message: Fifo<u8>) -> Result<(), Self::SendErr> {
+        self.inner.send(message)
+    }
+
+    fn get(&self) -> Result<Fifo<u8>, Option<Self::RecvErr>> {
+        self.pass();
+        self.inner.get()
+    }
+}
+
+struct Harness {
+    controller: Cont,
+    stop: Arc<AtomicBool>,
+    device_thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+
+        // The device is waiting for its turn; give it one last one so it can
+        // see that it's time to stop.
+        let _ = self.controller.transport.inner.inner.give.send(());
+
+        if let Some(handle) = self.device_thread.take() {
+            handle.join().unwrap();
+        }
+    }
+}
+
+fn harness(faults: FaultConfig, reliability: ReliabilityConfig) -> Harness {
+    let stop = Arc::new(AtomicBool::new(false));
+    let (cont_side, dev_side) = TakeTurns::pair(&stop);
+
+    // Use different seeds for each direction:
+    let dev_faults = FaultConfig { seed: faults.seed.wrapping_add(1), ..faults };
+
+    let cont_transport = ReliableTransport::new(
+        FaultInjectingTransport::new(cont_side, faults),
+        PollCount::new(),
+        reliability,
+    );
+    let dev_transport = ReliableTransport::new(
+        FaultInjectingTransport::new(dev_side, dev_faults),
+        PollCount::new(),
+        reliability,
+    );
+
+    // Leaked so that each test gets its own:
+    let cont_state: &'static _ = Box::leak(Box::new(SyncEventFutureSharedState::new()));
+    let sim_state: &'static _ = Box::leak(Box::new(SyncEventFutureSharedState::new()));
+    let flags: &'static _ = Box::leak(Box::new(PeripheralInterruptFlags::new()));
+
+    let controller = Controller::new(
+        PostcardEncode::new(new_cobs as NewCobs),
+        PostcardDecode::new(),
+        cont_transport,
+        cont_state,
+    );
+
+    let mut device = Device::<_, Sim, RequestMessage, ResponseMessage, _, _>::new(
+        PostcardEncode::new(new_cobs as NewCobs),
+        PostcardDecode::<RequestMessage, Cobs<Fifo<u8>>>::new(),
+        dev_transport,
+    );
+
+    let stop_dev = stop.clone();
+
+    let device_thread = ThreadBuilder::new()
+        .name("Device Thread".to_string())
+        .stack_size(1024 * 1024 * 8)
+        .spawn(move || {
+            let mut interp: Interpreter<'static, MemoryShim, PeripheralsStub<'static>> =
+                InterpreterBuilder::new().with_defaults().build();
+
+            interp.reset();
+            interp.init(flags);
+
+            let mut sim = Simulator::new_with_state(interp, sim_state);
+            sim.reset();
+
+            // The controller goes first.
+            device.transport.inner.inner.wait();
+
+            while !stop_dev.load(Ordering::SeqCst) {
+                let _ = device.step(&mut sim);
+            }
+        })
+        .unwrap();
+
+    Harness {
+        controller,
+        stop,
+        device_thread: Some(device_thread),
+    }
+}
+
+fn exercise(c: &mut Cont) {
+    for i in 0..64 {
+        c.write_word(0x4000 + i, i * 3 + 1);
+    }
+
+    for i in 0..64 {
+        assert_eq!(i * 3 + 1, c.read_word(0x4000 + i));
+    }
+
+    for (idx, reg) in [R0, R1, R2, R3, R4, R5, R6, R7].iter().enumerate() {
+        c.set_register(*reg, 0x1000 + idx as Word);
+    }
+
+    for (idx, reg) in [R0, R1, R2, R3, R4, R5, R6, R7].iter().enumerate() {
+        assert_eq!(0x1000 + idx as Word, c.get_register(*reg));
+    }
+
+    // Run a little program: R0 = R0 + 1, 8 times.
+    for i in 0..8 {
+        c.write_word(0x3000 + i, Instruction::new_add_imm(R0, R0, 1).into());
+    }
+
+    c.set_pc(0x3000);
+    c.set_register(R0, 0);
+
+    for _ in 0..8 {
+        let _ = c.step();
+    }
+
+    assert_eq!(8, c.get_register(R0));
+    assert_eq!(0x3008, c.get_pc());
+}
+
+fn run(faults: FaultConfig) -> Harness {
+    let mut h = harness(faults, RELIABILITY);
+    exercise(&mut h.controller);
+
+    assert!(h.controller.take_error().is_none());
+
+    h
+}
+
+fn injected(h: &Harness) -> FaultStats {
+    h.controller.transport.inner.stats()
+}
+
+#[test]
+fn no_faults() {
+    let h = run(FaultConfig::default());
+    let stats = injected(&h);
+
+    assert_eq!(FaultStats { messages: stats.messages, ..FaultStats::default() }, stats);
+    assert_eq!(0, h.controller.transport.stats().bad_frames);
+}
+
+#[test]
+fn corruption() {
+    let h = run(FaultConfig { seed: 1, corrupt: 0.2, ..FaultConfig::default() });
+    assert!(injected(&h).corrupted > 0);
+}
+
+#[test]
+fn truncation() {
+    let h = run(FaultConfig { seed: 2, truncate: 0.2, ..FaultConfig::default() });
+    assert!(injected(&h).truncated > 0);
+}
+
+#[test]
+fn drops() {
+    let h = run(FaultConfig { seed: 3, drop: 0.2, ..FaultConfig::default() });
+    assert!(injected(&h).dropped > 0);
+}
+
+#[test]
+fn duplication() {
+    let h = run(FaultConfig { seed: 4, duplicate: 0.2, ..FaultConfig::default() });
+    assert!(injected(&h).duplicated > 0);
+}
+
+#[test]
+fn reordering_and_latency() {
+    let h = run(FaultConfig { seed: 5, reorder: 0.3, latency: (0, 4), ..FaultConfig::default() });
+
+    assert!(injected(&h).reordered > 0);
+    assert!(injected(&h).delayed > 0);
+}
+
+#[test]
+fn everything_at_once() {
+    let h = run(FaultConfig {
+        seed: 6,
+        corrupt: 0.05,
+        truncate: 0.05,
+        drop: 0.05,
+        duplicate: 0.05,
+        reorder: 0.05,
+        latency: (0, 3),
+    });
+
+    assert!(h.controller.transport.stats().retransmits > 0);
+}
+
+#[test]
+fn handshake() {
+    let mut h = harness(FaultConfig { seed: 7, drop: 0.1, ..FaultConfig::default() }, RELIABILITY);
+
+    assert!(h.controller.peer().is_none());
+    h.controller.handshake().unwrap();
+    assert_eq!(PROTOCOL_VERSION, h.controller.peer().unwrap().protocol);
+
+    exercise(&mut h.controller);
+}
+
+#[test]
+fn compressed_load() {
+    let mut h = harness(FaultConfig { seed: 8, drop: 0.05, corrupt: 0.05, ..FaultConfig::default() }, RELIABILITY);
+    let c = &mut h.controller;
+
+    c.handshake().unwrap();
+    assert!(c.get_max_compressed_chunk_size() > 0);
+
+    let mut dump = MemoryDump::blank();
+    dump.layer_iterator((0x3000..0x3300).map(|a| (a, a ^ 0x5A5A)));
+    dump.layer_iterator((0x4000..0x4100).map(|a| (a, 7)));
+    dump[0x5000] = 0x1234;
+
+    let check = |c: &Cont, dump: &MemoryDump| {
+        for addr in (0x2FF0..0x3310).chain(0x3FF0..0x4110).chain(0x5000..0x5001) {
+            assert_eq!(dump[addr as usize], c.read_word(addr as Addr), "at {:#06X}", addr);
+        }
+    };
+
+    // Nothing's been loaded yet so every page gets sent:
+    let progress = Progress::new();
+    load_memory_dump_compressed(c, &dump, None, Some(&progress)).unwrap();
+    assert_eq!(0xFE, progress.total_pages.load(SeqCst));
+    check(c, &dump);
+
+    // Change a word; only its page should be sent (diffed against the
+    // previous dump):
+    let previous = dump.clone();
+    dump[0x3123] = 0xBEEF;
+
+    let progress = Progress::new();
+    load_memory_dump_compressed(c, &dump, Some(&previous), Some(&progress)).unwrap();
+    assert_eq!(1, progress.total_pages.load(SeqCst));
+    check(c, &dump);
+
+    // Pages are compared with what they go back to on a reset and not with
+    // what's in memory right now; x5000's page has to be sent even though
+    // it's currently blank:
+    c.write_word(0x5000, 0);
+    dump[0x5000] = 0;
+
+    let progress = Progress::new();
+    load_memory_dump_compressed(c, &dump, None, Some(&progress)).unwrap();
+    assert_eq!(1, progress.total_pages.load(SeqCst));
+    check(c, &dump);
+
+    assert!(c.take_error().is_none());
+}
+
+#[test]
+fn undecodable_requests_are_nakd() {
+    let h = harness(FaultConfig::default(), RELIABILITY);
+
+    // A (COBS framed) request with a variant that doesn't exist:
+    let mut garbage = Fifo::new();
+    garbage.push_slice(&[0x02, 0x7F, 0x00]).unwrap();
+    h.controller.transport.send(garbage).unwrap();
+
+    let resp = loop {
+        match h.controller.transport.get() {
+            Ok(resp) => break resp,
+            Err(None) => {},
+            Err(Some(e)) => panic!("transport error: {:?}", e),
+        }
+    };
+
+    let resp: ResponseMessage = PostcardDecode::<ResponseMessage, Cobs<Fifo<u8>>>::new()
+        .decode(&resp)
+        .unwrap();
+    assert_eq!(ResponseMessage::Nak, resp);
+}
+
+#[test]
+fn dead_link_is_an_error_not_a_hang() {
+    let h = harness(
+        FaultConfig { drop: 1.0, ..FaultConfig::default() },
+        ReliabilityConfig { timeout: 2, max_retransmits: 3 },
+    );
+
+    match h.controller.request(RequestMessage::GetPc) {
+        Err(ControllerError::Recv(ReliableTransportError::RetriesExhausted { seq: 0 })) => {},
+        other => panic!("expected the request to fail, got: {:?}", other),
+    }
+}
+
+#[test]
+fn corrupt_link_is_an_error_not_a_hang() {
+    // Every frame (NAKs included) is corrupted; neither side should end up
+    // NAKing or retransmitting forever.
+    let h = harness(
+        FaultConfig { corrupt: 1.0, ..FaultConfig::default() },
+        ReliabilityConfig { timeout: 2, max_retransmits: 3 },
+    );
+
+    match h.controller.request(RequestMessage::GetPc) {
+        Err(ControllerError::Recv(ReliableTransportError::RetriesExhausted { seq: 0 })) => {},
+        other => panic!("expected the request to fail, got: {:?}", other),
+    }
+}
+
+#[test]
+fn naks_are_not_resent_forever() {
+    let state: &'static _ = Box::leak(Box::new(SyncEventFutureSharedState::new()));
+    let (cont_side, dev_side) = MpscTransport::<RequestMessage, ResponseMessage>::new();
+
+    let controller = Controller::<_, _>::new(
+        Default::default(),
+        Default::default(),
+        cont_side,
+        state,
+    ).with_max_resends(3);
+
+    // A device that can't decode anything:
+    for _ in 0..=3 { dev_side.send(ResponseMessage::Nak).unwrap(); }
+
+    match controller.request(RequestMessage::GetPc) {
+        Err(ControllerError::ResendsExhausted { resends: 3 }) => {},
+        other => panic!("expected the request to fail, got: {:?}", other),
+    }
+
+    // The original request and the 3 resends:
+    let mut sent = 0;
+    while let Ok(m) = dev_side.get() {
+        assert_eq!(RequestMessage::GetPc, m);
+        sent += 1;
+    }
+    assert_eq!(4, sent);
+}
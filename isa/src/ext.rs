@@ -0,0 +1,59 @@
+//! Custom instructions in the reserved opcode (`0b1101`).
+//!
+//! The LC-3 leaves one opcode unused; other than [`BRK`](Instruction::Brk)
+//! (`1101 0000 xxxx xxxx`), words with this opcode are illegal instructions.
+//! Extensions give them meaning (i.e. a `MUL`, shifts, or `PUSH`/`POP`): an
+//! [`Extension`] decodes the words it recognizes into its own instruction
+//! type, which is what gets displayed and (in the simulator) executed.
+//!
+//! This module is only available with the `extensions` feature; without it,
+//! the ISA is strictly the LC-3's.
+
+use crate::{Instruction, Word};
+
+use core::convert::TryFrom;
+use core::fmt::{Debug, Display};
+
+/// The opcode extensions live in.
+pub const RESERVED_OPCODE: u8 = 0b1101;
+
+/// How wide the base instructions' mnemonics are padded to when displayed
+/// (i.e. `ADD   R0, R1, R2`); extensions should do the same so that listings
+/// line up.
+pub const MNEMONIC_WIDTH: usize = 6;
+
+/// A set of custom instructions.
+pub trait Extension {
+    /// The extension's instructions.
+    type Insn: Copy + Debug + Display;
+
+    /// Decodes a word in the [reserved opcode](RESERVED_OPCODE).
+    ///
+    /// Only gets words that aren't already an [`Instruction`] (so never a
+    /// `BRK`). Returns `None` for words that aren't part of the extension;
+    /// these stay illegal instructions.
+    fn decode(&self, word: Word) -> Option<Self::Insn>;
+}
+
+/// An [`Instruction`] or an instruction from an [`Extension`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Decoded<X> {
+    /// A regular LC-3 instruction.
+    Base(Instruction),
+    /// One of the extension's instructions.
+    Ext(X),
+}
+
+/// Decodes `word` as a base instruction, falling back to `ext`.
+///
+/// Like [`Instruction::try_from`], words that can't be decoded are returned as
+/// the error.
+pub fn decode<X: Extension + ?Sized>(ext: &X, word: Word) -> Result<Decoded<X::Insn>, Word> {
+    match Instruction::try_from(word) {
+        Ok(insn) => Ok(Decoded::Base(insn)),
+        Err(word) if (word >> 12) as u8 == RESERVED_OPCODE => {
+            ext.decode(word).map(Decoded::Ext).ok_or(word)
+        }
+        Err(word) => Err(word),
+    }
+}
@@ -44,6 +44,18 @@ impl Display for Instruction {
             Sti { sr, offset9 } => write!(fmt, "STI   {}, #{}", sr, offset9),
             Str { sr, base, offset6 } => write!(fmt, "STR   {}, {}, #{}", sr, base, offset6),
             Trap { trapvec } => write!(fmt, "TRAP  x{:X}", trapvec),
+            Brk { code } => write!(fmt, "BRK   x{:X}", code),
+        }
+    }
+}
+
+#[cfg(feature = "extensions")]
+impl<X: Display> Display for crate::ext::Decoded<X> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use crate::ext::Decoded::*;
+        match self {
+            Base(insn) => insn.fmt(fmt),
+            Ext(insn) => insn.fmt(fmt),
         }
     }
 }
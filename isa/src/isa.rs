@@ -246,6 +246,11 @@ pub enum Instruction {
     Sti { sr: Reg, offset9: Sw },                   // R9
     Str { sr: Reg, base: Reg, offset6: Sw },        // RR6
     Trap { trapvec: u8 },                           // 8
+    /// A software breakpoint. This lives in the reserved opcode (`0b1101`);
+    /// only `1101 0000 cccc cccc` is `BRK` (`c` is a code the debugger gets
+    /// along with the breakpoint). Whether it actually stops the machine is up
+    /// to the interpreter.
+    Brk { code: u8 },                               // 8
 }
 
 /// We use the bit representation of [`Instruction`] for equality specifically
@@ -585,6 +590,17 @@ impl Instruction {
         // trapvec, an 8 bit value represented by a u8, can't be out of bounds.
         Instruction::Trap { trapvec }
     }
+
+    /// Creates a new `BRK` instruction ([`Instruction::Brk`]).
+    ///
+    /// ```rust
+    /// # use lc3_isa::Instruction;
+    /// assert_eq!(Instruction::new_brk(0x12).to_word(), 0xD012);
+    /// ```
+    pub const fn new_brk(code: u8) -> Self {
+        // Like trapvec, code can't be out of bounds.
+        Instruction::Brk { code }
+    }
 }
 
 impl Instruction {
@@ -610,7 +626,8 @@ impl Instruction {
             | St { .. }
             | Sti { .. }
             | Str { .. }
-            | Trap { .. } => false,
+            | Trap { .. }
+            | Brk { .. } => false,
         }
     }
 }
@@ -716,8 +733,12 @@ impl TryFrom<Word> for Instruction {
 
         let op_code: u8 = (w >> 12).try_into().unwrap();
 
+        // The only part of the reserved opcode that's in use is BRK:
         if op_code == 0b1101 {
-            return Err(w)
+            return match w.u8(8..11) {
+                0 => Ok(Brk { code: w.u8(0..7) }),
+                _ => Err(w),
+            }
         }
 
         Ok(match op_code {
@@ -807,6 +828,7 @@ impl Instruction {
             Sti { sr, offset9 }       => Op(0b1011) | Dr(sr)              | O9(offset9),
             Str { sr, base, offset6 } => Op(0b0111) | Dr(sr) | Base(base) | O6(offset6),
             Trap { trapvec }          => Op(0b1111)          | Trapvec(trapvec)        ,
+            Brk { code }              => Op(0b1101)          | Trapvec(code)           ,
         }
     }]}
 }
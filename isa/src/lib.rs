@@ -119,6 +119,21 @@ pub const PRIVILEGE_MODE_VIOLATION_EXCEPTION_VECTOR: u8 = 0x00;
 pub const ILLEGAL_OPCODE_EXCEPTION_VECTOR: u8 = 0x01;
 pub const ACCESS_CONTROL_VIOLATION_EXCEPTION_VECTOR: u8 = 0x02; // TODO: Verify
 
+/// Raised when the memory protection table forbids fetching an instruction
+/// from memory that can be read but not executed (i.e. data). This isn't part
+/// of the original LC-3.
+pub const EXECUTE_FROM_DATA_EXCEPTION_VECTOR: u8 = 0x03;
+/// Raised when the memory protection table forbids writing to memory that can
+/// be executed but not written (i.e. code). This isn't part of the original
+/// LC-3.
+pub const WRITE_TO_CODE_EXCEPTION_VECTOR: u8 = 0x04;
+
+/// The exception vector that holds the address execution starts at after a
+/// reset (this isn't part of the original LC-3; we use the last exception
+/// vector since it's otherwise unused).
+pub const RESET_VECTOR: u8 = 0x7F;
+pub const RESET_VECTOR_ADDR: Addr = EXCEPTION_SERVICE_ROUTINES_START_ADDR + (RESET_VECTOR as Addr); // 0x017F
+
 /// Maximum possible word value.
 pub const WORD_MAX_VAL: Word = Word::max_value();
 
@@ -128,6 +143,8 @@ pub const ADDR_SPACE_SIZE_IN_WORDS: usize = (ADDR_MAX_VAL as usize) + 1;
 /// Size of the LC-3 address space in bytes.
 pub const ADDR_SPACE_SIZE_IN_BYTES: usize = ADDR_SPACE_SIZE_IN_WORDS * size_of::<Word>();
 
+#[cfg(feature = "extensions")]
+pub mod ext;
 mod fmt;
 mod isa;
 mod macros;
@@ -98,6 +98,13 @@ macro_rules! insn {
 
     (TRAP #$trapvec:expr $(,)? $(=> $($extra:tt)*)?) => {
         $crate::Instruction::new_trap($trapvec)
+    };
+
+    (BRK #$code:expr $(,)? $(=> $($extra:tt)*)?) => {
+        $crate::Instruction::new_brk($code)
+    };
+    (BRK $(=> $($extra:tt)*)?) => {
+        $crate::Instruction::new_brk(0)
     }
 }
 
@@ -447,6 +454,20 @@ mod tests {
         let _ = insn!(ADD R0, R5, #16);
     }
 
+    #[test]
+    fn brk() {
+        assert_eq!(insn!(BRK), Brk { code: 0 });
+        assert_eq!(insn!(BRK #0x7 => with a comment), Brk { code: 7 });
+        assert_eq!(word!(BRK #0xAB), 0xD0AB);
+
+        let prog = loadable! {
+            .ORIG #0x3000;
+            BRK;
+            BRK #1;
+        };
+        assert_eq!(prog, [(0x3000, 0xD000), (0x3001, 0xD001)]);
+    }
+
     #[test]
     fn word() {
         assert_eq!(
@@ -37,3 +37,9 @@ fn br_display() {
     check(T, F, T, -5, "BRnp  #-5");
     check(T, T, T, -5, "BRnzp #-5");
 }
+
+#[test]
+fn brk_display() {
+    assert_eq!(format!("{}", Instruction::new_brk(0)), "BRK   x0");
+    assert_eq!(format!("{}", Instruction::new_brk(0x3A)), "BRK   x3A");
+}
@@ -94,6 +94,10 @@ fn all_trap() -> impl Iterator<Item = Instruction> + Clone {
     iproduct!(0..=255).map(|trapvec| Instruction::new_trap(trapvec))
 }
 
+fn all_brk() -> impl Iterator<Item = Instruction> + Clone {
+    iproduct!(0..=255).map(|code| Instruction::new_brk(code))
+}
+
 fn all_insns() -> impl Iterator<Item = Instruction> + Clone {
     // let insns: Vec<Instruction> = Vec::new();
 
@@ -115,7 +119,8 @@ fn all_insns() -> impl Iterator<Item = Instruction> + Clone {
         .chain(all_st())
         .chain(all_sti())
         .chain(all_str())
-        .chain(all_trap());
+        .chain(all_trap())
+        .chain(all_brk());
 
     // for i in 0..19 {
     //     match i {
@@ -155,7 +160,7 @@ mod roundtrip_instruction_tests {
 
     #[test]
     fn number_of_instructions() {
-        assert_eq!(all_insns().count(), 39673);
+        assert_eq!(all_insns().count(), 39929);
     }
 
     #[test]
@@ -176,6 +181,17 @@ mod roundtrip_instruction_tests {
         })
     }
 
+    #[test]
+    fn reserved() {
+        // Only the BRK part of the reserved opcode decodes:
+        for w in 0xD000..=0xDFFF {
+            match Instruction::try_from(w as Word) {
+                Ok(insn) => assert_eq!(insn, Instruction::new_brk(w as u8)),
+                Err(err) => { assert!(w > 0xD0FF); assert_eq!(err, w as Word) },
+            }
+        }
+    }
+
     #[test]
     #[ignore] // This one takes a while..
     fn full_unique() {
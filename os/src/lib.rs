@@ -72,11 +72,11 @@ extern crate static_assertions as sa;
 // the end of this page. The idea here is to minimize the number of pages that
 // get modified (i.e. are dirty).
 
-pub const USER_PROG_START_ADDR: lc3_isa::Addr = 0x0600;
-pub const ERROR_ON_ACV_SETTING_ADDR: lc3_isa::Addr = 0x0601;
-pub const OS_STARTING_SP_ADDR: lc3_isa::Addr = 0x0602;
+pub const USER_PROG_START_ADDR: lc3_isa::Addr = 0x0700;
+pub const ERROR_ON_ACV_SETTING_ADDR: lc3_isa::Addr = 0x0701;
+pub const OS_STARTING_SP_ADDR: lc3_isa::Addr = 0x0702;
 
-pub const OS_DEFAULT_STARTING_SP: lc3_isa::Word = 0x0700;
+pub const OS_DEFAULT_STARTING_SP: lc3_isa::Word = 0x0800;
 
 #[allow(unused_extern_crates)]
 extern crate core; // makes rls actually look into the standard library (hack)
@@ -6,8 +6,8 @@ use super::{OS_DEFAULT_STARTING_SP, OS_STARTING_SP_ADDR};
 use lc3_isa::util::{AssembledProgram, MemoryDump};
 use lc3_isa::{Word, OS_START_ADDR};
 use lc3_baseline_sim::{KBSR_ADDR, KBDR_ADDR, DSR_ADDR, DDR_ADDR};
-use lc3_baseline_sim::{G0CR_ADDR, A0CR_ADDR, P0CR_ADDR, T0CR_ADDR, CLKR_ADDR};
-use lc3_baseline_sim::{GPIO_OFFSET, ADC_OFFSET, PWM_OFFSET, TIMER_OFFSET, MISC_OFFSET};
+use lc3_baseline_sim::{G0CR_ADDR, A0CR_ADDR, P0CR_ADDR, T0CR_ADDR, T0CCR_ADDR, CLKR_ADDR, VXR_ADDR, DKSR_ADDR, USR_ADDR, RNGSR_ADDR};
+use lc3_baseline_sim::{GPIO_OFFSET, ADC_OFFSET, PWM_OFFSET, TIMER_OFFSET, MISC_OFFSET, DISPLAY_OFFSET, DISK_OFFSET, UART_OFFSET, RNG_OFFSET};
 use lc3_baseline_sim::{GPIO_BASE_INT_VEC, TIMER_BASE_INT_VEC};
 
 use lazy_static::lazy_static;
@@ -97,8 +97,8 @@ fn os() -> AssembledProgram {
         .ORIG #t::gpio::GET_MODE     as W;  .FILL @TRAP_READ_GPIO_MODE;         // 0x34
         .ORIG #t::gpio::WRITE        as W;  .FILL @TRAP_WRITE_GPIO_DATA;        // 0x35
         .ORIG #t::gpio::READ         as W;  .FILL @TRAP_READ_GPIO_DATA;         // 0x36
-        .FILL @UNKNOWN_TRAP; // 0x37
-        .FILL @UNKNOWN_TRAP; // 0x38
+        .ORIG #t::gpio::INTERRUPT_MODE as W; .FILL @TRAP_SET_GPIO_INTERRUPT_MODE; // 0x37
+        .ORIG #t::gpio::DEBOUNCE     as W;  .FILL @TRAP_SET_GPIO_DEBOUNCE;      // 0x38
         .FILL @UNKNOWN_TRAP; // 0x39
         .FILL @UNKNOWN_TRAP; // 0x3A
         .FILL @UNKNOWN_TRAP; // 0x3B
@@ -149,9 +149,9 @@ fn os() -> AssembledProgram {
         .ORIG #t::timers::DISABLE    as W;  .FILL @TRAP_SET_TIMER_DISABLE;      // 0x62
         .ORIG #t::timers::GET_MODE   as W;  .FILL @TRAP_READ_TIMER_MODE;        // 0x63
         .ORIG #t::timers::GET_PERIOD as W;  .FILL @TRAP_READ_TIMER_PERIOD;      // 0x64
-        .FILL @UNKNOWN_TRAP; // 0x65
-        .FILL @UNKNOWN_TRAP; // 0x66
-        .FILL @UNKNOWN_TRAP; // 0x67
+        .ORIG #t::timers::GET_REMAINING as W;  .FILL @TRAP_READ_TIMER_REMAINING;  // 0x65
+        .ORIG #t::timers::SET_CAPTURE   as W;  .FILL @TRAP_SET_TIMER_CAPTURE;     // 0x66
+        .ORIG #t::timers::GET_CAPTURE   as W;  .FILL @TRAP_READ_TIMER_CAPTURE;    // 0x67
         .FILL @UNKNOWN_TRAP; // 0x68
         .FILL @UNKNOWN_TRAP; // 0x69
         .FILL @UNKNOWN_TRAP; // 0x6A
@@ -179,9 +179,10 @@ fn os() -> AssembledProgram {
         .FILL @UNKNOWN_TRAP; // 0x7E
         .FILL @UNKNOWN_TRAP; // 0x7F
 
-        .FILL @UNKNOWN_TRAP; // 0x80
-        .FILL @UNKNOWN_TRAP; // 0x81
-        .FILL @UNKNOWN_TRAP; // 0x82
+        .ORIG #DISPLAY_OFFSET as Word;
+        .ORIG #t::display::DRAW      as W;  .FILL @TRAP_DRAW_PIXEL;             // 0x80
+        .ORIG #t::display::READ      as W;  .FILL @TRAP_READ_PIXEL;             // 0x81
+        .ORIG #t::display::FILL      as W;  .FILL @TRAP_FILL_DISPLAY;           // 0x82
         .FILL @UNKNOWN_TRAP; // 0x83
         .FILL @UNKNOWN_TRAP; // 0x84
         .FILL @UNKNOWN_TRAP; // 0x85
@@ -195,9 +196,10 @@ fn os() -> AssembledProgram {
         .FILL @UNKNOWN_TRAP; // 0x8D
         .FILL @UNKNOWN_TRAP; // 0x8E
         .FILL @UNKNOWN_TRAP; // 0x8F
-        .FILL @UNKNOWN_TRAP; // 0x90
-        .FILL @UNKNOWN_TRAP; // 0x91
-        .FILL @UNKNOWN_TRAP; // 0x92
+        .ORIG #DISK_OFFSET as Word;
+        .ORIG #t::disk::READ         as W;  .FILL @TRAP_READ_SECTOR;            // 0x90
+        .ORIG #t::disk::WRITE        as W;  .FILL @TRAP_WRITE_SECTOR;           // 0x91
+        .ORIG #t::disk::SIZE         as W;  .FILL @TRAP_DISK_SIZE;              // 0x92
         .FILL @UNKNOWN_TRAP; // 0x93
         .FILL @UNKNOWN_TRAP; // 0x94
         .FILL @UNKNOWN_TRAP; // 0x95
@@ -211,9 +213,10 @@ fn os() -> AssembledProgram {
         .FILL @UNKNOWN_TRAP; // 0x9D
         .FILL @UNKNOWN_TRAP; // 0x9E
         .FILL @UNKNOWN_TRAP; // 0x9F
-        .FILL @UNKNOWN_TRAP; // 0xA0
-        .FILL @UNKNOWN_TRAP; // 0xA1
-        .FILL @UNKNOWN_TRAP; // 0xA2
+        .ORIG #UART_OFFSET as Word;
+        .ORIG #t::uart::SEND         as W;  .FILL @TRAP_UART_SEND;              // 0xA0
+        .ORIG #t::uart::RECEIVE      as W;  .FILL @TRAP_UART_RECEIVE;           // 0xA1
+        .ORIG #t::uart::POLL         as W;  .FILL @TRAP_UART_POLL;              // 0xA2
         .FILL @UNKNOWN_TRAP; // 0xA3
         .FILL @UNKNOWN_TRAP; // 0xA4
         .FILL @UNKNOWN_TRAP; // 0xA5
@@ -227,8 +230,9 @@ fn os() -> AssembledProgram {
         .FILL @UNKNOWN_TRAP; // 0xAD
         .FILL @UNKNOWN_TRAP; // 0xAE
         .FILL @UNKNOWN_TRAP; // 0xAF
-        .FILL @UNKNOWN_TRAP; // 0xB0
-        .FILL @UNKNOWN_TRAP; // 0xB1
+        .ORIG #RNG_OFFSET as Word;
+        .ORIG #t::rng::RANDOM        as W;  .FILL @TRAP_RNG_RANDOM;             // 0xB0
+        .ORIG #t::rng::SEED          as W;  .FILL @TRAP_RNG_SEED;               // 0xB1
         .FILL @UNKNOWN_TRAP; // 0xB2
         .FILL @UNKNOWN_TRAP; // 0xB3
         .FILL @UNKNOWN_TRAP; // 0xB4
@@ -313,8 +317,8 @@ fn os() -> AssembledProgram {
         .FILL @PRIVILEGE_MODE_EX_HANDLER; // 0x100 -- TODO: only used for calling RTI when not in an interrupt
         .FILL @ILLEGAL_OPCODE_EX_HANDLER; // 0x101
         .FILL @ACV_EX_HANDLER;            // 0x102 -- TODO: verify
-        .FILL @DEFAULT_EXCEPTION_HANDLER; // 0x103
-        .FILL @DEFAULT_EXCEPTION_HANDLER; // 0x104
+        .FILL @DEFAULT_EXCEPTION_HANDLER; // 0x103 -- execute from data (memory protection)
+        .FILL @DEFAULT_EXCEPTION_HANDLER; // 0x104 -- write to code (memory protection)
         .FILL @DEFAULT_EXCEPTION_HANDLER; // 0x105
         .FILL @DEFAULT_EXCEPTION_HANDLER; // 0x106
         .FILL @DEFAULT_EXCEPTION_HANDLER; // 0x107
@@ -437,14 +441,14 @@ fn os() -> AssembledProgram {
         .FILL @DEFAULT_EXCEPTION_HANDLER; // 0x17C
         .FILL @DEFAULT_EXCEPTION_HANDLER; // 0x17D
         .FILL @DEFAULT_EXCEPTION_HANDLER; // 0x17E
-        .FILL @DEFAULT_EXCEPTION_HANDLER; // 0x17F
+        .FILL #OS_START_ADDR;             // 0x17F: Reset Vector
 
         //// The Interrupt vector table (0x0180 - 0x01FF) ////
         .FILL @DEFAULT_INT_HANDLER; // 0x180: Keyboard Interrupt
         .FILL @DEFAULT_INT_HANDLER; // 0x181: Display Interrupt (TODO: verify)
-        .FILL @DEFAULT_INT_HANDLER; // 0x182
-        .FILL @DEFAULT_INT_HANDLER; // 0x183
-        .FILL @DEFAULT_INT_HANDLER; // 0x184
+        .FILL @DEFAULT_INT_HANDLER; // 0x182: Disk Interrupt
+        .FILL @DEFAULT_INT_HANDLER; // 0x183: UART Interrupt
+        .FILL @DEFAULT_INT_HANDLER; // 0x184: Watchdog Interrupt
         .FILL @DEFAULT_INT_HANDLER; // 0x185
         .FILL @DEFAULT_INT_HANDLER; // 0x186
         .FILL @DEFAULT_INT_HANDLER; // 0x187
@@ -827,10 +831,9 @@ fn os() -> AssembledProgram {
             PUTS;
 
             LDI R0, @MCR;             // Set the top bit of the MCR to 0.
-            LD R1, @MASK_HI_BIT;      // The masking isn't strictly necessary
-            AND R0, R0, R1;           // since (afaik) only the top bit of the
-            STI R0, @MCR;             // MCR has functionality, but we'll be
-                                      // good citizens.
+            LD R1, @MASK_HI_BIT;      // Leave the rest of the MCR (the debug
+            AND R0, R0, R1;           // break bit and the throttle) alone.
+            STI R0, @MCR;
 
             BRnzp @TRAP_HALT;         // If at first you don't succeed, try, try
                                       // again.
@@ -1043,7 +1046,7 @@ fn os() -> AssembledProgram {
             .FILL #('\n' as Word);
             .FILL #('\0' as Word);
 
-        // Checks if R0 is within range of 0 to R4
+        // Checks if R0 is within range of 0 to R4 (inclusive)
         // R0 = value to check
         // R4 = max value
         // -> cc = n if out of bounds
@@ -1055,8 +1058,8 @@ fn os() -> AssembledProgram {
             BRn @OUT_OF_BOUNDS_RET;
             NOT R4, R4;                     // Negate R4
             ADD R4, R4, #1;
-            ADD R4, R0, R4;                 // Check if R0 is less than R4
-            BRzp @OUT_OF_BOUNDS;
+            ADD R4, R0, R4;                 // Check if R0 is at most R4
+            BRp @OUT_OF_BOUNDS;
             ADD R0, R0, #0;                 // If not, set cc to z or p
             BR @OUT_OF_BOUNDS_RET;
         @OUT_OF_BOUNDS
@@ -1068,23 +1071,28 @@ fn os() -> AssembledProgram {
         // R0 = GPIO pin to enable
         // R1 = mode to set
         @SET_GPIO_MODE
-            ADD R6, R6, #-2;                // Save R4, R7 on stack
-            STR R4, R6, #1;
+            ADD R6, R6, #-3;                // Save R4, R5, R7 on stack
+            STR R4, R6, #2;
+            STR R5, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of GPIO pins
-            ADD R4, R4, #lc3_traits::peripherals::gpio::GpioPin::NUM_PINS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last GPIO pin
+            ADD R4, R4, #lc3_traits::peripherals::gpio::GpioPin::NUM_PINS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_SET_GPIO_MODE;
 
             LD R4, @OS_GPIO_BASE_ADDR;      // Load GPIO base address into R2
             ADD R4, R4, R0;                 // Calculate pin address offset by doubling pin number
             ADD R4, R4, R0;                 // R4 contains control address of pin number in R0
-            STR R1, R4, #0;                 // Write GPIO mode to control register
+            LDR R5, R4, #0;                 // Keep the interrupt mode and debounce time
+            AND R5, R5, #-4;                // (everything but the lower two bits)
+            ADD R5, R5, R1;
+            STR R5, R4, #0;                 // Write GPIO mode to control register
         @SKIP_SET_GPIO_MODE
-            LDR R7, R6, #0;                 // Restore R4, R7
-            LDR R4, R6, #1;
-            ADD R6, R6, #2;
+            LDR R7, R6, #0;                 // Restore R4, R5, R7
+            LDR R5, R6, #1;
+            LDR R4, R6, #2;
+            ADD R6, R6, #3;
             RET;
 
         // Sets GPIO pin to input mode
@@ -1126,8 +1134,8 @@ fn os() -> AssembledProgram {
             STR R4, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of GPIO pins
-            ADD R4, R4, #lc3_traits::peripherals::gpio::GpioPin::NUM_PINS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last GPIO pin
+            ADD R4, R4, #lc3_traits::peripherals::gpio::GpioPin::NUM_PINS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_SET_GPIO_INTERRUPT;
 
@@ -1135,12 +1143,9 @@ fn os() -> AssembledProgram {
             ADD R4, R4, R0;                 // R4 contains address of pin in R0
             STR R1, R4, #0;                 // Load service routine address into vector table
 
-            LD R4, @OS_GPIO_BASE_ADDR;      // Load GPIO base address into R4
-            ADD R4, R4, R0;                 // Calculate pin address offset by doubling pin number
-            ADD R4, R4, R0;                 // R4 contains control address of pin number in R0
             AND R1, R1, #0;                 // Set R1 to 3 (Interrupt)
             ADD R1, R1, #3;
-            STR R1, R4, #0;                 // Write GPIO mode to control register
+            JSR @SET_GPIO_MODE;
         @SKIP_SET_GPIO_INTERRUPT
             LDR R7, R6, #0;                 // Restore R1, R4, R7
             LDR R4, R6, #1;
@@ -1170,8 +1175,8 @@ fn os() -> AssembledProgram {
             STR R4, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of GPIO pins
-            ADD R4, R4, #lc3_traits::peripherals::gpio::GpioPin::NUM_PINS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last GPIO pin
+            ADD R4, R4, #lc3_traits::peripherals::gpio::GpioPin::NUM_PINS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_READ_GPIO_MODE;
 
@@ -1179,6 +1184,7 @@ fn os() -> AssembledProgram {
             ADD R4, R4, R0;                 // Calculate pin address offset by doubling pin number
             ADD R4, R4, R0;                 // R3 contains data address of pin number in R0
             LDR R0, R4, #0;                 // Reads mode from pin into R0
+            AND R0, R0, #3;                 // Drop the interrupt mode and debounce time
         @SKIP_READ_GPIO_MODE
             LDR R7, R6, #0;                 // Restore R4, R7
             LDR R4, R6, #1;
@@ -1193,8 +1199,8 @@ fn os() -> AssembledProgram {
             STR R4, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of GPIO pins
-            ADD R4, R4, #lc3_traits::peripherals::gpio::GpioPin::NUM_PINS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last GPIO pin
+            ADD R4, R4, #lc3_traits::peripherals::gpio::GpioPin::NUM_PINS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_WRITE_GPIO_DATA;
 
@@ -1217,8 +1223,8 @@ fn os() -> AssembledProgram {
             STR R4, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of GPIO pins
-            ADD R4, R4, #lc3_traits::peripherals::gpio::GpioPin::NUM_PINS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last GPIO pin
+            ADD R4, R4, #lc3_traits::peripherals::gpio::GpioPin::NUM_PINS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_READ_GPIO_DATA;
 
@@ -1233,6 +1239,83 @@ fn os() -> AssembledProgram {
             ADD R6, R6, #2;
             RTI;
 
+        // Sets the interrupt mode of a GPIO pin
+        // R0 = GPIO pin to set
+        // R1 = interrupt mode
+        @TRAP_SET_GPIO_INTERRUPT_MODE
+            ADD R6, R6, #-4;                // Save R1, R4, R5, R7 on stack
+            STR R1, R6, #3;
+            STR R4, R6, #2;
+            STR R5, R6, #1;
+            STR R7, R6, #0;
+
+            AND R4, R4, #0;                 // Set R4 to the last GPIO pin
+            ADD R4, R4, #lc3_traits::peripherals::gpio::GpioPin::NUM_PINS as i16 - 1;
+            JSR @CHECK_OUT_OF_BOUNDS;
+            BRn @SKIP_SET_GPIO_INTERRUPT_MODE;
+
+            ADD R1, R1, #0;                 // Ignore modes that aren't in [0, # of modes)
+            BRn @SKIP_SET_GPIO_INTERRUPT_MODE;
+            ADD R4, R1, #-(lc3_traits::peripherals::gpio::GpioInterruptMode::NUM_MODES as i16);
+            BRzp @SKIP_SET_GPIO_INTERRUPT_MODE;
+
+            ADD R1, R1, R1;                 // Shift the mode into bits 2 through 4
+            ADD R1, R1, R1;
+
+            LD R4, @OS_GPIO_BASE_ADDR;      // Load GPIO base address into R4
+            ADD R4, R4, R0;                 // Calculate pin address offset by doubling pin number
+            ADD R4, R4, R0;                 // R4 contains control address of pin number in R0
+            LDR R5, R4, #0;                 // Clear the old interrupt mode
+            LD R7, @OS_GPIO_INT_MODE_MASK;
+            AND R5, R5, R7;
+            ADD R5, R5, R1;
+            STR R5, R4, #0;                 // Write the new one to the control register
+        @SKIP_SET_GPIO_INTERRUPT_MODE
+            LDR R7, R6, #0;                 // Restore R1, R4, R5, R7
+            LDR R5, R6, #1;
+            LDR R4, R6, #2;
+            LDR R1, R6, #3;
+            ADD R6, R6, #4;
+            RTI;
+
+        // Sets the debounce time of a GPIO pin
+        // R0 = GPIO pin to set
+        // R1 = debounce time in milliseconds (only the lower 8 bits are used)
+        @TRAP_SET_GPIO_DEBOUNCE
+            ADD R6, R6, #-4;                // Save R1, R4, R5, R7 on stack
+            STR R1, R6, #3;
+            STR R4, R6, #2;
+            STR R5, R6, #1;
+            STR R7, R6, #0;
+
+            AND R4, R4, #0;                 // Set R4 to the last GPIO pin
+            ADD R4, R4, #lc3_traits::peripherals::gpio::GpioPin::NUM_PINS as i16 - 1;
+            JSR @CHECK_OUT_OF_BOUNDS;
+            BRn @SKIP_SET_GPIO_DEBOUNCE;
+
+            AND R4, R4, #0;                 // Shift the debounce time into the upper
+            ADD R4, R4, #8;                 // byte (dropping the rest of R1)
+        @SHIFT_GPIO_DEBOUNCE
+            ADD R1, R1, R1;
+            ADD R4, R4, #-1;
+            BRp @SHIFT_GPIO_DEBOUNCE;
+
+            LD R4, @OS_GPIO_BASE_ADDR;      // Load GPIO base address into R4
+            ADD R4, R4, R0;                 // Calculate pin address offset by doubling pin number
+            ADD R4, R4, R0;                 // R4 contains control address of pin number in R0
+            LDR R5, R4, #0;                 // Clear the old debounce time
+            LD R7, @OS_GPIO_DEBOUNCE_MASK;
+            AND R5, R5, R7;
+            ADD R5, R5, R1;
+            STR R5, R4, #0;                 // Write the new one to the control register
+        @SKIP_SET_GPIO_DEBOUNCE
+            LDR R7, R6, #0;                 // Restore R1, R4, R5, R7
+            LDR R5, R6, #1;
+            LDR R4, R6, #2;
+            LDR R1, R6, #3;
+            ADD R6, R6, #4;
+            RTI;
+
         // Sets mode of ADC pin
         // R0 = ADC pin to set mode of
         // R1 = mode to set
@@ -1242,8 +1325,8 @@ fn os() -> AssembledProgram {
             STR R4, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of ADC pins
-            ADD R4, R4, #lc3_traits::peripherals::adc::AdcPin::NUM_PINS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last ADC pin
+            ADD R4, R4, #lc3_traits::peripherals::adc::AdcPin::NUM_PINS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_SET_ADC_MODE;
 
@@ -1295,8 +1378,8 @@ fn os() -> AssembledProgram {
             STR R4, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of ADC pins
-            ADD R4, R4, #lc3_traits::peripherals::adc::AdcPin::NUM_PINS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last ADC pin
+            ADD R4, R4, #lc3_traits::peripherals::adc::AdcPin::NUM_PINS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_READ_ADC_MODE;
 
@@ -1318,8 +1401,8 @@ fn os() -> AssembledProgram {
             STR R4, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of ADC pins
-            ADD R4, R4, #lc3_traits::peripherals::adc::AdcPin::NUM_PINS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last ADC pin
+            ADD R4, R4, #lc3_traits::peripherals::adc::AdcPin::NUM_PINS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_READ_ADC_DATA;
 
@@ -1339,9 +1422,13 @@ fn os() -> AssembledProgram {
         @OS_ADC_BASE_ADDR .FILL #A0CR_ADDR;
         @OS_CLOCK_BASE_ADDR .FILL #CLKR_ADDR;
         @OS_TIMER_BASE_ADDR .FILL #T0CR_ADDR;
+        @OS_TIMER_CAPTURE_BASE_ADDR .FILL #T0CCR_ADDR;
         @OS_PWM_BASE_ADDR .FILL #P0CR_ADDR;
 
         @OS_GPIO_BASE_INTVEC .FILL #GPIO_BASE_INT_VEC;
+
+        @OS_GPIO_INT_MODE_MASK .FILL #0xFFE3;  // Everything but bits 2 through 4
+        @OS_GPIO_DEBOUNCE_MASK .FILL #0x00FF;  // Everything but the upper byte
         @OS_TIMER_BASE_INTVEC .FILL #TIMER_BASE_INT_VEC;
 
         // PWM set
@@ -1353,8 +1440,8 @@ fn os() -> AssembledProgram {
             STR R4, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of PWM pins
-            ADD R4, R4, #lc3_traits::peripherals::pwm::PwmPin::NUM_PINS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last PWM pin
+            ADD R4, R4, #lc3_traits::peripherals::pwm::PwmPin::NUM_PINS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_SET_PWM;
 
@@ -1377,8 +1464,8 @@ fn os() -> AssembledProgram {
             STR R4, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of PWM pins
-            ADD R4, R4, #lc3_traits::peripherals::pwm::PwmPin::NUM_PINS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last PWM pin
+            ADD R4, R4, #lc3_traits::peripherals::pwm::PwmPin::NUM_PINS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_DISABLE_PWM;
 
@@ -1401,8 +1488,8 @@ fn os() -> AssembledProgram {
             STR R4, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of PWM pins
-            ADD R4, R4, #lc3_traits::peripherals::pwm::PwmPin::NUM_PINS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last PWM pin
+            ADD R4, R4, #lc3_traits::peripherals::pwm::PwmPin::NUM_PINS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_READ_PWM_PERIOD;
 
@@ -1429,8 +1516,8 @@ fn os() -> AssembledProgram {
             STR R4, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of PWM pins
-            ADD R4, R4, #lc3_traits::peripherals::pwm::PwmPin::NUM_PINS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last PWM pin
+            ADD R4, R4, #lc3_traits::peripherals::pwm::PwmPin::NUM_PINS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_READ_PWM_DUTY_CYCLE;
 
@@ -1453,8 +1540,8 @@ fn os() -> AssembledProgram {
             STR R4, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of timers
-            ADD R4, R4, #lc3_traits::peripherals::timers::TimerId::NUM_TIMERS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last timer
+            ADD R4, R4, #lc3_traits::peripherals::timers::TimerId::NUM_TIMERS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_SET_TIMER_MODE;
 
@@ -1476,8 +1563,8 @@ fn os() -> AssembledProgram {
             STR R4, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of timers
-            ADD R4, R4, #lc3_traits::peripherals::timers::TimerId::NUM_TIMERS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last timer
+            ADD R4, R4, #lc3_traits::peripherals::timers::TimerId::NUM_TIMERS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_WRITE_TIMER_PERIOD;
 
@@ -1563,8 +1650,8 @@ fn os() -> AssembledProgram {
             STR R4, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of timers
-            ADD R4, R4, #lc3_traits::peripherals::timers::TimerId::NUM_TIMERS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last timer
+            ADD R4, R4, #lc3_traits::peripherals::timers::TimerId::NUM_TIMERS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_READ_TIMER_MODE;
 
@@ -1586,8 +1673,8 @@ fn os() -> AssembledProgram {
             STR R4, R6, #1;
             STR R7, R6, #0;
 
-            AND R4, R4, #0;                 // Set R4 to # of timers
-            ADD R4, R4, #lc3_traits::peripherals::timers::TimerId::NUM_TIMERS as i16;
+            AND R4, R4, #0;                 // Set R4 to the last timer
+            ADD R4, R4, #lc3_traits::peripherals::timers::TimerId::NUM_TIMERS as i16 - 1;
             JSR @CHECK_OUT_OF_BOUNDS;
             BRn @SKIP_READ_TIMER_PERIOD;
 
@@ -1602,6 +1689,79 @@ fn os() -> AssembledProgram {
             ADD R6, R6, #2;
             RTI;
 
+        // Finds the capture registers of a Timer
+        // R0 = Timer to find the registers of
+        // -> R4 = address of the Timer's capture control register
+        @TIMER_CAPTURE_ADDR
+            LD R4, @OS_TIMER_CAPTURE_BASE_ADDR;
+            ADD R4, R4, R0;                 // Each timer has three registers,
+            ADD R4, R4, R0;                 // so offset by triple the timer
+            ADD R4, R4, R0;                 // number
+            RET;
+
+        // Reads and returns the time left on a Timer
+        // R0 = Timer to read from
+        // -> R0 = time left, in milliseconds
+        @TRAP_READ_TIMER_REMAINING
+            ADD R6, R6, #-2;                // Save R4, R7 on stack
+            STR R4, R6, #1;
+            STR R7, R6, #0;
+
+            AND R4, R4, #0;                 // Set R4 to the last timer
+            ADD R4, R4, #lc3_traits::peripherals::timers::TimerId::NUM_TIMERS as i16 - 1;
+            JSR @CHECK_OUT_OF_BOUNDS;
+            BRn @SKIP_READ_TIMER_REMAINING;
+
+            JSR @TIMER_CAPTURE_ADDR;
+            LDR R0, R4, #1;                 // Reads the count register into R0
+        @SKIP_READ_TIMER_REMAINING
+            LDR R7, R6, #0;                 // Restore R4, R7
+            LDR R4, R6, #1;
+            ADD R6, R6, #2;
+            RTI;
+
+        // Sets up input capture on a Timer
+        // R0 = Timer to set up
+        // R1 = value for the capture control register
+        @TRAP_SET_TIMER_CAPTURE
+            ADD R6, R6, #-2;                // Save R4, R7 on stack
+            STR R4, R6, #1;
+            STR R7, R6, #0;
+
+            AND R4, R4, #0;                 // Set R4 to the last timer
+            ADD R4, R4, #lc3_traits::peripherals::timers::TimerId::NUM_TIMERS as i16 - 1;
+            JSR @CHECK_OUT_OF_BOUNDS;
+            BRn @SKIP_SET_TIMER_CAPTURE;
+
+            JSR @TIMER_CAPTURE_ADDR;
+            STR R1, R4, #0;                 // Writes R1 to the capture control register
+        @SKIP_SET_TIMER_CAPTURE
+            LDR R7, R6, #0;                 // Restore R4, R7
+            LDR R4, R6, #1;
+            ADD R6, R6, #2;
+            RTI;
+
+        // Reads and returns the last value a Timer captured
+        // R0 = Timer to read from
+        // -> R0 = captured clock value
+        @TRAP_READ_TIMER_CAPTURE
+            ADD R6, R6, #-2;                // Save R4, R7 on stack
+            STR R4, R6, #1;
+            STR R7, R6, #0;
+
+            AND R4, R4, #0;                 // Set R4 to the last timer
+            ADD R4, R4, #lc3_traits::peripherals::timers::TimerId::NUM_TIMERS as i16 - 1;
+            JSR @CHECK_OUT_OF_BOUNDS;
+            BRn @SKIP_READ_TIMER_CAPTURE;
+
+            JSR @TIMER_CAPTURE_ADDR;
+            LDR R0, R4, #2;                 // Reads the capture register into R0
+        @SKIP_READ_TIMER_CAPTURE
+            LDR R7, R6, #0;                 // Restore R4, R7
+            LDR R4, R6, #1;
+            ADD R6, R6, #2;
+            RTI;
+
         // Sets clock
         // R0 = data to set
         @TRAP_SET_CLOCK
@@ -1622,6 +1782,252 @@ fn os() -> AssembledProgram {
             LDR R0, R0, #0;                 // Read data from clock
             RTI;
 
+        // Draws a pixel
+        // R0 = x
+        // R1 = y
+        // R2 = color
+        @TRAP_DRAW_PIXEL
+            ADD R6, R6, #-3;                // Save R0, R4, R7 on stack
+            STR R0, R6, #2;
+            STR R4, R6, #1;
+            STR R7, R6, #0;
+
+            LD R4, @OS_DISPLAY_MAX_X;       // Check x
+            JSR @CHECK_OUT_OF_BOUNDS;
+            BRn @SKIP_DRAW_PIXEL;
+            ADD R0, R1, #0;                 // Check y
+            LD R4, @OS_DISPLAY_MAX_Y;
+            JSR @CHECK_OUT_OF_BOUNDS;
+            BRn @SKIP_DRAW_PIXEL;
+
+            LDR R0, R6, #2;                 // Get x back
+            LD R4, @OS_DISPLAY_BASE_ADDR;   // Load display base address into R4
+            STR R0, R4, #0;                 // Write x, y, and then the color
+            STR R1, R4, #1;
+            STR R2, R4, #2;
+        @SKIP_DRAW_PIXEL
+            LDR R7, R6, #0;                 // Restore R0, R4, R7
+            LDR R4, R6, #1;
+            LDR R0, R6, #2;
+            ADD R6, R6, #3;
+            RTI;
+
+        // Reads a pixel
+        // R0 = x
+        // R1 = y
+        // -> R0 = color
+        @TRAP_READ_PIXEL
+            ADD R6, R6, #-4;                // Save R0, R2, R4, R7 on stack
+            STR R0, R6, #3;
+            STR R2, R6, #2;
+            STR R4, R6, #1;
+            STR R7, R6, #0;
+
+            ADD R2, R0, #0;                 // Keep x in R2
+            LD R4, @OS_DISPLAY_MAX_X;       // Check x
+            JSR @CHECK_OUT_OF_BOUNDS;
+            BRn @SKIP_READ_PIXEL;
+            ADD R0, R1, #0;                 // Check y
+            LD R4, @OS_DISPLAY_MAX_Y;
+            JSR @CHECK_OUT_OF_BOUNDS;
+            BRn @SKIP_READ_PIXEL;
+
+            LD R4, @OS_DISPLAY_BASE_ADDR;   // Load display base address into R4
+            STR R2, R4, #0;                 // Write x and y
+            STR R1, R4, #1;
+            LDR R0, R4, #2;                 // Read the color into R0
+            STR R0, R6, #3;                 // (so that it survives the restore below)
+        @SKIP_READ_PIXEL
+            LDR R7, R6, #0;                 // Restore R0, R2, R4, R7
+            LDR R4, R6, #1;
+            LDR R2, R6, #2;
+            LDR R0, R6, #3;
+            ADD R6, R6, #4;
+            RTI;
+
+        // Fills the display
+        // R0 = color
+        @TRAP_FILL_DISPLAY
+            ADD R6, R6, #-1;                // Save R1 on stack
+            STR R1, R6, #0;
+
+            LD R1, @OS_DISPLAY_BASE_ADDR;   // Load display base address into R1
+            STR R0, R1, #3;                 // Write the color to the fill register
+
+            LDR R1, R6, #0;                 // Restore R1
+            ADD R6, R6, #1;
+            RTI;
+
+        @OS_DISPLAY_BASE_ADDR .FILL #VXR_ADDR;
+        @OS_DISPLAY_MAX_X .FILL #(lc3_traits::peripherals::display::DISPLAY_WIDTH - 1);
+        @OS_DISPLAY_MAX_Y .FILL #(lc3_traits::peripherals::display::DISPLAY_HEIGHT - 1);
+
+        // Reads a sector from the disk into memory
+        // R0 = sector
+        // R1 = address to read the sector into
+        // -> R0 = 0 on success, 1 on error
+        @TRAP_READ_SECTOR
+            ADD R6, R6, #-1;                // Save R4 on stack
+            STR R4, R6, #0;
+
+            LD R4, @OS_DISK_BASE_ADDR;      // Load disk base address into R4
+            STR R0, R4, #2;                 // Write the sector and buffer address
+            STR R1, R4, #3;
+            AND R0, R0, #0;                 // Run the read command
+            ADD R0, R0, #1;
+            STR R0, R4, #1;
+            BR @DISK_CMD_STATUS;
+
+        // Writes a sector's worth of memory to the disk
+        // R0 = sector
+        // R1 = address to write the sector from
+        // -> R0 = 0 on success, 1 on error
+        @TRAP_WRITE_SECTOR
+            ADD R6, R6, #-1;                // Save R4 on stack
+            STR R4, R6, #0;
+
+            LD R4, @OS_DISK_BASE_ADDR;      // Load disk base address into R4
+            STR R0, R4, #2;                 // Write the sector and buffer address
+            STR R1, R4, #3;
+            AND R0, R0, #0;                 // Run the write command
+            ADD R0, R0, #2;
+            STR R0, R4, #1;
+        @DISK_CMD_STATUS
+            LDR R0, R4, #0;                 // Get the error bit from the status register
+            AND R0, R0, #1;
+
+            LDR R4, R6, #0;                 // Restore R4
+            ADD R6, R6, #1;
+            RTI;
+
+        // Gets the number of sectors on the disk
+        // -> R0 = number of sectors
+        @TRAP_DISK_SIZE
+            LD R0, @OS_DISK_BASE_ADDR;      // Load disk base address into R0
+            LDR R0, R0, #4;                 // Read the number of sectors
+            RTI;
+
+        @OS_DISK_BASE_ADDR .FILL #DKSR_ADDR;
+
+        // Sends a byte over the UART, waiting for room in the transmit FIFO
+        // R0 = byte to send
+        // -> R0 = 0 on success, 1 if there's no UART
+        @TRAP_UART_SEND
+            ADD R6, R6, #-3;                // Save R1, R2, R4 on stack
+            STR R1, R6, #2;
+            STR R2, R6, #1;
+            STR R4, R6, #0;
+
+            LD R4, @OS_UART_BASE_ADDR;      // Load UART base address into R4
+            LD R2, @OS_UART_PRESENT_MASK;   // Check that there's a UART
+            LDR R1, R4, #0;
+            AND R1, R1, R2;
+            BRz @UART_SEND_NO_UART;
+
+            LD R2, @OS_UART_TX_READY_MASK;
+        @UART_SEND_WAIT
+            LDR R1, R4, #0;
+            AND R1, R1, R2;
+            BRz @UART_SEND_WAIT;            // Spin until there's room
+
+            STR R0, R4, #1;                 // Send the byte
+            AND R0, R0, #0;
+            BR @UART_SEND_RET;
+        @UART_SEND_NO_UART
+            AND R0, R0, #0;
+            ADD R0, R0, #1;
+        @UART_SEND_RET
+            LDR R4, R6, #0;                 // Restore R1, R2, R4
+            LDR R2, R6, #1;
+            LDR R1, R6, #2;
+            ADD R6, R6, #3;
+            RTI;
+
+        // Receives a byte from the UART, waiting for one if there isn't one yet
+        // -> R0 = byte received, -1 if there's no UART
+        @TRAP_UART_RECEIVE
+            ADD R6, R6, #-2;                // Save R1, R4 on stack
+            STR R1, R6, #1;
+            STR R4, R6, #0;
+
+            LD R4, @OS_UART_BASE_ADDR;      // Load UART base address into R4
+            LD R1, @OS_UART_PRESENT_MASK;   // Check that there's a UART
+            LDR R0, R4, #0;
+            AND R0, R0, R1;
+            BRz @UART_RECEIVE_NO_UART;
+
+        @UART_RECEIVE_WAIT
+            LDR R0, R4, #0;
+            BRzp @UART_RECEIVE_WAIT;        // Spin until there's a byte (bit 15)
+
+            LDR R0, R4, #1;                 // Read it
+            BR @UART_RECEIVE_RET;
+        @UART_RECEIVE_NO_UART
+            AND R0, R0, #0;
+            ADD R0, R0, #-1;
+        @UART_RECEIVE_RET
+            LDR R4, R6, #0;                 // Restore R1, R4
+            LDR R1, R6, #1;
+            ADD R6, R6, #2;
+            RTI;
+
+        // Receives a byte from the UART if there is one
+        // -> R0 = byte received, -1 if there isn't one
+        @TRAP_UART_POLL
+            ADD R6, R6, #-1;                // Save R4 on stack
+            STR R4, R6, #0;
+
+            LD R4, @OS_UART_BASE_ADDR;      // Load UART base address into R4
+            LDR R0, R4, #0;
+            BRn @UART_POLL_READ;            // Is there a byte? (bit 15)
+
+            AND R0, R0, #0;
+            ADD R0, R0, #-1;
+            BR @UART_POLL_RET;
+        @UART_POLL_READ
+            LDR R0, R4, #1;                 // Read it
+        @UART_POLL_RET
+            LDR R4, R6, #0;                 // Restore R4
+            ADD R6, R6, #1;
+            RTI;
+
+        @OS_UART_BASE_ADDR .FILL #USR_ADDR;
+        @OS_UART_PRESENT_MASK .FILL #0x0100;
+        @OS_UART_TX_READY_MASK .FILL #0x2000;
+
+        // Gets a random word
+        // -> R0 = random word, 0 if there's no RNG
+        @TRAP_RNG_RANDOM
+            LD R0, @OS_RNG_BASE_ADDR;       // Load RNG base address into R0
+            LDR R0, R0, #1;                 // Read the next random word
+            RTI;
+
+        // Seeds the RNG so that it produces a fixed sequence
+        // R0 = seed
+        // -> R0 = 0 on success, 1 if there's no RNG
+        @TRAP_RNG_SEED
+            ADD R6, R6, #-2;                // Save R1, R4 on stack
+            STR R1, R6, #1;
+            STR R4, R6, #0;
+
+            LD R4, @OS_RNG_BASE_ADDR;       // Load RNG base address into R4
+            LDR R1, R4, #0;
+            BRzp @RNG_SEED_NO_RNG;          // Is there an RNG? (bit 15)
+
+            STR R0, R4, #1;                 // Seed it
+            AND R0, R0, #0;
+            BR @RNG_SEED_RET;
+        @RNG_SEED_NO_RNG
+            AND R0, R0, #0;
+            ADD R0, R0, #1;
+        @RNG_SEED_RET
+            LDR R4, R6, #0;                 // Restore R1, R4
+            LDR R1, R6, #1;
+            ADD R6, R6, #2;
+            RTI;
+
+        @OS_RNG_BASE_ADDR .FILL #RNGSR_ADDR;
+
         //// Exception Handlers ////
 
         // Triggered when an RTI is called when in user mode.
@@ -16,6 +16,8 @@
 //! | **`0x34`** | [GPIO_GET_MODE]    | [`R0`] - [pin][gpin] #                                                | [`R0`] - [GPIO mode] <br>`n` bit   | Returns the [mode][gmode] of a [GPIO] [pin][gpin].                             |
 //! | **`0x35`** | [GPIO_WRITE]       | [`R0`] - [pin][gpin] # <br>[`R1`] - data to write                     | `n` bit                            | Writes to a [GPIO] [pin][gpin] in [Output mode][gOutput].                      |
 //! | **`0x36`** | [GPIO_READ]        | [`R0`] - [pin][gpin] #                                                | [`R0`] - data from pin <br>`n` bit | Reads data from a [GPIO] [pin][gpin].                                          |
+//! | **`0x37`** | [GPIO_INTERRUPT_MODE] | [`R0`] - [pin][gpin] # <br>[`R1`] - [interrupt mode][gIntMode]     | `n` bit                            | Sets what triggers interrupts on a [GPIO] [pin][gpin].                         |
+//! | **`0x38`** | [GPIO_DEBOUNCE]    | [`R0`] - [pin][gpin] # <br>[`R1`] - time in ms                        | `n` bit                            | Sets how long changes on a [GPIO] [pin][gpin] are ignored for after a change.  |
 //! | **`0x40`** | [ADC_ENABLE]       | [`R0`] - [pin][apin] #                                                | `n` bit                            | Puts an [ADC] [pin][apin] in [Enabled mode][aEnabled].                         |
 //! | **`0x41`** | [ADC_DISABLE]      | [`R0`] - [pin][apin] #                                                | `n` bit                            | Puts an [ADC] [pin][apin] in [Disabled mode][aDisabled].                       |
 //! | **`0x42`** | [ADC_GET_MODE]     | [`R0`] - [pin][apin] #                                                | [`R0`] - [ADC mode] <br>`n` bit    | Returns the mode of an [ADC] [pin][apin].                                      |
@@ -29,8 +31,22 @@
 //! | **`0x62`** | [TIMER_DISABLE]    | [`R0`] - [id][tid] #                                                  | `n` bit                            | Puts a [Timer] in [Disabled mode][tDisabled].                                  |
 //! | **`0x63`** | [TIMER_GET_MODE]   | [`R0`] - [id][tid] #                                                  | [`R0`] - [Timer mode] <br>`n` bit  | Returns the [mode][tMode] of a [Timer].                                        |
 //! | **`0x64`** | [TIMER_GET_PERIOD] | [`R0`] - [id][tid] #                                                  | [`R0`] - period                    | Returns the [period][tState] of a [Timer].                                     |
+//! | **`0x65`** | [TIMER_GET_REMAINING] | [`R0`] - [id][tid] #                                               | [`R0`] - time left <br>`n` bit     | Returns how long is left until a [Timer] next fires.                           |
+//! | **`0x66`** | [TIMER_SET_CAPTURE] | [`R0`] - [id][tid] # <br>[`R1`] - capture control                   | `n` bit                            | Sets up input capture on a [Timer].                                            |
+//! | **`0x67`** | [TIMER_GET_CAPTURE] | [`R0`] - [id][tid] #                                                 | [`R0`] - captured time <br>`n` bit | Returns the clock value a [Timer] last captured.                               |
 //! | **`0x70`** | [CLOCK_SET]        | [`R0`] - value to set                                                 | none                               | Sets the value of the [Clock].                                                 |
 //! | **`0x71`** | [CLOCK_GET]        | none                                                                  | [`R0`] - value of clock            | Gets the value of the [Clock].                                                 |
+//! | **`0x80`** | [DISPLAY_DRAW]     | [`R0`] - x <br>[`R1`] - y <br>[`R2`] - [color]                        | `n` bit                            | Sets the color of a pixel on the [Display].                                    |
+//! | **`0x81`** | [DISPLAY_READ]     | [`R0`] - x <br>[`R1`] - y                                             | [`R0`] - [color] <br>`n` bit       | Gets the color of a pixel on the [Display].                                    |
+//! | **`0x82`** | [DISPLAY_FILL]     | [`R0`] - [color]                                                      | none                               | Sets every pixel on the [Display] to one color.                                |
+//! | **`0x90`** | [DISK_READ]        | [`R0`] - [sector] # <br>[`R1`] - address                              | [`R0`] - 0 on success, 1 on error  | Reads a [sector] from the [Disk] into memory.                                  |
+//! | **`0x91`** | [DISK_WRITE]       | [`R0`] - [sector] # <br>[`R1`] - address                              | [`R0`] - 0 on success, 1 on error  | Writes a [sector]'s worth of memory to the [Disk].                             |
+//! | **`0x92`** | [DISK_SIZE]        | none                                                                  | [`R0`] - number of sectors         | Gets the number of [sectors][sector] on the [Disk].                            |
+//! | **`0xA0`** | [UART_SEND]        | [`R0`] - byte to send                                                 | [`R0`] - 0 on success, 1 on error  | Sends a byte over the [UART], waiting for room if need be.                     |
+//! | **`0xA1`** | [UART_RECEIVE]     | none                                                                  | [`R0`] - byte received             | Receives a byte from the [UART], waiting for one if need be.                   |
+//! | **`0xA2`** | [UART_POLL]        | none                                                                  | [`R0`] - byte received or -1       | Receives a byte from the [UART] if one is waiting.                             |
+//! | **`0xB0`** | [RNG_RANDOM]       | none                                                                  | [`R0`] - random word               | Gets a random word from the [RNG].                                             |
+//! | **`0xB1`** | [RNG_SEED]         | [`R0`] - seed                                                         | [`R0`] - 0 on success, 1 on error  | Seeds the [RNG] so that it produces a fixed sequence.                          |
 //!
 //! [GETC]: builtin::GETC
 //! [OUT]: builtin::OUT
@@ -46,6 +62,8 @@
 //! [GPIO_GET_MODE]: gpio::GET_MODE
 //! [GPIO_WRITE]: gpio::WRITE
 //! [GPIO_READ]: gpio::READ
+//! [GPIO_INTERRUPT_MODE]: gpio::INTERRUPT_MODE
+//! [GPIO_DEBOUNCE]: gpio::DEBOUNCE
 //! [ADC_ENABLE]: adc::ENABLE
 //! [ADC_DISABLE]: adc::DISABLE
 //! [ADC_GET_MODE]: adc::GET_MODE
@@ -59,8 +77,22 @@
 //! [TIMER_DISABLE]: timers::DISABLE
 //! [TIMER_GET_MODE]: timers::GET_MODE
 //! [TIMER_GET_PERIOD]: timers::GET_PERIOD
+//! [TIMER_GET_REMAINING]: timers::GET_REMAINING
+//! [TIMER_SET_CAPTURE]: timers::SET_CAPTURE
+//! [TIMER_GET_CAPTURE]: timers::GET_CAPTURE
 //! [CLOCK_SET]: clock::SET
 //! [CLOCK_GET]: clock::GET
+//! [DISPLAY_DRAW]: display::DRAW
+//! [DISPLAY_READ]: display::READ
+//! [DISPLAY_FILL]: display::FILL
+//! [DISK_READ]: disk::READ
+//! [DISK_WRITE]: disk::WRITE
+//! [DISK_SIZE]: disk::SIZE
+//! [UART_SEND]: uart::SEND
+//! [UART_RECEIVE]: uart::RECEIVE
+//! [UART_POLL]: uart::POLL
+//! [RNG_RANDOM]: rng::RANDOM
+//! [RNG_SEED]: rng::SEED
 //!
 //! [`R0`]: lc3_isa::Reg::R0
 //! [`R1`]: lc3_isa::Reg::R1
@@ -73,6 +105,7 @@
 //! [gOutput]: lc3_traits::peripherals::gpio::GpioState::Output
 //! [gInterrupt]: lc3_traits::peripherals::gpio::GpioState::Interrupt
 //! [gDisabled]: lc3_traits::peripherals::gpio::GpioState::Disabled
+//! [gIntMode]: lc3_traits::peripherals::gpio::GpioInterruptMode
 //!
 //! [ADC]: lc3_traits::peripherals::adc::Adc
 //! [apin]: lc3_traits::peripherals::adc::AdcPin
@@ -94,6 +127,16 @@
 //!
 //! [Clock]: lc3_traits::peripherals::clock::Clock
 //!
+//! [Display]: lc3_traits::peripherals::display::Display
+//! [color]: lc3_traits::peripherals::display::Color
+//!
+//! [Disk]: lc3_traits::peripherals::disk::Disk
+//! [sector]: lc3_traits::peripherals::disk::Sector
+//!
+//! [UART]: lc3_traits::peripherals::uart::Uart
+//!
+//! [RNG]: lc3_traits::peripherals::rng::Rng
+//!
 //! [GPIO Mode]: lc3_traits::peripherals::gpio::GpioState
 //! [ADC Mode]: lc3_traits::peripherals::adc::AdcState
 //! [Timer Mode]: lc3_traits::peripherals::timers::TimerMode
@@ -489,6 +532,106 @@ pub mod gpio {
       /// [`NUM_GPIO_PINS`]: lc3_traits::peripherals::gpio::GpioPin::NUM_PINS
       /// [`G0`]: lc3_traits::peripherals::gpio::GpioPin::G0
       [0x36] READ,
+      /// Sets what triggers interrupts on a [GPIO] [Pin].
+      ///
+      /// ## Inputs
+      ///  - [`R0`]: A [GPIO] [Pin] number.
+      ///  - [`R1`]: An [interrupt mode].
+      ///
+      /// ## Outputs
+      ///  - `n` bit: set on error, cleared on success.
+      ///
+      /// ## Usage
+      ///
+      /// This TRAP sets the [interrupt mode] of the [GPIO] [Pin] indicated by
+      /// [`R0`] to the mode indicated by [`R1`]. The values are as follows:
+      ///
+      /// | Interrupt Mode  | Value |
+      /// | --------------- | ----- |
+      /// | [`RisingEdge`]  | 0     |
+      /// | [`FallingEdge`] | 1     |
+      /// | [`BothEdges`]   | 2     |
+      /// | [`LevelHigh`]   | 3     |
+      /// | [`LevelLow`]    | 4     |
+      ///
+      /// Pins start out in [`RisingEdge`] mode. The interrupt mode is kept
+      /// when the pin's [mode] changes, so this can be used before or after
+      /// [`INTERRUPT`].
+      ///
+      /// In the level modes the interrupt fires again after its ISR returns
+      /// if the pin is still at that level.
+      ///
+      /// When [`R0`] does not hold a valid pin number, the `n` bit is set.
+      /// When [`R1`] does not hold a valid interrupt mode, nothing happens.
+      ///
+      /// All registers (including [`R0`] and [`R1`]) are preserved.
+      ///
+      /// ## Example
+      /// The below makes [`G0`] fire interrupts on falling edges:
+      /// ```{ARM Assembly}
+      /// AND R0, R0, #0      ; Sets R0 to 0
+      /// AND R1, R1, #0      ; Sets R1 to 1 (Falling Edge)
+      /// ADD R1, R1, #1
+      /// TRAP 0x37           ; Sets G0's interrupt mode
+      /// LEA R1, ISR         ; Sets R1 to the address of ISR
+      /// TRAP 0x32           ; Sets G0 to Interrupt w/ ISR
+      /// ```
+      ///
+      /// [GPIO]: lc3_traits::peripherals::Gpio
+      /// [Pin]: lc3_traits::peripherals::gpio::GpioPin
+      /// [mode]: lc3_traits::peripherals::gpio::GpioState
+      /// [interrupt mode]: lc3_traits::peripherals::gpio::GpioInterruptMode
+      /// [`RisingEdge`]: lc3_traits::peripherals::gpio::GpioInterruptMode::RisingEdge
+      /// [`FallingEdge`]: lc3_traits::peripherals::gpio::GpioInterruptMode::FallingEdge
+      /// [`BothEdges`]: lc3_traits::peripherals::gpio::GpioInterruptMode::BothEdges
+      /// [`LevelHigh`]: lc3_traits::peripherals::gpio::GpioInterruptMode::LevelHigh
+      /// [`LevelLow`]: lc3_traits::peripherals::gpio::GpioInterruptMode::LevelLow
+      /// [`INTERRUPT`]: INTERRUPT
+      /// [`R0`]: lc3_isa::Reg::R0
+      /// [`R1`]: lc3_isa::Reg::R1
+      /// [`G0`]: lc3_traits::peripherals::gpio::GpioPin::G0
+      [0x37] INTERRUPT_MODE,
+      /// Sets the debounce time of a [GPIO] [Pin].
+      ///
+      /// ## Inputs
+      ///  - [`R0`]: A [GPIO] [Pin] number.
+      ///  - [`R1`]: A debounce time, in milliseconds.
+      ///
+      /// ## Outputs
+      ///  - `n` bit: set on error, cleared on success.
+      ///
+      /// ## Usage
+      ///
+      /// After a change on the [GPIO] [Pin] indicated by [`R0`], further
+      /// changes are ignored for [`R1`] milliseconds. This applies to pins in
+      /// [Input] and [Interrupt] mode. A debounce time of 0 (the default)
+      /// turns debouncing off.
+      ///
+      /// Only the lower 8 bits of [`R1`] are used, so the longest debounce
+      /// time is 255 milliseconds.
+      ///
+      /// When [`R0`] does not hold a valid pin number, the `n` bit is set.
+      ///
+      /// All registers (including [`R0`] and [`R1`]) are preserved.
+      ///
+      /// ## Example
+      /// The below ignores changes on [`G0`] that happen within 10ms of each
+      /// other:
+      /// ```{ARM Assembly}
+      /// AND R0, R0, #0      ; Sets R0 to 0
+      /// AND R1, R1, #0      ; Sets R1 to 10
+      /// ADD R1, R1, #10
+      /// TRAP 0x38           ; Sets G0's debounce time
+      /// ```
+      ///
+      /// [GPIO]: lc3_traits::peripherals::Gpio
+      /// [Pin]: lc3_traits::peripherals::gpio::GpioPin
+      /// [Input]: lc3_traits::peripherals::gpio::GpioState::Input
+      /// [Interrupt]: lc3_traits::peripherals::gpio::GpioState::Interrupt
+      /// [`R0`]: lc3_isa::Reg::R0
+      /// [`R1`]: lc3_isa::Reg::R1
+      /// [`G0`]: lc3_traits::peripherals::gpio::GpioPin::G0
+      [0x38] DEBOUNCE,
   });
 }
 
@@ -1209,6 +1352,123 @@ pub mod timers {
       /// [`T0`]: lc3_traits::peripherals::timers::TimerId::T0
       /// [mode]: lc3_traits::peripherals::timers::TimerMode
       [0x64] GET_PERIOD,
+      /// Returns how long is left until a [Timer] next fires.
+      ///
+      /// ## Inputs
+      ///  - [`R0`]: A [Timer] [ID] number.
+      ///
+      /// ## Outputs
+      ///  - [`R0`]: The time remaining, in milliseconds.
+      ///  - `n` bit: set on error, cleared on success.
+      ///
+      /// ## Usage
+      ///
+      /// This TRAP writes the number of milliseconds until the [Timer]
+      /// indicated by [`R0`] next fires into [`R0`]. [Disabled] timers (and
+      /// [SingleShot] timers that have already fired) report 0.
+      ///
+      /// When [`R0`] does not hold a valid timer number, the `n` bit is set.
+      ///
+      /// All registers (**excluding** [`R0`]) are preserved.
+      ///
+      /// ## Example
+      /// The below starts [`T0`] as a [SingleShot] and then reads how long
+      /// it has left:
+      /// ```{ARM Assembly}
+      /// AND R0, R0, #0      ; Sets R0 to 0
+      /// LD R1, PERIOD       ; Sets R1 to 1000
+      /// LEA R2, ISR         ; Sets R2 to address of ISR
+      /// TRAP 0x60           ; Sets T0 to SingleShot w/ period of 1000 and ISR
+      /// TRAP 0x65           ; Sets R0 to the time left on T0 (<= 1000)
+      /// HALT
+      ///
+      /// PERIOD .FILL #1000
+      ///
+      /// ISR                 ; Dummy ISR
+      /// RTI
+      /// ```
+      ///
+      /// [Timer]: lc3_traits::peripherals::timers
+      /// [SingleShot]: lc3_traits::peripherals::timers::TimerMode::SingleShot
+      /// [Disabled]: lc3_traits::peripherals::timers::TimerState::Disabled
+      /// [ID]: lc3_traits::peripherals::timers::TimerId
+      /// [`R0`]: lc3_isa::Reg::R0
+      /// [`T0`]: lc3_traits::peripherals::timers::TimerId::T0
+      [0x65] GET_REMAINING,
+      /// Sets up (or turns off) input capture on a [Timer].
+      ///
+      /// ## Inputs
+      ///  - [`R0`]: A [Timer] [ID] number.
+      ///  - [`R1`]: A capture control value (see below).
+      ///
+      /// ## Outputs
+      ///  - `n` bit: set on error, cleared on success.
+      ///
+      /// ## Usage
+      ///
+      /// [`R1`] is written to the [Timer]'s capture control register as is:
+      ///  - bits \[2:0\]: the [GPIO] [Pin] to watch
+      ///  - bits \[4:3\]: the [edge] to capture on (0 is rising, 1 is
+      ///    falling, 2 is both)
+      ///  - bit 5: whether a capture should also fire the [Timer]'s
+      ///    interrupt
+      ///  - bit 6: the high bit of the [Pin] (only used when there are 16
+      ///    GPIO pins)
+      ///  - bit 15: whether capture is on at all
+      ///
+      /// Clearing bit 15 turns capture off. Any change also clears the
+      /// last captured value.
+      ///
+      /// When [`R0`] does not hold a valid timer number, the `n` bit is set.
+      ///
+      /// All registers (including [`R0`] and [`R1`]) are preserved.
+      ///
+      /// ## Example
+      /// The below makes [`T1`] capture rising edges on [`G2`]:
+      /// ```{ARM Assembly}
+      /// AND R0, R0, #0      ; Sets R0 to 1
+      /// ADD R0, R0, #1
+      /// LD R1, CAPTURE      ; Sets R1 to the capture control value
+      /// TRAP 0x66           ; Starts capturing on T1
+      /// HALT
+      ///
+      /// CAPTURE .FILL x8002 ; Enabled, rising edges, G2
+      /// ```
+      ///
+      /// [Timer]: lc3_traits::peripherals::timers
+      /// [edge]: lc3_traits::peripherals::timers::CaptureEdge
+      /// [GPIO]: lc3_traits::peripherals::Gpio
+      /// [Pin]: lc3_traits::peripherals::gpio::GpioPin
+      /// [ID]: lc3_traits::peripherals::timers::TimerId
+      /// [`R0`]: lc3_isa::Reg::R0
+      /// [`R1`]: lc3_isa::Reg::R1
+      /// [`T1`]: lc3_traits::peripherals::timers::TimerId::T1
+      /// [`G2`]: lc3_traits::peripherals::gpio::GpioPin::G2
+      [0x66] SET_CAPTURE,
+      /// Returns the clock value a [Timer] last captured.
+      ///
+      /// ## Inputs
+      ///  - [`R0`]: A [Timer] [ID] number.
+      ///
+      /// ## Outputs
+      ///  - [`R0`]: The [clock] value (in milliseconds) at the last capture.
+      ///  - `n` bit: set on error, cleared on success.
+      ///
+      /// ## Usage
+      ///
+      /// Returns 0 if nothing has been captured since capture was last set
+      /// up with [`SET_CAPTURE`].
+      ///
+      /// When [`R0`] does not hold a valid timer number, the `n` bit is set.
+      ///
+      /// All registers (**excluding** [`R0`]) are preserved.
+      ///
+      /// [Timer]: lc3_traits::peripherals::timers
+      /// [clock]: lc3_traits::peripherals::Clock
+      /// [ID]: lc3_traits::peripherals::timers::TimerId
+      /// [`SET_CAPTURE`]: SET_CAPTURE
+      /// [`R0`]: lc3_isa::Reg::R0
+      [0x67] GET_CAPTURE,
   });
 
 }
@@ -1271,6 +1531,348 @@ pub mod clock {
   });
 }
 
+/// Trap vectors for the [`Display`](lc3_traits::peripherals::Display)
+/// peripheral.
+pub mod display {
+  define!([super::mm::DISPLAY_OFFSET] <- {
+      /// Sets the color of a pixel on the [Display].
+      ///
+      /// ## Inputs
+      ///  - [`R0`]: x coordinate of the pixel (column).
+      ///  - [`R1`]: y coordinate of the pixel (row).
+      ///  - [`R2`]: [Color] to set the pixel to.
+      ///
+      /// ## Outputs
+      ///  - `n` bit: set on error, cleared on success.
+      ///
+      /// ## Usage
+      ///
+      /// This TRAP sets the pixel at ([`R0`], [`R1`]) to the [color][Color] in
+      /// [`R2`]. (0, 0) is the top left corner of the display. If the pixel is
+      /// off the display (x ≥ 128 or y ≥ 124), the `n` condition code is set
+      /// and the display is not changed.
+      ///
+      /// All registers (including [`R0`]) are preserved.
+      ///
+      /// ## Example
+      /// The below draws a red pixel in the top left corner of the display:
+      /// ```{ARM Assembly}
+      /// AND R0, R0, #0      ; x = 0
+      /// AND R1, R1, #0      ; y = 0
+      /// LD R2, RED          ; R2 = x7C00
+      /// TRAP 0x80
+      /// ```
+      ///
+      /// [Display]: lc3_traits::peripherals::display::Display
+      /// [Color]: lc3_traits::peripherals::display::Color
+      /// [`R0`]: lc3_isa::Reg::R0
+      /// [`R1`]: lc3_isa::Reg::R1
+      /// [`R2`]: lc3_isa::Reg::R2
+      [0x80] DRAW,
+      /// Gets the color of a pixel on the [Display].
+      ///
+      /// ## Inputs
+      ///  - [`R0`]: x coordinate of the pixel (column).
+      ///  - [`R1`]: y coordinate of the pixel (row).
+      ///
+      /// ## Outputs
+      ///  - [`R0`]: [Color] of the pixel.
+      ///  - `n` bit: set on error, cleared on success.
+      ///
+      /// ## Usage
+      ///
+      /// This TRAP gets the [color][Color] of the pixel at ([`R0`], [`R1`])
+      /// and stores it in [`R0`]. If the pixel is off the display, the `n`
+      /// condition code is set and [`R0`] is not changed.
+      ///
+      /// All registers (**excluding** [`R0`]) are preserved.
+      ///
+      /// ## Example
+      /// The below reads the pixel at (5, 7):
+      /// ```{ARM Assembly}
+      /// AND R0, R0, #0
+      /// ADD R0, R0, #5      ; x = 5
+      /// AND R1, R1, #0
+      /// ADD R1, R1, #7      ; y = 7
+      /// TRAP 0x81           ; R0 = color of the pixel
+      /// ```
+      ///
+      /// [Display]: lc3_traits::peripherals::display::Display
+      /// [Color]: lc3_traits::peripherals::display::Color
+      /// [`R0`]: lc3_isa::Reg::R0
+      /// [`R1`]: lc3_isa::Reg::R1
+      [0x81] READ,
+      /// Sets every pixel on the [Display] to one color.
+      ///
+      /// ## Inputs
+      ///  - [`R0`]: [Color] to fill the display with.
+      ///
+      /// ## Outputs
+      ///  - None
+      ///
+      /// ## Usage
+      ///
+      /// This TRAP sets every pixel on the display to the [color][Color] in
+      /// [`R0`].
+      ///
+      /// All registers (including [`R0`]) are preserved.
+      ///
+      /// ## Example
+      /// The below clears the display:
+      /// ```{ARM Assembly}
+      /// AND R0, R0, #0      ; Black
+      /// TRAP 0x82
+      /// ```
+      ///
+      /// [Display]: lc3_traits::peripherals::display::Display
+      /// [Color]: lc3_traits::peripherals::display::Color
+      /// [`R0`]: lc3_isa::Reg::R0
+      [0x82] FILL,
+  });
+}
+
+/// Trap vectors for the [`Disk`](lc3_traits::peripherals::Disk)
+/// peripheral.
+pub mod disk {
+  define!([super::mm::DISK_OFFSET] <- {
+      /// Reads a sector from the [Disk] into memory.
+      ///
+      /// ## Inputs
+      ///  - [`R0`]: the [sector] to read.
+      ///  - [`R1`]: the address to read the sector into.
+      ///
+      /// ## Outputs
+      ///  - [`R0`]: 0 on success, 1 on error.
+      ///
+      /// ## Usage
+      ///
+      /// This TRAP copies the 256 words in [sector] [`R0`] into memory starting
+      /// at the address in [`R1`]. If there's no disk, the sector is past the
+      /// end of the disk, or the 256 words starting at [`R1`] would run into
+      /// the memory mapped region (`0xFE00` and up), memory is not changed and
+      /// [`R0`] is set to 1.
+      ///
+      /// All registers (**excluding** [`R0`]) are preserved.
+      ///
+      /// ## Example
+      /// The below reads sector 3 into memory starting at `x4000`:
+      /// ```{ARM Assembly}
+      /// AND R0, R0, #0
+      /// ADD R0, R0, #3      ; sector 3
+      /// LD R1, BUF          ; R1 = x4000
+      /// TRAP 0x90           ; R0 = 0 if the read worked
+      /// ```
+      ///
+      /// [Disk]: lc3_traits::peripherals::disk::Disk
+      /// [sector]: lc3_traits::peripherals::disk::Sector
+      /// [`R0`]: lc3_isa::Reg::R0
+      /// [`R1`]: lc3_isa::Reg::R1
+      [0x90] READ,
+      /// Writes a sector's worth of memory to the [Disk].
+      ///
+      /// ## Inputs
+      ///  - [`R0`]: the [sector] to write.
+      ///  - [`R1`]: the address of the words to write.
+      ///
+      /// ## Outputs
+      ///  - [`R0`]: 0 on success, 1 on error.
+      ///
+      /// ## Usage
+      ///
+      /// This TRAP copies the 256 words in memory starting at the address in
+      /// [`R1`] into [sector] [`R0`]. The same things that make
+      /// [`READ`](READ) fail make this TRAP fail; on failure the disk is not
+      /// changed and [`R0`] is set to 1.
+      ///
+      /// All registers (**excluding** [`R0`]) are preserved.
+      ///
+      /// ## Example
+      /// The below writes the 256 words starting at `x4000` to sector 0:
+      /// ```{ARM Assembly}
+      /// AND R0, R0, #0      ; sector 0
+      /// LD R1, BUF          ; R1 = x4000
+      /// TRAP 0x91           ; R0 = 0 if the write worked
+      /// ```
+      ///
+      /// [Disk]: lc3_traits::peripherals::disk::Disk
+      /// [sector]: lc3_traits::peripherals::disk::Sector
+      /// [`R0`]: lc3_isa::Reg::R0
+      /// [`R1`]: lc3_isa::Reg::R1
+      [0x91] WRITE,
+      /// Gets the number of sectors on the [Disk].
+      ///
+      /// ## Inputs
+      ///  - None
+      ///
+      /// ## Outputs
+      ///  - [`R0`]: the number of [sectors][sector] on the disk (0 if there
+      ///    isn't one).
+      ///
+      /// ## Usage
+      ///
+      /// This TRAP stores the size of the disk, in [sectors][sector], in
+      /// [`R0`]. Valid sectors go from 0 up to (but not including) this.
+      ///
+      /// All registers (**excluding** [`R0`]) are preserved.
+      ///
+      /// ## Example
+      /// ```{ARM Assembly}
+      /// TRAP 0x92           ; R0 = number of sectors
+      /// ```
+      ///
+      /// [Disk]: lc3_traits::peripherals::disk::Disk
+      /// [sector]: lc3_traits::peripherals::disk::Sector
+      /// [`R0`]: lc3_isa::Reg::R0
+      [0x92] SIZE,
+  });
+}
+
+/// Trap vectors for the [`Uart`](lc3_traits::peripherals::Uart)
+/// peripheral.
+pub mod uart {
+  define!([super::mm::UART_OFFSET] <- {
+      /// Sends a byte over the [UART].
+      ///
+      /// ## Inputs
+      ///  - [`R0`]: the byte to send (only the low 8 bits are used).
+      ///
+      /// ## Outputs
+      ///  - [`R0`]: 0 on success, 1 if there's no UART.
+      ///
+      /// ## Usage
+      ///
+      /// This TRAP waits until there's room in the UART's transmit FIFO and
+      /// then puts the byte in [`R0`] in it.
+      ///
+      /// All registers (**excluding** [`R0`]) are preserved.
+      ///
+      /// ## Example
+      /// The below sends an `!`:
+      /// ```{ARM Assembly}
+      /// LD R0, BANG
+      /// TRAP 0xA0           ; R0 = 0 if there's a UART
+      /// HALT
+      ///
+      /// BANG .FILL x21
+      /// ```
+      ///
+      /// [UART]: lc3_traits::peripherals::uart::Uart
+      /// [`R0`]: lc3_isa::Reg::R0
+      [0xA0] SEND,
+      /// Receives a byte from the [UART].
+      ///
+      /// ## Inputs
+      ///  - None
+      ///
+      /// ## Outputs
+      ///  - [`R0`]: the byte received (-1 if there's no UART).
+      ///
+      /// ## Usage
+      ///
+      /// This TRAP waits until there's a byte in the UART's receive FIFO and
+      /// then takes it out and stores it in [`R0`]. Use [`POLL`] to avoid
+      /// waiting.
+      ///
+      /// All registers (**excluding** [`R0`]) are preserved.
+      ///
+      /// ## Example
+      /// ```{ARM Assembly}
+      /// TRAP 0xA1           ; R0 = the next byte from the UART
+      /// ```
+      ///
+      /// [UART]: lc3_traits::peripherals::uart::Uart
+      /// [`R0`]: lc3_isa::Reg::R0
+      /// [`POLL`]: POLL
+      [0xA1] RECEIVE,
+      /// Receives a byte from the [UART] if there's one waiting.
+      ///
+      /// ## Inputs
+      ///  - None
+      ///
+      /// ## Outputs
+      ///  - [`R0`]: the byte received, or -1 if there isn't one.
+      ///
+      /// ## Usage
+      ///
+      /// Like [`RECEIVE`], but returns -1 in [`R0`] right away if the UART's
+      /// receive FIFO is empty (or there's no UART) instead of waiting.
+      ///
+      /// All registers (**excluding** [`R0`]) are preserved.
+      ///
+      /// ## Example
+      /// ```{ARM Assembly}
+      /// TRAP 0xA2           ; R0 = the next byte from the UART or -1
+      /// BRn NOTHING
+      /// ```
+      ///
+      /// [UART]: lc3_traits::peripherals::uart::Uart
+      /// [`R0`]: lc3_isa::Reg::R0
+      /// [`RECEIVE`]: RECEIVE
+      [0xA2] POLL,
+  });
+}
+
+/// Trap vectors for the [`Rng`](lc3_traits::peripherals::Rng) peripheral.
+pub mod rng {
+  define!([super::mm::RNG_OFFSET] <- {
+      /// Gets a random word from the [RNG].
+      ///
+      /// ## Inputs
+      ///  - None
+      ///
+      /// ## Outputs
+      ///  - [`R0`]: a random word (0 if there's no RNG).
+      ///
+      /// ## Usage
+      ///
+      /// This TRAP stores the next word the [RNG] produces in [`R0`]. Until
+      /// the RNG is [seeded](SEED) these can be different every time the
+      /// program is run.
+      ///
+      /// All registers (**excluding** [`R0`]) are preserved.
+      ///
+      /// ## Example
+      /// The below picks a number from 0 to 7:
+      /// ```{ARM Assembly}
+      /// TRAP 0xB0           ; R0 = random word
+      /// AND R0, R0, #7      ; Keep the low 3 bits
+      /// ```
+      ///
+      /// [RNG]: lc3_traits::peripherals::rng::Rng
+      /// [`R0`]: lc3_isa::Reg::R0
+      [0xB0] RANDOM,
+      /// Seeds the [RNG].
+      ///
+      /// ## Inputs
+      ///  - [`R0`]: the seed.
+      ///
+      /// ## Outputs
+      ///  - [`R0`]: 0 on success, 1 if there's no RNG.
+      ///
+      /// ## Usage
+      ///
+      /// After this TRAP, [`RANDOM`] produces a sequence that's entirely
+      /// determined by the seed in [`R0`]; using the same seed again starts
+      /// the same sequence over. This is useful for making programs that use
+      /// random numbers testable.
+      ///
+      /// All registers (**excluding** [`R0`]) are preserved.
+      ///
+      /// ## Example
+      /// ```{ARM Assembly}
+      /// AND R0, R0, #0
+      /// ADD R0, R0, #7
+      /// TRAP 0xB1           ; Seed the RNG with 7
+      /// TRAP 0xB0           ; R0 = the first word in 7's sequence
+      /// ```
+      ///
+      /// [RNG]: lc3_traits::peripherals::rng::Rng
+      /// [`R0`]: lc3_isa::Reg::R0
+      /// [`RANDOM`]: RANDOM
+      [0xB1] SEED,
+  });
+}
+
 /// Trap vectors for the [`Input`](lc3_traits::peripherals::Input)
 /// peripheral.
 pub mod input {
@@ -8,5 +8,12 @@ use lti::{assert_eq, with_larger_stack};
 
 #[test]
 fn os_size() {
-    with_larger_stack(None, || assert_eq!(OS.into_iter().count(), 0x0535 /*1333*/));
+    with_larger_stack(None, || assert_eq!(OS.into_iter().count(), 0x063C /*1596*/));
+}
+
+#[test]
+fn reset_vector() {
+    with_larger_stack(None, || {
+        assert_eq!(OS_IMAGE[lc3_isa::RESET_VECTOR_ADDR as usize], lc3_isa::OS_START_ADDR)
+    });
 }
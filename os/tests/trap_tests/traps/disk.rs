@@ -0,0 +1,110 @@
+use super::*;
+
+use lc3_shims::peripherals::DiskShim;
+use lc3_traits::error::Error;
+use lc3_traits::peripherals::disk::{Disk, DiskError, SECTOR_SIZE_IN_WORDS};
+
+fn with_disk<'int, 'io>() -> ShareablePeripheralsShim<'int, 'io> {
+    let p = new_shareable_peripherals_shim(TimeSource::Real);
+    *p.get_disk().write().unwrap() = DiskShim::in_memory(4);
+
+    p
+}
+
+single_test! {
+    write_then_read,
+    prefill: {
+        0x3010: 0x4000, 0x3011: 0x4100, 0x3012: 0xFFFF, 0x3013: 0xFFFF,
+        0x4000: 0x1234, 0x40FF: 0xBEEF,
+    },
+    insns: [
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #1 },
+        { LD R1, #13 },
+        { TRAP #0x91 },
+        { ST R0, #13 },
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #1 },
+        { LD R1, #9 },
+        { TRAP #0x90 },
+        { ST R0, #9 },
+        { TRAP #0x25 },
+    ],
+    with custom peripherals: { with_disk() } -> [ShareablePeripheralsShim],
+    post: |i| {
+        eq!(i.get_word_unchecked(0x3012), 0);
+        eq!(i.get_word_unchecked(0x3013), 0);
+        eq!(i.get_word_unchecked(0x4100), 0x1234);
+        eq!(i.get_word_unchecked(0x41FF), 0xBEEF);
+
+        let mut sector = [0; SECTOR_SIZE_IN_WORDS];
+        Disk::read_sector(i.get_peripherals(), 1, &mut sector).unwrap();
+        eq!((sector[0], sector[255]), (0x1234, 0xBEEF));
+    },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
+
+single_test! {
+    read_out_of_range,
+    prefill: { 0x3006: 0x4000, 0x3007: 0xFFFF, 0x4000: 0x5555 },
+    insns: [
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #4 },
+        { LD R1, #3 },
+        { TRAP #0x90 },
+        { ST R0, #2 },
+        { TRAP #0x25 },
+    ],
+    with custom peripherals: { with_disk() } -> [ShareablePeripheralsShim],
+    post: |i| {
+        eq!(i.get_word_unchecked(0x3007), 1);
+        eq!(i.get_word_unchecked(0x4000), 0x5555);
+        eq!(i.get_error(), Some(Error::DiskError(DiskError::SectorOutOfRange(4))));
+    },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
+
+single_test! {
+    write_into_mem_mapped_region,
+    prefill: { 0x3005: 0xFF00, 0x3006: 0xFFFF },
+    insns: [
+        { AND R0, R0, #0 },
+        { LD R1, #3 },
+        { TRAP #0x91 },
+        { ST R0, #2 },
+        { TRAP #0x25 },
+    ],
+    with custom peripherals: { with_disk() } -> [ShareablePeripheralsShim],
+    post: |i| {
+        eq!(i.get_word_unchecked(0x3006), 1);
+        eq!(i.get_error(), Some(Error::DiskError(DiskError::InvalidBuffer(0xFF00))));
+    },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
+
+single_test! {
+    no_disk,
+    prefill: { 0x3005: 0x4000, 0x3006: 0xFFFF },
+    insns: [
+        { AND R0, R0, #0 },
+        { LD R1, #3 },
+        { TRAP #0x90 },
+        { ST R0, #2 },
+        { TRAP #0x25 },
+    ],
+    post: |i| { eq!(i.get_word_unchecked(0x3006), 1); },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
+
+single_test! {
+    size,
+    prefill: { 0x3003: 0xFFFF },
+    insns: [
+        { TRAP #0x92 },
+        { ST R0, #1 },
+        { TRAP #0x25 },
+    ],
+    with custom peripherals: { with_disk() } -> [ShareablePeripheralsShim],
+    post: |i| { eq!(i.get_word_unchecked(0x3003), 4); },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
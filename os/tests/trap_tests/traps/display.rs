@@ -0,0 +1,73 @@
+use super::*;
+
+use lc3_traits::peripherals::display::Display;
+
+single_test! {
+    draw,
+    prefill: { 0x3006: 0x7C00 },
+    insns: [
+        { AND R0, R0, #0 },
+        { AND R1, R1, #0 },
+        { ADD R1, R1, #7 },
+        { LD R2, #2 },
+        { TRAP #0x80 },
+        { TRAP #0x25 },
+    ],
+    pre: |p| { Display::fill(p, 0x001F); },
+    post: |i| {
+        let p = i.get_peripherals();
+        eq!(Display::get_pixel(p, 0, 7), Ok(0x7C00));
+        eq!(Display::get_pixel(p, 1, 7), Ok(0x001F));
+    },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
+
+single_test! {
+    draw_out_of_bounds,
+    prefill: { 0x3006: 124, 0x3007: 0x7FFF },
+    insns: [
+        { AND R0, R0, #0 },
+        { LD R1, #4 },
+        { LD R2, #4 },
+        { TRAP #0x80 },
+        { TRAP #0x25 },
+    ],
+    post: |i| {
+        let p = i.get_peripherals();
+        eq!(Display::get_pixel(p, 0, 123), Ok(0));
+        eq!(i.get_error(), None);
+    },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
+
+single_test! {
+    read,
+    prefill: { 0x3006: 0 },
+    insns: [
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #3 },
+        { AND R1, R1, #0 },
+        { TRAP #0x81 },
+        { ST R0, #1 },
+        { TRAP #0x25 },
+    ],
+    pre: |p| { Display::set_pixel(p, 3, 0, 0x03E0).unwrap(); },
+    post: |i| { eq!(i.get_word_unchecked(0x3006), 0x03E0); },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
+
+single_test! {
+    fill,
+    prefill: { 0x3003: 0x1234 },
+    insns: [
+        { LD R0, #2 },
+        { TRAP #0x82 },
+        { TRAP #0x25 },
+    ],
+    post: |i| {
+        let p = i.get_peripherals();
+        eq!(Display::get_pixel(p, 0, 0), Ok(0x1234));
+        eq!(Display::get_pixel(p, 127, 123), Ok(0x1234));
+    },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
@@ -1,6 +1,6 @@
 use super::*;
 
-use lc3_traits::peripherals::gpio::{Gpio, GpioPin, GpioState, GPIO_PINS};
+use lc3_traits::peripherals::gpio::{Gpio, GpioInterruptMode, GpioPin, GpioState, GPIO_PINS};
 use lc3_baseline_sim::mem_mapped::{
     G0_INT_VEC, G1_INT_VEC, G2_INT_VEC, G3_INT_VEC,
     G4_INT_VEC, G5_INT_VEC, G6_INT_VEC, G7_INT_VEC,
@@ -70,4 +70,106 @@ mod states {
         post: |i| { eq!(i.get_word_unchecked(0x3004), 1); },
         with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
     }
+
+    // Only the state should come back, not the interrupt mode or debounce
+    // time:
+    single_test! {
+        get_mode_with_interrupt_config,
+        prefill: { 0x3004: 0 },
+        insns: [
+            { AND R0, R0, #0 },
+            { TRAP #0x34 },
+            { ST R0, #1 },
+            { TRAP #0x25 },
+        ],
+        pre: |p| {
+            Gpio::set_state(p, G0, Input).unwrap();
+            Gpio::set_interrupt_mode(p, G0, GpioInterruptMode::LevelLow).unwrap();
+            Gpio::set_debounce(p, G0, 20).unwrap();
+        },
+        post: |i| { eq!(i.get_word_unchecked(0x3004), 2); },
+        with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+    }
+}
+
+mod interrupt_config {
+    use super::*;
+
+    single_test! {
+        interrupt_mode,
+        insns: [
+            { AND R0, R0, #0 },
+            { ADD R0, R0, #5 },
+            { AND R1, R1, #0 },
+            { ADD R1, R1, #2 },
+            { TRAP #0x37 },
+            { TRAP #0x25 },
+        ],
+        pre: |p| { Gpio::set_debounce(p, G5, 7).unwrap(); },
+        post: |i| {
+            let p = i.get_peripherals();
+            eq!(Gpio::get_interrupt_mode(p, G5), GpioInterruptMode::BothEdges);
+            eq!(Gpio::get_debounce(p, G5), 7);
+            eq!(Gpio::get_state(p, G5), Disabled);
+        },
+        with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+    }
+
+    single_test! {
+        invalid_interrupt_mode,
+        insns: [
+            { AND R0, R0, #0 },
+            { AND R1, R1, #0 },
+            { ADD R1, R1, #5 },
+            { TRAP #0x37 },
+            { TRAP #0x25 },
+        ],
+        pre: |p| { Gpio::set_interrupt_mode(p, G0, GpioInterruptMode::FallingEdge).unwrap(); },
+        post: |i| {
+            eq!(Gpio::get_interrupt_mode(i.get_peripherals(), G0), GpioInterruptMode::FallingEdge);
+            eq!(InstructionInterpreter::get_error(i), None);
+        },
+        with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+    }
+
+    single_test! {
+        debounce,
+        insns: [
+            { AND R0, R0, #0 },
+            { ADD R0, R0, #1 },
+            { AND R1, R1, #0 },
+            { ADD R1, R1, #12 },
+            { TRAP #0x38 },
+            { TRAP #0x25 },
+        ],
+        pre: |p| { Gpio::set_interrupt_mode(p, G1, GpioInterruptMode::LevelHigh).unwrap(); },
+        post: |i| {
+            let p = i.get_peripherals();
+            eq!(Gpio::get_debounce(p, G1), 12);
+            eq!(Gpio::get_interrupt_mode(p, G1), GpioInterruptMode::LevelHigh);
+        },
+        with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+    }
+
+    // Changing the state of a pin shouldn't reset its interrupt configuration:
+    single_test! {
+        kept_across_state_changes,
+        insns: [
+            { AND R0, R0, #0 },
+            { AND R1, R1, #0 },
+            { ADD R1, R1, #1 },
+            { TRAP #0x37 },
+            { TRAP #0x38 },
+            { TRAP #0x30 },
+            { TRAP #0x33 },
+            { TRAP #0x25 },
+        ],
+        post: |i| {
+            let p = i.get_peripherals();
+            eq!(Gpio::get_state(p, G0), Disabled);
+            eq!(Gpio::get_interrupt_mode(p, G0), GpioInterruptMode::FallingEdge);
+            eq!(Gpio::get_debounce(p, G0), 1);
+        },
+        with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+    }
 }
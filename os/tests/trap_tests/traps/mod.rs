@@ -18,9 +18,13 @@ use lc3_isa::OS_START_ADDR;
 
 mod adc;
 mod clock;
+mod display;
+mod disk;
 mod gpio;
 mod pwm;
+mod rng;
 mod timers;
+mod uart;
 
 // mod input;
 mod output;
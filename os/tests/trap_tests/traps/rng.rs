@@ -0,0 +1,55 @@
+use super::*;
+
+use lc3_shims::peripherals::RngShim;
+use lc3_traits::peripherals::rng::Rng;
+
+fn with_seeded_rng<'int, 'io>(seed: u64) -> ShareablePeripheralsShim<'int, 'io> {
+    let p = new_shareable_peripherals_shim(TimeSource::Real);
+    *p.get_rng().write().unwrap() = RngShim::seeded(seed);
+
+    p
+}
+
+fn sequence(seed: u64, n: usize) -> Vec<u16> {
+    let rng = RngShim::seeded(seed);
+    (0..n).map(|_| rng.next_word().unwrap()).collect()
+}
+
+single_test! {
+    random,
+    prefill: { 0x3005: 0, 0x3006: 0 },
+    insns: [
+        { TRAP #0xB0 },
+        { ST R0, #3 },
+        { TRAP #0xB0 },
+        { ST R0, #2 },
+        { TRAP #0x25 },
+    ],
+    with custom peripherals: { with_seeded_rng(42) } -> [ShareablePeripheralsShim],
+    post: |i| {
+        let expected = sequence(42, 2);
+        eq!(i.get_word_unchecked(0x3005), expected[0]);
+        eq!(i.get_word_unchecked(0x3006), expected[1]);
+    },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
+
+single_test! {
+    seed,
+    prefill: { 0x3007: 0xFFFF, 0x3008: 0 },
+    insns: [
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #7 },
+        { TRAP #0xB1 },
+        { ST R0, #3 },
+        { TRAP #0xB0 },
+        { ST R0, #2 },
+        { TRAP #0x25 },
+    ],
+    post: |i| {
+        eq!(i.get_word_unchecked(0x3007), 0);
+        eq!(i.get_word_unchecked(0x3008), sequence(7, 1)[0]);
+        assert!(Rng::is_seeded(i.get_peripherals()));
+    },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
@@ -1,11 +1,15 @@
 use super::*;
 
-use lc3_traits::peripherals::timers::{Timers, TimerId, TimerState};
+use lc3_traits::peripherals::gpio::GpioPin;
+use lc3_traits::peripherals::timers::{CaptureEdge, TimerCapture, Timers, TimerId, TimerState};
 
 use TimerId::*;
 use TimerState::*;
 
-// TODO: flaky (race condition in shim/interpreter's interrupt handling?)
+// These tests use virtual time so that the timers fire after the same number of
+// instructions on every run.
+const INSTRUCTIONS_PER_MS: u64 = 10;
+
 single_test! {
     singleshot,
     prefill: {
@@ -33,11 +37,13 @@ single_test! {
         { ADD R6, R6, #1 },
         { RTI } // x300E
     ],
+    with custom peripherals: {
+        new_shareable_peripherals_shim(TimeSource::virtual_time(INSTRUCTIONS_PER_MS))
+    } -> [ShareablePeripheralsShim],
     with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
 }
 
 
-// TODO: check timing is correct. Seems too fast
 single_test! {
     repeated,
     prefill: {
@@ -68,10 +74,12 @@ single_test! {
         { ADD R6, R6, #1 },
         { RTI } // x3010
     ],
+    with custom peripherals: {
+        new_shareable_peripherals_shim(TimeSource::virtual_time(INSTRUCTIONS_PER_MS))
+    } -> [ShareablePeripheralsShim],
     with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
 }
 
-// TODO: check timing is correct. Seems too fast
 single_test! {
     disable,
     prefill: {
@@ -107,6 +115,9 @@ single_test! {
         { ADD R6, R6, #1 },
         { RTI } // x3013
     ],
+    with custom peripherals: {
+        new_shareable_peripherals_shim(TimeSource::virtual_time(INSTRUCTIONS_PER_MS))
+    } -> [ShareablePeripheralsShim],
     post: |i| {
         let p = i.get_peripherals();
         eq!(Timers::get_state(p, T1), Disabled);
@@ -157,3 +168,70 @@ single_test! {
     },
     with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
 }
+
+single_test! {
+    get_remaining,
+    prefill: {
+        0x3008: 200,
+        0x3009: 0,
+    },
+    insns: [
+        { AND R0, R0, #0 },
+        { LD R1, #6 },
+        { LEA R2, #4 },
+        { TRAP #0x60 },
+        { TRAP #0x65 },
+        { ST R0, #3 },
+        { TRAP #0x25 },
+        { RTI }, // x3007
+    ],
+    with custom peripherals: {
+        new_shareable_peripherals_shim(TimeSource::virtual_time(INSTRUCTIONS_PER_MS))
+    } -> [ShareablePeripheralsShim],
+    post: |i| {
+        let remaining = i.get_word_unchecked(0x3009);
+        assert!(remaining > 0 && remaining < 200, "remaining: {}", remaining);
+    },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
+
+single_test! {
+    set_capture,
+    prefill: {
+        0x3005: 0x800A,
+    },
+    insns: [
+        { AND R0, R0, #0 },
+        { ADD R0, R0, #1 },
+        { LD R1, #2 },
+        { TRAP #0x66 },
+        { TRAP #0x25 },
+    ],
+    post: |i| {
+        let p = i.get_peripherals();
+        eq!(Timers::get_capture(p, T1), Some(TimerCapture {
+            pin: GpioPin::G2,
+            edge: CaptureEdge::Falling,
+            interrupt: false,
+        }));
+        eq!(Timers::get_capture(p, T0), None);
+    },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
+
+single_test! {
+    get_capture,
+    prefill: {
+        0x3003: 0xFFFF,
+    },
+    insns: [
+        { AND R0, R0, #0 },
+        { TRAP #0x67 },
+        { ST R0, #0 },
+        { TRAP #0x25 },
+    ],
+    post: |i| {
+        eq!(i.get_word_unchecked(0x3003), 0);
+    },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
@@ -0,0 +1,86 @@
+use super::*;
+
+use lc3_shims::peripherals::UartShim;
+use lc3_traits::peripherals::uart::Uart;
+
+// The pipe is dropped here but the link stays up; anything sent beforehand
+// stays in the receive FIFO.
+fn with_uart<'int, 'io>(incoming: &[u8]) -> ShareablePeripheralsShim<'int, 'io> {
+    let p = new_shareable_peripherals_shim(TimeSource::Real);
+    let (uart, pipe) = UartShim::pipe();
+    eq!(pipe.send_all(incoming), incoming.len());
+    *p.get_uart().write().unwrap() = uart;
+
+    p
+}
+
+single_test! {
+    send,
+    prefill: { 0x3004: 0x0021, 0x3005: 0xFFFF },
+    insns: [
+        { LD R0, #3 },
+        { TRAP #0xA0 },
+        { ST R0, #2 },
+        { TRAP #0x25 },
+    ],
+    with custom peripherals: { with_uart(&[]) } -> [ShareablePeripheralsShim],
+    post: |i| {
+        eq!(i.get_word_unchecked(0x3005), 0);
+        eq!(Uart::tx_count(i.get_peripherals()), 1);
+    },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
+
+single_test! {
+    receive,
+    prefill: { 0x3005: 0xFFFF, 0x3006: 0xFFFF },
+    insns: [
+        { TRAP #0xA1 },
+        { ST R0, #3 },
+        { TRAP #0xA1 },
+        { ST R0, #2 },
+        { TRAP #0x25 },
+    ],
+    with custom peripherals: { with_uart(b"ok") } -> [ShareablePeripheralsShim],
+    post: |i| {
+        eq!(i.get_word_unchecked(0x3005), b'o' as u16);
+        eq!(i.get_word_unchecked(0x3006), b'k' as u16);
+        eq!(Uart::rx_count(i.get_peripherals()), 0);
+    },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
+
+single_test! {
+    poll,
+    prefill: { 0x3005: 0, 0x3006: 0 },
+    insns: [
+        { TRAP #0xA2 },
+        { ST R0, #3 },
+        { TRAP #0xA2 },
+        { ST R0, #2 },
+        { TRAP #0x25 },
+    ],
+    with custom peripherals: { with_uart(b"x") } -> [ShareablePeripheralsShim],
+    post: |i| {
+        eq!(i.get_word_unchecked(0x3005), b'x' as u16);
+        eq!(i.get_word_unchecked(0x3006), 0xFFFF);
+    },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
+
+single_test! {
+    no_uart,
+    prefill: { 0x3004: 0xFFFF, 0x3005: 0 },
+    insns: [
+        { TRAP #0xA0 },
+        { ST R0, #2 },
+        { TRAP #0xA1 },
+        { ST R0, #1 },
+        { TRAP #0x25 },
+    ],
+    post: |i| {
+        eq!(i.get_word_unchecked(0x3004), 1);
+        eq!(i.get_word_unchecked(0x3005), 0xFFFF);
+    },
+    with os { MemoryShim::new(**OS_IMAGE) } @ OS_START_ADDR
+}
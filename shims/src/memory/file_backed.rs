@@ -13,7 +13,7 @@ use lc3_isa::util::MemoryDump;
 use lc3_traits::memory::Memory;
 use lc3_traits::control::Control;
 use lc3_traits::control::metadata::{LongIdentifier, ProgramMetadata};
-use lc3_traits::control::load::{PageIndex, Index as PIdx, PageAccess, PAGE_SIZE_IN_WORDS, LoadMemoryProgress, LoadMemoryDumpError, load_whole_memory_dump};
+use lc3_traits::control::load::{PageIndex, Index as PIdx, PageAccess, PAGE_SIZE_IN_WORDS, LoadMemoryProgress, LoadMemoryDumpError, load_memory_dump_compressed};
 
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 
@@ -108,6 +108,10 @@ impl IndexMut<Addr> for FileBackedMemoryShim {
 }
 
 impl Memory for FileBackedMemoryShim {
+    fn read_committed_word(&self, addr: Addr) -> Word {
+        self.mem[TryInto::<usize>::try_into(addr).unwrap()]
+    }
+
     // Note: doesn't wipe out the corresponding page from the temporary memory.
     // Must call reset for that to happen.
     fn commit_page(&mut self, page_idx: PageIndex, page: &[Word; PAGE_SIZE_IN_WORDS as usize]) {
@@ -173,8 +177,11 @@ pub(super) fn write_to_file<P: AsRef<Path>>(
 
 impl FileBackedMemoryShim {
     // Note: loads the persistent copy (mem) and not the staging copy or the file.
+    //
+    // Only sends the pages that differ from what the device has (and sends
+    // them compressed, if the device supports it).
     pub fn load<C: Control, P: LoadMemoryProgress>(&self, sim: &mut C, progress: Option<&P>) -> Result<(), LoadMemoryDumpError> {
-        load_whole_memory_dump(sim, &self.mem.into(), progress)?;
+        load_memory_dump_compressed(sim, &self.mem.into(), None, progress)?;
         sim.set_program_metadata(self.metadata.clone());
 
         Ok(())
@@ -88,6 +88,10 @@ impl IndexMut<Addr> for MemoryShim {
 }
 
 impl Memory for MemoryShim {
+    fn read_committed_word(&self, addr: Addr) -> Word {
+        self.mem[TryInto::<usize>::try_into(addr).unwrap()]
+    }
+
     // Note: doesn't wipe out the corresponding page from the temporary memory.
     // Must call reset for that to happen.
     fn commit_page(&mut self, page_idx: PageIndex, page: &[Word; PAGE_SIZE_IN_WORDS as usize]) {
@@ -1,26 +1,49 @@
 use lc3_traits::peripherals::adc::{
-    Adc, AdcMiscError, AdcPin as Pin, AdcPinArr as PinArr, AdcReadError as ReadError, AdcState,
-    AdcStateMismatch as StateMismatch,
+    Adc, AdcMiscError, AdcPin as Pin, AdcPinArr as PinArr, AdcReadError as ReadError, AdcSource,
+    AdcState, AdcStateMismatch as StateMismatch,
 };
 
+use super::time::TimeSource;
+
+use std::f64::consts::PI;
+use std::io::{self, BufRead, BufReader, Read};
+use std::time::Instant;
+
+/// A simple reference implementation of the [`Adc` peripheral
+/// trait](lc3_traits::peripherals::Adc) whose readings are generated.
+///
+/// Each pin gets its readings from a source: one of the [`AdcSource`] signals
+/// (see [`set_source`](Adc::set_source)) or a list of samples that gets played
+/// back (see [`set_samples`](AdcShim::set_samples) and
+/// [`load_csv`](AdcShim::load_csv)). Pins start out with a constant reading of
+/// 0.
+///
+/// Sources are kept across state changes and can be set while a pin is
+/// disabled. Signals are driven by the shim's [`TimeSource`] (the wall clock by
+/// default) and start when the source is set.
 #[derive(Debug, Clone)]
 pub struct AdcShim {
-    states: PinArr<State>,
+    states: PinArr<AdcState>,
+    sources: PinArr<Source>,
+    starts: PinArr<u64>,
+
+    time: TimeSource,
+    start_time: Instant,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub enum State {
-    Enabled(u8),
-    Disabled,
+#[derive(Debug, Clone, PartialEq)]
+enum Source {
+    Signal(AdcSource),
+    Samples {
+        samples: Vec<u8>,
+        ms_per_sample: u64,
+        repeat: bool,
+    },
 }
 
-impl From<State> for AdcState {
-    fn from(state: State) -> AdcState {
-        use AdcState::*;
-        match state {
-            State::Enabled(_) => Enabled,
-            State::Disabled => Disabled,
-        }
+impl Default for Source {
+    fn default() -> Self {
+        Source::Signal(AdcSource::Constant(INIT_VALUE))
     }
 }
 
@@ -28,9 +51,7 @@ const INIT_VALUE: u8 = 0;
 
 impl Default for AdcShim {
     fn default() -> Self {
-        Self {
-            states: PinArr([State::Disabled; Pin::NUM_PINS]),
-        }
+        Self::with_time_source(TimeSource::Real)
     }
 }
 
@@ -42,37 +63,162 @@ impl AdcShim {
         Self::default()
     }
 
+    pub fn with_time_source(time: TimeSource) -> Self {
+        Self {
+            states: PinArr([AdcState::Disabled; Pin::NUM_PINS]),
+            sources: PinArr(Default::default()),
+            starts: PinArr([0; Pin::NUM_PINS]),
+
+            time,
+            start_time: Instant::now(),
+        }
+    }
+
+    fn now_ms(&self) -> u64 {
+        match &self.time {
+            TimeSource::Real => self.start_time.elapsed().as_millis() as u64,
+            TimeSource::Virtual(time) => time.milliseconds(),
+        }
+    }
+
+    /// Gives an enabled pin a constant reading.
     pub fn set_value(&mut self, pin: Pin, value: u8) -> Result<(), SetError> {
-        use State::*;
-        self.states[pin] = match self.states[pin] {
-            Enabled(_) => Enabled(value),
-            Disabled => return Err(SetError((pin, self.get_state(pin)))),
-        };
+        match self.states[pin] {
+            AdcState::Enabled => {
+                self.set_source(pin, AdcSource::Constant(value)).unwrap();
+                Ok(())
+            }
+            AdcState::Disabled => Err(SetError((pin, self.get_state(pin)))),
+        }
+    }
+
+    /// Plays back `samples`, moving to the next sample every `ms_per_sample`
+    /// milliseconds. Once the samples run out the pin either starts over (if
+    /// `repeat` is set) or keeps reading the last sample.
+    ///
+    /// Errors if there are no samples or if `ms_per_sample` is 0.
+    pub fn set_samples(&mut self, pin: Pin, samples: Vec<u8>, ms_per_sample: u64, repeat: bool) -> Result<(), AdcMiscError> {
+        if samples.is_empty() || ms_per_sample == 0 {
+            return Err(AdcMiscError);
+        }
+
+        self.sources[pin] = Source::Samples { samples, ms_per_sample, repeat };
+        self.restart(pin);
+
         Ok(())
     }
+
+    /// Like [`set_samples`](AdcShim::set_samples) but with samples that come
+    /// from a CSV file.
+    ///
+    /// Each (non-empty) line is a sample; when a line has more than one column
+    /// the last one is used, so files with a timestamp column work (the
+    /// timestamps are ignored: samples are assumed to be evenly spaced). If the
+    /// first line isn't a number it's treated as a header and skipped.
+    pub fn load_csv<R: Read>(&mut self, pin: Pin, csv: R, ms_per_sample: u64, repeat: bool) -> io::Result<()> {
+        let mut samples = Vec::new();
+
+        for (idx, line) in BufReader::new(csv).lines().enumerate() {
+            let line = line?;
+            let cell = match line.rsplit(',').next().map(str::trim) {
+                Some(c) if !c.is_empty() => c,
+                _ => continue,
+            };
+
+            match cell.parse::<u8>() {
+                Ok(sample) => samples.push(sample),
+                Err(_) if idx == 0 => continue,
+                Err(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("line {}: `{}` isn't a valid ADC reading (0 to 255)", idx + 1, cell),
+                    ))
+                }
+            }
+        }
+
+        self.set_samples(pin, samples, ms_per_sample, repeat)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "no samples or a sample period of 0"))
+    }
+
+    // Signals are measured from when they're set.
+    fn restart(&mut self, pin: Pin) {
+        self.starts[pin] = self.now_ms();
+    }
+
+    fn sample(&self, pin: Pin) -> u8 {
+        let t = self.now_ms().saturating_sub(self.starts[pin]);
+
+        match &self.sources[pin] {
+            Source::Signal(signal) => sample_signal(*signal, t),
+            Source::Samples { samples, ms_per_sample, repeat } => {
+                let idx = (t / ms_per_sample) as usize;
+                let idx = if *repeat { idx % samples.len() } else { idx.min(samples.len() - 1) };
+
+                samples[idx]
+            }
+        }
+    }
+}
+
+fn sample_signal(signal: AdcSource, t: u64) -> u8 {
+    use AdcSource::*;
+
+    // How far (in [0, 1)) into the current period we are:
+    let phase = |period_ms: u16| {
+        if period_ms == 0 { 0.0 } else { (t % period_ms as u64) as f64 / period_ms as f64 }
+    };
+    let lerp = |a: u8, b: u8, frac: f64| (a as f64 + (b as f64 - a as f64) * frac).round() as u8;
+
+    match signal {
+        Constant(v) => v,
+        Ramp { from, to, period_ms } => lerp(from, to, phase(period_ms)),
+        Sine { min, max, period_ms } => {
+            let frac = ((2.0 * PI * phase(period_ms)).sin() + 1.0) / 2.0;
+            lerp(min, max, frac)
+        }
+        Square { low, high, period_ms } => if phase(period_ms) < 0.5 { high } else { low },
+        Noise { min, max, seed } => {
+            let (min, max) = if min <= max { (min, max) } else { (max, min) };
+            let range = max as u64 - min as u64 + 1;
+
+            min + (splitmix64(seed ^ t.wrapping_mul(0x9E37_79B9_7F4A_7C15)) % range) as u8
+        }
+    }
+}
+
+// Good enough for noise and, unlike a seeded RNG, doesn't need any state: the
+// reading at a particular time only depends on the seed and the time.
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
 }
 
 impl Adc for AdcShim {
     fn set_state(&mut self, pin: Pin, state: AdcState) -> Result<(), AdcMiscError> {
-        use AdcState::*;
-        self.states[pin] = match state {
-            Enabled => State::Enabled(INIT_VALUE),
-            Disabled => State::Disabled,
-        };
+        self.states[pin] = state;
         Ok(())
     }
 
     fn get_state(&self, pin: Pin) -> AdcState {
-        self.states[pin].into()
+        self.states[pin]
     }
 
     fn read(&self, pin: Pin) -> Result<u8, ReadError> {
-        use State::*;
         match self.states[pin] {
-            Enabled(value) => Ok(value),
-            valueless => Err(ReadError((pin, valueless.into()))),
+            AdcState::Enabled => Ok(self.sample(pin)),
+            AdcState::Disabled => Err(ReadError((pin, AdcState::Disabled))),
         }
     }
+
+    fn set_source(&mut self, pin: Pin, source: AdcSource) -> Result<(), AdcMiscError> {
+        self.sources[pin] = Source::Signal(source);
+        self.restart(pin);
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -80,6 +226,8 @@ mod tests {
     use super::*;
     use lc3_traits::peripherals::adc::{Adc, AdcPin::*, AdcState};
 
+    use crate::peripherals::time::VirtualTime;
+
     use lc3_test_infrastructure::{assert_eq, assert_ne};
 
     #[test]
@@ -118,4 +266,105 @@ mod tests {
         let val = shim.read(A0);
         assert_eq!(val, Err(ReadError((A0, AdcState::Disabled))))
     }
+
+    fn virtual_shim() -> (AdcShim, VirtualTime) {
+        let time = VirtualTime::new(1);
+        let mut shim = AdcShim::with_time_source(TimeSource::Virtual(time.clone()));
+        shim.set_state(A1, AdcState::Enabled).unwrap();
+
+        (shim, time)
+    }
+
+    fn readings(shim: &AdcShim, time: &VirtualTime, ms: &[u64]) -> Vec<u8> {
+        let start = time.milliseconds();
+        ms.iter()
+            .map(|t| {
+                time.advance(start + t - time.milliseconds());
+                shim.read(A1).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn signals() {
+        let (mut shim, time) = virtual_shim();
+
+        shim.set_source(A1, AdcSource::Ramp { from: 0, to: 100, period_ms: 10 }).unwrap();
+        assert_eq!(readings(&shim, &time, &[0, 5, 9, 10, 12]), vec![0, 50, 90, 0, 20]);
+
+        shim.set_source(A1, AdcSource::Ramp { from: 200, to: 100, period_ms: 4 }).unwrap();
+        assert_eq!(readings(&shim, &time, &[0, 1, 3]), vec![200, 175, 125]);
+
+        shim.set_source(A1, AdcSource::Square { low: 3, high: 250, period_ms: 8 }).unwrap();
+        assert_eq!(readings(&shim, &time, &[0, 3, 4, 7, 8]), vec![250, 250, 3, 3, 250]);
+
+        shim.set_source(A1, AdcSource::Sine { min: 0, max: 200, period_ms: 100 }).unwrap();
+        assert_eq!(readings(&shim, &time, &[0, 25, 50, 75, 100]), vec![100, 200, 100, 0, 100]);
+
+        shim.set_source(A1, AdcSource::Constant(77)).unwrap();
+        assert_eq!(readings(&shim, &time, &[0, 1000]), vec![77, 77]);
+
+        // A period of 0 holds the starting value:
+        shim.set_source(A1, AdcSource::Ramp { from: 9, to: 100, period_ms: 0 }).unwrap();
+        assert_eq!(readings(&shim, &time, &[0, 7]), vec![9, 9]);
+    }
+
+    #[test]
+    fn noise() {
+        let (mut shim, time) = virtual_shim();
+        let ms: Vec<u64> = (0..200).collect();
+
+        shim.set_source(A1, AdcSource::Noise { min: 10, max: 20, seed: 42 }).unwrap();
+        let a = readings(&shim, &time, &ms);
+        assert!(a.iter().all(|r| (10..=20).contains(r)));
+        assert!(a.iter().any(|r| *r != a[0]));
+
+        // Same seed, same readings:
+        shim.set_source(A1, AdcSource::Noise { min: 10, max: 20, seed: 42 }).unwrap();
+        assert_eq!(readings(&shim, &time, &ms), a);
+
+        shim.set_source(A1, AdcSource::Noise { min: 10, max: 20, seed: 43 }).unwrap();
+        assert_ne!(readings(&shim, &time, &ms), a);
+    }
+
+    #[test]
+    fn samples() {
+        let (mut shim, time) = virtual_shim();
+
+        shim.set_samples(A1, vec![1, 2, 3], 10, false).unwrap();
+        assert_eq!(readings(&shim, &time, &[0, 9, 10, 25, 100]), vec![1, 1, 2, 3, 3]);
+
+        shim.set_samples(A1, vec![1, 2, 3], 10, true).unwrap();
+        assert_eq!(readings(&shim, &time, &[0, 20, 30, 45]), vec![1, 3, 1, 2]);
+
+        assert_eq!(shim.set_samples(A1, vec![], 10, true), Err(AdcMiscError));
+        assert_eq!(shim.set_samples(A1, vec![1], 0, true), Err(AdcMiscError));
+    }
+
+    #[test]
+    fn csv() {
+        let (mut shim, time) = virtual_shim();
+
+        let csv = "time,reading\n0, 12\n5,200\n\n10,7\n";
+        shim.load_csv(A1, csv.as_bytes(), 5, false).unwrap();
+        assert_eq!(readings(&shim, &time, &[0, 5, 10, 15]), vec![12, 200, 7, 7]);
+
+        shim.load_csv(A1, "4\n5\n".as_bytes(), 1, true).unwrap();
+        assert_eq!(readings(&shim, &time, &[0, 1, 2]), vec![4, 5, 4]);
+
+        assert!(shim.load_csv(A1, "1\n300\n".as_bytes(), 1, true).is_err());
+        assert!(shim.load_csv(A1, "header\n".as_bytes(), 1, true).is_err());
+    }
+
+    #[test]
+    fn sources_kept_across_state_changes() {
+        let mut shim = AdcShim::new();
+        shim.set_source(A2, AdcSource::Constant(30)).unwrap();
+
+        shim.set_state(A2, AdcState::Enabled).unwrap();
+        assert_eq!(shim.read(A2), Ok(30));
+        shim.set_state(A2, AdcState::Disabled).unwrap();
+        shim.set_state(A2, AdcState::Enabled).unwrap();
+        assert_eq!(shim.read(A2), Ok(30));
+    }
 }
@@ -2,34 +2,83 @@ use core::convert::TryInto;
 use lc3_isa::{Word, WORD_MAX_VAL};
 use lc3_traits::peripherals::clock::Clock;
 
+use super::time::{TimeSource, VirtualTime};
+
 use std::time::{Duration, Instant};
 
+#[derive(Debug, Clone)]
+enum ClockState {
+    Real { start_time: Instant },
+    // The value the clock was set to and the (virtual) time at which it was
+    // set.
+    Virtual { time: VirtualTime, set_to: Word, set_at: u64 },
+}
+
 #[derive(Debug, Clone)]
 pub struct ClockShim {
-    start_time: Instant,
+    state: ClockState,
 }
 
 impl Default for ClockShim {
     fn default() -> Self {
-        Self {
-            start_time: Instant::now(),
-        }
+        Self::with_time_source(TimeSource::Real)
+    }
+}
+
+impl ClockShim {
+    pub fn with_time_source(time: TimeSource) -> Self {
+        let state = match time {
+            TimeSource::Real => ClockState::Real {
+                start_time: Instant::now(),
+            },
+            TimeSource::Virtual(time) => ClockState::Virtual {
+                set_at: time.milliseconds(),
+                set_to: 0,
+                time,
+            },
+        };
+
+        Self { state }
     }
 }
 
 impl Clock for ClockShim {
     fn get_milliseconds(&self) -> Word {
-        (self.start_time.elapsed().as_millis() % (WORD_MAX_VAL as u128))
-            .try_into()
-            .unwrap()
+        match &self.state {
+            ClockState::Real { start_time } => {
+                (start_time.elapsed().as_millis() % (WORD_MAX_VAL as u128))
+                    .try_into()
+                    .unwrap()
+            }
+            ClockState::Virtual { time, set_to, set_at } => {
+                let elapsed = time.milliseconds() - set_at;
+                ((*set_to as u64 + elapsed) % (WORD_MAX_VAL as u64))
+                    .try_into()
+                    .unwrap()
+            }
+        }
     }
 
     // they set milliseconds - adding to the current time,
     // next time that they call get_milliseconds(),
     // they will get the input milliseconds
     fn set_milliseconds(&mut self, ms: Word) {
-        let time = Duration::from_millis(ms as u64);
-        self.start_time = Instant::now().checked_sub(time).unwrap();
+        match &mut self.state {
+            ClockState::Real { start_time } => {
+                let time = Duration::from_millis(ms as u64);
+                *start_time = Instant::now().checked_sub(time).unwrap();
+            }
+            ClockState::Virtual { time, set_to, set_at } => {
+                *set_to = ms;
+                *set_at = time.milliseconds();
+            }
+        }
+    }
+
+    fn instructions_executed(&self, count: u64) {
+        if let ClockState::Virtual { time, .. } = &self.state {
+            time.advance(count)
+        }
     }
 }
 
@@ -112,6 +161,20 @@ mod tests {
         )
     }
 
+    #[test]
+    fn virtual_time() {
+        let time = VirtualTime::new(100);
+        let mut clock = ClockShim::with_time_source(TimeSource::Virtual(time.clone()));
+        assert_eq!(clock.get_milliseconds(), 0);
+
+        clock.instructions_executed(250);
+        assert_eq!(clock.get_milliseconds(), 2);
+
+        clock.set_milliseconds(WORD_MAX_VAL - 1);
+        time.advance(300);
+        assert_eq!(clock.get_milliseconds(), 2);
+    }
+
     #[test]
     fn set_milliseconds_wrong() {
         let mut clock = ClockShim::default();
@@ -0,0 +1,212 @@
+use lc3_isa::Word;
+use lc3_traits::peripherals::disk::{Disk, DiskError, Sector, SectorIdx, SECTOR_SIZE_IN_WORDS};
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+use std::convert::TryFrom;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+const SECTOR_SIZE_IN_BYTES: u64 = (SECTOR_SIZE_IN_WORDS * 2) as u64;
+
+#[derive(Debug)]
+enum Image {
+    Memory(Vec<Word>),
+    /// Words are stored big endian, like in LC-3 object files.
+    File { file: File, num_sectors: SectorIdx },
+}
+
+/// A [`Disk`] backed by a file or by a buffer in memory.
+///
+/// The default `DiskShim` has no disk in it (i.e. it isn't
+/// [present](Disk::is_present)); use [`DiskShim::in_memory`],
+/// [`DiskShim::open`], or [`DiskShim::create`] to get one that does.
+#[derive(Debug, Default)]
+pub struct DiskShim {
+    image: Option<Image>,
+}
+
+impl DiskShim {
+    /// A blank (zeroed) disk with `num_sectors` sectors that lives in memory.
+    pub fn in_memory(num_sectors: SectorIdx) -> Self {
+        Self {
+            image: Some(Image::Memory(vec![0; num_sectors as usize * SECTOR_SIZE_IN_WORDS])),
+        }
+    }
+
+    /// Uses an existing disk image file.
+    ///
+    /// The file's length has to be a whole number of sectors.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let len = file.metadata()?.len();
+
+        if len % SECTOR_SIZE_IN_BYTES != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "disk images must be a whole number of sectors long",
+            ));
+        }
+
+        let num_sectors = SectorIdx::try_from(len / SECTOR_SIZE_IN_BYTES).map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidData, "disk image has too many sectors")
+        })?;
+
+        Ok(Self {
+            image: Some(Image::File { file, num_sectors }),
+        })
+    }
+
+    /// Creates a blank disk image file with `num_sectors` sectors, replacing
+    /// `path` if it already exists.
+    pub fn create<P: AsRef<Path>>(path: P, num_sectors: SectorIdx) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(num_sectors as u64 * SECTOR_SIZE_IN_BYTES)?;
+
+        Ok(Self {
+            image: Some(Image::File { file, num_sectors }),
+        })
+    }
+
+    fn check_sector(&self, sector: SectorIdx) -> Result<(), DiskError> {
+        if self.image.is_none() {
+            Err(DiskError::NoDisk)
+        } else if sector >= self.num_sectors() {
+            Err(DiskError::SectorOutOfRange(sector))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Disk for DiskShim {
+    fn num_sectors(&self) -> SectorIdx {
+        match &self.image {
+            None => 0,
+            Some(Image::Memory(words)) => (words.len() / SECTOR_SIZE_IN_WORDS) as SectorIdx,
+            Some(Image::File { num_sectors, .. }) => *num_sectors,
+        }
+    }
+
+    fn read_sector(&self, sector: SectorIdx, buf: &mut Sector) -> Result<(), DiskError> {
+        self.check_sector(sector)?;
+        let start = sector as usize * SECTOR_SIZE_IN_WORDS;
+
+        match &self.image {
+            None => unreachable!(),
+            Some(Image::Memory(words)) => {
+                buf.copy_from_slice(&words[start..(start + SECTOR_SIZE_IN_WORDS)])
+            }
+            Some(Image::File { file, .. }) => {
+                // `&File` is `Read + Seek` so we don't need `&mut self` here.
+                let mut file: &File = file;
+                let _ = file.seek(SeekFrom::Start(sector as u64 * SECTOR_SIZE_IN_BYTES))?;
+
+                let mut bytes = [0u8; SECTOR_SIZE_IN_BYTES as usize];
+                file.read_exact(&mut bytes)?;
+                (&bytes[..]).read_u16_into::<BigEndian>(buf)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_sector(&mut self, sector: SectorIdx, buf: &Sector) -> Result<(), DiskError> {
+        self.check_sector(sector)?;
+        let start = sector as usize * SECTOR_SIZE_IN_WORDS;
+
+        match &mut self.image {
+            None => unreachable!(),
+            Some(Image::Memory(words)) => {
+                words[start..(start + SECTOR_SIZE_IN_WORDS)].copy_from_slice(buf)
+            }
+            Some(Image::File { file, .. }) => {
+                let mut bytes = Vec::with_capacity(SECTOR_SIZE_IN_BYTES as usize);
+                buf.iter().try_for_each(|w| bytes.write_u16::<BigEndian>(*w))?;
+
+                let _ = file.seek(SeekFrom::Start(sector as u64 * SECTOR_SIZE_IN_BYTES))?;
+                file.write_all(&bytes)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_present(&self) -> bool {
+        self.image.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use lc3_test_infrastructure::assert_eq;
+
+    fn round_trip(mut disk: DiskShim) {
+        let mut sector = [0; SECTOR_SIZE_IN_WORDS];
+        sector.iter_mut().enumerate().for_each(|(i, w)| *w = (i as Word) * 3);
+
+        disk.write_sector(2, &sector).unwrap();
+
+        let mut buf = [0xFFFF; SECTOR_SIZE_IN_WORDS];
+        disk.read_sector(2, &mut buf).unwrap();
+        assert_eq!(&buf[..], &sector[..]);
+
+        disk.read_sector(1, &mut buf).unwrap();
+        assert!(buf.iter().all(|w| *w == 0));
+
+        assert_eq!(disk.read_sector(4, &mut buf), Err(DiskError::SectorOutOfRange(4)));
+        assert_eq!(disk.write_sector(9, &sector), Err(DiskError::SectorOutOfRange(9)));
+    }
+
+    #[test]
+    fn no_disk() {
+        let mut disk = DiskShim::default();
+        let mut buf = [0; SECTOR_SIZE_IN_WORDS];
+
+        assert!(!disk.is_present());
+        assert_eq!(disk.num_sectors(), 0);
+        assert_eq!(disk.read_sector(0, &mut buf), Err(DiskError::NoDisk));
+        assert_eq!(disk.write_sector(0, &buf), Err(DiskError::NoDisk));
+    }
+
+    #[test]
+    fn in_memory() {
+        let disk = DiskShim::in_memory(4);
+        assert!(disk.is_present());
+        assert_eq!(disk.num_sectors(), 4);
+
+        round_trip(disk);
+    }
+
+    #[test]
+    fn file_backed() {
+        let path = std::env::temp_dir().join(format!("lc3-disk-shim-{}.img", std::process::id()));
+
+        let disk = DiskShim::create(&path, 4).unwrap();
+        assert_eq!(disk.num_sectors(), 4);
+        round_trip(disk);
+
+        // Changes should make it to the file (big endian):
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(bytes.len(), 4 * 512);
+        assert_eq!(&bytes[1024..1028], &[0x00, 0x00, 0x00, 0x03]);
+
+        let disk = DiskShim::open(&path).unwrap();
+        let mut buf = [0; SECTOR_SIZE_IN_WORDS];
+        disk.read_sector(2, &mut buf).unwrap();
+        assert_eq!(buf[255], 255 * 3);
+
+        std::fs::write(&path, [0u8; 3]).unwrap();
+        assert!(DiskShim::open(&path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,156 @@
+use lc3_isa::Word;
+use lc3_traits::peripherals::display::{
+    color_to_rgb, Color, Display, DisplayError, DISPLAY_HEIGHT, DISPLAY_PIXELS, DISPLAY_WIDTH,
+};
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// A [`Display`] that's just a framebuffer in memory.
+///
+/// Frames can be exported as [PPM](DisplayShim::write_ppm) or
+/// [PNG](DisplayShim::write_png) images.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayShim {
+    frame: Vec<Color>,
+}
+
+impl Default for DisplayShim {
+    fn default() -> Self {
+        Self {
+            frame: vec![0; DISPLAY_PIXELS],
+        }
+    }
+}
+
+impl DisplayShim {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The pixels on the display, row by row (starting with the top row).
+    pub fn frame(&self) -> &[Color] {
+        &self.frame
+    }
+
+    /// The frame as 8-bit RGB triples, row by row.
+    pub fn frame_rgb(&self) -> Vec<u8> {
+        self.frame.iter().flat_map(|c| color_to_rgb(*c).to_vec()).collect()
+    }
+
+    fn idx(x: Word, y: Word) -> Result<usize, DisplayError> {
+        if x < DISPLAY_WIDTH && y < DISPLAY_HEIGHT {
+            Ok((y as usize) * (DISPLAY_WIDTH as usize) + (x as usize))
+        } else {
+            Err(DisplayError((x, y)))
+        }
+    }
+
+    /// Writes out the current frame as a binary PPM (P6) image.
+    pub fn write_ppm<W: Write>(&self, mut w: W) -> io::Result<()> {
+        write!(w, "P6\n{} {}\n255\n", DISPLAY_WIDTH, DISPLAY_HEIGHT)?;
+        w.write_all(&self.frame_rgb())
+    }
+
+    /// Writes out the current frame as a PNG image.
+    pub fn write_png<W: Write>(&self, w: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(w, DISPLAY_WIDTH as u32, DISPLAY_HEIGHT as u32);
+        encoder.set_color(png::ColorType::RGB);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        encoder
+            .write_header()
+            .and_then(|mut w| w.write_image_data(&self.frame_rgb()))
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+
+    /// Saves the current frame to `path`; the format (PPM or PNG) is picked
+    /// using the file extension.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let ext = path.extension().and_then(|e| e.to_str()).map(str::to_lowercase);
+
+        let file = BufWriter::new(File::create(path)?);
+        match ext.as_deref() {
+            Some("ppm") => self.write_ppm(file),
+            Some("png") => self.write_png(file),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "display frames can only be saved as `.ppm` or `.png` files",
+            )),
+        }
+    }
+}
+
+impl Display for DisplayShim {
+    fn set_pixel(&mut self, x: Word, y: Word, color: Color) -> Result<(), DisplayError> {
+        let idx = Self::idx(x, y)?;
+        self.frame[idx] = color;
+
+        Ok(())
+    }
+
+    fn get_pixel(&self, x: Word, y: Word) -> Result<Color, DisplayError> {
+        Self::idx(x, y).map(|idx| self.frame[idx])
+    }
+
+    fn fill(&mut self, color: Color) {
+        self.frame.iter_mut().for_each(|p| *p = color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use lc3_test_infrastructure::assert_eq;
+
+    #[test]
+    fn pixels() {
+        let mut shim = DisplayShim::new();
+        assert_eq!(shim.get_pixel(5, 7), Ok(0));
+
+        shim.set_pixel(5, 7, 0x7C00).unwrap();
+        assert_eq!(shim.get_pixel(5, 7), Ok(0x7C00));
+        assert_eq!(shim.frame()[7 * 128 + 5], 0x7C00);
+
+        assert_eq!(shim.set_pixel(128, 0, 1), Err(DisplayError((128, 0))));
+        assert_eq!(shim.get_pixel(0, 124), Err(DisplayError((0, 124))));
+
+        shim.fill(0x001F);
+        assert!(shim.frame().iter().all(|p| *p == 0x001F));
+    }
+
+    #[test]
+    fn ppm() {
+        let mut shim = DisplayShim::new();
+        shim.set_pixel(0, 0, 0x7FFF).unwrap();
+        shim.set_pixel(1, 0, 0x03E0).unwrap();
+
+        let mut out = Vec::new();
+        shim.write_ppm(&mut out).unwrap();
+
+        let header = b"P6\n128 124\n255\n";
+        assert_eq!(&out[..header.len()], &header[..]);
+        assert_eq!(out.len(), header.len() + 128 * 124 * 3);
+        assert_eq!(&out[header.len()..header.len() + 6], &[0xFF, 0xFF, 0xFF, 0x00, 0xFF, 0x00][..]);
+    }
+
+    #[test]
+    fn png() {
+        let mut shim = DisplayShim::new();
+        shim.set_pixel(3, 2, 0x7C00).unwrap();
+
+        let mut out = Vec::new();
+        shim.write_png(&mut out).unwrap();
+
+        let decoder = png::Decoder::new(&out[..]);
+        let (info, mut reader) = decoder.read_info().unwrap();
+        assert_eq!((info.width, info.height), (128, 124));
+
+        let mut buf = vec![0; info.buffer_size()];
+        reader.next_frame(&mut buf).unwrap();
+        assert_eq!(buf, shim.frame_rgb());
+    }
+}
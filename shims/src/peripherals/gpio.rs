@@ -2,9 +2,13 @@ use core::ops::{Index, IndexMut};
 use core::sync::atomic::{AtomicBool, Ordering};
 use lc3_traits::peripherals::gpio::GpioState::Interrupt;
 use lc3_traits::peripherals::gpio::{
-    Gpio, GpioMiscError, GpioPin, GpioPinArr, GpioReadError, GpioState, GpioWriteError,
+    Gpio, GpioInterruptMode, GpioMiscError, GpioPin, GpioPinArr, GpioReadError, GpioState,
+    GpioWriteError,
 };
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+use super::time::TimeSource;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum State {
@@ -39,9 +43,20 @@ impl From<State> for GpioState {
 ///     mode.
 ///   - The state of a pin (input, output, interrupt, or disabled) can be
 ///     retrieved at any time.
+///   - All the [interrupt modes](GpioInterruptMode) and debouncing are
+///     supported. Debouncing applies to changes made with
+///     [`set_pin`](GpioShim::set_pin) in both input and interrupt mode and uses
+///     the shim's [`TimeSource`] (wall clock time by default).
 pub struct GpioShim<'gint> {
     states: GpioPinArr<State>,
+    modes: GpioPinArr<GpioInterruptMode>,
+    debounce: GpioPinArr<u8>,
+    // When (in milliseconds) each pin's last accepted change happened.
+    last_change: GpioPinArr<Option<u64>>,
     flags: Option<&'gint GpioPinArr<AtomicBool>>,
+
+    time: TimeSource,
+    start_time: Instant,
 }
 
 impl Index<GpioPin> for GpioShim<'_> {
@@ -60,10 +75,7 @@ impl IndexMut<GpioPin> for GpioShim<'_> {
 
 impl Default for GpioShim<'_> {
     fn default() -> Self {
-        Self {
-            states: GpioPinArr([State::Disabled; GpioPin::NUM_PINS]),
-            flags: None,
-        }
+        Self::with_time_source(TimeSource::Real)
     }
 }
 
@@ -76,23 +88,68 @@ impl GpioShim<'_> {
         Arc::<RwLock<Self>>::default()
     }
 
+    /// A `GpioShim` that uses `time` for debouncing.
+    pub fn with_time_source(time: TimeSource) -> Self {
+        Self {
+            states: GpioPinArr([State::Disabled; GpioPin::NUM_PINS]),
+            modes: GpioPinArr([GpioInterruptMode::default(); GpioPin::NUM_PINS]),
+            debounce: GpioPinArr([0; GpioPin::NUM_PINS]),
+            last_change: GpioPinArr([None; GpioPin::NUM_PINS]),
+            flags: None,
+
+            time,
+            start_time: Instant::now(),
+        }
+    }
+
+    fn now_ms(&self) -> u64 {
+        match &self.time {
+            TimeSource::Real => self.start_time.elapsed().as_millis() as u64,
+            TimeSource::Virtual(time) => time.milliseconds(),
+        }
+    }
+
     /// Sets a pin if it's in input or interrupt mode.
     ///
+    /// If the pin is being debounced and its last change was too recent, the
+    /// change is ignored (but this still succeeds).
+    ///
     /// Returns `Some(())` on success and `None` on failure.
     pub fn set_pin(&mut self, pin: GpioPin, bit: bool) -> Option<()> {
         use State::*;
 
+        let prev = match self[pin] {
+            Input(prev) | Interrupt(prev) => prev,
+            Output(_) | Disabled => return None,
+        };
+
+        if prev != bit {
+            let now = self.now_ms();
+            let debounce = self.debounce[pin] as u64;
+
+            match self.last_change[pin] {
+                Some(last) if debounce != 0 && now.saturating_sub(last) < debounce => {
+                    return Some(())
+                }
+                _ => self.last_change[pin] = Some(now),
+            }
+        }
+
         self[pin] = match self[pin] {
             Input(_) => Input(bit),
-            Interrupt(prev) => {
-                // Rising edge!
-                if bit && !prev {
+            Interrupt(_) => {
+                let mode = self.modes[pin];
+
+                if mode.triggered_by(prev, bit) {
                     self.raise_interrupt(pin)
+                } else if mode.is_level_triggered() {
+                    // The level went away before the interrupt was serviced.
+                    self.lower_interrupt(pin)
                 }
 
                 Interrupt(bit)
             }
-            Output(_) | Disabled => return None,
+            Output(_) | Disabled => unreachable!(),
         };
 
         Some(())
@@ -105,6 +162,25 @@ impl GpioShim<'_> {
         }
     }
 
+    fn lower_interrupt(&self, pin: GpioPin) {
+        match self.flags {
+            Some(flags) => flags[pin].store(false, Ordering::SeqCst),
+            None => unreachable!(),
+        }
+    }
+
+    // Level triggered interrupts stay pending while the pin is at the level;
+    // this (re)raises the interrupt if that's the case.
+    fn check_level(&self, pin: GpioPin) {
+        if let (State::Interrupt(bit), Some(_)) = (self[pin], self.flags) {
+            let mode = self.modes[pin];
+
+            if mode.is_level_triggered() && mode.triggered_by(bit, bit) {
+                self.raise_interrupt(pin)
+            }
+        }
+    }
+
     /// Gets the value of a pin.
     ///
     /// Returns `None` when the pin is disabled.
@@ -148,6 +224,7 @@ impl<'a> Gpio<'a> for GpioShim<'a> {
             (_, Disabled) => State::Disabled,
         };
 
+        self.check_level(pin);
         Ok(())
     }
 
@@ -200,16 +277,34 @@ impl<'a> Gpio<'a> for GpioShim<'a> {
 
     // TODO: decide functionality when no previous flag registered
     fn reset_interrupt_flag(&mut self, pin: GpioPin) {
-        match self.flags {
-            Some(flags) => flags[pin].store(false, Ordering::SeqCst),
-            None => unreachable!(),
-        }
+        self.lower_interrupt(pin);
+        self.check_level(pin);
     }
 
     // TODO: make this default implementation?
     fn interrupts_enabled(&self, pin: GpioPin) -> bool {
         self.get_state(pin) == Interrupt
     }
+
+    fn set_interrupt_mode(&mut self, pin: GpioPin, mode: GpioInterruptMode) -> Result<(), GpioMiscError> {
+        self.modes[pin] = mode;
+        self.check_level(pin);
+
+        Ok(())
+    }
+
+    fn get_interrupt_mode(&self, pin: GpioPin) -> GpioInterruptMode {
+        self.modes[pin]
+    }
+
+    fn set_debounce(&mut self, pin: GpioPin, ms: u8) -> Result<(), GpioMiscError> {
+        self.debounce[pin] = ms;
+        Ok(())
+    }
+
+    fn get_debounce(&self, pin: GpioPin) -> u8 {
+        self.debounce[pin]
+    }
 }
 
 #[cfg(test)]
@@ -270,4 +365,101 @@ mod tests {
         let result = shim.write(G0, true);
         assert_eq!(result, Err(GpioWriteError((G0, gpio::GpioState::Input))));
     }
+
+    fn flags() -> GpioPinArr<AtomicBool> {
+        GpioPinArr(core::array::from_fn(|_| AtomicBool::new(false)))
+    }
+
+    #[test]
+    fn edge_modes() {
+        use GpioInterruptMode::*;
+
+        // (mode, fires on 0 -> 1, fires on 1 -> 0)
+        for (mode, rising, falling) in [
+            (RisingEdge, true, false),
+            (FallingEdge, false, true),
+            (BothEdges, true, true),
+        ].iter() {
+            let flags = flags();
+            let mut shim = GpioShim::new();
+            shim.register_interrupt_flags(&flags);
+            shim.set_state(G3, gpio::GpioState::Interrupt).unwrap();
+            shim.set_interrupt_mode(G3, *mode).unwrap();
+            assert_eq!(shim.get_interrupt_mode(G3), *mode);
+
+            shim.set_pin(G3, true).unwrap();
+            assert_eq!(shim.interrupt_occurred(G3), *rising, "{:?}", mode);
+            shim.reset_interrupt_flag(G3);
+
+            // No change, no interrupt:
+            shim.set_pin(G3, true).unwrap();
+            assert!(!shim.interrupt_occurred(G3));
+
+            shim.set_pin(G3, false).unwrap();
+            assert_eq!(shim.interrupt_occurred(G3), *falling, "{:?}", mode);
+            shim.reset_interrupt_flag(G3);
+            assert!(!shim.interrupt_occurred(G3));
+        }
+    }
+
+    #[test]
+    fn level_modes() {
+        let flags = flags();
+        let mut shim = GpioShim::new();
+        shim.register_interrupt_flags(&flags);
+        shim.set_state(G0, gpio::GpioState::Interrupt).unwrap();
+        shim.set_interrupt_mode(G0, GpioInterruptMode::LevelHigh).unwrap();
+        assert!(!shim.interrupt_occurred(G0));
+
+        // Stays pending until the pin goes low:
+        shim.set_pin(G0, true).unwrap();
+        assert!(shim.interrupt_occurred(G0));
+        shim.reset_interrupt_flag(G0);
+        assert!(shim.interrupt_occurred(G0));
+
+        shim.set_pin(G0, false).unwrap();
+        assert!(!shim.interrupt_occurred(G0));
+
+        // The pin is already low so switching to level low should fire right
+        // away:
+        shim.set_interrupt_mode(G0, GpioInterruptMode::LevelLow).unwrap();
+        assert!(shim.interrupt_occurred(G0));
+        shim.set_pin(G0, true).unwrap();
+        assert!(!shim.interrupt_occurred(G0));
+
+        // Modes are kept across state changes:
+        shim.set_state(G0, gpio::GpioState::Output).unwrap();
+        shim.set_state(G0, gpio::GpioState::Interrupt).unwrap();
+        assert_eq!(shim.get_interrupt_mode(G0), GpioInterruptMode::LevelLow);
+        assert!(shim.interrupt_occurred(G0));
+    }
+
+    #[test]
+    fn debounce() {
+        use crate::peripherals::time::VirtualTime;
+
+        let time = VirtualTime::new(10);
+        let flags = flags();
+        let mut shim = GpioShim::with_time_source(TimeSource::Virtual(time.clone()));
+        shim.register_interrupt_flags(&flags);
+        shim.set_state(G1, gpio::GpioState::Interrupt).unwrap();
+        shim.set_interrupt_mode(G1, GpioInterruptMode::BothEdges).unwrap();
+        shim.set_debounce(G1, 5).unwrap();
+        assert_eq!(shim.get_debounce(G1), 5);
+
+        shim.set_pin(G1, true).unwrap();
+        assert!(shim.interrupt_occurred(G1));
+        shim.reset_interrupt_flag(G1);
+
+        // Bounces within 5ms are ignored:
+        time.advance(20);
+        shim.set_pin(G1, false).unwrap();
+        assert_eq!(shim.read(G1), Ok(true));
+        assert!(!shim.interrupt_occurred(G1));
+
+        time.advance(30);
+        shim.set_pin(G1, false).unwrap();
+        assert_eq!(shim.read(G1), Ok(false));
+        assert!(shim.interrupt_occurred(G1));
+    }
 }
@@ -7,20 +7,34 @@ pub mod gpio;
 pub mod pwm;
 pub mod timers;
 
+pub mod time;
+
 // Devices:
 pub mod input;
 pub mod output;
+pub mod display;
+pub mod disk;
+pub mod uart;
+pub mod rng;
+pub mod watchdog;
 
 use lc3_traits::peripherals::PeripheralSet;
 
 pub use adc::AdcShim;
 pub use clock::ClockShim;
 pub use gpio::GpioShim;
-pub use pwm::PwmShim;
+pub use pwm::{PwmCapture, PwmShim, PwmTransition};
 pub use timers::TimersShim;
 
 pub use input::{InputShim, Source, SourceShim};
 pub use output::{OutputShim, Sink};
+pub use display::DisplayShim;
+pub use disk::DiskShim;
+pub use uart::{UartPipe, UartShim};
+pub use rng::RngShim;
+pub use watchdog::WatchdogShim;
+
+pub use time::{TimeSource, VirtualTime};
 use std::ops::{Deref, DerefMut};
 use std::sync::{Arc, RwLock, Mutex};
 
@@ -33,10 +47,35 @@ pub type ShareablePeripheralsShim<'int, 'io> = PeripheralSet<
     Arc<RwLock<ClockShim>>,
     Arc<Mutex<InputShim<'io, 'int>>>,
     Arc<Mutex<OutputShim<'io, 'int>>>,
+    Arc<RwLock<DisplayShim>>,
+    Arc<RwLock<DiskShim>>,
+    Arc<RwLock<UartShim>>,
+    Arc<RwLock<RngShim>>,
+    Arc<RwLock<WatchdogShim>>,
 >;
 
 sa::assert_impl_all!(ShareablePeripheralsShim<'_, '_>: Sync, Send);
 
+/// Makes a [`ShareablePeripheralsShim`] whose GPIO, ADC, clock, timers, PWM, and
+/// watchdog shims all use `time` (see the [`time` module](time)) and whose
+/// input, output, display, disk, UART, and RNG shims are the defaults.
+pub fn new_shareable_peripherals_shim<'int, 'io>(time: TimeSource) -> ShareablePeripheralsShim<'int, 'io> {
+    PeripheralSet::new(
+        Arc::new(RwLock::new(GpioShim::with_time_source(time.clone()))),
+        Arc::new(RwLock::new(AdcShim::with_time_source(time.clone()))),
+        Arc::new(Mutex::new(PwmShim::with_time_source(time.clone()))),
+        Arc::new(Mutex::new(TimersShim::with_time_source(time.clone()))),
+        Arc::new(RwLock::new(ClockShim::with_time_source(time.clone()))),
+        Arc::new(Mutex::new(InputShim::default())),
+        Arc::new(Mutex::new(OutputShim::default())),
+        Arc::new(RwLock::new(DisplayShim::default())),
+        Arc::new(RwLock::new(DiskShim::default())),
+        Arc::new(RwLock::new(UartShim::default())),
+        Arc::new(RwLock::new(RngShim::default())),
+        Arc::new(RwLock::new(WatchdogShim::with_time_source(time))),
+    )
+}
+
 // The assumption here is that your interrupt flags and input source/output sink
 // live for the same amount of time (or, can be made to live for the same
 // amount of time with lifetime sub-typing).
@@ -52,6 +91,11 @@ pub type PeripheralsShim<'s> = PeripheralSet<
     ClockShim,
     InputShim<'s, 's>,
     OutputShim<'s, 's>,
+    DisplayShim,
+    DiskShim,
+    UartShim,
+    RngShim,
+    WatchdogShim,
 >;
 
 #[derive(Debug)]
@@ -1,43 +1,36 @@
-use core::num::NonZeroU8;
 use lc3_traits::peripherals::pwm::{
-    Pwm, PwmPin, PwmPinArr, PwmState, PwmDutyCycle,
+    Pwm, PwmConfig, PwmDutyCycle, PwmPin, PwmPinArr, PwmState, PWM_PINS,
 };
-use std::sync::mpsc;
-use std::sync::Arc;
-use std::sync::Mutex;
-use std::thread;
-use std::time::Duration;
-use timer;
-use chrono;
-use core::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::channel;
-use std::sync::atomic::Ordering::SeqCst;
-use std::thread::sleep;
-
-const MAX_PERIOD: u8 = u8::max_value();
-const MAX_DUTY_CYCLE: PwmDutyCycle = PwmDutyCycle::max_value();
 
+use super::time::TimeSource;
+
+use std::io::{self, Write};
+use std::time::Instant;
+
+/// A [`Pwm`] implementation whose pins are computed from the time (given by
+/// its [`TimeSource`]) rather than driven by a thread.
+///
+/// Each pin's wave starts (high, unless the duty cycle is 0) when the pin is
+/// enabled or its duty cycle changes; see [`PwmConfig`] for the shape of the
+/// wave and [`get_pin`](PwmShim::get_pin) to read a pin.
+///
+/// The shim can also record what its pins do over some stretch of time; see
+/// [`start_capture`](PwmShim::start_capture) and [`PwmCapture`].
 pub struct PwmShim {
     states: PwmPinArr<PwmState>,
     duty_cycle: PwmPinArr<PwmDutyCycle>,
-    rising_edge_guards: PwmPinArr<Option<timer::Guard>>,
-    falling_edge_guards: PwmPinArr<Option<timer::Guard>>,
-    bit_states: Arc<PwmPinArr<AtomicBool>>,
-    timers: PwmPinArr<timer::Timer>,
+    // The tick (see `now`) at which each pin's current wave started.
+    wave_starts: PwmPinArr<u64>,
+
+    time: TimeSource,
+    start_time: Instant,
+
+    capture: Option<PwmCapture>,
 }
 
 impl Default for PwmShim {
     fn default() -> Self {
-        //let pins = [Arc::new(Mutex::new(false)), Arc::new(Mutex::new(false))];
-
-        Self {
-            states: PwmPinArr([PwmState::Disabled; PwmPin::NUM_PINS]),
-            duty_cycle: PwmPinArr([0; PwmPin::NUM_PINS]), // start with duty_cycle low
-            rising_edge_guards: PwmPinArr([None, None]),
-            falling_edge_guards: PwmPinArr([None, None]),
-            bit_states: Arc::new(PwmPinArr([AtomicBool::new(false), AtomicBool::new(false)])),
-            timers: PwmPinArr([timer::Timer::new(), timer::Timer::new()])
-        }
+        Self::with_time_source(TimeSource::Real)
     }
 }
 
@@ -46,66 +39,231 @@ impl PwmShim {
         Self::default()
     }
 
+    pub fn with_time_source(time: TimeSource) -> Self {
+        Self {
+            states: PwmPinArr([PwmState::Disabled; PwmPin::NUM_PINS]),
+            duty_cycle: PwmPinArr([0; PwmPin::NUM_PINS]), // start with duty_cycle low
+            wave_starts: PwmPinArr([0; PwmPin::NUM_PINS]),
+
+            time,
+            start_time: Instant::now(),
+
+            capture: None,
+        }
+    }
+
     // TODO: remove?
     pub fn get_pin_state(&self, pin: PwmPin) -> PwmState {
         self.states[pin].into()
     }
 
-    fn start_wave(&mut self, pin: PwmPin, period: NonZeroU8) {
-        self.stop_wave(pin);
+    // With virtual time a tick is an instruction; otherwise it's a
+    // microsecond.
+    fn now(&self) -> u64 {
+        match &self.time {
+            TimeSource::Real => self.start_time.elapsed().as_micros() as u64,
+            TimeSource::Virtual(time) => time.instructions(),
+        }
+    }
 
-        let period = period.get();
-        let duration = chrono::Duration::milliseconds(period as i64);
+    fn ticks_per_ms(&self) -> u64 {
+        match &self.time {
+            TimeSource::Real => 1_000,
+            TimeSource::Virtual(time) => time.instructions_per_ms(),
+        }
+    }
 
-        self.bit_states[pin].store(true, SeqCst); // start with rising edge
+    fn restart_wave(&mut self, pin: PwmPin) {
+        let now = self.now();
+        self.wave_starts[pin] = now;
 
-        // Schedule future rising edges
-        let pin_clone = self.bit_states.clone();
-        let rising_edge_guard = self.timers[pin].schedule_repeating(duration, move | | {
-            pin_clone[pin].store(true, SeqCst);
-        });
+        let config = self.get_config(pin);
+        if let Some(capture) = &mut self.capture {
+            capture.changes[pin].push(Change { at: now, wave_start: now, config });
+        }
+    }
 
-        // Wait to schedule falling edges
-        let high_time = (period as u64) * (self.duty_cycle[pin] as u64) / (MAX_DUTY_CYCLE as u64);
-        sleep(Duration::from_millis(high_time));
+    /// Whether `pin` is currently high.
+    pub fn get_pin(&self, pin: PwmPin) -> bool {
+        level(self.get_config(pin), self.ticks_per_ms(), self.wave_starts[pin], self.now())
+    }
 
-        self.bit_states[pin].store(true, SeqCst); // make a falling edge
+    /// Starts recording the pins' transitions, discarding any capture that's
+    /// already in progress.
+    ///
+    /// This is most useful with [virtual time](TimeSource::Virtual) where the
+    /// capture lines up exactly with the instructions executed.
+    pub fn start_capture(&mut self) {
+        let now = self.now();
+        let mut changes = PwmPinArr(core::array::from_fn(|_| Vec::new()));
+
+        for pin in PWM_PINS.iter() {
+            changes[*pin].push(Change { at: now, wave_start: self.wave_starts[*pin], config: self.get_config(*pin) });
+        }
 
-        // Schedule future falling edges
-        let pin_clone = self.bit_states.clone();
-        let falling_edge_guard = self.timers[pin].schedule_repeating(duration, move | | {
-            pin_clone[pin].store(false, SeqCst);
-        });
+        self.capture = Some(PwmCapture { ticks_per_ms: self.ticks_per_ms(), start: now, end: now, changes });
+    }
 
-        self.rising_edge_guards[pin] = Some(rising_edge_guard);
-        self.falling_edge_guards[pin] = Some(falling_edge_guard);
+    /// The capture in progress (up to now), if there is one.
+    pub fn capture(&self) -> Option<PwmCapture> {
+        self.capture.clone().map(|mut c| { c.end = self.now(); c })
     }
 
-    fn stop_wave(&mut self, pin: PwmPin) {
-        let reg = self.rising_edge_guards[pin].take();
-        drop(reg);
-        let feg = self.falling_edge_guards[pin].take();
-        drop(feg);
+    /// Ends the capture in progress (if there is one) and returns it.
+    pub fn stop_capture(&mut self) -> Option<PwmCapture> {
+        let capture = self.capture();
+        self.capture = None;
+
+        capture
     }
+}
 
-    fn get_pin(&self, pin: PwmPin) -> bool {
-        return self.bit_states[pin].load(SeqCst);
+fn level(config: PwmConfig, ticks_per_ms: u64, wave_start: u64, at: u64) -> bool {
+    match config.period_and_high_time(ticks_per_ms) {
+        Some((period, high)) => (at - wave_start) % period < high,
+        None => false,
     }
+}
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Change {
+    at: u64,
+    wave_start: u64,
+    config: PwmConfig,
 }
 
-impl Pwm for PwmShim {
-    fn set_state(&mut self, pin: PwmPin, state: PwmState)  {
-        use PwmState::*;
-        match state {
-            Enabled(period) => {
-                self.start_wave(pin, period);
+/// A pin going high or low.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PwmTransition {
+    /// Nanoseconds since the start of the capture.
+    pub time_ns: u64,
+    pub pin: PwmPin,
+    pub high: bool,
+}
+
+/// A record of the PWM pins over some stretch of time; made by
+/// [`PwmShim::start_capture`].
+///
+/// Rather than sampling the pins, this keeps track of how they were configured
+/// and when, so every transition is captured no matter how often (or rarely)
+/// anything looks at the pins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PwmCapture {
+    ticks_per_ms: u64,
+    start: u64,
+    end: u64,
+    // Every pin starts with the configuration it had when the capture started.
+    changes: PwmPinArr<Vec<Change>>,
+}
+
+impl PwmCapture {
+    fn to_ns(&self, tick: u64) -> u64 {
+        (tick - self.start) * 1_000_000 / self.ticks_per_ms
+    }
+
+    /// How long the capture lasted, in nanoseconds.
+    pub fn duration_ns(&self) -> u64 {
+        self.to_ns(self.end)
+    }
+
+    /// Whether each pin was high when the capture started.
+    pub fn initial_levels(&self) -> PwmPinArr<bool> {
+        let mut levels = PwmPinArr([false; PwmPin::NUM_PINS]);
+
+        for pin in PWM_PINS.iter() {
+            let Change { at, wave_start, config } = self.changes[*pin][0];
+            levels[*pin] = level(config, self.ticks_per_ms, wave_start, at);
+        }
+
+        levels
+    }
+
+    /// Every time a pin went high or low during the capture, in order.
+    pub fn transitions(&self) -> Vec<PwmTransition> {
+        let initial = self.initial_levels();
+        let mut transitions = Vec::new();
+
+        for pin in PWM_PINS.iter() {
+            let changes = &self.changes[*pin];
+            let mut current = initial[*pin];
+
+            for (idx, change) in changes.iter().enumerate() {
+                let until = changes.get(idx + 1).map(|c| c.at).unwrap_or(self.end);
+                let mut edge = |at: u64, high: bool, current: &mut bool| {
+                    if high != *current {
+                        *current = high;
+                        transitions.push(PwmTransition { time_ns: self.to_ns(at), pin: *pin, high });
+                    }
+                };
+
+                edge(change.at, level(change.config, self.ticks_per_ms, change.wave_start, change.at), &mut current);
+
+                // Only waves that actually go up and down have edges:
+                let (period, high) = match change.config.period_and_high_time(self.ticks_per_ms) {
+                    Some((p, h)) if h > 0 && h < p => (p, h),
+                    _ => continue,
+                };
+
+                let mut cycle_start = change.wave_start + (change.at - change.wave_start) / period * period;
+                while cycle_start < until {
+                    for (at, level) in [(cycle_start, true), (cycle_start + high, false)].iter() {
+                        if *at > change.at && *at < until {
+                            edge(*at, *level, &mut current);
+                        }
+                    }
+
+                    cycle_start += period;
+                }
             }
-            Disabled => {
-                self.stop_wave(pin);
+        }
+
+        transitions.sort_by_key(|t| (t.time_ns, t.pin));
+        transitions
+    }
+
+    /// Writes out the capture as a [VCD] file (with a timescale of 1ns) that
+    /// waveform viewers (i.e. GTKWave) can open.
+    ///
+    /// [VCD]: https://en.wikipedia.org/wiki/Value_change_dump
+    pub fn write_vcd<W: Write>(&self, mut w: W) -> io::Result<()> {
+        let id = |pin: PwmPin| (b'!' + usize::from(pin) as u8) as char;
+        let bit = |high: bool| if high { '1' } else { '0' };
+
+        writeln!(w, "$version lc3-shims PwmShim $end")?;
+        writeln!(w, "$timescale 1ns $end")?;
+        writeln!(w, "$scope module pwm $end")?;
+        for pin in PWM_PINS.iter() {
+            writeln!(w, "$var wire 1 {} {} $end", id(*pin), pin)?;
+        }
+        writeln!(w, "$upscope $end")?;
+        writeln!(w, "$enddefinitions $end")?;
+
+        writeln!(w, "#0")?;
+        writeln!(w, "$dumpvars")?;
+        let initial = self.initial_levels();
+        for pin in PWM_PINS.iter() {
+            writeln!(w, "{}{}", bit(initial[*pin]), id(*pin))?;
+        }
+        writeln!(w, "$end")?;
+
+        let mut last_time = 0;
+        for t in self.transitions() {
+            if t.time_ns != last_time {
+                writeln!(w, "#{}", t.time_ns)?;
+                last_time = t.time_ns;
             }
-        };
+
+            writeln!(w, "{}{}", bit(t.high), id(t.pin))?;
+        }
+
+        writeln!(w, "#{}", self.duration_ns())
+    }
+}
+
+impl Pwm for PwmShim {
+    fn set_state(&mut self, pin: PwmPin, state: PwmState)  {
         self.states[pin] = state;
+        self.restart_wave(pin);
     }
 
     fn get_state(&self, pin: PwmPin) -> PwmState {
@@ -114,8 +272,8 @@ impl Pwm for PwmShim {
 
     fn set_duty_cycle(&mut self, pin: PwmPin, duty: PwmDutyCycle) {
         self.duty_cycle[pin] = duty;
-        if let PwmState::Enabled(period) = self.states[pin] {
-            self.start_wave(pin, period);
+        if let PwmState::Enabled(_) = self.states[pin] {
+            self.restart_wave(pin);
         }
     }
 
@@ -128,6 +286,15 @@ impl Pwm for PwmShim {
 mod tests {
     use super::*;
     use lc3_traits::peripherals::pwm::{self, Pwm, PwmPin::*, PwmState};
+    use crate::peripherals::time::VirtualTime;
+
+    use core::num::NonZeroU8;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    const MAX_PERIOD: u8 = u8::max_value();
+    const MAX_DUTY_CYCLE: PwmDutyCycle = PwmDutyCycle::max_value();
 
     use lc3_test_infrastructure::{
         assert_eq, assert_is_about, run_periodically_for_a_time
@@ -161,6 +328,7 @@ mod tests {
     #[test]
     fn get_pin_initial() {
         let mut shim = PwmShim::new();
+        shim.set_duty_cycle(P0, MAX_DUTY_CYCLE / 2);
         let res = shim.set_state(P0, pwm::PwmState::Enabled(NonZeroU8::new(MAX_PERIOD).unwrap()));
 
         let b = shim.get_pin(P0);
@@ -184,13 +352,99 @@ mod tests {
         let res0 = shim.set_state(P0, pwm::PwmState::Enabled(NonZeroU8::new(255).unwrap()));
         let res1 = shim.set_duty_cycle(P0, 100); // this starts pwm
 
+        // The pin should stay high for the first 100ms:
         let b = shim.get_pin(P0);
-        thread::sleep(Duration::from_millis(100));
+        thread::sleep(Duration::from_millis(50));
         let b2 = shim.get_pin(P0);
 
         assert_eq!(b, b2);
     }
 
+    #[test]
+    fn virtual_wave() {
+        let time = VirtualTime::new(2);
+        let mut shim = PwmShim::with_time_source(TimeSource::Virtual(time.clone()));
+
+        shim.set_duty_cycle(P0, MAX_DUTY_CYCLE / 5 + 1); // 52/255 of 10ms ≈ 2ms
+        shim.set_state(P0, pwm::PwmState::Enabled(NonZeroU8::new(10).unwrap()));
+
+        let mut wave = Vec::new();
+        for _ in 0..40 {
+            wave.push(shim.get_pin(P0));
+            time.advance(1);
+        }
+
+        let period: Vec<bool> = (0..20).map(|i| i < 4).collect();
+        assert_eq!(&wave[..20], &period[..]);
+        assert_eq!(&wave[20..], &period[..]);
+
+        shim.set_state(P0, pwm::PwmState::Disabled);
+        assert_eq!(shim.get_pin(P0), false);
+    }
+
+    #[test]
+    fn config() {
+        let mut shim = PwmShim::new();
+        assert_eq!(shim.get_config(P0), PwmConfig::Disabled);
+
+        let config = PwmConfig::Enabled { period_ms: NonZeroU8::new(20).unwrap(), duty_cycle: 64 };
+        shim.set_config(P0, config);
+        assert_eq!(shim.get_config(P0), config);
+        assert_eq!(shim.get_state(P0), PwmState::Enabled(NonZeroU8::new(20).unwrap()));
+        assert_eq!(shim.get_duty_cycle(P0), 64);
+
+        shim.set_config(P0, PwmConfig::Disabled);
+        assert_eq!(shim.get_configs(), PwmPinArr([PwmConfig::Disabled; PwmPin::NUM_PINS]));
+        assert_eq!(shim.get_duty_cycle(P0), 64);
+    }
+
+    fn transition(time_ns: u64, pin: PwmPin, high: bool) -> PwmTransition {
+        PwmTransition { time_ns, pin, high }
+    }
+
+    #[test]
+    fn capture() {
+        let time = VirtualTime::new(10);
+        let mut shim = PwmShim::with_time_source(TimeSource::Virtual(time.clone()));
+
+        // 4ms period, high for 1ms:
+        shim.set_config(P0, PwmConfig::Enabled { period_ms: NonZeroU8::new(4).unwrap(), duty_cycle: 64 });
+        time.advance(5); // half a millisecond in
+
+        shim.start_capture();
+        time.advance(85);
+        shim.set_config(P1, PwmConfig::Enabled { period_ms: NonZeroU8::new(2).unwrap(), duty_cycle: MAX_DUTY_CYCLE });
+        time.advance(20);
+        shim.set_duty_cycle(P0, 0);
+        time.advance(30);
+
+        let capture = shim.stop_capture().unwrap();
+        assert!(shim.capture().is_none());
+
+        assert_eq!(capture.duration_ns(), 13_500_000);
+        assert_eq!(capture.initial_levels(), PwmPinArr([true, false]));
+        assert_eq!(capture.transitions(), vec![
+            transition(  500_000, P0, false),
+            transition(3_500_000, P0, true),
+            transition(4_500_000, P0, false),
+            transition(7_500_000, P0, true),
+            transition(8_500_000, P0, false),
+            transition(8_500_000, P1, true), // always high
+            // Setting P0's duty cycle to 0 doesn't produce a transition since
+            // it was already low.
+        ]);
+
+        let mut vcd = Vec::new();
+        capture.write_vcd(&mut vcd).unwrap();
+        let vcd = String::from_utf8(vcd).unwrap();
+
+        assert!(vcd.starts_with("$version lc3-shims PwmShim $end\n$timescale 1ns $end\n"));
+        assert!(vcd.contains("$var wire 1 ! P0 $end\n$var wire 1 \" P1 $end\n"));
+        assert!(vcd.contains("#0\n$dumpvars\n1!\n0\"\n$end\n#500000\n0!\n#3500000\n1!\n"));
+        assert!(vcd.contains("#8500000\n0!\n1\"\n"));
+        assert!(vcd.ends_with("#13500000\n"));
+    }
+
     #[test]
     fn P0_toggle_once_check() {
         let mut shim = PwmShim::new();
@@ -0,0 +1,132 @@
+use lc3_isa::Word;
+use lc3_traits::peripherals::rng::{Rng, RngError};
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// An [`Rng`] that either pulls its numbers from the OS's source of entropy
+/// (the default; good for interactive use) or produces a fixed sequence from a
+/// seed (good for tests).
+///
+/// The seeded sequence comes from an [xorshift*] generator and is the same on
+/// every platform.
+///
+/// [xorshift*]: https://en.wikipedia.org/wiki/Xorshift#xorshift*
+#[derive(Debug)]
+pub struct RngShim {
+    // `None` when using entropy.
+    state: Option<AtomicU64>,
+}
+
+impl Default for RngShim {
+    fn default() -> Self {
+        Self::entropy()
+    }
+}
+
+impl RngShim {
+    /// An RNG whose numbers come from the OS.
+    pub fn entropy() -> Self {
+        Self { state: None }
+    }
+
+    /// An RNG that produces the sequence determined by `seed`.
+    pub fn seeded(seed: u64) -> Self {
+        Self { state: Some(AtomicU64::new(Self::initial_state(seed))) }
+    }
+
+    // Runs the seed through splitmix64 so that similar seeds don't produce
+    // similar sequences (and so that a seed of 0 doesn't get stuck at 0).
+    fn initial_state(seed: u64) -> u64 {
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        if z == 0 { 0x9E37_79B9_7F4A_7C15 } else { z }
+    }
+
+    fn step(mut x: u64) -> u64 {
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        x
+    }
+}
+
+impl Rng for RngShim {
+    fn next_word(&self) -> Result<Word, RngError> {
+        let word = match &self.state {
+            Some(state) => {
+                let prev = state
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |x| Some(Self::step(x)))
+                    .unwrap();
+
+                // The high bits of the scrambled output are the good ones.
+                (Self::step(prev).wrapping_mul(0x2545_F491_4F6C_DD1D) >> 48) as Word
+            }
+            None => {
+                let mut buf = [0u8; 2];
+                getrandom::getrandom(&mut buf).map_err(|_| RngError::EntropyUnavailable)?;
+
+                Word::from_le_bytes(buf)
+            }
+        };
+
+        Ok(word)
+    }
+
+    fn set_seed(&mut self, seed: u64) -> Result<(), RngError> {
+        *self = Self::seeded(seed);
+        Ok(())
+    }
+
+    fn is_seeded(&self) -> bool {
+        self.state.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use lc3_test_infrastructure::assert_eq;
+
+    fn take(rng: &RngShim, n: usize) -> Vec<Word> {
+        (0..n).map(|_| rng.next_word().unwrap()).collect()
+    }
+
+    #[test]
+    fn seeded_is_deterministic() {
+        let (a, b) = (RngShim::seeded(1234), RngShim::seeded(1234));
+        assert!(a.is_seeded());
+        assert_eq!(take(&a, 32), take(&b, 32));
+
+        let c = RngShim::seeded(1235);
+        assert_ne!(take(&RngShim::seeded(1234), 32), take(&c, 32));
+    }
+
+    #[test]
+    fn reseeding_restarts_the_sequence() {
+        let mut rng = RngShim::default();
+        assert!(!rng.is_seeded());
+
+        rng.set_seed(0).unwrap();
+        assert!(rng.is_seeded());
+        let first = take(&rng, 8);
+
+        rng.set_seed(0).unwrap();
+        assert_eq!(take(&rng, 8), first);
+
+        // A seed of 0 still gives something that looks random:
+        assert!(first.iter().any(|w| *w != first[0]));
+    }
+
+    #[test]
+    fn entropy() {
+        let rng = RngShim::entropy();
+
+        // 64 identical words in a row isn't going to happen:
+        let words = take(&rng, 64);
+        assert!(words.iter().any(|w| *w != words[0]));
+    }
+}
@@ -0,0 +1,122 @@
+//! Where the shims that deal with time ([`ClockShim`], [`TimersShim`],
+//! [`PwmShim`], [`WatchdogShim`], [`GpioShim`] for debouncing, and
+//! [`AdcShim`] for its signal sources) get the time from.
+//!
+//! By default these shims use the wall clock (i.e. `Instant::now()` and real OS
+//! timers) which means that programs that use timer interrupts or the clock
+//! behave a little differently every time they're run, depending on how fast
+//! the host is and what else it's doing.
+//!
+//! With [`TimeSource::Virtual`] the shims instead use a [`VirtualTime`] that
+//! only moves forward as instructions are executed: every
+//! [`instructions_per_ms`](VirtualTime::instructions_per_ms) instructions is
+//! one millisecond. The interpreter tells the [`Clock`] peripheral how many
+//! instructions it has executed (see [`Clock::instructions_executed`]) and
+//! [`ClockShim`] passes this along to its [`VirtualTime`]; so that everything
+//! stays in sync, the clock, timers, PWM, watchdog, GPIO, and ADC shims in a
+//! peripheral set should all be given (clones of) the _same_ [`VirtualTime`].
+//!
+//! [`new_shareable_peripherals_shim`](super::new_shareable_peripherals_shim)
+//! does this for you.
+//!
+//! Note that time doesn't pass while the machine is halted.
+//!
+//! [`ClockShim`]: super::ClockShim
+//! [`TimersShim`]: super::TimersShim
+//! [`PwmShim`]: super::PwmShim
+//! [`WatchdogShim`]: super::WatchdogShim
+//! [`GpioShim`]: super::GpioShim
+//! [`AdcShim`]: super::AdcShim
+//! [`Clock`]: lc3_traits::peripherals::clock::Clock
+//! [`Clock::instructions_executed`]: lc3_traits::peripherals::clock::Clock::instructions_executed
+
+use std::sync::atomic::{AtomicU64, Ordering::SeqCst};
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub enum TimeSource {
+    /// Wall clock time.
+    Real,
+    /// Time that's driven by the number of instructions executed.
+    Virtual(VirtualTime),
+}
+
+impl Default for TimeSource {
+    fn default() -> Self {
+        TimeSource::Real
+    }
+}
+
+impl TimeSource {
+    /// A new [`VirtualTime`] that starts at 0.
+    pub fn virtual_time(instructions_per_ms: u64) -> Self {
+        TimeSource::Virtual(VirtualTime::new(instructions_per_ms))
+    }
+}
+
+#[derive(Debug)]
+struct VirtualTimeInner {
+    instructions: AtomicU64,
+    instructions_per_ms: u64,
+}
+
+/// A shared count of executed instructions that stands in for time. Clones
+/// share the same count.
+#[derive(Debug, Clone)]
+pub struct VirtualTime(Arc<VirtualTimeInner>);
+
+impl VirtualTime {
+    /// Panics if `instructions_per_ms` is 0.
+    pub fn new(instructions_per_ms: u64) -> Self {
+        assert!(instructions_per_ms > 0, "need at least one instruction per millisecond");
+
+        VirtualTime(Arc::new(VirtualTimeInner {
+            instructions: AtomicU64::new(0),
+            instructions_per_ms,
+        }))
+    }
+
+    pub fn instructions_per_ms(&self) -> u64 {
+        self.0.instructions_per_ms
+    }
+
+    /// The number of instructions executed so far.
+    pub fn instructions(&self) -> u64 {
+        self.0.instructions.load(SeqCst)
+    }
+
+    pub fn advance(&self, instructions: u64) {
+        let _ = self.0.instructions.fetch_add(instructions, SeqCst);
+    }
+
+    /// The number of (whole) milliseconds that have passed.
+    pub fn milliseconds(&self) -> u64 {
+        self.instructions() / self.instructions_per_ms()
+    }
+
+    /// The number of instructions in `ms` milliseconds.
+    pub fn instructions_in(&self, ms: u64) -> u64 {
+        ms * self.instructions_per_ms()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use lc3_test_infrastructure::assert_eq;
+
+    #[test]
+    fn clones_share_time() {
+        let a = VirtualTime::new(10);
+        let b = a.clone();
+
+        a.advance(25);
+        assert_eq!(b.instructions(), 25);
+        assert_eq!(b.milliseconds(), 2);
+
+        b.advance(5);
+        assert_eq!(a.milliseconds(), 3);
+        assert_eq!(a.instructions_in(4), 40);
+    }
+}
@@ -1,14 +1,20 @@
 
+use lc3_isa::Word;
 use lc3_traits::peripherals::timers::{
-    Timers, TimerArr, TimerId, TimerMode, TimerState, Period, TIMERS
+    Timers, TimerArr, TimerCapture, TimerId, TimerMode, TimerState, Period, TIMERS
 };
 use lc3_traits::control::Snapshot;
 
+use super::time::TimeSource;
+
 use timer;
 
 use std::sync::{Arc, Mutex};
 use std::time::{Instant, Duration};
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+// Deadline value for timers that aren't running (virtual time only).
+const NO_DEADLINE: u64 = u64::max_value();
 
 pub struct TimersShim<'tint> {
     states: Arc<TimerArr<Mutex<TimerState>>>,
@@ -21,13 +27,31 @@ pub struct TimersShim<'tint> {
     timers: TimerArr<timer::Timer>,
 
     start_times: TimerArr<Option<Instant>>,
+
+    time: TimeSource,
+    // When using virtual time, the instruction count at which each timer is
+    // next due to fire.
+    deadlines: TimerArr<AtomicU64>,
+
+    captures: TimerArr<Option<TimerCapture>>,
+    captured: TimerArr<Option<Word>>,
 }
 
-macro_rules! arr { ($v:expr) => { TimerArr([$v, $v]) }; }
+macro_rules! arr { ($v:expr) => { TimerArr(core::array::from_fn(|_| $v)) }; }
 
 impl Default for TimersShim<'_> {
     fn default() -> Self {
-       Self {
+        Self::with_time_source(TimeSource::Real)
+    }
+}
+
+impl TimersShim<'_> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_time_source(time: TimeSource) -> Self {
+        Self {
             states: Arc::new(arr!(Mutex::new(TimerState::Disabled))),
             modes: arr!(TimerMode::SingleShot),
 
@@ -38,18 +62,25 @@ impl Default for TimersShim<'_> {
             timers: arr!(timer::Timer::new()),
 
             start_times: arr!(None),
-        }
-    }
-}
 
-impl TimersShim<'_> {
-    pub fn new() -> Self {
-        Self::default()
+            time,
+            deadlines: arr!(AtomicU64::new(NO_DEADLINE)),
+
+            captures: arr!(None),
+            captured: arr!(None),
+        }
     }
 
     fn start_timer(&mut self, timer: TimerId, period: Period) {
         use TimerMode::*;
 
+        if let TimeSource::Virtual(time) = &self.time {
+            let deadline = time.instructions() + time.instructions_in(period.get() as u64);
+            self.deadlines[timer].store(deadline, Ordering::SeqCst);
+
+            return;
+        }
+
         let duration = chrono::Duration::milliseconds(period.get() as i64);
 
         let flags = self.internal_flags.clone();
@@ -80,9 +111,73 @@ impl TimersShim<'_> {
     }
 
     fn stop_timer(&mut self, timer: TimerId) {
+        self.deadlines[timer].store(NO_DEADLINE, Ordering::SeqCst);
         drop(self.guards[timer].take())
     }
 
+    // With virtual time there's nothing running in the background to fire our
+    // timers so we instead check if any deadlines have passed whenever we're
+    // asked about a timer.
+    fn update_virtual(&self, timer: TimerId) {
+        use Ordering::SeqCst;
+
+        let time = if let TimeSource::Virtual(time) = &self.time {
+            time
+        } else {
+            return;
+        };
+
+        let deadline = self.deadlines[timer].load(SeqCst);
+        let now = time.instructions();
+        if deadline == NO_DEADLINE || now < deadline {
+            return;
+        }
+
+        self.internal_flags[timer].store(true, SeqCst);
+
+        let mut state = self.states[timer].lock().unwrap();
+        match (self.modes[timer], *state) {
+            (TimerMode::Repeated, TimerState::WithPeriod(p)) => {
+                // Multiple periods may have elapsed since we were last
+                // checked; skip to the next deadline that's in the future.
+                let period = time.instructions_in(p.get() as u64);
+                let next = deadline + ((now - deadline) / period + 1) * period;
+
+                self.deadlines[timer].store(next, SeqCst);
+            }
+            _ => {
+                self.deadlines[timer].store(NO_DEADLINE, SeqCst);
+                *state = TimerState::Disabled;
+            }
+        }
+    }
+
+    // The time left on a running timer's count, in milliseconds (rounded up).
+    fn remaining_ms(&self, timer: TimerId) -> Option<u64> {
+        let period = match self.get_state(timer) {
+            TimerState::WithPeriod(p) => p.get() as u64,
+            TimerState::Disabled => return None,
+        };
+
+        match &self.time {
+            TimeSource::Virtual(time) => {
+                let remaining = self.deadlines[timer].load(Ordering::SeqCst) - time.instructions();
+                let per_ms = time.instructions_per_ms();
+
+                Some((remaining + per_ms - 1) / per_ms)
+            }
+            TimeSource::Real => {
+                let elapsed = self.start_times[timer]?.elapsed().as_micros() as u64;
+                let remaining = match self.modes[timer] {
+                    TimerMode::Repeated => period * 1_000 - elapsed % (period * 1_000),
+                    TimerMode::SingleShot => (period * 1_000).saturating_sub(elapsed),
+                };
+
+                Some((remaining + 999) / 1_000)
+            }
+        }
+    }
+
 }
 
 impl<'a> Timers<'a> for TimersShim<'a> {
@@ -110,6 +205,7 @@ impl<'a> Timers<'a> for TimersShim<'a> {
     }
 
     fn get_state(&self, timer: TimerId) -> TimerState {
+        self.update_virtual(timer);
         *self.states[timer].lock().unwrap()
     }
 
@@ -128,6 +224,8 @@ impl<'a> Timers<'a> for TimersShim<'a> {
     fn interrupt_occurred(&self, timer: TimerId) -> bool {
         use Ordering::SeqCst;
 
+        self.update_virtual(timer);
+
         let occurred = self.internal_flags[timer].load(SeqCst);
         self.external_flags.unwrap()[timer].store(occurred, SeqCst);
 
@@ -141,6 +239,33 @@ impl<'a> Timers<'a> for TimersShim<'a> {
         self.external_flags.unwrap()[timer].store(false, SeqCst);
         self.internal_flags[timer].store(false, SeqCst);
     }
+
+    fn get_remaining(&self, timer: TimerId) -> Option<Word> {
+        self.remaining_ms(timer).map(|r| r as Word)
+    }
+
+    fn set_capture(&mut self, timer: TimerId, capture: Option<TimerCapture>) {
+        self.captures[timer] = capture;
+        self.captured[timer] = None;
+    }
+
+    fn get_capture(&self, timer: TimerId) -> Option<TimerCapture> {
+        self.captures[timer]
+    }
+
+    fn capture(&mut self, timer: TimerId, clock: Word) {
+        if let Some(capture) = self.captures[timer] {
+            self.captured[timer] = Some(clock);
+
+            if capture.interrupt {
+                self.internal_flags[timer].store(true, Ordering::SeqCst);
+            }
+        }
+    }
+
+    fn get_captured(&self, timer: TimerId) -> Option<Word> {
+        self.captured[timer]
+    }
 }
 
 #[derive(Debug)]
@@ -151,6 +276,13 @@ pub struct TimersSnapshot {
     flags: TimerArr<bool>,
     start_times: TimerArr<Option<Instant>>,
     snapshot_time: Instant,
+
+    // Virtual time only: the number of instructions left until each timer
+    // fires.
+    remaining_instructions: TimerArr<Option<u64>>,
+
+    captures: TimerArr<Option<TimerCapture>>,
+    captured: TimerArr<Option<Word>>,
 }
 
 impl<'a> Snapshot for TimersShim<'a> {
@@ -158,19 +290,28 @@ impl<'a> Snapshot for TimersShim<'a> {
     type Err = core::convert::Infallible;
 
     fn record(&self) -> Result<Self::Snap, Self::Err> {
+        TIMERS.iter().for_each(|t| self.update_virtual(*t));
+
+        let remaining = |t: TimerId| match &self.time {
+            TimeSource::Virtual(time) => match self.deadlines[t].load(Ordering::SeqCst) {
+                NO_DEADLINE => None,
+                d => Some(d - time.instructions()),
+            },
+            TimeSource::Real => None,
+        };
+
         Ok(TimersSnapshot {
-            states: TimerArr([
-                *self.states[TimerId::T0].lock().unwrap(),
-                *self.states[TimerId::T1].lock().unwrap(),
-            ]),
+            states: TimerArr(TIMERS.map(|t| *self.states[t].lock().unwrap())),
             modes: self.modes.clone(),
 
-            flags: TimerArr([
-                self.internal_flags[TimerId::T0].load(Ordering::SeqCst),
-                self.internal_flags[TimerId::T1].load(Ordering::SeqCst),
-            ]),
+            flags: TimerArr(TIMERS.map(|t| self.internal_flags[t].load(Ordering::SeqCst))),
             start_times: self.start_times.clone(),
             snapshot_time: Instant::now(),
+
+            remaining_instructions: TimerArr(TIMERS.map(remaining)),
+
+            captures: self.captures.clone(),
+            captured: self.captured.clone(),
         })
     }
 
@@ -188,11 +329,28 @@ impl<'a> Snapshot for TimersShim<'a> {
 
         self.start_times = snap.start_times;
 
+        self.captures = snap.captures;
+        self.captured = snap.captured;
+
         for t in TIMERS.iter() {
             self.internal_flags[*t].store(snap.flags[*t], Ordering::SeqCst);
             self.external_flags.unwrap()[*t].store(snap.flags[*t], Ordering::SeqCst);
         }
 
+        // With virtual time, running timers just need their deadlines moved
+        // to be relative to the current time.
+        if let TimeSource::Virtual(time) = &self.time {
+            for t in TIMERS.iter() {
+                let deadline = snap.remaining_instructions[*t]
+                    .map(|r| time.instructions() + r)
+                    .unwrap_or(NO_DEADLINE);
+
+                self.deadlines[*t].store(deadline, Ordering::SeqCst);
+            }
+
+            return Ok(());
+        }
+
         // The problem is dealing with timers that were already running at the
         // time the snapshot was taken.
         for t in TIMERS.iter() {
@@ -269,10 +427,13 @@ impl<'a> Snapshot for TimersShim<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use lc3_traits::peripherals::gpio::GpioPin;
     use lc3_traits::peripherals::timers::{
-        TimerId::*, TimerMode::*, TimerState::*
+        CaptureEdge, TimerId::*, TimerMode::*, TimerState::*
     };
 
+    use crate::peripherals::time::VirtualTime;
+
     use lc3_test_infrastructure::{
         assert_eq, assert_is_about, run_periodically_for_a_time
     };
@@ -315,7 +476,9 @@ mod tests {
             _shim
         }};
         (flags) => {{
-            static _FLAGS: TimerArr<AtomicBool> = arr!(AtomicBool::new(false));
+            #[allow(clippy::declare_interior_mutable_const)]
+            const F: AtomicBool = AtomicBool::new(false);
+            static _FLAGS: TimerArr<AtomicBool> = TimerArr([F; TimerId::NUM_TIMERS]);
             &_FLAGS
         }};
     }
@@ -478,6 +641,151 @@ mod tests {
             .for_each(|(idx, t)| assert_is_about(t, (idx + 1) as u16 * 50, 2));
     }
 
+    macro_rules! virtual_shim {
+        ($time:expr) => {{
+            let mut _shim = TimersShim::with_time_source(TimeSource::Virtual($time.clone()));
+            _shim.register_interrupt_flags(shim!(flags));
+            _shim
+        }};
+    }
+
+    #[test]
+    fn virtual_singleshot() {
+        let time = VirtualTime::new(10);
+        let mut shim = virtual_shim!(time);
+
+        shim.set_mode(T0, SingleShot);
+        shim.set_state(T0, p!(20));
+
+        time.advance(199);
+        assert_eq!(shim.interrupt_occurred(T0), false);
+        assert_eq!(shim.get_state(T0), p!(20));
+
+        time.advance(1);
+        assert_eq!(shim.interrupt_occurred(T0), true);
+        assert_eq!(shim.get_state(T0), Disabled);
+        shim.reset_interrupt_flag(T0);
+
+        time.advance(1_000);
+        assert_eq!(shim.interrupt_occurred(T0), false);
+    }
+
+    #[test]
+    fn virtual_repeated() {
+        let time = VirtualTime::new(10);
+        let mut shim = virtual_shim!(time);
+
+        shim.set_mode(T1, Repeated);
+        shim.set_state(T1, p!(5));
+
+        let mut fired_at = Vec::new();
+        for i in 1..=200 {
+            time.advance(1);
+            if shim.interrupt_occurred(T1) {
+                fired_at.push(i);
+                shim.reset_interrupt_flag(T1);
+            }
+        }
+
+        assert_eq!(fired_at, vec![50, 100, 150, 200]);
+        assert_eq!(shim.get_state(T1), p!(5));
+    }
+
+    #[test]
+    fn virtual_snapshot() {
+        let time = VirtualTime::new(1);
+        let mut shim = virtual_shim!(time);
+
+        shim.set_state(T0, p!(100));
+        time.advance(40);
+        let snap = shim.record().unwrap();
+
+        time.advance(1_000);
+        shim.restore(snap).unwrap();
+        assert_eq!(shim.get_state(T0), p!(100));
+
+        time.advance(59);
+        assert_eq!(shim.interrupt_occurred(T0), false);
+        time.advance(1);
+        assert_eq!(shim.interrupt_occurred(T0), true);
+    }
+
+    #[test]
+    fn virtual_remaining() {
+        let time = VirtualTime::new(10);
+        let mut shim = virtual_shim!(time);
+
+        assert_eq!(shim.get_remaining(T0), None);
+
+        shim.set_mode(T0, SingleShot);
+        shim.set_state(T0, p!(20));
+        assert_eq!(shim.get_remaining(T0), Some(20));
+
+        time.advance(15);
+        assert_eq!(shim.get_remaining(T0), Some(19));
+
+        time.advance(185);
+        assert_eq!(shim.get_remaining(T0), None);
+
+        shim.set_mode(T1, Repeated);
+        shim.set_state(T1, p!(5));
+        time.advance(70);
+        assert_eq!(shim.get_remaining(T1), Some(3));
+    }
+
+    #[test]
+    fn capture() {
+        let mut shim = TimersShim::new();
+        let capture = TimerCapture {
+            pin: GpioPin::G3,
+            edge: CaptureEdge::Both,
+            interrupt: false,
+        };
+
+        // Nothing is latched until capture is set up:
+        shim.capture(T0, 10);
+        assert_eq!(shim.get_captured(T0), None);
+
+        shim.set_capture(T0, Some(capture));
+        assert_eq!(shim.get_capture(T0), Some(capture));
+        assert_eq!(shim.get_capture(T1), None);
+
+        shim.capture(T0, 10);
+        shim.capture(T0, 25);
+        assert_eq!(shim.get_captured(T0), Some(25));
+
+        // Changing the capture config clears the captured value:
+        shim.set_capture(T0, Some(capture));
+        assert_eq!(shim.get_captured(T0), None);
+
+        shim.set_capture(T0, None);
+        shim.capture(T0, 30);
+        assert_eq!(shim.get_captured(T0), None);
+    }
+
+    #[test]
+    fn capture_interrupt() {
+        let flags = arr!(AtomicBool::new(false));
+        let mut shim = TimersShim::new();
+        shim.register_interrupt_flags(&flags);
+
+        let mut capture = TimerCapture {
+            pin: GpioPin::G0,
+            edge: CaptureEdge::Rising,
+            interrupt: false,
+        };
+
+        shim.set_capture(T1, Some(capture));
+        shim.capture(T1, 1);
+        assert_eq!(shim.interrupt_occurred(T1), false);
+
+        capture.interrupt = true;
+        shim.set_capture(T1, Some(capture));
+        shim.capture(T1, 2);
+        assert_eq!(shim.interrupt_occurred(T1), true);
+        assert_eq!(shim.interrupt_occurred(T0), false);
+    }
+
     #[test]
     fn get_repeated_interrupt_occurred() {
         let mut shim = shim!();
@@ -0,0 +1,324 @@
+use lc3_traits::peripherals::uart::{Uart, UartError, UART_FIFO_DEPTH};
+
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::{self, ErrorKind, Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+#[derive(Debug, Default)]
+struct Link {
+    /// Bytes waiting for the LC-3 program.
+    rx: VecDeque<u8>,
+    /// Bytes the LC-3 program sent that the other end hasn't taken yet.
+    tx: VecDeque<u8>,
+    overrun: bool,
+    /// Set when the shim goes away so that the I/O threads know to stop.
+    closed: bool,
+}
+
+impl Link {
+    fn deliver(&mut self, byte: u8) -> bool {
+        if self.rx.len() >= UART_FIFO_DEPTH {
+            self.overrun = true;
+            false
+        } else {
+            self.rx.push_back(byte);
+            true
+        }
+    }
+}
+
+type Shared = Arc<(Mutex<Link>, Condvar)>;
+
+/// A [`Uart`] whose other end is either a [`UartPipe`] or something that
+/// implements [`Read`] and [`Write`] (i.e. a pty or a serial port).
+///
+/// The default `UartShim` isn't connected to anything (i.e. it isn't
+/// [present](Uart::is_present)); use [`UartShim::pipe`],
+/// [`UartShim::with_io`], or [`UartShim::open`] to get one that is.
+#[derive(Debug, Default)]
+pub struct UartShim {
+    link: Option<Shared>,
+    rx_interrupt_enable_bit: bool,
+    tx_interrupt_enable_bit: bool,
+}
+
+/// The other end of a [`UartShim::pipe`].
+///
+/// Bytes sent with [`send`](UartPipe::send) show up in the UART's receive
+/// FIFO and bytes the LC-3 program transmits come out of
+/// [`recv`](UartPipe::recv). Cloning gives another handle to the same end.
+#[derive(Debug, Clone)]
+pub struct UartPipe {
+    link: Shared,
+}
+
+impl UartShim {
+    fn connected() -> (Self, Shared) {
+        let link: Shared = Default::default();
+
+        (Self { link: Some(link.clone()), ..Default::default() }, link)
+    }
+
+    /// A UART that's connected to an in-memory pipe.
+    pub fn pipe() -> (Self, UartPipe) {
+        let (shim, link) = Self::connected();
+        (shim, UartPipe { link })
+    }
+
+    /// A UART that's connected to a reader and a writer.
+    ///
+    /// Bytes are read from `reader` and written to `writer` on background
+    /// threads; the threads stop when the shim is dropped (or when `reader`
+    /// hits EOF or an error, in the case of the reading thread).
+    pub fn with_io<R, W>(mut reader: R, mut writer: W) -> Self
+    where
+        R: Read + Send + 'static,
+        W: Write + Send + 'static,
+    {
+        let (shim, link) = Self::connected();
+
+        let rx_link = link.clone();
+        let _ = thread::spawn(move || {
+            let mut byte = [0u8];
+            loop {
+                match reader.read(&mut byte) {
+                    Ok(0) => return,
+                    Ok(_) => {
+                        let mut link = rx_link.0.lock().unwrap();
+                        if link.closed { return; }
+
+                        let _ = link.deliver(byte[0]);
+                    }
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(_) => return,
+                }
+            }
+        });
+
+        let _ = thread::spawn(move || {
+            let (lock, cvar) = &*link;
+            let mut guard = lock.lock().unwrap();
+            loop {
+                if let Some(byte) = guard.tx.front().copied() {
+                    // Don't hold the lock while writing; the byte stays in the
+                    // FIFO until it's actually been sent.
+                    drop(guard);
+                    if writer.write_all(&[byte]).and_then(|()| writer.flush()).is_err() {
+                        return;
+                    }
+
+                    guard = lock.lock().unwrap();
+                    let _ = guard.tx.pop_front();
+                } else if guard.closed {
+                    return;
+                } else {
+                    guard = cvar.wait(guard).unwrap();
+                }
+            }
+        });
+
+        shim
+    }
+
+    /// A UART that's connected to a pty or a serial port (or any other file
+    /// that can be read from and written to).
+    ///
+    /// For example, `socat -d -d pty,raw,echo=0 pty,raw,echo=0` makes a pair
+    /// of connected ptys; open one here and hand the other to whatever the LC-3
+    /// program should be talking to.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let reader = file.try_clone()?;
+
+        Ok(Self::with_io(reader, file))
+    }
+
+    fn with_link<R>(&self, func: impl FnOnce(&mut Link, &Condvar) -> R) -> Result<R, UartError> {
+        let (lock, cvar) = &**self.link.as_ref().ok_or(UartError::NoUart)?;
+        let mut link = lock.lock().unwrap();
+
+        Ok(func(&mut link, cvar))
+    }
+}
+
+impl Drop for UartShim {
+    fn drop(&mut self) {
+        let _ = self.with_link(|link, cvar| {
+            link.closed = true;
+            cvar.notify_all();
+        });
+    }
+}
+
+impl Uart for UartShim {
+    fn transmit(&mut self, byte: u8) -> Result<(), UartError> {
+        self.with_link(|link, cvar| {
+            if link.tx.len() >= UART_FIFO_DEPTH {
+                Err(UartError::TxFifoFull)
+            } else {
+                link.tx.push_back(byte);
+                cvar.notify_all();
+                Ok(())
+            }
+        })?
+    }
+
+    fn receive(&self) -> Result<u8, UartError> {
+        self.with_link(|link, _| link.rx.pop_front().ok_or(UartError::RxFifoEmpty))?
+    }
+
+    fn rx_count(&self) -> usize {
+        self.with_link(|link, _| link.rx.len()).unwrap_or(0)
+    }
+
+    fn tx_count(&self) -> usize {
+        self.with_link(|link, _| link.tx.len()).unwrap_or(0)
+    }
+
+    fn overrun(&self) -> bool {
+        self.with_link(|link, _| link.overrun).unwrap_or(false)
+    }
+
+    fn clear_overrun(&mut self) {
+        let _ = self.with_link(|link, _| link.overrun = false);
+    }
+
+    fn set_rx_interrupt_enable_bit(&mut self, bit: bool) {
+        self.rx_interrupt_enable_bit = bit;
+    }
+
+    fn rx_interrupt_enabled(&self) -> bool {
+        self.rx_interrupt_enable_bit
+    }
+
+    fn set_tx_interrupt_enable_bit(&mut self, bit: bool) {
+        self.tx_interrupt_enable_bit = bit;
+    }
+
+    fn tx_interrupt_enabled(&self) -> bool {
+        self.tx_interrupt_enable_bit
+    }
+
+    fn is_present(&self) -> bool {
+        self.link.is_some()
+    }
+}
+
+impl UartPipe {
+    /// Sends a byte to the UART.
+    ///
+    /// Returns false (and sets the UART's overrun flag) if the UART's receive
+    /// FIFO is full, in which case the byte is dropped.
+    pub fn send(&self, byte: u8) -> bool {
+        self.link.0.lock().unwrap().deliver(byte)
+    }
+
+    /// Sends bytes to the UART until its receive FIFO fills up; returns how
+    /// many bytes made it.
+    pub fn send_all(&self, bytes: &[u8]) -> usize {
+        let mut link = self.link.0.lock().unwrap();
+        bytes.iter().take_while(|b| link.deliver(**b)).count()
+    }
+
+    /// Takes the next byte the UART transmitted, if there is one.
+    pub fn recv(&self) -> Option<u8> {
+        self.link.0.lock().unwrap().tx.pop_front()
+    }
+
+    /// Takes everything the UART has transmitted so far.
+    pub fn recv_all(&self) -> Vec<u8> {
+        self.link.0.lock().unwrap().tx.drain(..).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use lc3_test_infrastructure::assert_eq;
+
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn no_uart() {
+        let mut uart = UartShim::default();
+
+        assert!(!uart.is_present());
+        assert_eq!(uart.transmit(b'a'), Err(UartError::NoUart));
+        assert_eq!(uart.receive(), Err(UartError::NoUart));
+        assert_eq!(uart.rx_count(), 0);
+        assert_eq!(uart.tx_count(), 0);
+    }
+
+    #[test]
+    fn pipe() {
+        let (mut uart, pipe) = UartShim::pipe();
+        assert!(uart.is_present());
+
+        assert!(pipe.send(b'h'));
+        assert_eq!(pipe.send_all(b"ey"), 2);
+        assert_eq!(uart.rx_count(), 3);
+        assert_eq!(uart.receive(), Ok(b'h'));
+        assert_eq!(uart.receive(), Ok(b'e'));
+        assert_eq!(uart.receive(), Ok(b'y'));
+        assert_eq!(uart.receive(), Err(UartError::RxFifoEmpty));
+
+        uart.transmit(b'o').unwrap();
+        uart.transmit(b'k').unwrap();
+        assert_eq!(uart.tx_count(), 2);
+        assert_eq!(pipe.recv(), Some(b'o'));
+        assert_eq!(pipe.recv_all(), b"k".to_vec());
+        assert_eq!(pipe.recv(), None);
+        assert_eq!(uart.tx_count(), 0);
+    }
+
+    #[test]
+    fn full_fifos() {
+        let (mut uart, pipe) = UartShim::pipe();
+
+        assert_eq!(pipe.send_all(&[7; UART_FIFO_DEPTH + 4]), UART_FIFO_DEPTH);
+        assert!(uart.overrun());
+        assert!(!pipe.send(8));
+
+        assert_eq!(uart.receive(), Ok(7));
+        uart.clear_overrun();
+        assert!(!uart.overrun());
+        assert!(pipe.send(8));
+
+        for _ in 0..UART_FIFO_DEPTH { uart.transmit(1).unwrap(); }
+        assert_eq!(uart.transmit(2), Err(UartError::TxFifoFull));
+        assert_eq!(pipe.recv_all().len(), UART_FIFO_DEPTH);
+        assert_eq!(uart.transmit(2), Ok(()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn io() {
+        use std::os::unix::net::UnixStream;
+
+        fn wait_for(mut cond: impl FnMut() -> bool) {
+            let start = Instant::now();
+            while !cond() {
+                assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+
+        let (ours, mut theirs) = UnixStream::pair().unwrap();
+        let mut uart = UartShim::with_io(ours.try_clone().unwrap(), ours);
+
+        theirs.write_all(b"hi").unwrap();
+        wait_for(|| uart.rx_count() == 2);
+        assert_eq!(uart.receive(), Ok(b'h'));
+        assert_eq!(uart.receive(), Ok(b'i'));
+
+        uart.transmit(b'!').unwrap();
+        let mut buf = [0u8];
+        theirs.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [b'!']);
+        wait_for(|| uart.tx_count() == 0);
+    }
+}
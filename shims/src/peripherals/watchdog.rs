@@ -0,0 +1,204 @@
+use lc3_isa::Word;
+use lc3_traits::peripherals::watchdog::Watchdog;
+
+use super::time::TimeSource;
+
+use std::time::Instant;
+
+#[derive(Debug, Clone)]
+pub struct WatchdogShim {
+    time: TimeSource,
+    // Only used with real time.
+    origin: Instant,
+
+    timeout: Word,
+    // In milliseconds since `origin` (or in virtual milliseconds).
+    kicked_at: u64,
+
+    interrupt_enable_bit: bool,
+    interrupt_handled: bool,
+    caused_reset: bool,
+    disabled_by_debugger: bool,
+}
+
+impl Default for WatchdogShim {
+    fn default() -> Self {
+        Self::with_time_source(TimeSource::Real)
+    }
+}
+
+impl WatchdogShim {
+    pub fn with_time_source(time: TimeSource) -> Self {
+        let mut shim = Self {
+            time,
+            origin: Instant::now(),
+
+            timeout: 0,
+            kicked_at: 0,
+
+            interrupt_enable_bit: false,
+            interrupt_handled: false,
+            caused_reset: false,
+            disabled_by_debugger: false,
+        };
+
+        shim.kick();
+        shim
+    }
+
+    fn now(&self) -> u64 {
+        match &self.time {
+            TimeSource::Real => self.origin.elapsed().as_millis() as u64,
+            TimeSource::Virtual(time) => time.milliseconds(),
+        }
+    }
+
+    fn running(&self) -> bool {
+        self.timeout != 0 && !self.disabled_by_debugger
+    }
+
+    fn elapsed(&self) -> u64 {
+        self.now() - self.kicked_at
+    }
+}
+
+impl Watchdog for WatchdogShim {
+    fn set_timeout(&mut self, ms: Word) {
+        self.timeout = ms;
+        self.kick();
+    }
+
+    fn get_timeout(&self) -> Word {
+        self.timeout
+    }
+
+    fn kick(&mut self) {
+        self.kicked_at = self.now();
+        self.interrupt_handled = false;
+    }
+
+    fn remaining(&self) -> Word {
+        if !self.running() { return 0; }
+
+        let timeout = self.timeout as u64;
+        (timeout - (self.elapsed() % timeout)) as Word
+    }
+
+    fn expirations(&self) -> u16 {
+        if !self.running() { return 0; }
+
+        (self.elapsed() / (self.timeout as u64)).min(u16::max_value() as u64) as u16
+    }
+
+    fn set_interrupt_enable_bit(&mut self, bit: bool) {
+        self.interrupt_enable_bit = bit;
+    }
+
+    fn interrupt_enabled(&self) -> bool {
+        self.interrupt_enable_bit
+    }
+
+    fn interrupt_occurred(&self) -> bool {
+        self.expirations() > 0 && !self.interrupt_handled
+    }
+
+    fn reset_interrupt_flag(&mut self) {
+        self.interrupt_handled = true;
+    }
+
+    fn caused_reset(&self) -> bool {
+        self.caused_reset
+    }
+
+    fn set_caused_reset(&mut self, caused: bool) {
+        self.caused_reset = caused;
+    }
+
+    fn set_disabled_by_debugger(&mut self, disabled: bool) {
+        self.disabled_by_debugger = disabled;
+        if !disabled { self.kick(); }
+    }
+
+    fn disabled_by_debugger(&self) -> bool {
+        self.disabled_by_debugger
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use lc3_test_infrastructure::assert_eq;
+
+    use crate::peripherals::time::VirtualTime;
+
+    fn shim() -> (WatchdogShim, VirtualTime) {
+        let time = VirtualTime::new(1);
+        (WatchdogShim::with_time_source(TimeSource::Virtual(time.clone())), time)
+    }
+
+    #[test]
+    fn off_by_default() {
+        let (wd, time) = shim();
+        time.advance(100_000);
+
+        assert_eq!(wd.get_timeout(), 0);
+        assert_eq!(wd.expirations(), 0);
+        assert_eq!(wd.remaining(), 0);
+        assert!(!wd.interrupt_occurred());
+    }
+
+    #[test]
+    fn expires() {
+        let (mut wd, time) = shim();
+        wd.set_timeout(10);
+        assert_eq!(wd.remaining(), 10);
+
+        time.advance(9);
+        assert_eq!(wd.expirations(), 0);
+        assert_eq!(wd.remaining(), 1);
+
+        time.advance(1);
+        assert_eq!(wd.expirations(), 1);
+        assert!(wd.interrupt_occurred());
+        assert_eq!(wd.remaining(), 10);
+
+        wd.reset_interrupt_flag();
+        assert!(!wd.interrupt_occurred());
+
+        time.advance(10);
+        assert_eq!(wd.expirations(), 2);
+        assert!(!wd.interrupt_occurred());
+    }
+
+    #[test]
+    fn kicking() {
+        let (mut wd, time) = shim();
+        wd.set_timeout(10);
+
+        for _ in 0..5 {
+            time.advance(8);
+            wd.kick();
+        }
+
+        assert_eq!(wd.expirations(), 0);
+        assert_eq!(wd.remaining(), 10);
+    }
+
+    #[test]
+    fn disabled_by_debugger() {
+        let (mut wd, time) = shim();
+        wd.set_timeout(10);
+
+        wd.set_disabled_by_debugger(true);
+        time.advance(50);
+        assert_eq!(wd.expirations(), 0);
+        assert!(!wd.interrupt_occurred());
+
+        // Re-enabling starts the countdown over:
+        wd.set_disabled_by_debugger(false);
+        assert_eq!(wd.expirations(), 0);
+        time.advance(10);
+        assert_eq!(wd.expirations(), 1);
+    }
+}
@@ -46,7 +46,8 @@ pub use {
     lc3_shims::{
         memory::MemoryShim,
         peripherals::{
-            PeripheralsShim, ShareablePeripheralsShim, SourceShim
+            PeripheralsShim, ShareablePeripheralsShim, SourceShim,
+            TimeSource, new_shareable_peripherals_shim, DiskShim, UartShim, UartPipe, RngShim, WatchdogShim,
         },
     },
     lc3_baseline_sim::interp::{
@@ -1,5 +1,7 @@
 //! Utilities for test running.
 
+use crate::{ShareablePeripheralsShim, TimeSource, new_shareable_peripherals_shim};
+
 use std::thread;
 use std::time::{Instant, Duration};
 
@@ -61,6 +63,13 @@ pub fn with_larger_stack<R: Send + 'static, F: FnOnce() -> R + Send + 'static>(n
     child.join().unwrap()
 }
 
+/// Peripherals that run on [virtual time](TimeSource::Virtual) at one
+/// instruction per millisecond, so that the clock, the timers, and the
+/// watchdog move in lockstep with the instructions a test runs.
+pub fn virtual_time_peripherals<'int, 'io>() -> ShareablePeripheralsShim<'int, 'io> {
+    new_shareable_peripherals_shim(TimeSource::virtual_time(1))
+}
+
 // Won't work as expected for tolerances greater than half u16 width.
 // ...but that wouldn't test anything anyway.
 pub fn assert_is_about(actual: u16, expected: u16, tolerance: u16) {
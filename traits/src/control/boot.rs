@@ -0,0 +1,121 @@
+//! Types that describe how the machine starts up after a reset (part of the
+//! [`Control` trait](crate::control::Control); see
+//! [`Control::reset_with`](crate::control::Control::reset_with)).
+
+use lc3_isa::{Addr, Word, MEM_MAPPED_START_ADDR, USER_PROGRAM_START_ADDR};
+
+use core::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+/// Where execution starts after a reset.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BootEntry {
+    /// Fetch the starting address from the reset vector
+    /// ([`RESET_VECTOR_ADDR`](lc3_isa::RESET_VECTOR_ADDR)).
+    ///
+    /// Memory images that don't set the reset vector (i.e. where it's 0)
+    /// start at [`OS_START_ADDR`](lc3_isa::OS_START_ADDR).
+    ResetVector,
+    /// Start at the given address, regardless of what's in the reset vector.
+    Addr(Addr),
+}
+
+/// The state the machine starts in after a reset.
+///
+/// All the registers other than R6 start out as 0. R6 gets the stack pointer
+/// for the mode the [PSR](BootConfig::psr) puts the machine in and the other
+/// stack pointer goes in the backup stack pointer register.
+///
+/// The [default](BootConfig::os) boots into the OS.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BootConfig {
+    pub entry: BootEntry,
+    /// The starting processor status register: the privilege mode (bit 15),
+    /// priority (bits 10-8), and condition codes (bits 2-0).
+    pub psr: Word,
+    /// The starting supervisor stack pointer.
+    pub ssp: Word,
+    /// The starting user stack pointer.
+    pub usp: Word,
+}
+
+impl BootConfig {
+    /// Supervisor mode, priority 7, and the Z condition code.
+    pub const OS_PSR: Word = 0x0702;
+    /// User mode, priority 0, and the Z condition code.
+    pub const USER_PSR: Word = 0x8002;
+
+    /// Starts at the reset vector in supervisor mode at the highest priority
+    /// with both stack pointers zeroed; the OS sets up its own stack.
+    pub const fn os() -> Self {
+        Self {
+            entry: BootEntry::ResetVector,
+            psr: Self::OS_PSR,
+            ssp: 0,
+            usp: 0,
+        }
+    }
+
+    /// Skips the OS and starts the user program at `addr` directly, in user
+    /// mode at priority 0.
+    ///
+    /// The user stack starts at the top of user space (just below the memory
+    /// mapped region) and the supervisor stack starts just below user space.
+    /// Note that without an OS there's nothing in the trap and interrupt vector
+    /// tables unless the program puts something there and that programs that
+    /// start below user space will immediately get an access control
+    /// violation.
+    pub const fn user_program(addr: Addr) -> Self {
+        Self {
+            entry: BootEntry::Addr(addr),
+            psr: Self::USER_PSR,
+            ssp: USER_PROGRAM_START_ADDR,
+            usp: MEM_MAPPED_START_ADDR,
+        }
+    }
+
+    pub fn in_user_mode(&self) -> bool {
+        self.psr & 0x8000 != 0
+    }
+}
+
+impl Default for BootConfig {
+    fn default() -> Self {
+        Self::os()
+    }
+}
+
+/// What a reset does to memory.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResetKind {
+    /// Memory goes back to the image it was loaded with (this is what
+    /// [`Control::reset`](crate::control::Control::reset) does).
+    Cold,
+    /// Memory is left alone, like pressing a reset button.
+    Warm,
+}
+
+impl Default for ResetKind {
+    fn default() -> Self {
+        ResetKind::Cold
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResetError {
+    /// The simulator can't be reset with a [`ResetKind`] or [`BootConfig`].
+    Unsupported,
+}
+
+impl Display for ResetError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ResetError::*;
+
+        match self {
+            Unsupported => write!(fmt, "This simulator doesn't support configurable resets"),
+        }
+    }
+}
+
+using_std! { impl std::error::Error for ResetError { } }
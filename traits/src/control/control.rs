@@ -8,15 +8,22 @@
 //! TODO!
 
 use crate::error::Error;
-use crate::peripherals::adc::{AdcPinArr, AdcReadError, AdcState};
+use crate::peripherals::adc::{AdcMiscError, AdcPin, AdcPinArr, AdcReadError, AdcSource, AdcState};
 use crate::peripherals::gpio::{GpioPinArr, GpioReadError, GpioState};
-use crate::peripherals::pwm::{PwmPinArr, PwmState};
-use crate::peripherals::timers::{TimerArr, TimerState, TimerMode};
+use crate::peripherals::pwm::{PwmConfig, PwmPinArr, PwmState};
+use crate::peripherals::timers::{TimerArr, TimerStatus, TimerMode};
+use crate::peripherals::display::{Color, DISPLAY_PIXELS_PER_MESSAGE};
+use crate::peripherals::disk::{DiskError, SectorIdx, DISK_WORDS_PER_MESSAGE};
+use crate::peripherals::rng::RngError;
+use crate::peripherals::watchdog::WatchdogError;
+use super::boot::{BootConfig, ResetKind, ResetError};
+use super::mpu::{MpuRegion, MpuError};
 use super::{Capabilities, DeviceInfo, ProgramMetadata, Identifier};
 use super::UnifiedRange;
 use super::load::{
     PageIndex, PageWriteStart, StartPageWriteError, PageChunkError,
-    FinishPageWriteError, LoadApiSession, Offset, CHUNK_SIZE_IN_WORDS
+    FinishPageWriteError, LoadApiSession, Offset, CHUNK_SIZE_IN_WORDS,
+    CompressedChunk, PAGE_HASHES_PER_MESSAGE,
 };
 
 use lc3_isa::{Addr, Reg, Word, PSR};
@@ -65,6 +72,8 @@ pub enum Event {
     Error { err: Error },
     Interrupted, // If we get paused or stepped, this is returned. (TODO: we currently only return this if we're paused!! not sure if stopping on a step is reasonable behavior)
     Halted,
+    SoftwareBreak { addr: Addr }, // The program asked to be paused (i.e. with the MCR's debug break bit); `addr` is where it stopped.
+    BreakInstruction { addr: Addr, code: u8 }, // A `BRK` instruction was reached (see `Control::set_break_instructions_enabled`); `addr` is where it is.
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -163,6 +172,45 @@ pub trait Control {
         page: LoadApiSession<PageIndex>,
     ) -> Result<(), FinishPageWriteError>;
 
+    /// The largest [compressed chunk](crate::control::load::compression) (in
+    /// bytes) this implementation will accept.
+    ///
+    /// 0 (the default) means compressed chunks aren't supported at all.
+    fn get_max_compressed_chunk_size(&self) -> u8 {
+        0
+    }
+
+    /// Like [`send_page_chunk`](send) but with a [compressed
+    /// chunk](crate::control::load::compression) that starts at the given
+    /// offset.
+    ///
+    /// The offset for this comes from [`with_compressed_chunk_offset`][wco]
+    /// instead of [`with_offset`][wo].
+    ///
+    /// [send]: crate::control::control::Control::send_page_chunk
+    /// [wco]: crate::control::load::LoadApiSession<PageIndex>::with_compressed_chunk_offset
+    /// [wo]: crate::control::load::LoadApiSession<PageIndex>::with_offset
+    fn send_compressed_page_chunk(
+        &mut self,
+        _offset: LoadApiSession<Offset>,
+        _chunk: CompressedChunk,
+    ) -> Result<(), PageChunkError> {
+        Err(PageChunkError::CompressedChunksNotSupported)
+    }
+
+    /// Gets the [hashes](crate::control::load::hash_page) of the
+    /// [`PAGE_HASHES_PER_MESSAGE`] pages starting at `first`, as they were
+    /// last committed (i.e. what the pages go back to on a reset, _not_ what's
+    /// in memory right now).
+    ///
+    /// A hash of 0 means the page has to be sent again no matter what. Pages
+    /// that are memory mapped (or past the end of the address space) get a
+    /// hash of 0, as does every page by default since not every
+    /// implementation can see its committed copy of memory.
+    fn get_page_hashes(&self, _first: PageIndex) -> [u64; PAGE_HASHES_PER_MESSAGE] {
+        [0; PAGE_HASHES_PER_MESSAGE]
+    }
+
     fn set_breakpoint(&mut self, addr: Addr) -> Result<Idx, ()>;
     fn unset_breakpoint(&mut self, idx: Idx) -> Result<(), ()>;
     fn get_breakpoints(&self) -> [Option<Addr>; MAX_BREAKPOINTS];
@@ -255,6 +303,20 @@ pub trait Control {
 
     fn reset(&mut self); // Note: needs to reset memory!
 
+    /// Resets the machine like [`reset`](Control::reset) does but lets the
+    /// caller pick whether memory is reset (see [`ResetKind`]) and how the
+    /// machine starts up (see [`BootConfig`]).
+    ///
+    /// The boot config sticks: later resets (including ones the machine does
+    /// on its own, like when the watchdog expires) use it too.
+    ///
+    /// Implementations that don't support this (the default) return
+    /// [`ResetError::Unsupported`].
+    fn reset_with(&mut self, kind: ResetKind, boot: BootConfig) -> Result<(), ResetError> {
+        let _ = (kind, boot);
+        Err(ResetError::Unsupported)
+    }
+
     // TBD whether this is literally just an error for the last step or if it's the last error encountered.
     // If it's the latter, we should return the PC value when the error was encountered.
     //
@@ -267,12 +329,142 @@ pub trait Control {
     fn get_gpio_readings(&self) -> GpioPinArr<Result<bool, GpioReadError>>;
     fn get_adc_states(&self) -> AdcPinArr<AdcState>;
     fn get_adc_readings(&self) -> AdcPinArr<Result<u8, AdcReadError>>;
+    /// Sets where an [ADC](crate::peripherals::adc) pin's readings come from;
+    /// see [`Adc::set_source`](crate::peripherals::adc::Adc::set_source).
+    ///
+    /// Implementations whose ADC readings aren't generated (the default)
+    /// return an error.
+    fn set_adc_source(&mut self, _pin: AdcPin, _source: AdcSource) -> Result<(), AdcMiscError> {
+        Err(AdcMiscError)
+    }
     fn get_timer_modes(&self) -> TimerArr<TimerMode>;
-    fn get_timer_states(&self) -> TimerArr<TimerState>;
+    /// The state of each [timer](crate::peripherals::timers), along with the
+    /// time left on its count and its captured value.
+    fn get_timer_states(&self) -> TimerArr<TimerStatus>;
     fn get_pwm_states(&self) -> PwmPinArr<PwmState>;
-    fn get_pwm_config(&self) -> PwmPinArr<u8>; // TODO: ditto with using u8 here; probably should be some kind of enum (the conflict is then we're kinda pushing implementors to represent state a certain way.. or at least to have to translate it to our enum).
+    /// The period and duty cycle of each [PWM](crate::peripherals::pwm) pin.
+    fn get_pwm_config(&self) -> PwmPinArr<PwmConfig>;
     fn get_clock(&self) -> Word;
 
+    /// Gets the colors of the [`DISPLAY_PIXELS_PER_MESSAGE`] pixels on the
+    /// [display](crate::peripherals::display) starting at pixel `first`.
+    ///
+    /// Pixels are numbered row by row, starting from the top left corner of
+    /// the display. Pixels past the end of the display are 0, as are all the
+    /// pixels of implementations that don't have a display (the default).
+    ///
+    /// See [`DisplayControl::get_display_frame`] to get the whole display.
+    ///
+    /// [`DisplayControl::get_display_frame`]: crate::control::ext::DisplayControl::get_display_frame
+    fn get_display_pixels(&self, first: u16) -> [Color; DISPLAY_PIXELS_PER_MESSAGE] {
+        let _ = first;
+        [0; DISPLAY_PIXELS_PER_MESSAGE]
+    }
+
+    /// The number of sectors on the [disk](crate::peripherals::disk); 0 if
+    /// there isn't one (the default).
+    fn get_disk_num_sectors(&self) -> SectorIdx {
+        0
+    }
+
+    /// Gets the [`DISK_WORDS_PER_MESSAGE`] words in `sector` on the
+    /// [disk](crate::peripherals::disk) starting at word `first`. Words past
+    /// the end of the sector are 0.
+    ///
+    /// This is for inspecting the disk image and doesn't touch the disk
+    /// registers or raise the disk's interrupt. Implementations that don't
+    /// have a disk (the default) return [`DiskError::NoDisk`].
+    ///
+    /// See [`DiskControl::get_disk_sector`] to get a whole sector.
+    ///
+    /// [`DiskControl::get_disk_sector`]: crate::control::ext::DiskControl::get_disk_sector
+    fn get_disk_words(&self, sector: SectorIdx, first: u16) -> Result<[Word; DISK_WORDS_PER_MESSAGE], DiskError> {
+        let _ = (sector, first);
+        Err(DiskError::NoDisk)
+    }
+
+    /// Seeds the [random number generator](crate::peripherals::rng) so that
+    /// the numbers it produces from here on are reproducible; see
+    /// [`Rng::set_seed`](crate::peripherals::rng::Rng::set_seed).
+    ///
+    /// Implementations that don't have a random number generator (the
+    /// default) return [`RngError::NoRng`].
+    fn set_rng_seed(&mut self, seed: u64) -> Result<(), RngError> {
+        let _ = seed;
+        Err(RngError::NoRng)
+    }
+
+    /// Stops (or restarts) the [watchdog](crate::peripherals::watchdog) so
+    /// that a program that's paused or being stepped through doesn't get
+    /// reset out from under the debugger; see
+    /// [`Watchdog::set_disabled_by_debugger`](crate::peripherals::watchdog::Watchdog::set_disabled_by_debugger).
+    ///
+    /// Implementations that don't have a watchdog (the default) return
+    /// [`WatchdogError::NoWatchdog`].
+    fn set_watchdog_disabled(&mut self, disabled: bool) -> Result<(), WatchdogError> {
+        let _ = disabled;
+        Err(WatchdogError::NoWatchdog)
+    }
+
+    /// Turns [`BRK`](lc3_isa::Instruction::Brk) instructions into software
+    /// breakpoints: when execution reaches one, the machine pauses with a
+    /// [`BreakInstruction`](Event::BreakInstruction) event instead of raising
+    /// the illegal opcode exception.
+    ///
+    /// Unlike [`set_breakpoint`](Control::set_breakpoint) there's no limit on
+    /// how many of these can be set; debuggers can swap a `BRK` in for the
+    /// instruction at an address (with [`write_word`](Control::write_word))
+    /// and swap the original back in to continue past it. A `BRK` that's left
+    /// in place is hit again every time execution continues.
+    ///
+    /// Implementations that don't support this (the default) return an error.
+    fn set_break_instructions_enabled(&mut self, enabled: bool) -> Result<(), ()> {
+        let _ = enabled;
+        Err(())
+    }
+
+    /// The number of cycles the machine has run for since it was last reset,
+    /// according to the implementation's timing model.
+    ///
+    /// Implementations that don't model timing (the default, or simulators
+    /// that haven't been given a timing model) return an error.
+    fn get_cycle_count(&self) -> Result<u64, ()> {
+        Err(())
+    }
+
+    /// Reports reads of registers and memory that the program hasn't set yet
+    /// (i.e. a variable that was never given a value) as
+    /// [`Error::UninitializedRead`](crate::error::Error::UninitializedRead)s
+    /// which, like other errors, pause the machine.
+    ///
+    /// What counts as set is up to the implementation.
+    ///
+    /// Implementations that don't support this (the default) return an error.
+    fn set_uninitialized_read_detection(&mut self, enabled: bool) -> Result<(), ()> {
+        let _ = enabled;
+        Err(())
+    }
+
+    /// Sets (or, with `None`, clears) region `idx` of the memory protection
+    /// table (see [`mpu`](crate::control::mpu)). Programs running in
+    /// supervisor mode can change the table too.
+    ///
+    /// Implementations that don't have a memory protection table (the
+    /// default) return [`MpuError::NoMpu`].
+    fn set_mpu_region(&mut self, idx: Idx, region: Option<MpuRegion>) -> Result<(), MpuError> {
+        let _ = (idx, region);
+        Err(MpuError::NoMpu)
+    }
+
+    /// Region `idx` of the memory protection table, if it's set.
+    ///
+    /// Implementations that don't have a memory protection table (the
+    /// default) return [`MpuError::NoMpu`].
+    fn get_mpu_region(&self, idx: Idx) -> Result<Option<MpuRegion>, MpuError> {
+        let _ = idx;
+        Err(MpuError::NoMpu)
+    }
+
     // So with some of these functions that are basically straight wrappers over their Memory/Peripheral trait counterparts,
     // we have a bit of a choice. We can make Control a super trait of those traits so that we can have default impls of said
     // functions or we can make the implementor of Control manually wrap those functions.
@@ -3,6 +3,8 @@
 //! [`Control`]: super::Control
 
 use super::Control;
+use crate::peripherals::display::{Color, DISPLAY_PIXELS, DISPLAY_PIXELS_PER_MESSAGE};
+use crate::peripherals::disk::{DiskError, Sector, SectorIdx, DISK_WORDS_PER_MESSAGE};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum DepthBreakpoint {
@@ -39,3 +41,31 @@ pub trait StepControl: Control {
 }
 
 impl<C: Control + ?Sized> StepControl for C { }
+
+pub trait DisplayControl: Control {
+    /// Reads out the entire display, row by row (see
+    /// [`get_display_pixels`](Control::get_display_pixels)).
+    fn get_display_frame(&self, frame: &mut [Color; DISPLAY_PIXELS]) {
+        for (idx, chunk) in frame.chunks_mut(DISPLAY_PIXELS_PER_MESSAGE).enumerate() {
+            let first = idx * DISPLAY_PIXELS_PER_MESSAGE;
+            chunk.copy_from_slice(&self.get_display_pixels(first as u16)[..chunk.len()]);
+        }
+    }
+}
+
+impl<C: Control + ?Sized> DisplayControl for C { }
+
+pub trait DiskControl: Control {
+    /// Reads out an entire sector of the disk (see
+    /// [`get_disk_words`](Control::get_disk_words)).
+    fn get_disk_sector(&self, sector: SectorIdx, buf: &mut Sector) -> Result<(), DiskError> {
+        for (idx, chunk) in buf.chunks_mut(DISK_WORDS_PER_MESSAGE).enumerate() {
+            let first = idx * DISK_WORDS_PER_MESSAGE;
+            chunk.copy_from_slice(&self.get_disk_words(sector, first as u16)?);
+        }
+
+        Ok(())
+    }
+}
+
+impl<C: Control + ?Sized> DiskControl for C { }
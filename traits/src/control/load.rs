@@ -23,6 +23,8 @@ use core::ops::Range;
 
 use serde::{Deserialize, Serialize};
 
+pub mod compression;
+pub use compression::{CompressedChunk, PageCompressor, ChunkDecodeError, COMPRESSED_CHUNK_CAPACITY, MIN_COMPRESSED_CHUNK_SIZE};
 
 pub type PageIndex = u8;
 pub type PageOffset = u8;
@@ -38,6 +40,10 @@ pub const CHUNK_SIZE_IN_WORDS: PageOffset = 8;
 pub const CHUNKS_IN_A_PAGE: usize = (PAGE_SIZE_IN_WORDS as usize) / (CHUNK_SIZE_IN_WORDS as usize);
 sa::const_assert_eq!(CHUNKS_IN_A_PAGE * (CHUNK_SIZE_IN_WORDS as usize), (PAGE_SIZE_IN_WORDS as usize));
 
+// The number of page hashes `Control::get_page_hashes` returns at once. Pages
+// past the end of the (non mem-mapped) address space get a hash of 0.
+pub const PAGE_HASHES_PER_MESSAGE: usize = 4;
+
 // TODO: Ideally this would take a reference to an array and not a slice, but
 // alas; since we can't easily go from, for example, a slice of a memory dump
 // to [Word; PAGE_SIZE_IN_WORDS as usize], we're just taking a size instead.
@@ -86,6 +92,8 @@ pub enum PageChunkError {
     NoCurrentSession,
     WrongPage { expected_page: PageIndex, received_address: Addr, },
     ChunkCrossesPageBoundary { page: PageIndex, received_address: Addr, },
+    MalformedChunk { page: PageIndex, received_address: Addr, }, // Compressed chunks only.
+    CompressedChunksNotSupported,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -237,6 +245,16 @@ impl LoadApiSession<PageIndex> {
             Err(PageChunkError::WrongPage { expected_page: self.0, received_address: addr })
         }
     }
+
+    // Compressed chunks don't have a fixed size so we can only check that
+    // they _start_ in the right page; the device checks that they end there.
+    pub fn with_compressed_chunk_offset(&self, addr: Addr) -> Result<LoadApiSession<Offset>, PageChunkError> {
+        if addr.page_idx() == self.0 {
+            Ok(LoadApiSession(Offset(addr.page_offset())))
+        } else {
+            Err(PageChunkError::WrongPage { expected_page: self.0, received_address: addr })
+        }
+    }
 }
 
 mod private {
@@ -503,7 +521,9 @@ pub fn load_memory_dump<C: Control + ?Sized, P: LoadMemoryProgress>(sim: &mut C,
                         Ok(()) => { },
                         Err(PageChunkError::ChunkCrossesPageBoundary { .. }) |
                         Err(PageChunkError::NoCurrentSession) |
-                        Err(PageChunkError::WrongPage { .. }) => unreachable!(),
+                        Err(PageChunkError::WrongPage { .. }) |
+                        Err(PageChunkError::MalformedChunk { .. }) |
+                        Err(PageChunkError::CompressedChunksNotSupported) => unreachable!(),
                     }
                 }
             }
@@ -528,6 +548,123 @@ pub fn load_memory_dump<C: Control + ?Sized, P: LoadMemoryProgress>(sim: &mut C,
     Ok(())
 }
 
+/// Like [`load_memory_dump`] but sends pages as [compressed
+/// chunks](compression) and, instead of needing a `previous` dump to figure
+/// out which pages have changed, asks the device for the hashes of the pages it
+/// last committed (see [`Control::get_page_hashes`]).
+///
+/// If a `previous` dump _is_ given, pages that the device still has the
+/// `previous` version of are word-diffed against it so that only the words
+/// that changed are sent.
+///
+/// Devices that don't support compressed chunks (i.e. ones that report a
+/// [max chunk size](Control::get_max_compressed_chunk_size) that's too small)
+/// get [`load_memory_dump`] instead.
+pub fn load_memory_dump_compressed<C: Control + ?Sized, P: LoadMemoryProgress>(sim: &mut C, dump: &MemoryDump, previous: Option<&MemoryDump>, progress: Option<&P>) -> Result<(), LoadMemoryDumpError> {
+    // See `load_memory_dump` for why this is okay.
+
+    macro_rules! p {
+        ($p:ident -> $($all:tt)*) => { if let Some($p) = progress { $($all)* }};
+    }
+
+    // Agree on a chunk size:
+    let max_chunk_size = (sim.get_max_compressed_chunk_size() as usize).min(COMPRESSED_CHUNK_CAPACITY);
+    if max_chunk_size < MIN_COMPRESSED_CHUNK_SIZE {
+        return load_memory_dump(sim, dump, previous, progress);
+    }
+
+    if (MEM_MAPPED_START_ADDR..=ADDR_MAX_VAL).map(|addr| dump[addr as usize]).any(|v| v != 0) {
+        return Err(LoadMemoryDumpError::MemMappedPagesNotEmpty)
+    }
+
+    const NUM_WRITABLE_PAGES: usize = NUM_PAGES - NUM_MEM_MAPPED_PAGES;
+    // (no `TryFrom<&[T]>` for arrays this big)
+    let page_of = |d: &MemoryDump, p_idx: usize| {
+        let mut page = [0; PAGE_SIZE_IN_WORDS as usize];
+        page.copy_from_slice(&d[Index(p_idx as PageIndex).as_index_range()]);
+        page
+    };
+
+    // Ask the device what it's got:
+    let mut device_hashes = [0u64; NUM_WRITABLE_PAGES];
+    for first in (0..NUM_WRITABLE_PAGES).step_by(PAGE_HASHES_PER_MESSAGE) {
+        sim.get_page_hashes(first as PageIndex)
+            .iter()
+            .zip(device_hashes[first..].iter_mut())
+            .for_each(|(theirs, ours)| *ours = *theirs);
+    }
+
+    let hashes_match = |d: &MemoryDump, p_idx: usize| hash_page(&page_of(d, p_idx)) == device_hashes[p_idx];
+
+    let mut write_or_not = [false; NUM_WRITABLE_PAGES];
+    write_or_not.iter_mut().enumerate().for_each(|(p_idx, w)| *w = !hashes_match(dump, p_idx));
+
+    p!(p -> p.total_number_of_pages_to_send(write_or_not.iter().filter(|w| **w).count()));
+
+    for (p_idx, _) in write_or_not.iter().enumerate().filter(|(_, to_write)| **to_write) {
+        let page = page_of(dump, p_idx);
+        let checksum = hash_page(&page);
+
+        // Only diff against the previous dump if the device actually has it:
+        let mut base = previous
+            .filter(|prev| hashes_match(prev, p_idx))
+            .map(|prev| page_of(prev, p_idx));
+
+        loop {
+            let token = {
+                // (this is safe; see the blurb at the top of `load_memory_dump`)
+                #[allow(unsafe_code)]
+                let page = unsafe { LoadApiSession::new(p_idx as PageIndex) }.unwrap();
+
+                p!(p -> p.page_attempt());
+                match sim.start_page_write(page, checksum) {
+                    Ok(token) => token,
+                    Err(StartPageWriteError::InvalidPage { .. }) => unreachable!(),
+                    Err(StartPageWriteError::UnfinishedSessionExists { unfinished_page }) => {
+                        return Err(LoadMemoryDumpError::ExistingUnfinishedSession { unfinished_page })
+                    }
+                }
+            };
+
+            let mut num_chunks = 0;
+
+            for (offset, chunk) in PageCompressor::new(&page, base.as_ref().map(|b| &b[..]), max_chunk_size) {
+                num_chunks += 1;
+
+                let offset = token.with_compressed_chunk_offset(Index(p_idx as PageIndex).with_offset(offset)).unwrap();
+
+                p!(p -> p.chunk_attempt());
+                match sim.send_compressed_page_chunk(offset, chunk) {
+                    Ok(()) => { },
+                    Err(PageChunkError::ChunkCrossesPageBoundary { .. }) |
+                    Err(PageChunkError::NoCurrentSession) |
+                    Err(PageChunkError::WrongPage { .. }) |
+                    Err(PageChunkError::MalformedChunk { .. }) |
+                    Err(PageChunkError::CompressedChunksNotSupported) => unreachable!(),
+                }
+            }
+
+            match sim.finish_page_write(token) {
+                Ok(()) => { p!(p -> p.page_success(num_chunks)); break; }
+                Err(FinishPageWriteError::NoCurrentSession) |
+                Err(FinishPageWriteError::SessionMismatch { .. }) => unreachable!(),
+                Err(FinishPageWriteError::ChecksumMismatch { page, given_checksum, computed_checksum }) => {
+                    assert_eq!(page, p_idx as u8);
+                    assert_eq!(checksum, given_checksum);
+                    assert_ne!(checksum, computed_checksum);
+
+                    // If the device's memory changed out from under us, the
+                    // diff is no good; try again without it:
+                    base = None;
+                }
+            }
+        }
+    }
+
+    sim.reset();
+    Ok(())
+}
+
 pub fn load_whole_memory_dump<C: Control + ?Sized, P: LoadMemoryProgress>(sim: &mut C, dump: &MemoryDump, progress: Option<&P>) -> Result<(), LoadMemoryDumpError> {
     load_memory_dump(sim, dump, None, progress)
 }
@@ -0,0 +1,413 @@
+//! Compressed page chunks for the load API.
+//!
+//! [`send_page_chunk`](crate::control::Control::send_page_chunk) sends pages
+//! in fixed size, uncompressed chunks of [`CHUNK_SIZE_IN_WORDS`] which works
+//! but is slow over links like UART where every byte counts. Most pages in a
+//! typical program are mostly zeros (or, when reloading a program that was
+//! changed a little, mostly the same as what's already on the device) so we
+//! can do a lot better.
+//!
+//! A [`CompressedChunk`] is a short sequence of _ops_ that describe a run of
+//! words in a page, starting at some offset. Each op starts with a header
+//! byte: the top two bits are the kind of op and the bottom six are the
+//! number of words the op covers, minus one (so ops cover 1 to 64 words):
+//!
+//! | kind          | bits | followed by       | words are set to               |
+//! |---------------|------|-------------------|--------------------------------|
+//! | skip          | `00` | nothing           | what the device committed last |
+//! | zeros         | `01` | nothing           | zero                           |
+//! | repeat        | `10` | one word          | the word                       |
+//! | literal       | `11` | `count` words     | the words, in order            |
+//!
+//! Words are big endian.
+//!
+//! Skips are what makes word-diffing work: if the host knows what the device
+//! last committed to a page (i.e. what the page goes back to on a reset) it
+//! only has to send the words that are different.
+//!
+//! Like with regular chunks, words in a page that aren't covered by any chunk
+//! are zero once the page is written.
+//!
+//! The largest chunk a device will accept (in bytes) is negotiated with
+//! [`get_max_compressed_chunk_size`]; it's never larger than
+//! [`COMPRESSED_CHUNK_CAPACITY`].
+//!
+//! [`CHUNK_SIZE_IN_WORDS`]: super::CHUNK_SIZE_IN_WORDS
+//! [`get_max_compressed_chunk_size`]: crate::control::Control::get_max_compressed_chunk_size
+
+use super::{PageOffset, PAGE_SIZE_IN_WORDS};
+
+use lc3_isa::Word;
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+
+use core::fmt::{self, Debug};
+
+/// The maximum number of bytes in a [`CompressedChunk`].
+///
+/// This is picked so that the message carrying a chunk isn't any larger than
+/// the largest message we already have.
+pub const COMPRESSED_CHUNK_CAPACITY: usize = 32;
+
+/// The smallest useful chunk size: a literal op with one word in it.
+pub const MIN_COMPRESSED_CHUNK_SIZE: usize = 3;
+
+const MAX_RUN: usize = 64;
+
+const SKIP: u8 = 0b00;
+const ZEROS: u8 = 0b01;
+const REPEAT: u8 = 0b10;
+const LITERAL: u8 = 0b11;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ChunkDecodeError {
+    /// An op needs more bytes than are left in the chunk.
+    Malformed,
+    /// An op runs past the end of the page.
+    CrossesPageBoundary,
+}
+
+/// See the [module docs](self).
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CompressedChunk {
+    len: u8,
+    data: [u8; COMPRESSED_CHUNK_CAPACITY],
+}
+
+impl Default for CompressedChunk {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for CompressedChunk {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_tuple("CompressedChunk").field(&self.as_bytes()).finish()
+    }
+}
+
+impl CompressedChunk {
+    pub const fn new() -> Self {
+        Self { len: 0, data: [0; COMPRESSED_CHUNK_CAPACITY] }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ()> {
+        let mut chunk = Self::new();
+        chunk.push(bytes)?;
+
+        Ok(chunk)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data[..(self.len as usize)]
+    }
+
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn push(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        let start = self.len as usize;
+        let end = start + bytes.len();
+
+        if end > COMPRESSED_CHUNK_CAPACITY {
+            return Err(());
+        }
+
+        self.data[start..end].copy_from_slice(bytes);
+        self.len = end as u8;
+
+        Ok(())
+    }
+
+    /// Writes the words this chunk describes into `page`, starting at
+    /// `offset`.
+    ///
+    /// Words covered by skips are filled in with `current`, which is given the
+    /// offset of the word in the page.
+    ///
+    /// Returns the number of words written. `page` may have been partially
+    /// written to if this fails.
+    pub fn decompress(
+        &self,
+        offset: PageOffset,
+        page: &mut [Word; PAGE_SIZE_IN_WORDS as usize],
+        mut current: impl FnMut(PageOffset) -> Word,
+    ) -> Result<usize, ChunkDecodeError> {
+        use ChunkDecodeError::*;
+
+        let bytes = self.as_bytes();
+        let mut idx = 0;
+        let mut pos = offset as usize;
+
+        let word = |idx: &mut usize| -> Result<Word, ChunkDecodeError> {
+            match bytes.get(*idx..(*idx + 2)) {
+                Some(&[hi, lo]) => { *idx += 2; Ok(((hi as Word) << 8) | (lo as Word)) },
+                _ => Err(Malformed),
+            }
+        };
+
+        while idx < bytes.len() {
+            let header = bytes[idx];
+            idx += 1;
+
+            let count = ((header & 0b11_1111) as usize) + 1;
+            let range = pos..(pos + count);
+            if range.end > page.len() { return Err(CrossesPageBoundary); }
+
+            match header >> 6 {
+                SKIP => range.for_each(|p| page[p] = current(p as PageOffset)),
+                ZEROS => range.for_each(|p| page[p] = 0),
+                REPEAT => { let w = word(&mut idx)?; range.for_each(|p| page[p] = w) },
+                LITERAL => { for p in range { page[p] = word(&mut idx)?; } },
+                _ => unreachable!(),
+            }
+
+            pos += count;
+        }
+
+        Ok(pos - (offset as usize))
+    }
+}
+
+impl Serialize for CompressedChunk {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // As bytes so that only the used part of the chunk is sent (with
+        // encodings that support this).
+        serializer.serialize_bytes(self.as_bytes())
+    }
+}
+
+impl<'de> Deserialize<'de> for CompressedChunk {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ChunkVisitor;
+
+        impl<'de> Visitor<'de> for ChunkVisitor {
+            type Value = CompressedChunk;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(fmt, "at most {} bytes", COMPRESSED_CHUNK_CAPACITY)
+            }
+
+            fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<CompressedChunk, E> {
+                CompressedChunk::from_bytes(bytes)
+                    .map_err(|()| E::invalid_length(bytes.len(), &self))
+            }
+
+            // For encodings (i.e. JSON) that represent bytes as sequences.
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<CompressedChunk, A::Error> {
+                let mut chunk = CompressedChunk::new();
+
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    chunk.push(&[byte])
+                        .map_err(|()| de::Error::invalid_length(chunk.len() + 1, &self))?;
+                }
+
+                Ok(chunk)
+            }
+        }
+
+        deserializer.deserialize_bytes(ChunkVisitor)
+    }
+}
+
+/// Splits a page into [`CompressedChunk`]s.
+///
+/// If a `base` is given (what the device currently has in this page), words
+/// that match it are skipped instead of being sent.
+///
+/// Produces `(offset, chunk)` pairs.
+#[derive(Debug, Clone)]
+pub struct PageCompressor<'p> {
+    page: &'p [Word],
+    base: Option<&'p [Word]>,
+    max_chunk_size: usize,
+    pos: usize,
+}
+
+impl<'p> PageCompressor<'p> {
+    /// Panics if the page (or base) isn't [`PAGE_SIZE_IN_WORDS`] words long
+    /// or if `max_chunk_size` is smaller than [`MIN_COMPRESSED_CHUNK_SIZE`].
+    pub fn new(page: &'p [Word], base: Option<&'p [Word]>, max_chunk_size: usize) -> Self {
+        assert_eq!(page.len(), PAGE_SIZE_IN_WORDS as usize);
+        if let Some(base) = base { assert_eq!(base.len(), PAGE_SIZE_IN_WORDS as usize); }
+        assert!(max_chunk_size >= MIN_COMPRESSED_CHUNK_SIZE);
+
+        Self {
+            page,
+            base,
+            max_chunk_size: max_chunk_size.min(COMPRESSED_CHUNK_CAPACITY),
+            pos: 0,
+        }
+    }
+
+    fn is_zero(&self, pos: usize) -> bool {
+        self.page[pos] == 0
+    }
+
+    fn is_unchanged(&self, pos: usize) -> bool {
+        self.base.map(|b| b[pos] == self.page[pos]).unwrap_or(false)
+    }
+
+    fn run_length(&self, pos: usize, pred: impl Fn(usize) -> bool) -> usize {
+        (pos..self.page.len()).take(MAX_RUN).take_while(|p| pred(*p)).count()
+    }
+
+    fn repeats(&self, pos: usize) -> usize {
+        let w = self.page[pos];
+        self.run_length(pos, |p| self.page[p] == w && !self.is_unchanged(p))
+    }
+
+    /// Emits one op at `self.pos` into `chunk`, if it fits. Returns false if
+    /// the chunk is full.
+    fn op(&mut self, chunk: &mut CompressedChunk) -> bool {
+        let room = self.max_chunk_size - chunk.len();
+        let header = |kind: u8, count: usize| (kind << 6) | ((count - 1) as u8);
+
+        // Zeros come first since they're free at the end of a page or chunk.
+        let zeros = self.run_length(self.pos, |p| self.is_zero(p));
+        if zeros > 0 {
+            if self.page[self.pos..].iter().all(|w| *w == 0) {
+                self.pos = self.page.len();
+                return true;
+            }
+
+            if room < 1 { return false; }
+            chunk.push(&[header(ZEROS, zeros)]).unwrap();
+            self.pos += zeros;
+            return true;
+        }
+
+        let unchanged = self.run_length(self.pos, |p| self.is_unchanged(p));
+        if unchanged > 0 {
+            if room < 1 { return false; }
+            chunk.push(&[header(SKIP, unchanged)]).unwrap();
+            self.pos += unchanged;
+            return true;
+        }
+
+        let repeats = self.repeats(self.pos);
+        if repeats >= 3 {
+            if room < 3 { return false; }
+            let [hi, lo] = self.page[self.pos].to_be_bytes();
+            chunk.push(&[header(REPEAT, repeats), hi, lo]).unwrap();
+            self.pos += repeats;
+            return true;
+        }
+
+        // Otherwise, a literal that goes until the next thing that's better
+        // represented some other way (or until we run out of room):
+        if room < 3 { return false; }
+        let max_words = ((room - 1) / 2).min(MAX_RUN);
+        let words = (self.pos..self.page.len())
+            .take(max_words)
+            .take_while(|p| *p == self.pos || !(self.is_zero(*p) || self.is_unchanged(*p) || self.repeats(*p) >= 3))
+            .count();
+
+        chunk.push(&[header(LITERAL, words)]).unwrap();
+        for w in &self.page[self.pos..(self.pos + words)] {
+            chunk.push(&w.to_be_bytes()).unwrap();
+        }
+
+        self.pos += words;
+        true
+    }
+}
+
+impl<'p> Iterator for PageCompressor<'p> {
+    type Item = (PageOffset, CompressedChunk);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Zeros at the start of a chunk don't need to be sent:
+        while self.pos < self.page.len() && self.is_zero(self.pos) {
+            self.pos += 1;
+        }
+
+        if self.pos >= self.page.len() {
+            return None;
+        }
+
+        let offset = self.pos as PageOffset;
+        let mut chunk = CompressedChunk::new();
+
+        while self.pos < self.page.len() && self.op(&mut chunk) { }
+
+        Some((offset, chunk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: usize = PAGE_SIZE_IN_WORDS as usize;
+
+    fn round_trip(page: &[Word; PAGE], base: Option<&[Word; PAGE]>, max: usize) -> usize {
+        let mut out = [0; PAGE];
+        let mut chunks = 0;
+
+        for (offset, chunk) in PageCompressor::new(page, base.map(|b| &b[..]), max) {
+            assert!(chunk.len() <= max);
+            chunk.decompress(offset, &mut out, |o| base.unwrap()[o as usize]).unwrap();
+            chunks += 1;
+        }
+
+        assert_eq!(&page[..], &out[..]);
+        chunks
+    }
+
+    #[test]
+    fn empty_page() {
+        assert_eq!(0, round_trip(&[0; PAGE], None, COMPRESSED_CHUNK_CAPACITY));
+    }
+
+    #[test]
+    fn runs() {
+        let mut page = [0; PAGE];
+        page[3..200].iter_mut().for_each(|w| *w = 0x1234);
+        page[250] = 7;
+
+        assert_eq!(1, round_trip(&page, None, COMPRESSED_CHUNK_CAPACITY));
+    }
+
+    #[test]
+    fn literals() {
+        let mut page = [0; PAGE];
+        page.iter_mut().enumerate().for_each(|(i, w)| *w = (i as Word) * 3 + 1);
+
+        for max in [MIN_COMPRESSED_CHUNK_SIZE, 4, 17, COMPRESSED_CHUNK_CAPACITY].iter() {
+            round_trip(&page, None, *max);
+        }
+    }
+
+    #[test]
+    fn diff() {
+        let mut base = [0; PAGE];
+        base.iter_mut().enumerate().for_each(|(i, w)| *w = (i as Word) ^ 0xA5A5);
+
+        let mut page = base;
+        page[10] = 0;
+        page[100] = 1;
+        page[101] = 2;
+
+        assert_eq!(1, round_trip(&page, Some(&base), COMPRESSED_CHUNK_CAPACITY));
+    }
+
+    #[test]
+    fn bad_chunks() {
+        let mut page = [0; PAGE];
+
+        let truncated = CompressedChunk::from_bytes(&[(LITERAL << 6) | 1, 0, 1, 0]).unwrap();
+        assert_eq!(Err(ChunkDecodeError::Malformed), truncated.decompress(0, &mut page, |_| 0));
+
+        let too_long = CompressedChunk::from_bytes(&[(ZEROS << 6) | 63]).unwrap();
+        assert_eq!(Err(ChunkDecodeError::CrossesPageBoundary), too_long.decompress(200, &mut page, |_| 0));
+
+        assert!(CompressedChunk::from_bytes(&[0; COMPRESSED_CHUNK_CAPACITY + 1]).is_err());
+    }
+}
@@ -11,7 +11,13 @@ pub mod control;
 pub use control::{Control, Event, State, ProcessorMode, Idx};
 
 pub mod ext;
-pub use ext::StepControl;
+pub use ext::{StepControl, DisplayControl, DiskControl};
+
+pub mod boot;
+pub use boot::{BootConfig, BootEntry, ResetKind, ResetError};
+
+pub mod mpu;
+pub use mpu::{MpuRegion, MpuError, Permissions, NUM_MPU_REGIONS};
 
 pub mod load;
 pub use load::{load_memory_dump, Progress};
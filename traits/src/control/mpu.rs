@@ -0,0 +1,112 @@
+//! Types that describe a memory protection table: regions of memory with read,
+//! write, and execute permissions for each privilege level (part of the
+//! [`Control` trait](crate::control::Control); see
+//! [`Control::set_mpu_region`](crate::control::Control::set_mpu_region)).
+//!
+//! Without any regions, the usual LC-3 rule applies: user mode can only touch
+//! user space ([`USER_PROGRAM_START_ADDR`] up to [`MEM_MAPPED_START_ADDR`]) and
+//! supervisor mode can touch everything. Addresses that fall in a region get
+//! the region's permissions instead (for the highest numbered region, if
+//! there's more than one). How accesses that aren't permitted are reported is
+//! up to the implementation, but the intent is for fetches from data (memory
+//! that can be read but not executed) and writes to code (memory that can be
+//! executed but not written) to raise their own exceptions
+//! ([`EXECUTE_FROM_DATA_EXCEPTION_VECTOR`] and
+//! [`WRITE_TO_CODE_EXCEPTION_VECTOR`]) and for everything else to be an
+//! access control violation.
+//!
+//! [`USER_PROGRAM_START_ADDR`]: lc3_isa::USER_PROGRAM_START_ADDR
+//! [`MEM_MAPPED_START_ADDR`]: lc3_isa::MEM_MAPPED_START_ADDR
+//! [`EXECUTE_FROM_DATA_EXCEPTION_VECTOR`]: lc3_isa::EXECUTE_FROM_DATA_EXCEPTION_VECTOR
+//! [`WRITE_TO_CODE_EXCEPTION_VECTOR`]: lc3_isa::WRITE_TO_CODE_EXCEPTION_VECTOR
+
+use lc3_isa::Addr;
+
+use core::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+/// The number of regions in the table.
+pub const NUM_MPU_REGIONS: usize = 8;
+
+/// What one privilege level is allowed to do with a region.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Permissions {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+}
+
+impl Permissions {
+    pub const NONE: Self = Self::new(false, false, false);
+    /// Data that can't be changed.
+    pub const READ_ONLY: Self = Self::new(true, false, false);
+    /// Data.
+    pub const READ_WRITE: Self = Self::new(true, true, false);
+    /// Code.
+    pub const READ_EXECUTE: Self = Self::new(true, false, true);
+    pub const ALL: Self = Self::new(true, true, true);
+
+    pub const fn new(read: bool, write: bool, execute: bool) -> Self {
+        Self { read, write, execute }
+    }
+
+    /// Bit 0 is read, bit 1 is write, and bit 2 is execute; the other bits
+    /// are ignored.
+    pub const fn from_bits(bits: u8) -> Self {
+        Self::new(bits & 0b001 != 0, bits & 0b010 != 0, bits & 0b100 != 0)
+    }
+
+    /// The inverse of [`from_bits`](Permissions::from_bits).
+    pub const fn to_bits(self) -> u8 {
+        (self.read as u8) | ((self.write as u8) << 1) | ((self.execute as u8) << 2)
+    }
+}
+
+/// A range of addresses (`start` to `end`, inclusive) and what each privilege
+/// level may do with them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MpuRegion {
+    pub start: Addr,
+    pub end: Addr,
+    pub user: Permissions,
+    pub supervisor: Permissions,
+}
+
+impl MpuRegion {
+    pub const fn new(start: Addr, end: Addr, user: Permissions, supervisor: Permissions) -> Self {
+        Self { start, end, user, supervisor }
+    }
+
+    pub fn contains(&self, addr: Addr) -> bool {
+        (self.start..=self.end).contains(&addr)
+    }
+
+    /// The permissions for user mode if `in_user_mode` is set and for
+    /// supervisor mode otherwise.
+    pub fn permissions(&self, in_user_mode: bool) -> Permissions {
+        if in_user_mode { self.user } else { self.supervisor }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MpuError {
+    /// The simulator doesn't have a memory protection table.
+    NoMpu,
+    /// The region index is past [`NUM_MPU_REGIONS`] or the region ends before
+    /// it starts.
+    InvalidRegion,
+}
+
+impl Display for MpuError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use MpuError::*;
+
+        match self {
+            NoMpu => write!(fmt, "This simulator doesn't have a memory protection table"),
+            InvalidRegion => write!(fmt, "Attempted to set a memory protection region that doesn't exist or that ends before it starts"),
+        }
+    }
+}
+
+using_std! { impl std::error::Error for MpuError { } }
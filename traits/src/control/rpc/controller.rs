@@ -9,27 +9,35 @@ use super::{State, Event, Control, Transport};
 use super::messages::{RequestMessage, ResponseMessage};
 use super::encoding::{Encode, Decode, Transparent};
 use super::futures::{EventFutureSharedStatePorcelain, EventFuture};
+use super::handshake::{HandshakeInfo, HandshakeError};
 use crate::control::control::{
     MAX_BREAKPOINTS, MAX_MEMORY_WATCHPOINTS, MAX_CALL_STACK_DEPTH,
     ProcessorMode, Idx
 };
 use crate::control::load::{
     LoadApiSession, CHUNK_SIZE_IN_WORDS, PageWriteStart, PageIndex, Offset,
-    StartPageWriteError, PageChunkError, FinishPageWriteError
+    StartPageWriteError, PageChunkError, FinishPageWriteError, CompressedChunk,
+    PAGE_HASHES_PER_MESSAGE,
 };
-use crate::control::{ProgramMetadata, DeviceInfo, UnifiedRange};
+use crate::control::{ProgramMetadata, DeviceInfo, UnifiedRange, Capabilities, Version};
+use crate::control::boot::{BootConfig, ResetKind, ResetError};
+use crate::control::mpu::{MpuRegion, MpuError};
 use crate::error::Error as Lc3Error;
 use crate::peripherals::{
-    adc::{AdcPinArr, AdcState, AdcReadError},
+    adc::{AdcMiscError, AdcPin, AdcPinArr, AdcReadError, AdcSource, AdcState},
     gpio::{GpioPinArr, GpioState, GpioReadError},
-    pwm::{PwmPinArr, PwmState},
-    timers::{TimerArr, TimerMode, TimerState},
+    pwm::{PwmConfig, PwmPin, PwmPinArr, PwmState, PWM_PINS},
+    timers::{TimerArr, TimerId, TimerMode, TimerStatus, TIMERS},
+    display::{Color, DISPLAY_PIXELS_PER_MESSAGE},
+    disk::{DiskError, SectorIdx, DISK_WORDS_PER_MESSAGE},
+    rng::RngError,
+    watchdog::WatchdogError,
 };
 
 use lc3_isa::{Reg, Addr, Word};
 
 use core::cell::RefCell;
-use core::fmt::Debug;
+use core::fmt::{self, Debug, Display};
 use core::marker::PhantomData;
 use core::sync::atomic::{AtomicBool, Ordering};
 
@@ -72,6 +80,83 @@ where
     shared_state: &'a S,
     waiting_for_event: AtomicBool, // TODO: no reason for this to be Atomic // Note: it's atomic so we can maintain interior mutability?
     // waiting_for_event: bool,
+    error: RefCell<Option<ControllerError<
+        <RespDec as Decode<Resp>>::Err,
+        <T as Transport<<ReqEnc as Encode<Req>>::Encoded, <RespDec as Decode<Resp>>::Encoded>>::SendErr,
+        <T as Transport<<ReqEnc as Encode<Req>>::Encoded, <RespDec as Decode<Resp>>::Encoded>>::RecvErr,
+    >>>,
+    /// What the device told us about itself during the handshake, if we've
+    /// done one.
+    peer: RefCell<Option<HandshakeInfo>>,
+    /// How many times a request is resent (because of NAKs or decode errors)
+    /// before [`Controller::request`] gives up.
+    max_resends: u8,
+}
+
+/// How many times the [`Controller`] resends a request before giving up, by
+/// default. This matches the default retransmit budget of the reliability
+/// layer in `lc3-device-support`.
+pub const DEFAULT_MAX_RESENDS: u8 = 5;
+
+/// Things that can go wrong when the [`Controller`] makes a request.
+///
+/// Because the [`Control`] trait's functions are infallible, the [`Control`]
+/// impl on [`Controller`] panics when it runs into one of these (with the
+/// error in the panic message). [`Controller::request`] can be used to make
+/// requests without panicking and [`Controller::take_error`] has the last
+/// error that happened in [`Control::tick`] (which does _not_ panic on
+/// transport errors).
+///
+/// Note that with a transport that never gives up (i.e. one that just drops
+/// messages that it can't deliver) the controller will still wait forever
+/// for a response; the [reliability layer] in `lc3-device-support` gives
+/// up after a bounded number of retransmits, which is what turns lost
+/// messages into `Recv` errors here.
+///
+/// [reliability layer]: https://docs.rs/lc3-device-support/latest/lc3_device_support/rpc/reliability/index.html
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControllerError<DecErr, SendErr, RecvErr> {
+    /// The transport failed to send the request.
+    Send(SendErr),
+    /// The transport failed to receive a response.
+    Recv(RecvErr),
+    /// We got a message that we couldn't decode (outside of a request; decode
+    /// errors while waiting on a response cause the request to be resent).
+    Decode(DecErr),
+    /// The device responded with a message that doesn't match the request
+    /// that was made.
+    UnexpectedResponse(ResponseMessage),
+    /// The [handshake](super::handshake) found that we can't talk to the
+    /// device.
+    Handshake(HandshakeError),
+    /// The request needs a newer version of the protocol than the device
+    /// speaks (according to the handshake).
+    Unsupported { required_minor: u8, device: Version },
+    /// The request was resent (because the device NAK'd it or because we
+    /// couldn't decode the response) as many times as we're allowed to (see
+    /// [`Controller::with_max_resends`]) without getting a response.
+    ResendsExhausted { resends: u8 },
+}
+
+impl<DecErr: Debug, SendErr: Debug, RecvErr: Debug> Display for ControllerError<DecErr, SendErr, RecvErr> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ControllerError::*;
+
+        match self {
+            Send(e) => write!(fmt, "Transport error while sending a request: `{:?}`", e),
+            Recv(e) => write!(fmt, "Transport error while waiting for a response: `{:?}`", e),
+            Decode(e) => write!(fmt, "Failed to decode a message from the device: `{:?}`", e),
+            UnexpectedResponse(r) => write!(fmt, "Incorrect response for message! Got: `{:?}`", r),
+            Handshake(e) => write!(fmt, "Handshake failed: {}", e),
+            Unsupported { required_minor, device } => write!(fmt,
+                "The device (protocol version {}.{}.x) doesn't support this request (needs {}.{}.x)",
+                device.major, device.minor, device.major, required_minor,
+            ),
+            ResendsExhausted { resends } => write!(fmt,
+                "Gave up on the request after resending it {} times", resends,
+            ),
+        }
+    }
 }
 
 // TODO: make a builder!
@@ -104,8 +189,27 @@ where
             shared_state,
             waiting_for_event: AtomicBool::new(false),
             // waiting_for_event: false,
+            error: RefCell::new(None),
+            peer: RefCell::new(None),
+            max_resends: DEFAULT_MAX_RESENDS,
         }
     }
+
+    /// Sets how many times a request is resent (because the device couldn't
+    /// decode it or because we couldn't decode the response) before
+    /// [`request`](Self::request) gives up with
+    /// [`ControllerError::ResendsExhausted`].
+    ///
+    /// Defaults to [`DEFAULT_MAX_RESENDS`].
+    pub fn with_max_resends(mut self, max_resends: u8) -> Self {
+        self.max_resends = max_resends;
+        self
+    }
+
+    /// Returns (and clears) the last error the controller ran into, if any.
+    pub fn take_error(&self) -> Option<ControllerError<D::Err, T::SendErr, T::RecvErr>> {
+        self.error.borrow_mut().take()
+    }
 }
 
 // TODO: this is a stopgap; eventually we should have an error variant on the
@@ -171,33 +275,95 @@ where
             Ok(message)
         }
     }
-}
 
+    /// Exchanges [`HandshakeInfo`] with the device and checks that we can talk
+    /// to it. See the [`handshake` module](super::handshake) for details.
+    ///
+    /// This should be called once, right after connecting. It isn't required
+    /// (the device doesn't insist on it) but without it, the controller has no
+    /// way to avoid sending requests the device doesn't understand.
+    ///
+    /// On success, returns what the device told us about itself.
+    pub fn handshake(&self) -> Result<HandshakeInfo, ControllerError<D::Err, T::SendErr, T::RecvErr>> {
+        let ours = HandshakeInfo::current(Capabilities::default());
+
+        match self.request(RequestMessage::Handshake { info: ours.clone() })? {
+            ResponseMessage::Handshake(theirs) => {
+                ours.check(&theirs).map_err(ControllerError::Handshake)?;
+                *self.peer.borrow_mut() = Some(theirs.clone());
+
+                Ok(theirs)
+            },
+            other => Err(ControllerError::UnexpectedResponse(other)),
+        }
+    }
+
+    /// What the device told us about itself during the handshake (if one has
+    /// happened).
+    pub fn peer(&self) -> Option<HandshakeInfo> {
+        self.peer.borrow().clone()
+    }
 
-macro_rules! ctrl {
-    ($s:ident, $req:expr, $resp:pat$(, $ret:expr)?) => {{
-        use RequestMessage::*;
-        use ResponseMessage as R;
-        let m = $req.into();
+    /// Whether the device supports the given request.
+    ///
+    /// If we haven't done a [handshake](Self::handshake), we don't know so we
+    /// assume the best.
+    pub fn peer_supports(&self, message: &RequestMessage) -> bool {
+        self.peer.borrow().as_ref().map(|p| p.supports(message)).unwrap_or(true)
+    }
+
+    /// Sends a request and waits for the response.
+    ///
+    /// Unlike the [`Control`] impl, this doesn't panic if the transport runs
+    /// into trouble; the error is returned instead. The response is _not_
+    /// checked against the request. Requests that the device is known not to
+    /// support (see [`peer_supports`](Self::peer_supports)) are not sent.
+    ///
+    /// Responses to `RunUntilEvent` requests (i.e. events) that show up while
+    /// we're waiting are handled as usual.
+    pub fn request(&self, message: RequestMessage) -> Result<ResponseMessage, ControllerError<D::Err, T::SendErr, T::RecvErr>> {
+        if let Some(peer) = self.peer.borrow().as_ref() {
+            if !peer.supports(&message) {
+                return Err(ControllerError::Unsupported {
+                    required_minor: message.protocol_minor(),
+                    device: peer.protocol.clone(),
+                });
+            }
+        }
+
+        let m = message.into();
 
-        $s.transport.send($s.enc.borrow_mut().encode(&m)).unwrap(); // TODO: don't panic? not sure how we'd realistically deal with any transport errors..
+        self.transport.send(self.enc.borrow_mut().encode(&m))
+            .map_err(ControllerError::Send)?;
+
+        // NAKs and decode errors share one budget:
+        let mut resends = 0;
+        let mut resend = |m: &Req| {
+            if resends >= self.max_resends {
+                return Err(ControllerError::ResendsExhausted { resends });
+            }
+            resends += 1;
+
+            self.transport.send(self.enc.borrow_mut().encode(m))
+                .map_err(ControllerError::Send)
+        };
 
         loop {
-            match Controller::tick($s) {
-                // If we got a message, process it:
-                Ok(m) => {
-                    if let $resp = m {
-                        break $($ret)?
-                    } else {
-                        panic!("Incorrect response for message!")
-                    }
-                },
+            match Controller::tick(self) {
+                // If the device couldn't decode our request, send it again:
+                Ok(ResponseMessage::Nak) => {
+                    log::trace!("Got a NAK; resending `{:?}`", m);
+                    resend(&m)?;
+                }
+
+                // If we got a message, we're done:
+                Ok(resp) => break Ok(resp),
 
                 // If we got no message, try, try again:
                 Err(None) => { },
 
                 // If we got a transport error, bail:
-                Err(Some(TickError::TransportError(e))) => panic!("Transport error! `{:?}`", e),
+                Err(Some(TickError::TransportError(e))) => break Err(ControllerError::Recv(e)),
 
                 // If we got a decode error, assume a problem in transmission
                 // and try again.
@@ -210,10 +376,36 @@ macro_rules! ctrl {
                     // impls but on the other hand it means we can't cache the
                     // encode in situations like these... Not sure what the
                     // right tradeoff is.
-                    $s.transport.send($s.enc.borrow_mut().encode(&m)).unwrap(); // TODO: don't panic?
+                    resend(&m)?;
                 }
             }
         }
+    }
+
+    // Panics with the error in the message.
+    //
+    // Note that the error is _not_ recorded (there'd be no way for anyone to
+    // call `take_error` and see it); users that want to handle errors from
+    // requests should use `request` instead.
+    fn fail(&self, err: ControllerError<D::Err, T::SendErr, T::RecvErr>) -> ! {
+        log::error!("{}", err);
+        panic!("{}", err)
+    }
+}
+
+
+macro_rules! ctrl {
+    ($s:ident, $req:expr, $resp:pat$(, $ret:expr)?) => {{
+        use RequestMessage::*;
+        use ResponseMessage as R;
+
+        // TODO: don't panic? Not sure how we'd realistically deal with any
+        // transport errors here since `Control` is infallible..
+        match $s.request($req) {
+            Ok($resp) => { $($ret)? },
+            Ok(other) => $s.fail(ControllerError::UnexpectedResponse(other)),
+            Err(err) => $s.fail(err),
+        }
     }};
 }
 
@@ -253,6 +445,19 @@ where
     fn finish_page_write(&mut self, page: LoadApiSession<PageIndex>) -> Result<(), FinishPageWriteError> {
         ctrl!(self, FinishPageWrite { page }, R::FinishPageWrite(r), r)
     }
+    fn get_max_compressed_chunk_size(&self) -> u8 {
+        // Devices that are too old to know about compressed chunks can still
+        // be loaded the old way:
+        if !self.peer_supports(&RequestMessage::GetMaxCompressedChunkSize) { return 0; }
+
+        ctrl!(self, GetMaxCompressedChunkSize, R::GetMaxCompressedChunkSize(s), s)
+    }
+    fn send_compressed_page_chunk(&mut self, offset: LoadApiSession<Offset>, chunk: CompressedChunk) -> Result<(), PageChunkError> {
+        ctrl!(self, SendCompressedPageChunk { offset, chunk }, R::SendCompressedPageChunk(r), r)
+    }
+    fn get_page_hashes(&self, first: PageIndex) -> [u64; PAGE_HASHES_PER_MESSAGE] {
+        ctrl!(self, GetPageHashes { first }, R::GetPageHashes(h), h)
+    }
 
     fn set_breakpoint(&mut self, addr: Addr) -> Result<Idx, ()> {
         ctrl!(self, SetBreakpoint { addr }, R::SetBreakpoint(r), r)
@@ -286,35 +491,14 @@ where
         // If we're in a sealed batch with pending futures, just crash.
         self.shared_state.add_new_future().expect("no new futures once a batch starts to resolve");
 
-        // TODO: factor out this code; it's a copy of that in the ctrl! macro.
-
-        let m = RequestMessage::RunUntilEvent.into();
-
         // If we're already waiting for an event, don't bother sending the
         // request along again:
         if !self.waiting_for_event.load(Ordering::SeqCst) {
-            self.transport.send(self.enc.borrow_mut().encode(&m)).unwrap();
-
             // Wait for the acknowledge:
-            loop {
-                match Controller::tick(self) {
-                    Ok(m) => if let ResponseMessage::RunUntilEventAck = m {
-                        break;
-                    } else {
-                        panic!("Incorrect response for message!")
-                    }
-
-                    Err(None) => {},
-
-                    Err(Some(TickError::TransportError(e))) => {
-                        panic!("Transport Error! {:?}", e)
-                    },
-
-                    Err(Some(TickError::DecodeError(e))) => {
-                        log::trace!("Decode Error: {:?}", e);
-                        self.transport.send(self.enc.borrow_mut().encode(&m)).unwrap();
-                    },
-                }
+            match self.request(RequestMessage::RunUntilEvent) {
+                Ok(ResponseMessage::RunUntilEventAck) => {},
+                Ok(other) => self.fail(ControllerError::UnexpectedResponse(other)),
+                Err(err) => self.fail(err),
             }
 
             self.waiting_for_event.store(true, Ordering::SeqCst);
@@ -333,10 +517,37 @@ where
         // We should never actually get a message here (run until event responses are
         // handled within `Self::tick()`) though.
         // Self::tick(self).unwrap_none(); // when this goes stable, use this, maybe (TODO)
-        if let Err(None) = Self::tick(self) {
-            /* We expect to get nothing here. */
-        } else {
-            panic!("Controller received a message in tick!")
+        match Self::tick(self) {
+            Err(None) => { /* We expect to get nothing here. */ },
+
+            // A NAK here is for a request we've already given up on (or for
+            // garbage the device got); there's nothing to resend.
+            Ok(ResponseMessage::Nak) => log::debug!("Ignoring a NAK in tick"),
+
+            // Anything else is a response to a request no one's waiting on.
+            // Like transport errors, we record it instead of panicking.
+            Ok(m) => {
+                log::warn!("Unexpected response in tick: `{:?}`", m);
+                *self.error.borrow_mut() = Some(ControllerError::UnexpectedResponse(m));
+            },
+
+            // Transport errors aren't fatal here; we record them (see
+            // `take_error`) instead. If we were waiting on an event, there's
+            // no use in continuing to wait so we resolve the futures with
+            // `Interrupted`.
+            Err(Some(TickError::TransportError(e))) => {
+                log::warn!("Transport error in tick: `{:?}`", e);
+                *self.error.borrow_mut() = Some(ControllerError::Recv(e));
+
+                if self.waiting_for_event.load(Ordering::SeqCst) {
+                    self.shared_state.resolve_all(Event::Interrupted).unwrap();
+                    self.waiting_for_event.store(false, Ordering::SeqCst);
+                }
+            },
+            Err(Some(TickError::DecodeError(e))) => {
+                log::warn!("Decode error in tick: `{:?}`", e);
+                *self.error.borrow_mut() = Some(ControllerError::Decode(e));
+            },
         }
 
         // This function can (probably, TODO) be safely _not_ called so we
@@ -359,6 +570,15 @@ where
         ctrl!(self, Reset, R::Reset)
     }
 
+    fn reset_with(&mut self, kind: ResetKind, boot: BootConfig) -> Result<(), ResetError> {
+        if !self.peer_supports(&RequestMessage::ResetWith { kind, boot }) { return Err(ResetError::Unsupported); }
+
+        // See `reset`.
+        self.shared_state.reset();
+
+        ctrl!(self, ResetWith { kind, boot }, R::ResetWith(r), r)
+    }
+
     fn get_error(&self) -> Option<Lc3Error> { ctrl!(self, GetError, R::GetError(r), r) }
 
     // I/O Access:
@@ -366,11 +586,101 @@ where
     fn get_gpio_readings(&self) -> GpioPinArr<Result<bool, GpioReadError>> { ctrl!(self, GetGpioReadings, R::GetGpioReadings(r), r) }
     fn get_adc_states(&self) -> AdcPinArr<AdcState> { ctrl!(self, GetAdcStates, R::GetAdcStates(r), r) }
     fn get_adc_readings(&self) -> AdcPinArr<Result<u8, AdcReadError>> { ctrl!(self, GetAdcReadings, R::GetAdcReadings(r), r) }
+    fn set_adc_source(&mut self, pin: AdcPin, source: AdcSource) -> Result<(), AdcMiscError> {
+        if !self.peer_supports(&RequestMessage::SetAdcSource { pin, source }) { return Err(AdcMiscError); }
+
+        ctrl!(self, SetAdcSource { pin, source }, R::SetAdcSource(r), r)
+    }
     fn get_timer_modes(&self) -> TimerArr<TimerMode> { ctrl!(self, GetTimerModes, R::GetTimerModes(r), r) }
-    fn get_timer_states(&self) -> TimerArr<TimerState> { ctrl!(self, GetTimerStates, R::GetTimerStates(r), r) }
+    fn get_timer_states(&self) -> TimerArr<TimerStatus> {
+        if self.peer_supports(&RequestMessage::GetTimerStatuses) {
+            return ctrl!(self, GetTimerStatuses, R::GetTimerStatuses(r), r);
+        }
+
+        // Older devices only send the states:
+        let states = ctrl!(self, GetTimerStates, R::GetTimerStates(r), r);
+
+        let mut statuses = TimerArr([TimerStatus::disabled(); TimerId::NUM_TIMERS]);
+        TIMERS.iter().for_each(|t| statuses[*t].state = states[*t]);
+
+        statuses
+    }
     fn get_pwm_states(&self) -> PwmPinArr<PwmState> { ctrl!(self, GetPwmStates, R::GetPwmStates(r), r) }
-    fn get_pwm_config(&self) -> PwmPinArr<u8> { ctrl!(self, GetPwmConfig, R::GetPwmConfig(r), r) }
+    fn get_pwm_config(&self) -> PwmPinArr<PwmConfig> {
+        if self.peer_supports(&RequestMessage::GetPwmConfigs) {
+            return ctrl!(self, GetPwmConfigs, R::GetPwmConfigs(r), r);
+        }
+
+        // Older devices only send the duty cycles; the periods come from the
+        // states:
+        let states = self.get_pwm_states();
+        let duty_cycles: PwmPinArr<u8> = ctrl!(self, GetPwmConfig, R::GetPwmConfig(r), r);
+
+        let mut configs = PwmPinArr([PwmConfig::Disabled; PwmPin::NUM_PINS]);
+        PWM_PINS.iter().for_each(|p| configs[*p] = PwmConfig::new(states[*p], duty_cycles[*p]));
+
+        configs
+    }
     fn get_clock(&self) -> Word { ctrl!(self, GetClock, R::GetClock(r), r) }
+    fn get_display_pixels(&self, first: u16) -> [Color; DISPLAY_PIXELS_PER_MESSAGE] {
+        // Devices that predate the display can't have one:
+        if !self.peer_supports(&RequestMessage::GetDisplayPixels { first }) { return [0; DISPLAY_PIXELS_PER_MESSAGE]; }
+
+        ctrl!(self, GetDisplayPixels { first }, R::GetDisplayPixels(p), p)
+    }
+    fn get_disk_num_sectors(&self) -> SectorIdx {
+        // Devices that predate the disk can't have one:
+        if !self.peer_supports(&RequestMessage::GetDiskNumSectors) { return 0; }
+
+        ctrl!(self, GetDiskNumSectors, R::GetDiskNumSectors(n), n)
+    }
+    fn get_disk_words(&self, sector: SectorIdx, first: u16) -> Result<[Word; DISK_WORDS_PER_MESSAGE], DiskError> {
+        if !self.peer_supports(&RequestMessage::GetDiskWords { sector, first }) { return Err(DiskError::NoDisk); }
+
+        ctrl!(self, GetDiskWords { sector, first }, R::GetDiskWords(r), r)
+    }
+
+    fn set_rng_seed(&mut self, seed: u64) -> Result<(), RngError> {
+        if !self.peer_supports(&RequestMessage::SetRngSeed { seed }) { return Err(RngError::NoRng); }
+
+        ctrl!(self, SetRngSeed { seed }, R::SetRngSeed(r), r)
+    }
+
+    fn set_watchdog_disabled(&mut self, disabled: bool) -> Result<(), WatchdogError> {
+        if !self.peer_supports(&RequestMessage::SetWatchdogDisabled { disabled }) { return Err(WatchdogError::NoWatchdog); }
+
+        ctrl!(self, SetWatchdogDisabled { disabled }, R::SetWatchdogDisabled(r), r)
+    }
+
+    fn set_break_instructions_enabled(&mut self, enabled: bool) -> Result<(), ()> {
+        if !self.peer_supports(&RequestMessage::SetBreakInstructionsEnabled { enabled }) { return Err(()); }
+
+        ctrl!(self, SetBreakInstructionsEnabled { enabled }, R::SetBreakInstructionsEnabled(r), r)
+    }
+
+    fn get_cycle_count(&self) -> Result<u64, ()> {
+        if !self.peer_supports(&RequestMessage::GetCycleCount) { return Err(()); }
+
+        ctrl!(self, GetCycleCount, R::GetCycleCount(r), r)
+    }
+
+    fn set_uninitialized_read_detection(&mut self, enabled: bool) -> Result<(), ()> {
+        if !self.peer_supports(&RequestMessage::SetUninitializedReadDetection { enabled }) { return Err(()); }
+
+        ctrl!(self, SetUninitializedReadDetection { enabled }, R::SetUninitializedReadDetection(r), r)
+    }
+
+    fn set_mpu_region(&mut self, idx: Idx, region: Option<MpuRegion>) -> Result<(), MpuError> {
+        if !self.peer_supports(&RequestMessage::SetMpuRegion { idx, region }) { return Err(MpuError::NoMpu); }
+
+        ctrl!(self, SetMpuRegion { idx, region }, R::SetMpuRegion(r), r)
+    }
+
+    fn get_mpu_region(&self, idx: Idx) -> Result<Option<MpuRegion>, MpuError> {
+        if !self.peer_supports(&RequestMessage::GetMpuRegion { idx }) { return Err(MpuError::NoMpu); }
+
+        ctrl!(self, GetMpuRegion { idx }, R::GetMpuRegion(r), r)
+    }
 
     fn get_device_info(&self) -> DeviceInfo { ctrl!(self, GetDeviceInfo, R::GetDeviceInfo(r), r) }
 
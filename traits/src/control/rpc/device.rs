@@ -136,6 +136,9 @@
 use super::{Encode, Decode, Transport};
 use super::{Control, RequestMessage, ResponseMessage};
 use super::encoding::Transparent;
+use super::handshake::HandshakeInfo;
+use crate::peripherals::pwm::{PwmConfig, PwmPin, PwmPinArr, PWM_PINS};
+use crate::peripherals::timers::{TimerArr, TimerId, TimerState, TIMERS};
 
 use core::marker::PhantomData;
 use core::task::{Context, Poll, Waker, RawWaker, RawWakerVTable};
@@ -179,6 +182,10 @@ where
     dec: ReqDec,
     // pending_event_future: Option<Pin<C::EventFuture>>,
     pending_event_future: Option<C::EventFuture>,
+    /// What the controller told us about itself during the handshake, if
+    /// it's done one. Used to avoid sending it events and errors it doesn't
+    /// know about.
+    peer: Option<HandshakeInfo>,
 }
 
 // TODO: make a builder!
@@ -209,8 +216,30 @@ where
             enc,
             dec,
             pending_event_future: None,
+            peer: None,
         }
     }
+
+    /// What the controller told us about itself during the handshake (if one
+    /// has happened).
+    pub fn peer(&self) -> Option<&HandshakeInfo> {
+        self.peer.as_ref()
+    }
+
+    // Sends a response, adjusted for the controller's protocol version (see
+    // `HandshakeInfo::downgrade`). Controllers that haven't done a handshake
+    // get the response as is.
+    fn respond(&mut self, resp: ResponseMessage) -> Result<(), T::SendErr> {
+        let resp = match &self.peer {
+            Some(peer) => match peer.downgrade(resp) {
+                Some(resp) => resp,
+                None => return Ok(()),
+            },
+            None => resp,
+        };
+
+        self.transport.send(self.enc.encode(&resp.into()))
+    }
 }
 
 // ╭──────╮
@@ -287,14 +316,37 @@ where
                 // println!("device future is done!");
                 self.pending_event_future = None;
 
-                let enc = self.enc.encode(&R::RunUntilEvent(event).into());
-                self.transport.send(enc).unwrap(); // TODO: don't panic?
+                self.respond(R::RunUntilEvent(event)).unwrap(); // TODO: don't panic?
             }
         }
 
-        // TODO: we don't panic on decode failures here, but this is only a stopgap,
-        // first pass solution.
-        while let Ok(m) = self.transport.get().and_then(|enc| self.dec.decode(&enc).map_err(|_| None).map(Into::into)) {
+        // We don't panic on decode failures here; we answer with a NAK instead
+        // so that the controller knows to resend the request (rather than
+        // waiting for a response that's never going to come).
+        //
+        // Transports that care about this (i.e. the reliability layer in
+        // `lc3-device-support`) can do better: they can check the integrity
+        // of the message before it gets here and ask for it to be resent.
+        loop {
+            let m: RequestMessage = match self.transport.get() {
+                Ok(enc) => match self.dec.decode(&enc) {
+                    Ok(m) => m.into(),
+                    Err(e) => {
+                        log::warn!("Failed to decode a request: `{:?}`", e);
+
+                        if let Err(e) = self.respond(R::Nak) {
+                            log::warn!("Failed to send a NAK: `{:?}`", e);
+                        }
+                        continue;
+                    },
+                },
+                Err(None) => break,
+                Err(Some(e)) => {
+                    log::warn!("Transport error: `{:?}`", e);
+                    break;
+                },
+            };
+
             num_processed_messages += 1;
 
             macro_rules! dev {
@@ -307,14 +359,26 @@ where
                             } else {
                                 // self.pending_event_future = Some(Pin::new(c.run_until_event()));
                                 self.pending_event_future = Some(c.run_until_event());
-                                self.transport.send(self.enc.encode(&R::RunUntilEventAck.into())).unwrap()
+                                self.respond(R::RunUntilEventAck).unwrap()
                             }
                         },
+                        Handshake { info } => {
+                            let ours = HandshakeInfo::current(c.get_device_info().capabilities);
+
+                            // We answer regardless; it's up to the controller
+                            // to decide what to do.
+                            if let Err(e) = ours.check(&info) {
+                                log::warn!("Handshake with an incompatible controller: {}", e);
+                            }
+
+                            self.peer = Some(info);
+                            self.respond(R::Handshake(ours)).unwrap()
+                        },
                         $(
-                            $req => self.transport.send(self.enc.encode(&{
+                            $req => self.respond({
                                 let $r = $resp_expr;
                                 $($resp)+
-                            }.into())).unwrap(),
+                            }).unwrap(),
                         )*
                     }
 
@@ -356,6 +420,7 @@ where
                 (Pause => R::Pause) with _ = c.pause();
                 (GetState => R::GetState(r)) with r = c.get_state();
                 (Reset => R::Reset) with _ = c.reset();
+                (ResetWith { kind, boot } => R::ResetWith(r)) with r = c.reset_with(kind, boot);
 
                 (GetError => R::GetError(r)) with r = c.get_error();
 
@@ -366,10 +431,27 @@ where
                 (GetAdcReadings => R::GetAdcReadings(r)) with r = c.get_adc_readings();
 
                 (GetTimerModes => R::GetTimerModes(r)) with r = c.get_timer_modes();
-                (GetTimerStates => R::GetTimerStates(r)) with r = c.get_timer_states();
+                (GetTimerStates => R::GetTimerStates(r)) with r = {
+                    let statuses = c.get_timer_states();
+                    let mut states = TimerArr([TimerState::Disabled; TimerId::NUM_TIMERS]);
+
+                    TIMERS.iter().for_each(|t| states[*t] = statuses[*t].state);
+
+                    states
+                };
 
                 (GetPwmStates => R::GetPwmStates(r)) with r = c.get_pwm_states();
-                (GetPwmConfig => R::GetPwmConfig(r)) with r = c.get_pwm_config();
+                (GetPwmConfig => R::GetPwmConfig(r)) with r = {
+                    let configs = c.get_pwm_config();
+                    let mut duty_cycles = PwmPinArr([0; PwmPin::NUM_PINS]);
+
+                    PWM_PINS.iter().for_each(|p| duty_cycles[*p] = match configs[*p] {
+                        PwmConfig::Enabled { duty_cycle, .. } => duty_cycle,
+                        PwmConfig::Disabled => 0,
+                    });
+
+                    duty_cycles
+                };
 
                 (GetClock => R::GetClock(r)) with r = c.get_clock();
 
@@ -377,6 +459,26 @@ where
 
                 (GetProgramMetadata => R::GetProgramMetadata(r)) with r = c.get_program_metadata();
                 (SetProgramMetadata { metadata } => R::SetProgramMetadata) with _ = c.set_program_metadata(metadata);
+
+                (GetMaxCompressedChunkSize => R::GetMaxCompressedChunkSize(r)) with r = c.get_max_compressed_chunk_size();
+                (SendCompressedPageChunk { offset, chunk } => R::SendCompressedPageChunk(r)) with r = c.send_compressed_page_chunk(offset, chunk);
+                (GetPageHashes { first } => R::GetPageHashes(r)) with r = c.get_page_hashes(first);
+
+                (GetDisplayPixels { first } => R::GetDisplayPixels(r)) with r = c.get_display_pixels(first);
+
+                (GetDiskNumSectors => R::GetDiskNumSectors(r)) with r = c.get_disk_num_sectors();
+                (GetDiskWords { sector, first } => R::GetDiskWords(r)) with r = c.get_disk_words(sector, first);
+
+                (SetAdcSource { pin, source } => R::SetAdcSource(r)) with r = c.set_adc_source(pin, source);
+                (GetPwmConfigs => R::GetPwmConfigs(r)) with r = c.get_pwm_config();
+                (GetTimerStatuses => R::GetTimerStatuses(r)) with r = c.get_timer_states();
+                (SetRngSeed { seed } => R::SetRngSeed(r)) with r = c.set_rng_seed(seed);
+                (SetWatchdogDisabled { disabled } => R::SetWatchdogDisabled(r)) with r = c.set_watchdog_disabled(disabled);
+                (SetBreakInstructionsEnabled { enabled } => R::SetBreakInstructionsEnabled(r)) with r = c.set_break_instructions_enabled(enabled);
+                (GetCycleCount => R::GetCycleCount(r)) with r = c.get_cycle_count();
+                (SetUninitializedReadDetection { enabled } => R::SetUninitializedReadDetection(r)) with r = c.set_uninitialized_read_detection(enabled);
+                (SetMpuRegion { idx, region } => R::SetMpuRegion(r)) with r = c.set_mpu_region(idx, region);
+                (GetMpuRegion { idx } => R::GetMpuRegion(r)) with r = c.get_mpu_region(idx);
             };
         }
 
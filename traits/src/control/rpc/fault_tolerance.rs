@@ -139,3 +139,15 @@
 //! of granularity is a byte, which is very helpful.
 //!
 //! [COBS]: (https://en.wikipedia.org/wiki/Consistent_Overhead_Byte_Stuffing)
+//!
+//! #### Update:
+//!
+//! The "Borked" scenario above is now handled by the reliability layer in
+//! `lc3-device-support` (`lc3_device_support::rpc::reliability`) which sits
+//! between the encoding and transport layers and does sequence numbers,
+//! CRCs, NAKs, timeouts, bounded retransmits, and duplicate suppression. When
+//! it gives up, the error makes its way to the [`Controller`] (see
+//! [`ControllerError`]) instead of the controller waiting forever.
+//!
+//! [`Controller`]: super::Controller
+//! [`ControllerError`]: super::ControllerError
@@ -0,0 +1,327 @@
+//! The handshake that a [`Controller`] and a [`Device`] do when they first
+//! connect.
+//!
+//! The encoding layers we use (postcard and JSON) don't carry any information
+//! about the layout of the messages being sent; if the two sides of a
+//! connection disagree about what [`RequestMessage`] and [`ResponseMessage`]
+//! look like, messages will (at best) fail to decode and (at worst) decode
+//! into something else entirely.
+//!
+//! So, when connecting, the controller sends a [`HandshakeInfo`] describing
+//! itself and the device answers with its own. Each side has:
+//!   - the version of the protocol ([`PROTOCOL_VERSION`]) it speaks
+//!   - a hash of the message set ([`MESSAGE_SET_HASH`]) it was built with
+//!   - the [peripheral counts] it was built with ([`PERIPHERAL_COUNTS`])
+//!   - its [`Capabilities`]
+//!
+//! The rules are:
+//!   - the _major_ protocol versions have to match
+//!   - the peripheral counts have to match (the `*Arr` types in the messages
+//!     are sized by them)
+//!   - if the _minor_ protocol versions match, the message set hashes have
+//!     to match too (if they don't, someone changed the messages without
+//!     bumping the protocol version)
+//!   - if the minor versions differ, the side with the newer version has to
+//!     avoid the messages the other side doesn't know about
+//!
+//! The last rule works because, within a major version, messages are only ever
+//! _added_ (to the end of the message enums) and each request message knows
+//! which minor version introduced it ([`RequestMessage::protocol_minor`]). The
+//! [`Controller`] checks this before sending requests.
+//!
+//! Responses can also carry [`Event`]s and [`Error`]s, which have grown new
+//! variants too. The [`Device`] remembers what the controller told it during
+//! the handshake and swaps out the ones the controller doesn't know about
+//! before sending them (see [`HandshakeInfo::downgrade`]). A device that
+//! hasn't been sent a handshake doesn't know which version the controller
+//! speaks and so sends everything as is.
+//!
+//! Devices that predate the handshake won't be able to decode the handshake
+//! request and so won't respond to it; with a transport that gives up
+//! eventually (i.e. the reliability layer in `lc3-device-support`), this shows
+//! up as a transport error from [`Controller::handshake`].
+//!
+//! When changing the message enums, remember to update [`MESSAGE_SET`] (and
+//! its hash) and to bump [`PROTOCOL_VERSION`].
+//!
+//! [`Controller`]: super::Controller
+//! [`Controller::handshake`]: super::Controller::handshake
+//! [`Device`]: super::Device
+//! [`Event`]: crate::control::Event
+//! [`Error`]: crate::error::Error
+//! [`RequestMessage`]: super::RequestMessage
+//! [`ResponseMessage`]: super::ResponseMessage
+//! [`RequestMessage::protocol_minor`]: super::RequestMessage::protocol_minor
+//! [`MESSAGE_SET`]: super::messages::MESSAGE_SET
+//! [peripheral counts]: crate::peripherals#peripheral-counts
+
+use super::messages::{RequestMessage, ResponseMessage, MESSAGE_SET_HASH};
+use crate::control::{Capabilities, Event, Version};
+use crate::error::Error;
+use crate::peripherals::{adc::AdcPin, gpio::GpioPin, pwm::PwmPin, timers::TimerId};
+
+use serde::{Deserialize, Serialize};
+
+use core::fmt::{self, Display};
+
+/// The version of the RPC protocol this crate speaks.
+///
+/// Minor version history:
+///   - 0: everything before the handshake
+///   - 1: the handshake (`RequestMessage::Handshake`)
+///   - 2: compressed page chunks and page hashes for the load API
+///   - 3: reading the display (`RequestMessage::GetDisplayPixels`)
+///   - 4: inspecting the disk (`RequestMessage::GetDiskNumSectors` and
+///        `RequestMessage::GetDiskWords`)
+///   - 5: ADC signal sources (`RequestMessage::SetAdcSource`)
+///   - 6: typed PWM configs (`RequestMessage::GetPwmConfigs`)
+///   - 7: timer counts and captures (`RequestMessage::GetTimerStatuses`)
+///   - 8: seeding the random number generator (`RequestMessage::SetRngSeed`)
+///   - 9: disabling the watchdog (`RequestMessage::SetWatchdogDisabled`)
+///   - 10: warm resets and boot configs (`RequestMessage::ResetWith`)
+///   - 11: software breaks (`Event::SoftwareBreak`)
+///   - 12: `BRK` instructions (`RequestMessage::SetBreakInstructionsEnabled`
+///         and `Event::BreakInstruction`)
+///   - 13: cycle counts (`RequestMessage::GetCycleCount`)
+///   - 14: uninitialized read detection
+///         (`RequestMessage::SetUninitializedReadDetection` and
+///         `Error::UninitializedRead`)
+///   - 15: memory protection regions (`RequestMessage::SetMpuRegion` and
+///         `RequestMessage::GetMpuRegion`)
+///   - 16: NAKs for requests the device couldn't decode
+///         (`ResponseMessage::Nak`)
+pub const PROTOCOL_VERSION: Version = Version::new(0, 16, 0, None);
+
+/// The [peripheral counts](crate::peripherals#peripheral-counts) this crate was
+/// built with: GPIO pins, ADC pins, PWM pins, and timers.
+pub const PERIPHERAL_COUNTS: [u8; 4] = [
+    GpioPin::NUM_PINS as u8,
+    AdcPin::NUM_PINS as u8,
+    PwmPin::NUM_PINS as u8,
+    TimerId::NUM_TIMERS as u8,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HandshakeInfo {
+    pub protocol: Version,
+    pub message_set: u64,
+    pub peripheral_counts: [u8; 4],
+    pub capabilities: Capabilities,
+}
+
+impl HandshakeInfo {
+    /// Describes this crate's protocol version and message set.
+    pub fn current(capabilities: Capabilities) -> Self {
+        Self {
+            protocol: PROTOCOL_VERSION,
+            message_set: MESSAGE_SET_HASH,
+            peripheral_counts: PERIPHERAL_COUNTS,
+            capabilities,
+        }
+    }
+
+    /// Checks whether we (`self`) can talk to the other side (`theirs`).
+    pub fn check(&self, theirs: &HandshakeInfo) -> Result<(), HandshakeError> {
+        if self.protocol.major != theirs.protocol.major {
+            return Err(HandshakeError::IncompatibleProtocol {
+                ours: self.protocol.clone(),
+                theirs: theirs.protocol.clone(),
+            });
+        }
+
+        if self.peripheral_counts != theirs.peripheral_counts {
+            return Err(HandshakeError::PeripheralCountMismatch {
+                ours: self.peripheral_counts,
+                theirs: theirs.peripheral_counts,
+            });
+        }
+
+        if self.protocol.minor == theirs.protocol.minor && self.message_set != theirs.message_set {
+            return Err(HandshakeError::MessageSetMismatch {
+                ours: self.message_set,
+                theirs: theirs.message_set,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Whether the side this describes knows about `message`.
+    pub fn supports(&self, message: &RequestMessage) -> bool {
+        message.protocol_minor() <= self.protocol.minor
+    }
+
+    /// Adjusts a response for the side this describes.
+    ///
+    /// Events that it doesn't know about become [`Event::Interrupted`] (the
+    /// machine is paused either way) and errors that it doesn't know about
+    /// are dropped (events with such errors also become
+    /// [`Event::Interrupted`]). Returns `None` for responses that shouldn't be
+    /// sent at all: [`ResponseMessage::Nak`] (older controllers just resend
+    /// requests that go unanswered).
+    pub fn downgrade(&self, response: ResponseMessage) -> Option<ResponseMessage> {
+        use ResponseMessage::*;
+
+        Some(match response {
+            RunUntilEvent(event) => RunUntilEvent(self.downgrade_event(event)),
+            Step(event) => Step(event.map(|e| self.downgrade_event(e))),
+            GetError(Some(err)) if error_minor(&err) > self.protocol.minor => GetError(None),
+            Nak if self.protocol.minor < 16 => return None,
+            other => other,
+        })
+    }
+
+    fn downgrade_event(&self, event: Event) -> Event {
+        let minor = match &event {
+            Event::Error { err } => error_minor(err),
+            other => event_minor(other),
+        };
+
+        if minor <= self.protocol.minor { event } else { Event::Interrupted }
+    }
+}
+
+/// The minor [protocol version](PROTOCOL_VERSION) that introduced `event`.
+fn event_minor(event: &Event) -> u8 {
+    use Event::*;
+
+    match event {
+        Breakpoint { .. } |
+        MemoryWatch { .. } |
+        DepthReached { .. } |
+        Error { .. } |
+        Interrupted |
+        Halted => 0,
+        SoftwareBreak { .. } => 11,
+        BreakInstruction { .. } => 12,
+    }
+}
+
+/// The minor [protocol version](PROTOCOL_VERSION) that introduced `err`.
+fn error_minor(err: &Error) -> u8 {
+    use Error::*;
+
+    match err {
+        InvalidGpioWrite(_) |
+        InvalidGpioWrites(_) |
+        InvalidGpioRead(_) |
+        InvalidGpioReads(_) |
+        GpioMiscError(_) |
+        InvalidAdcRead(_) |
+        InvalidAdcReads(_) |
+        AdcMiscError(_) |
+        InputError(_) |
+        OutputError(_) |
+        SystemStackOverflow => 0,
+        InvalidDisplayAccess(_) => 3,
+        DiskError(_) => 4,
+        WatchdogExpired => 9,
+        UninitializedRead { .. } => 14,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HandshakeError {
+    /// The major protocol versions don't match.
+    IncompatibleProtocol { ours: Version, theirs: Version },
+    /// The protocol versions match but the message sets don't.
+    MessageSetMismatch { ours: u64, theirs: u64 },
+    /// The two sides were built with different [peripheral
+    /// counts](PERIPHERAL_COUNTS).
+    PeripheralCountMismatch { ours: [u8; 4], theirs: [u8; 4] },
+}
+
+impl Display for HandshakeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use HandshakeError::*;
+
+        match self {
+            IncompatibleProtocol { ours, theirs } => write!(fmt,
+                "Incompatible RPC protocol versions: we speak {}.{}.x and the other side speaks {}.{}.x",
+                ours.major, ours.minor, theirs.major, theirs.minor,
+            ),
+            MessageSetMismatch { ours, theirs } => write!(fmt,
+                "RPC message sets differ even though the protocol versions match \
+                (ours: {:#018X}, theirs: {:#018X}); one side was probably built \
+                from a modified copy of the messages",
+                ours, theirs,
+            ),
+            PeripheralCountMismatch { ours, theirs } => write!(fmt,
+                "The two sides were built with different peripheral counts \
+                (GPIO pins, ADC pins, PWM pins, and timers; ours: {:?}, theirs: {:?})",
+                ours, theirs,
+            ),
+        }
+    }
+}
+
+/// 64-bit FNV-1a.
+///
+/// This isn't `const` (no loops in `const fn`s yet) so [`MESSAGE_SET_HASH`] is
+/// a literal; there's a test that checks that it matches this.
+pub fn message_set_hash(description: &str) -> u64 {
+    description.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ (byte as u64)).wrapping_mul(0x0000_0100_0000_01B3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::messages::MESSAGE_SET;
+
+    #[test]
+    fn message_set_hash_is_up_to_date() {
+        assert_eq!(
+            message_set_hash(MESSAGE_SET), MESSAGE_SET_HASH,
+            "`MESSAGE_SET_HASH` is out of date; it should be {:#018X}",
+            message_set_hash(MESSAGE_SET),
+        );
+    }
+
+    #[test]
+    fn compatibility() {
+        let ours = HandshakeInfo::current(Capabilities::default());
+
+        assert_eq!(Ok(()), ours.check(&ours));
+
+        let newer = HandshakeInfo { protocol: PROTOCOL_VERSION.minor(PROTOCOL_VERSION.minor + 1), message_set: 0, ..ours.clone() };
+        assert_eq!(Ok(()), ours.check(&newer));
+        assert_eq!(Ok(()), newer.check(&ours));
+
+        let modified = HandshakeInfo { message_set: 0, ..ours.clone() };
+        assert!(ours.check(&modified).is_err());
+
+        // Built with other peripheral counts (checked on both sides, whatever
+        // the protocol versions are):
+        let resized = HandshakeInfo { peripheral_counts: [16, 8, 4, 4], ..ours.clone() };
+        assert!(ours.check(&resized).is_err());
+        assert!(resized.check(&ours).is_err());
+        assert!(HandshakeInfo { protocol: newer.protocol.clone(), ..resized.clone() }.check(&ours).is_err());
+
+        let next_major = HandshakeInfo { protocol: PROTOCOL_VERSION.major(PROTOCOL_VERSION.major + 1), ..ours.clone() };
+        assert!(ours.check(&next_major).is_err());
+
+        let older = HandshakeInfo { protocol: PROTOCOL_VERSION.minor(0), ..ours.clone() };
+        assert!(!older.supports(&RequestMessage::Handshake { info: ours.clone() }));
+        assert!(older.supports(&RequestMessage::GetPc));
+    }
+
+    #[test]
+    fn downgrade() {
+        use ResponseMessage::*;
+
+        let at = |minor| HandshakeInfo { protocol: PROTOCOL_VERSION.minor(minor), ..HandshakeInfo::current(Capabilities::default()) };
+        let brk = Event::BreakInstruction { addr: 0x3000, code: 0 };
+        let watchdog = Event::Error { err: Error::WatchdogExpired };
+
+        assert_eq!(Some(Step(Some(brk))), at(12).downgrade(Step(Some(brk))));
+        assert_eq!(Some(Step(Some(Event::Interrupted))), at(11).downgrade(Step(Some(brk))));
+        assert_eq!(Some(RunUntilEvent(Event::Interrupted)), at(8).downgrade(RunUntilEvent(watchdog)));
+        assert_eq!(Some(GetError(None)), at(8).downgrade(GetError(Some(Error::WatchdogExpired))));
+        assert_eq!(Some(GetPc(0x3000)), at(0).downgrade(GetPc(0x3000)));
+
+        assert_eq!(None, at(15).downgrade(Nak));
+        assert_eq!(Some(Nak), at(16).downgrade(Nak));
+    }
+}
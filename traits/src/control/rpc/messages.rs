@@ -1,20 +1,28 @@
 //! Messages used for proxying [Control trait](super::Control) functions.
 
 use super::{State, Event};
+use super::handshake::HandshakeInfo;
 use crate::control::control::{
     MAX_BREAKPOINTS, MAX_MEMORY_WATCHPOINTS, MAX_CALL_STACK_DEPTH
 };
 use crate::control::load::{
     LoadApiSession, CHUNK_SIZE_IN_WORDS, PageWriteStart, PageIndex, Offset,
-    StartPageWriteError, PageChunkError, FinishPageWriteError
+    StartPageWriteError, PageChunkError, FinishPageWriteError, CompressedChunk,
+    PAGE_HASHES_PER_MESSAGE,
 };
 use crate::control::{ProgramMetadata, DeviceInfo, UnifiedRange, ProcessorMode, Idx};
+use crate::control::boot::{BootConfig, ResetKind, ResetError};
+use crate::control::mpu::{MpuRegion, MpuError};
 use crate::error::Error as Lc3Error;
 use crate::peripherals::{
-    adc::{AdcPinArr, AdcState, AdcReadError},
+    adc::{AdcMiscError, AdcPin, AdcPinArr, AdcReadError, AdcSource, AdcState},
     gpio::{GpioPinArr, GpioState, GpioReadError},
-    pwm::{PwmPinArr, PwmState},
-    timers::{TimerArr, TimerMode, TimerState},
+    pwm::{PwmConfig, PwmPinArr, PwmState},
+    timers::{TimerArr, TimerMode, TimerState, TimerStatus},
+    display::{Color, DISPLAY_PIXELS_PER_MESSAGE},
+    disk::{DiskError, SectorIdx, DISK_WORDS_PER_MESSAGE},
+    rng::RngError,
+    watchdog::WatchdogError,
 };
 
 use lc3_isa::{Addr, Reg, Word};
@@ -112,6 +120,99 @@ pub enum RequestMessage { // messages for everything but tick()
     SetProgramMetadata { metadata: ProgramMetadata },
 
     // no id!
+
+    // Everything below here was added after protocol version 0.0; new messages
+    // go at the end (see `handshake.rs`).
+
+    // Since 0.1:
+    Handshake { info: HandshakeInfo },
+
+    // Since 0.2:
+    GetMaxCompressedChunkSize,
+    SendCompressedPageChunk { offset: LoadApiSession<Offset>, chunk: CompressedChunk },
+    GetPageHashes { first: PageIndex },
+
+    // Since 0.3:
+    GetDisplayPixels { first: u16 },
+
+    // Since 0.4:
+    GetDiskNumSectors,
+    GetDiskWords { sector: SectorIdx, first: u16 },
+
+    // Since 0.5:
+    SetAdcSource { pin: AdcPin, source: AdcSource },
+
+    // Since 0.6:
+    GetPwmConfigs,
+
+    // Since 0.7:
+    GetTimerStatuses,
+
+    // Since 0.8:
+    SetRngSeed { seed: u64 },
+
+    // Since 0.9:
+    SetWatchdogDisabled { disabled: bool },
+
+    // Since 0.10:
+    ResetWith { kind: ResetKind, boot: BootConfig },
+
+    // Since 0.12:
+    SetBreakInstructionsEnabled { enabled: bool },
+
+    // Since 0.13:
+    GetCycleCount,
+
+    // Since 0.14:
+    SetUninitializedReadDetection { enabled: bool },
+
+    // Since 0.15:
+    SetMpuRegion { idx: Idx, region: Option<MpuRegion> },
+    GetMpuRegion { idx: Idx },
+}
+
+impl RequestMessage {
+    /// The minor [protocol version](super::handshake::PROTOCOL_VERSION) that
+    /// introduced this message.
+    pub fn protocol_minor(&self) -> u8 {
+        use RequestMessage::*;
+
+        match self {
+            Handshake { .. } => 1,
+            GetMaxCompressedChunkSize |
+            SendCompressedPageChunk { .. } |
+            GetPageHashes { .. } => 2,
+            GetDisplayPixels { .. } => 3,
+            GetDiskNumSectors |
+            GetDiskWords { .. } => 4,
+            SetAdcSource { .. } => 5,
+            GetPwmConfigs => 6,
+            GetTimerStatuses => 7,
+            SetRngSeed { .. } => 8,
+            SetWatchdogDisabled { .. } => 9,
+            ResetWith { .. } => 10,
+            SetBreakInstructionsEnabled { .. } => 12,
+            GetCycleCount => 13,
+            SetUninitializedReadDetection { .. } => 14,
+            SetMpuRegion { .. } |
+            GetMpuRegion { .. } => 15,
+
+            GetPc | SetPc { .. } |
+            GetRegister { .. } | SetRegister { .. } |
+            GetRegistersPsrAndPc |
+            ReadWord { .. } | WriteWord { .. } |
+            StartPageWrite { .. } | SendPageChunk { .. } | FinishPageWrite { .. } |
+            SetBreakpoint { .. } | UnsetBreakpoint { .. } | GetBreakpoints | GetMaxBreakpoints |
+            SetMemoryWatchpoint { .. } | UnsetMemoryWatchpoint { .. } |
+            GetMemoryWatchpoints | GetMaxMemoryWatchpoints |
+            SetDepthCondition { .. } | UnsetDepthCondition | GetDepth | GetCallStack |
+            RunUntilEvent | Step | Pause | GetState | Reset | GetError |
+            GetGpioStates | GetGpioReadings | GetAdcStates | GetAdcReadings |
+            GetTimerModes | GetTimerStates | GetPwmStates | GetPwmConfig | GetClock |
+            GetDeviceInfo |
+            GetProgramMetadata | SetProgramMetadata { .. } => 0,
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -177,7 +278,7 @@ pub enum ResponseMessage { // messages for everything but tick()
     GetTimerModes(TimerArr<TimerMode>),
     GetTimerStates(TimerArr<TimerState>),
     GetPwmStates(PwmPinArr<PwmState>),
-    GetPwmConfig(PwmPinArr<u8>), // TODO
+    GetPwmConfig(PwmPinArr<u8>), // Just the duty cycles; see `GetPwmConfigs`.
     GetClock(Word),
 
     GetDeviceInfo(DeviceInfo),
@@ -186,8 +287,149 @@ pub enum ResponseMessage { // messages for everything but tick()
     SetProgramMetadata,
 
     // no id!
+
+    // Since 0.1:
+    Handshake(HandshakeInfo),
+
+    // Since 0.2:
+    GetMaxCompressedChunkSize(u8),
+    SendCompressedPageChunk(Result<(), PageChunkError>),
+    GetPageHashes([u64; PAGE_HASHES_PER_MESSAGE]),
+
+    // Since 0.3:
+    GetDisplayPixels([Color; DISPLAY_PIXELS_PER_MESSAGE]),
+
+    // Since 0.4:
+    GetDiskNumSectors(SectorIdx),
+    GetDiskWords(Result<[Word; DISK_WORDS_PER_MESSAGE], DiskError>),
+
+    // Since 0.5:
+    SetAdcSource(Result<(), AdcMiscError>),
+
+    // Since 0.6:
+    GetPwmConfigs(PwmPinArr<PwmConfig>),
+
+    // Since 0.7:
+    GetTimerStatuses(TimerArr<TimerStatus>),
+
+    // Since 0.8:
+    SetRngSeed(Result<(), RngError>),
+
+    // Since 0.9:
+    SetWatchdogDisabled(Result<(), WatchdogError>),
+
+    // Since 0.10:
+    ResetWith(Result<(), ResetError>),
+
+    // Since 0.12:
+    SetBreakInstructionsEnabled(Result<(), ()>),
+
+    // Since 0.13:
+    GetCycleCount(Result<u64, ()>),
+
+    // Since 0.14:
+    SetUninitializedReadDetection(Result<(), ()>),
+
+    // Since 0.15:
+    SetMpuRegion(Result<(), MpuError>),
+    GetMpuRegion(Result<Option<MpuRegion>, MpuError>),
+
+    // Since 0.16:
+    Nak, // The device couldn't decode the last request; please resend it.
 }
 
+/// A description of the message set: the variants of [`RequestMessage`] and
+/// [`ResponseMessage`] (in order) and their fields, along with the variants of
+/// the [`Event`] and [`Error`](Lc3Error) enums that responses carry.
+///
+/// This (well, its hash: [`MESSAGE_SET_HASH`]) is exchanged during the
+/// [handshake](super::handshake) so that mismatched message sets get caught
+/// instead of producing garbage. It has to be updated by hand when the
+/// message enums change.
+pub const MESSAGE_SET: &str = "\
+    req:\
+        GetPc,SetPc{addr:Addr},GetRegister{reg:Reg},SetRegister{reg:Reg,data:Word},\
+        GetRegistersPsrAndPc,ReadWord{addr:Addr},WriteWord{addr:Addr,word:Word},\
+        StartPageWrite{page:LoadApiSession<PageWriteStart>,checksum:u64},\
+        SendPageChunk{offset:LoadApiSession<Offset>,chunk:[Word;8]},\
+        FinishPageWrite{page:LoadApiSession<PageIndex>},\
+        SetBreakpoint{addr:Addr},UnsetBreakpoint{idx:Idx},GetBreakpoints,GetMaxBreakpoints,\
+        SetMemoryWatchpoint{addr:Addr},UnsetMemoryWatchpoint{idx:Idx},GetMemoryWatchpoints,GetMaxMemoryWatchpoints,\
+        SetDepthCondition{condition:UnifiedRange<u64>},UnsetDepthCondition,GetDepth,GetCallStack,\
+        RunUntilEvent,Step,Pause,GetState,Reset,GetError,\
+        GetGpioStates,GetGpioReadings,GetAdcStates,GetAdcReadings,GetTimerModes,GetTimerStates,\
+        GetPwmStates,GetPwmConfig,GetClock,GetDeviceInfo,\
+        GetProgramMetadata,SetProgramMetadata{metadata:ProgramMetadata},\
+        Handshake{info:HandshakeInfo},\
+        GetMaxCompressedChunkSize,\
+        SendCompressedPageChunk{offset:LoadApiSession<Offset>,chunk:CompressedChunk},\
+        GetPageHashes{first:PageIndex},\
+        GetDisplayPixels{first:u16},\
+        GetDiskNumSectors,GetDiskWords{sector:SectorIdx,first:u16},\
+        SetAdcSource{pin:AdcPin,source:AdcSource},\
+        GetPwmConfigs,\
+        GetTimerStatuses,\
+        SetRngSeed{seed:u64},\
+        SetWatchdogDisabled{disabled:bool},\
+        ResetWith{kind:ResetKind,boot:BootConfig},\
+        SetBreakInstructionsEnabled{enabled:bool},\
+        GetCycleCount,\
+        SetUninitializedReadDetection{enabled:bool},\
+        SetMpuRegion{idx:Idx,region:Option<MpuRegion>},GetMpuRegion{idx:Idx};\
+    resp:\
+        GetPc(Addr),SetPc,GetRegister(Word),SetRegister,GetRegistersPsrAndPc(([Word;8],Word,Word)),\
+        ReadWord(Word),WriteWord,\
+        StartPageWrite(Result<LoadApiSession<PageIndex>,StartPageWriteError>),\
+        SendPageChunk(Result<(),PageChunkError>),FinishPageWrite(Result<(),FinishPageWriteError>),\
+        SetBreakpoint(Result<Idx,()>),UnsetBreakpoint(Result<(),()>),\
+        GetBreakpoints([Option<Addr>;MAX_BREAKPOINTS]),GetMaxBreakpoints(Idx),\
+        SetMemoryWatchpoint(Result<Idx,()>),UnsetMemoryWatchpoint(Result<(),()>),\
+        GetMemoryWatchpoints([Option<(Addr,Word)>;MAX_MEMORY_WATCHPOINTS]),GetMaxMemoryWatchpoints(Idx),\
+        SetDepthCondition(Result<Option<UnifiedRange<u64>>,()>),UnsetDepthCondition(Option<UnifiedRange<u64>>),\
+        GetDepth(Result<u64,()>),GetCallStack([Option<(Addr,ProcessorMode)>;MAX_CALL_STACK_DEPTH]),\
+        RunUntilEventAck,RunUntilEvent(Event),Step(Option<Event>),Pause,GetState(State),Reset,\
+        GetError(Option<Error>),\
+        GetGpioStates(GpioPinArr<GpioState>),GetGpioReadings(GpioPinArr<Result<bool,GpioReadError>>),\
+        GetAdcStates(AdcPinArr<AdcState>),GetAdcReadings(AdcPinArr<Result<u8,AdcReadError>>),\
+        GetTimerModes(TimerArr<TimerMode>),GetTimerStates(TimerArr<TimerState>),\
+        GetPwmStates(PwmPinArr<PwmState>),GetPwmConfig(PwmPinArr<u8>),GetClock(Word),\
+        GetDeviceInfo(DeviceInfo),GetProgramMetadata(ProgramMetadata),SetProgramMetadata,\
+        Handshake(HandshakeInfo),\
+        GetMaxCompressedChunkSize(u8),SendCompressedPageChunk(Result<(),PageChunkError>),\
+        GetPageHashes([u64;4]),\
+        GetDisplayPixels([Color;16]),\
+        GetDiskNumSectors(SectorIdx),GetDiskWords(Result<[Word;16],DiskError>),\
+        SetAdcSource(Result<(),AdcMiscError>),\
+        GetPwmConfigs(PwmPinArr<PwmConfig>),\
+        GetTimerStatuses(TimerArr<TimerStatus>),\
+        SetRngSeed(Result<(),RngError>),\
+        SetWatchdogDisabled(Result<(),WatchdogError>),\
+        ResetWith(Result<(),ResetError>),\
+        SetBreakInstructionsEnabled(Result<(),()>),\
+        GetCycleCount(Result<u64,()>),\
+        SetUninitializedReadDetection(Result<(),()>),\
+        SetMpuRegion(Result<(),MpuError>),GetMpuRegion(Result<Option<MpuRegion>,MpuError>),\
+        Nak;\
+    event:\
+        Breakpoint{addr:Addr},MemoryWatch{addr:Addr,data:Word},DepthReached{current_depth:u64},\
+        Error{err:Error},Interrupted,Halted,\
+        SoftwareBreak{addr:Addr},\
+        BreakInstruction{addr:Addr,code:u8};\
+    error:\
+        InvalidGpioWrite(GpioWriteError),InvalidGpioWrites(GpioWriteErrors),\
+        InvalidGpioRead(GpioReadError),InvalidGpioReads(GpioReadErrors),GpioMiscError(GpioMiscError),\
+        InvalidAdcRead(AdcReadError),InvalidAdcReads(AdcReadErrors),AdcMiscError(AdcMiscError),\
+        InputError(InputError),OutputError(OutputError),SystemStackOverflow,\
+        InvalidDisplayAccess(DisplayError),\
+        DiskError(DiskError),\
+        WatchdogExpired,\
+        UninitializedRead{pc:Addr,location:Location}\
+";
+
+/// [`message_set_hash`](super::handshake::message_set_hash) of
+/// [`MESSAGE_SET`].
+pub const MESSAGE_SET_HASH: u64 = 0x26A7_2893_F6EF_1BC0;
+
 
 // This workaround allows us to avoid having a Clone impl on RequestMessage and
 // ResponseMessage which allows us to avoid having a Clone impl on
@@ -261,6 +503,13 @@ impl Clone for RequestMessage {
                             page: unsafe { force_clone(page) }
                         }
                     }
+                    SendCompressedPageChunk { offset, chunk } => {
+                        SendCompressedPageChunk {
+                            #[allow(unsafe_code)]
+                            offset: unsafe { force_clone(offset) },
+                            chunk: *chunk,
+                        }
+                    }
                 }
             };
         }
@@ -302,7 +551,24 @@ impl Clone for RequestMessage {
             GetClock,
             GetDeviceInfo,
             GetProgramMetadata,
-            SetProgramMetadata { metadata }
+            SetProgramMetadata { metadata },
+            Handshake { info },
+            GetMaxCompressedChunkSize,
+            GetPageHashes { first },
+            GetDisplayPixels { first },
+            GetDiskNumSectors,
+            GetDiskWords { sector, first },
+            SetAdcSource { pin, source },
+            GetPwmConfigs,
+            GetTimerStatuses,
+            SetRngSeed { seed },
+            SetWatchdogDisabled { disabled },
+            ResetWith { kind, boot },
+            SetBreakInstructionsEnabled { enabled },
+            GetCycleCount,
+            SetUninitializedReadDetection { enabled },
+            SetMpuRegion { idx, region },
+            GetMpuRegion { idx }
         }
     }
 }
@@ -367,6 +633,25 @@ impl Clone for ResponseMessage {
             GetDeviceInfo(i),
             GetProgramMetadata(m),
             SetProgramMetadata,
+            Handshake(i),
+            GetMaxCompressedChunkSize(s),
+            SendCompressedPageChunk(r),
+            GetPageHashes(h),
+            GetDisplayPixels(p),
+            GetDiskNumSectors(n),
+            GetDiskWords(r),
+            SetAdcSource(r),
+            GetPwmConfigs(c),
+            GetTimerStatuses(s),
+            SetRngSeed(r),
+            SetWatchdogDisabled(r),
+            ResetWith(r),
+            SetBreakInstructionsEnabled(r),
+            GetCycleCount(r),
+            SetUninitializedReadDetection(r),
+            SetMpuRegion(r),
+            GetMpuRegion(r),
+            Nak,
 
             SendPageChunk(r),
             FinishPageWrite(r)
@@ -54,7 +54,10 @@ use super::{Event, State, Control};
 // log crate.
 
 mod messages;
-pub use messages::{RequestMessage, ResponseMessage};
+pub use messages::{RequestMessage, ResponseMessage, MESSAGE_SET, MESSAGE_SET_HASH};
+
+pub mod handshake;
+pub use handshake::{HandshakeInfo, HandshakeError, PROTOCOL_VERSION};
 
 pub mod encoding;
 pub use encoding::{Encode, Decode};
@@ -66,7 +69,7 @@ pub mod futures;
 pub use futures::{EventFutureSharedState, EventFutureSharedStatePorcelain, SimpleEventFutureSharedState, EventFuture};
 
 pub mod controller;
-pub use controller::Controller;
+pub use controller::{Controller, ControllerError, DEFAULT_MAX_RESENDS};
 
 pub mod device;
 pub use device::Device;
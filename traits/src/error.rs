@@ -5,7 +5,9 @@ use super::peripherals::gpio::{
 use super::peripherals::adc::{AdcReadError, AdcReadErrors, AdcMiscError};
 use super::peripherals::input::InputError;
 use super::peripherals::output::OutputError;
-use lc3_isa::Word;
+use super::peripherals::display::DisplayError;
+use super::peripherals::disk::DiskError;
+use lc3_isa::{Addr, Reg, Word};
 
 use core::fmt::Display;
 
@@ -37,9 +39,26 @@ pub enum Error {
     OutputError(OutputError),
 
     SystemStackOverflow,
+
+    InvalidDisplayAccess(DisplayError),
+    DiskError(DiskError),
+    /// The [watchdog](crate::peripherals::watchdog) wasn't kicked in time and
+    /// reset the machine.
+    WatchdogExpired,
+    /// The instruction at `pc` read a register or a word of memory that
+    /// hadn't been loaded or written to yet (see
+    /// [`Control::set_uninitialized_read_detection`](crate::control::Control::set_uninitialized_read_detection)).
+    UninitializedRead { pc: Addr, location: Location },
     ///// TODO: finish
 }
 
+/// Somewhere an [uninitialized read](Error::UninitializedRead) can happen.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Location {
+    Register(Reg),
+    Memory(Addr),
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         use Error::*;
@@ -59,6 +78,14 @@ impl Display for Error {
             OutputError(e) => write!(f, "{}", e),
             InputError(e) => write!(f, "{}", e),
             SystemStackOverflow => write!(f, "Overflowed system stack"),
+            InvalidDisplayAccess(err) =>
+                write!(f, "Attempted to access pixel ({}, {}) which is off the display", (err.0).0, (err.0).1),
+            DiskError(e) => write!(f, "{}", e),
+            WatchdogExpired => write!(f, "The watchdog wasn't kicked in time and reset the machine"),
+            UninitializedRead { pc, location: Location::Register(reg) } =>
+                write!(f, "The instruction at x{:04X} read {} before it was set", pc, reg),
+            UninitializedRead { pc, location: Location::Memory(addr) } =>
+                write!(f, "The instruction at x{:04X} read x{:04X} before it was set", pc, addr),
         }
     }
 }
@@ -97,6 +124,8 @@ err!(AdcReadErrors, Error::InvalidAdcReads);
 err!(AdcMiscError, Error::AdcMiscError);
 err!(InputError, Error::InputError);
 err!(OutputError, Error::OutputError);
+err!(DisplayError, Error::InvalidDisplayAccess);
+err!(DiskError, Error::DiskError);
 // TODO: finish
 
 /// Just some musings; if we go with something like this it won't live here.
@@ -135,6 +164,10 @@ impl From<Error> for ErrorHandlingStrategy {
             InputError(_) => Silent,        // TODO: what to actually do here?
             OutputError(_) => Silent,       // TODO: and here?
             SystemStackOverflow => Silent,
+            InvalidDisplayAccess(_) => DefaultValue(0u16),
+            DiskError(_) => Silent,
+            WatchdogExpired => Silent,
+            UninitializedRead { .. } => Silent,
         }
     }
 }
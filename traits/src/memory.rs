@@ -18,6 +18,15 @@ pub trait Memory: Index<Addr, Output = Word> + IndexMut<Addr, Output = Word> {
         self[addr] = word;
     }
 
+    /// Reads a word from the committed copy of memory (i.e. what `addr` will
+    /// hold after the next reset).
+    ///
+    /// The default just reads the current copy, which is only right for
+    /// memories whose `reset` doesn't undo `write_word`s.
+    fn read_committed_word(&self, addr: Addr) -> Word {
+        self.read_word(addr)
+    }
+
     fn commit_page(&mut self, page_idx: PageIndex, page: &[Word; PAGE_SIZE_IN_WORDS as usize]);
     fn reset(&mut self);
 
@@ -43,6 +52,8 @@ impl IndexMut<Addr> for MemoryStub {
 }
 
 impl Memory for MemoryStub {
+    fn read_committed_word(&self, _addr: Addr) -> Word { 0 }
+
     fn commit_page(&mut self, _page_idx: PageIndex, _page: &[Word; PAGE_SIZE_IN_WORDS as usize]) { }
 
     fn reset(&mut self) { self.0 = 0 }
@@ -13,15 +13,24 @@ use serde::{Deserialize, Serialize};
 #[rustfmt::skip]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[derive(DisplayUsingDebug)]
-pub enum AdcPin { A0, A1, A2, A3, A4, A5 }
+pub enum AdcPin {
+    A0, A1, A2, A3, A4, A5,
+    #[cfg(feature = "adc_pins_8")] A6,
+    #[cfg(feature = "adc_pins_8")] A7,
+}
 
 impl AdcPin {
-    pub const NUM_PINS: usize = 6;
+    pub const NUM_PINS: usize = if cfg!(feature = "adc_pins_8") { 8 } else { 6 };
 }
 
+#[rustfmt::skip]
 pub const ADC_PINS: AdcPinArr<AdcPin> = {
     use AdcPin::*;
-    AdcPinArr([A0, A1, A2, A3, A4, A5])
+    AdcPinArr([
+        A0, A1, A2, A3, A4, A5,
+        #[cfg(feature = "adc_pins_8")] A6,
+        #[cfg(feature = "adc_pins_8")] A7,
+    ])
 }; // TODO: once we get the derive macro, get rid of this.
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -31,17 +40,36 @@ pub enum AdcState {
     Disabled,
 }
 
+/// A generated signal for an ADC pin's readings (see [`Adc::set_source`]).
+///
+/// Periods are in milliseconds and are measured from when the source is set.
+/// A period of 0 holds the signal at its starting value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AdcSource {
+    /// Always reads the same value.
+    Constant(u8),
+    /// Goes from `from` to `to` over each period and then starts over (i.e. a
+    /// sawtooth wave).
+    Ramp { from: u8, to: u8, period_ms: u16 },
+    /// A sine wave between `min` and `max` that starts in the middle, heading
+    /// up.
+    Sine { min: u8, max: u8, period_ms: u16 },
+    /// `high` for the first half of each period and `low` for the second half.
+    Square { low: u8, high: u8, period_ms: u16 },
+    /// A pseudo-random reading in [`min`, `max`] that changes every
+    /// millisecond. The same seed always gives the same sequence of readings.
+    Noise { min: u8, max: u8, seed: u64 },
+}
+
+impl Default for AdcSource {
+    fn default() -> Self {
+        AdcSource::Constant(0)
+    }
+}
+
 impl From<AdcPin> for usize {
     fn from(pin: AdcPin) -> usize {
-        use AdcPin::*;
-        match pin {
-            A0 => 0,
-            A1 => 1,
-            A2 => 2,
-            A3 => 3,
-            A4 => 4,
-            A5 => 5,
-        }
+        pin as usize
     }
 }
 
@@ -107,6 +135,16 @@ pub trait Adc: Default {
         readings
     }
 
+    /// Changes where a pin's readings come from.
+    ///
+    /// This only makes sense for implementations that generate their readings
+    /// (i.e. simulators); the default implementation doesn't support any
+    /// sources and always errors.
+    #[inline]
+    fn set_source(&mut self, _pin: AdcPin, _source: AdcSource) -> Result<(), AdcMiscError> {
+        Err(AdcMiscError)
+    }
+
 }}
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -158,5 +196,9 @@ using_std! {
         fn read(&self, pin: AdcPin) -> Result<u8, AdcReadError> {
             RwLock::read(self).unwrap().read(pin)
         }
+
+        fn set_source(&mut self, pin: AdcPin, source: AdcSource) -> Result<(), AdcMiscError> {
+            RwLock::write(self).unwrap().set_source(pin, source)
+        }
     }
 }
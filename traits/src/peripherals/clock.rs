@@ -73,6 +73,15 @@ pub trait Clock: Default {
     fn get_milliseconds(&self) -> Word;
 
     fn set_milliseconds(&mut self, ms: Word);
+
+    /// Called by the simulator after it executes instructions.
+    ///
+    /// Clocks that keep _virtual_ time (time that's driven by the number of
+    /// instructions executed instead of by a wall clock) advance it here;
+    /// other clocks can ignore this (which is what the default impl does).
+    fn instructions_executed(&self, count: u64) {
+        let _ = count;
+    }
 }}
 
 // TODO: roll this into the macro
@@ -86,5 +95,9 @@ using_std! {
         fn set_milliseconds(&mut self, ms: Word) {
             RwLock::write(self).unwrap().set_milliseconds(ms)
         }
+
+        fn instructions_executed(&self, count: u64) {
+            RwLock::read(self).unwrap().instructions_executed(count)
+        }
     }
 }
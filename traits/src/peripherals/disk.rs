@@ -0,0 +1,132 @@
+//! [`Disk` peripheral trait](Disk) and associated types.
+
+use crate::peripheral_trait;
+
+use lc3_isa::{Addr, Word};
+
+use core::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+/// The number of words in a sector.
+pub const SECTOR_SIZE_IN_WORDS: usize = 256;
+
+/// A sector's worth of words.
+pub type Sector = [Word; SECTOR_SIZE_IN_WORDS];
+
+/// The index of a sector on a disk.
+pub type SectorIdx = Word;
+
+/// The number of words [`Control::get_disk_words`] returns at a time.
+///
+/// [`Control::get_disk_words`]: crate::control::Control::get_disk_words
+pub const DISK_WORDS_PER_MESSAGE: usize = 16;
+
+// Sectors should split evenly into messages.
+sa::const_assert!(SECTOR_SIZE_IN_WORDS % DISK_WORDS_PER_MESSAGE == 0);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DiskError {
+    /// There's no disk.
+    NoDisk,
+    /// The disk doesn't have a sector with this index.
+    SectorOutOfRange(SectorIdx),
+    /// A sector starting at this address would run into (or start in) the
+    /// memory mapped region.
+    InvalidBuffer(Addr),
+    /// This isn't a command the disk knows about.
+    InvalidCommand(Word),
+    /// Something went wrong with the disk image.
+    IoError,
+}
+
+impl Display for DiskError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use DiskError::*;
+
+        match self {
+            NoDisk => write!(fmt, "Attempted to access the disk when there isn't one"),
+            SectorOutOfRange(s) => write!(fmt, "Attempted to access sector {} which is past the end of the disk", s),
+            InvalidBuffer(a) => write!(fmt, "Attempted to transfer a sector to/from {:#06X} which overlaps the memory mapped region", a),
+            InvalidCommand(c) => write!(fmt, "Invalid disk command: {:#06X}", c),
+            IoError => write!(fmt, "I/O error when accessing the disk"),
+        }
+    }
+}
+
+using_std! {
+    impl From<std::io::Error> for DiskError {
+        fn from(_e: std::io::Error) -> DiskError {
+            DiskError::IoError
+        }
+    }
+}
+
+peripheral_trait! {disk,
+/// A [Disk peripheral](Disk) for an LC-3 simulator.
+///
+/// A block device: an array of [sectors](Sector) that are read and written a
+/// whole sector at a time. Accessing a sector past the end of the disk (see
+/// [`num_sectors`](Disk::num_sectors)) should fail with
+/// [`DiskError::SectorOutOfRange`].
+///
+/// Moving sectors between the disk and memory (and raising the completion
+/// interrupt) is the interpreter's job; implementations only deal with the
+/// disk itself.
+///
+/// Implementations that don't actually have a disk (i.e. the
+/// [stub](crate::peripherals::stubs::DiskStub)) should say so with
+/// [`is_present`](Disk::is_present); this is what gets reported in
+/// [`Capabilities::disk`](crate::control::metadata::Capabilities).
+pub trait Disk: Default {
+    fn num_sectors(&self) -> SectorIdx;
+
+    // Must use interior mutability if reading changes anything.
+    fn read_sector(&self, sector: SectorIdx, buf: &mut Sector) -> Result<(), DiskError>;
+    fn write_sector(&mut self, sector: SectorIdx, buf: &Sector) -> Result<(), DiskError>;
+
+    fn is_present(&self) -> bool {
+        true
+    }
+}}
+
+// TODO: roll this into the macro
+using_std! {
+    use std::sync::{Arc, Mutex, RwLock};
+
+    impl<D: Disk> Disk for Arc<RwLock<D>> {
+        fn num_sectors(&self) -> SectorIdx {
+            RwLock::read(self).unwrap().num_sectors()
+        }
+
+        fn read_sector(&self, sector: SectorIdx, buf: &mut Sector) -> Result<(), DiskError> {
+            RwLock::read(self).unwrap().read_sector(sector, buf)
+        }
+
+        fn write_sector(&mut self, sector: SectorIdx, buf: &Sector) -> Result<(), DiskError> {
+            RwLock::write(self).unwrap().write_sector(sector, buf)
+        }
+
+        fn is_present(&self) -> bool {
+            RwLock::read(self).unwrap().is_present()
+        }
+    }
+
+    impl<D: Disk> Disk for Arc<Mutex<D>> {
+        fn num_sectors(&self) -> SectorIdx {
+            Mutex::lock(self).unwrap().num_sectors()
+        }
+
+        fn read_sector(&self, sector: SectorIdx, buf: &mut Sector) -> Result<(), DiskError> {
+            Mutex::lock(self).unwrap().read_sector(sector, buf)
+        }
+
+        fn write_sector(&mut self, sector: SectorIdx, buf: &Sector) -> Result<(), DiskError> {
+            Mutex::lock(self).unwrap().write_sector(sector, buf)
+        }
+
+        fn is_present(&self) -> bool {
+            Mutex::lock(self).unwrap().is_present()
+        }
+    }
+}
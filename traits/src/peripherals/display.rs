@@ -0,0 +1,123 @@
+//! [`Display` peripheral trait](Display) and associated types.
+
+use crate::peripheral_trait;
+
+use lc3_isa::Word;
+
+use serde::{Deserialize, Serialize};
+
+/// The width of the display, in pixels.
+pub const DISPLAY_WIDTH: Word = 128;
+/// The height of the display, in pixels.
+pub const DISPLAY_HEIGHT: Word = 124;
+/// The number of pixels on the display.
+pub const DISPLAY_PIXELS: usize = (DISPLAY_WIDTH as usize) * (DISPLAY_HEIGHT as usize);
+
+/// The number of pixels [`Control::get_display_pixels`] returns at a time.
+///
+/// [`Control::get_display_pixels`]: crate::control::Control::get_display_pixels
+pub const DISPLAY_PIXELS_PER_MESSAGE: usize = 16;
+
+// Rows should split evenly into messages.
+sa::const_assert!((DISPLAY_WIDTH as usize) % DISPLAY_PIXELS_PER_MESSAGE == 0);
+
+/// A 16-bit color: 5 bits of red (bits 14 to 10), 5 bits of green (bits 9 to
+/// 5), and 5 bits of blue (bits 4 to 0). Bit 15 is ignored.
+///
+/// This is the same format the classic LC-3 video memory uses.
+pub type Color = Word;
+
+/// Splits a [`Color`] into its 8-bit red, green, and blue components.
+pub fn color_to_rgb(color: Color) -> [u8; 3] {
+    // Scale 5 bits up to 8 (and make sure 0x1F becomes 0xFF).
+    let scale = |c: Word| {
+        let c = (c & 0x1F) as u8;
+        (c << 3) | (c >> 2)
+    };
+
+    [scale(color >> 10), scale(color >> 5), scale(color)]
+}
+
+/// Packs 8-bit red, green, and blue components into a [`Color`], dropping the
+/// low 3 bits of each.
+pub fn rgb_to_color([r, g, b]: [u8; 3]) -> Color {
+    ((r as Word >> 3) << 10) | ((g as Word >> 3) << 5) | (b as Word >> 3)
+}
+
+/// Attempted to access the pixel at this (x, y) position, which is off the
+/// display.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DisplayError(pub (Word, Word));
+
+peripheral_trait! {display,
+/// A [Display peripheral](Display) for an LC-3 simulator.
+///
+/// A [`DISPLAY_WIDTH`] × [`DISPLAY_HEIGHT`] framebuffer of 16-bit
+/// [colors](Color). `(0, 0)` is the top left corner of the display; x increases
+/// to the right and y increases going down.
+///
+/// Accessing a pixel that's off the display should fail with a
+/// [`DisplayError`].
+///
+/// Implementations that don't actually have a display (i.e. the
+/// [stub](crate::peripherals::stubs::DisplayStub)) should say so with
+/// [`is_present`](Display::is_present); this is what gets reported in
+/// [`Capabilities::display`](crate::control::metadata::Capabilities).
+pub trait Display: Default {
+    fn set_pixel(&mut self, x: Word, y: Word, color: Color) -> Result<(), DisplayError>;
+    fn get_pixel(&self, x: Word, y: Word) -> Result<Color, DisplayError>;
+
+    /// Sets every pixel on the display to `color`.
+    fn fill(&mut self, color: Color) {
+        for y in 0..DISPLAY_HEIGHT {
+            for x in 0..DISPLAY_WIDTH {
+                self.set_pixel(x, y, color).unwrap()
+            }
+        }
+    }
+
+    fn is_present(&self) -> bool {
+        true
+    }
+}}
+
+// TODO: roll this into the macro
+using_std! {
+    use std::sync::{Arc, Mutex, RwLock};
+
+    impl<D: Display> Display for Arc<RwLock<D>> {
+        fn set_pixel(&mut self, x: Word, y: Word, color: Color) -> Result<(), DisplayError> {
+            RwLock::write(self).unwrap().set_pixel(x, y, color)
+        }
+
+        fn get_pixel(&self, x: Word, y: Word) -> Result<Color, DisplayError> {
+            RwLock::read(self).unwrap().get_pixel(x, y)
+        }
+
+        fn fill(&mut self, color: Color) {
+            RwLock::write(self).unwrap().fill(color)
+        }
+
+        fn is_present(&self) -> bool {
+            RwLock::read(self).unwrap().is_present()
+        }
+    }
+
+    impl<D: Display> Display for Arc<Mutex<D>> {
+        fn set_pixel(&mut self, x: Word, y: Word, color: Color) -> Result<(), DisplayError> {
+            Mutex::lock(self).unwrap().set_pixel(x, y, color)
+        }
+
+        fn get_pixel(&self, x: Word, y: Word) -> Result<Color, DisplayError> {
+            Mutex::lock(self).unwrap().get_pixel(x, y)
+        }
+
+        fn fill(&mut self, color: Color) {
+            Mutex::lock(self).unwrap().fill(color)
+        }
+
+        fn is_present(&self) -> bool {
+            Mutex::lock(self).unwrap().is_present()
+        }
+    }
+}
@@ -25,32 +25,43 @@ use serde::{Deserialize, Serialize};
 #[rustfmt::skip]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[derive(DisplayUsingDebug)]
-pub enum GpioPin { G0, G1, G2, G3, G4, G5, G6, G7 }
+pub enum GpioPin {
+    G0, G1, G2, G3, G4, G5, G6, G7,
+    #[cfg(feature = "gpio_pins_16")] G8,
+    #[cfg(feature = "gpio_pins_16")] G9,
+    #[cfg(feature = "gpio_pins_16")] G10,
+    #[cfg(feature = "gpio_pins_16")] G11,
+    #[cfg(feature = "gpio_pins_16")] G12,
+    #[cfg(feature = "gpio_pins_16")] G13,
+    #[cfg(feature = "gpio_pins_16")] G14,
+    #[cfg(feature = "gpio_pins_16")] G15,
+}
 
 impl GpioPin {
-    pub const NUM_PINS: usize = 8; // G0 - G7; TODO: derive macro (also get it to impl Display)
+    // G0 - G7 (or G15); TODO: derive macro (also get it to impl Display)
+    pub const NUM_PINS: usize = if cfg!(feature = "gpio_pins_16") { 16 } else { 8 };
 }
 
+#[rustfmt::skip]
 pub const GPIO_PINS: GpioPinArr<GpioPin> = {
     use GpioPin::*;
-    GpioPinArr([G0, G1, G2, G3, G4, G5, G6, G7])
+    GpioPinArr([
+        G0, G1, G2, G3, G4, G5, G6, G7,
+        #[cfg(feature = "gpio_pins_16")] G8,
+        #[cfg(feature = "gpio_pins_16")] G9,
+        #[cfg(feature = "gpio_pins_16")] G10,
+        #[cfg(feature = "gpio_pins_16")] G11,
+        #[cfg(feature = "gpio_pins_16")] G12,
+        #[cfg(feature = "gpio_pins_16")] G13,
+        #[cfg(feature = "gpio_pins_16")] G14,
+        #[cfg(feature = "gpio_pins_16")] G15,
+    ])
 }; // TODO: once we get the derive macro, get rid of this.
 
-// TODO: macro!!
+// The variants are in order so their discriminants are their indexes.
 impl From<GpioPin> for usize {
     fn from(pin: GpioPin) -> usize {
-        use GpioPin::*;
-
-        match pin {
-            G0 => 0,
-            G1 => 1,
-            G2 => 2,
-            G3 => 3,
-            G4 => 4,
-            G5 => 5,
-            G6 => 6,
-            G7 => 7,
-        }
+        pin as usize
     }
 }
 
@@ -69,6 +80,56 @@ pub enum GpioState {
     Disabled,
 }
 
+/// What makes a pin that's in [interrupt](GpioState::Interrupt) mode fire an
+/// interrupt.
+///
+/// Edge modes fire once per (matching) transition. Level modes keep the
+/// interrupt pending for as long as the pin stays at that level: after the
+/// interrupt is serviced it fires again unless the pin has changed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(DisplayUsingDebug)]
+pub enum GpioInterruptMode {
+    RisingEdge,
+    FallingEdge,
+    BothEdges,
+    LevelHigh,
+    LevelLow,
+    // 000 -> Rising Edge
+    // 001 -> Falling Edge
+    // 010 -> Both Edges
+    // 011 -> Level High
+    // 100 -> Level Low
+}
+
+impl GpioInterruptMode {
+    pub const NUM_MODES: usize = 5;
+
+    /// Whether a pin going from `prev` to `new` (or staying at `new`, for the
+    /// level modes) should fire an interrupt.
+    pub fn triggered_by(self, prev: bool, new: bool) -> bool {
+        use GpioInterruptMode::*;
+
+        match self {
+            RisingEdge => !prev && new,
+            FallingEdge => prev && !new,
+            BothEdges => prev != new,
+            LevelHigh => new,
+            LevelLow => !new,
+        }
+    }
+
+    pub fn is_level_triggered(self) -> bool {
+        matches!(self, GpioInterruptMode::LevelHigh | GpioInterruptMode::LevelLow)
+    }
+}
+
+/// Rising edge, to match what pins did before there were interrupt modes.
+impl Default for GpioInterruptMode {
+    fn default() -> Self {
+        GpioInterruptMode::RisingEdge
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct GpioPinArr<T>(pub [T; GpioPin::NUM_PINS]);
 
@@ -142,7 +203,9 @@ peripheral_trait! {gpio,
 /// GPIO access trait.
 ///
 /// Implementations of this trait must provide digital read, digital write, and rising
-/// edge trigger interrupt functionality for 8 GPIO pins which we'll call G0 - G7.
+/// edge trigger interrupt functionality (see [below](#interrupt-modes-and-debouncing)
+/// for other interrupt modes) for [`GpioPin::NUM_PINS`] GPIO pins which we'll call G0 -
+/// G7 (or G15, with the `gpio_pins_16` feature).
 ///
 /// Additionally, implementors of this trait must also provide an implementation of
 /// [`Default`](core::default::Default). Implementors are also free (and encouraged!) to
@@ -196,6 +259,23 @@ peripheral_trait! {gpio,
 /// rising edge *if the pin is in [interrupt](GpioState::Interrupt) mode* (not just if
 /// a handler function has been provided).
 ///
+/// ### Interrupt Modes and Debouncing
+/// By default pins in interrupt mode fire on rising edges. Implementations
+/// that can do more should override
+/// [`set_interrupt_mode`](Gpio::set_interrupt_mode) (and
+/// [`get_interrupt_mode`](Gpio::get_interrupt_mode)) to support the other
+/// [`GpioInterruptMode`]s and [`set_debounce`](Gpio::set_debounce) (and
+/// [`get_debounce`](Gpio::get_debounce)) to ignore changes on a pin that happen
+/// within some number of milliseconds of the last change that was accepted.
+///
+/// The default implementations only accept rising edge mode and no debouncing
+/// and error (with a [`GpioMiscError`]) otherwise. Both settings are per pin and
+/// should be kept across pin state changes.
+///
+/// For the level modes, implementations should raise the interrupt again in
+/// [`reset_interrupt_flag`](Gpio::reset_interrupt_flag) if the pin is still at
+/// the level that triggers the interrupt.
+///
 /// ### Default Function Implementations
 /// The trait provides naïve default implementations of
 /// [`get_states`](Gpio::get_states), [`read_all`](Gpio::read_all), and
@@ -270,6 +350,26 @@ pub trait Gpio<'a>: Default {
     fn interrupts_enabled(&self, pin: GpioPin) -> bool {
         matches!(self.get_state(pin), GpioState::Interrupt)
     }
+
+    #[inline]
+    fn set_interrupt_mode(&mut self, _pin: GpioPin, mode: GpioInterruptMode) -> Result<(), GpioMiscError> {
+        if mode == GpioInterruptMode::RisingEdge { Ok(()) } else { Err(GpioMiscError) }
+    }
+    #[inline]
+    fn get_interrupt_mode(&self, _pin: GpioPin) -> GpioInterruptMode {
+        GpioInterruptMode::RisingEdge
+    }
+
+    /// `ms` is the number of milliseconds after an accepted change during which
+    /// further changes to the pin are ignored; 0 turns debouncing off.
+    #[inline]
+    fn set_debounce(&mut self, _pin: GpioPin, ms: u8) -> Result<(), GpioMiscError> {
+        if ms == 0 { Ok(()) } else { Err(GpioMiscError) }
+    }
+    #[inline]
+    fn get_debounce(&self, _pin: GpioPin) -> u8 {
+        0
+    }
 }}
 
 impl TryFrom<GpioPinArr<Result<bool, GpioReadError>>> for GpioReadErrors {
@@ -362,5 +462,21 @@ using_std! {
         fn interrupts_enabled(&self, pin: GpioPin) -> bool {
             RwLock::read(self).unwrap().interrupts_enabled(pin)
         }
+
+        fn set_interrupt_mode(&mut self, pin: GpioPin, mode: GpioInterruptMode) -> Result<(), GpioMiscError> {
+            RwLock::write(self).unwrap().set_interrupt_mode(pin, mode)
+        }
+
+        fn get_interrupt_mode(&self, pin: GpioPin) -> GpioInterruptMode {
+            RwLock::read(self).unwrap().get_interrupt_mode(pin)
+        }
+
+        fn set_debounce(&mut self, pin: GpioPin, ms: u8) -> Result<(), GpioMiscError> {
+            RwLock::write(self).unwrap().set_debounce(pin, ms)
+        }
+
+        fn get_debounce(&self, pin: GpioPin) -> u8 {
+            RwLock::read(self).unwrap().get_debounce(pin)
+        }
     }
 }
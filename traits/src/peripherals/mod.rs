@@ -1,5 +1,24 @@
 //! Peripherals! The [`Peripherals` supertrait](peripherals::Peripherals) and the rest of the
 //! peripheral and device traits.
+//!
+//! ## Peripheral Counts
+//! By default there are 8 [GPIO pins](gpio::GpioPin), 6 [ADC pins](adc::AdcPin),
+//! 2 [PWM pins](pwm::PwmPin), and 2 [timers](timers::TimerId). Boards with more
+//! I/O can raise these with this crate's cargo features:
+//!
+//! | Feature        | Count                                        |
+//! | :------------- | :------------------------------------------- |
+//! | `gpio_pins_16` | 16 GPIO pins ([`G0`](gpio::GpioPin::G0) - `G15`) |
+//! | `adc_pins_8`   | 8 ADC pins ([`A0`](adc::AdcPin::A0) - `A7`)      |
+//! | `pwm_pins_4`   | 4 PWM pins ([`P0`](pwm::PwmPin::P0) - `P3`)      |
+//! | `timers_4`     | 4 timers ([`T0`](timers::TimerId::T0) - `T3`)    |
+//!
+//! Everything else (the `NUM_*` constants, the `*Arr` types, the simulator's
+//! memory map and interrupt vectors, the OS's bounds checks, and the RPC
+//! messages) follows from these. Since the wire format depends on the counts,
+//! both ends of a [`Control`](crate::control::Control) connection need to be
+//! built with the same features; the [handshake](crate::control::rpc) checks
+//! this.
 
 pub mod adc;
 pub mod clock;
@@ -8,6 +27,11 @@ pub mod pwm;
 pub mod timers;
 pub mod input;
 pub mod output;
+pub mod display;
+pub mod disk;
+pub mod uart;
+pub mod rng;
+pub mod watchdog;
 
 pub use gpio::Gpio;
 pub use adc::Adc;
@@ -16,6 +40,11 @@ pub use timers::Timers;
 pub use clock::Clock;
 pub use input::Input;
 pub use output::Output;
+pub use display::Display;
+pub use disk::Disk;
+pub use uart::Uart;
+pub use rng::Rng;
+pub use watchdog::Watchdog;
 
 pub mod stubs;
 
@@ -34,12 +63,12 @@ use core::marker::PhantomData;
 // }
 
 pub trait Peripherals<'int>:
-    Gpio<'int> + Adc + Pwm + Timers<'int> + Clock + Input<'int> + Output<'int>
+    Gpio<'int> + Adc + Pwm + Timers<'int> + Clock + Input<'int> + Output<'int> + Display + Disk + Uart + Rng + Watchdog
 {
     fn init(&mut self);
 }
 
-pub struct PeripheralSet<'int, G, A, P, T, C, I, O/*, GW, AW, PW, TW, CW, IW, OW*/>
+pub struct PeripheralSet<'int, G, A, P, T, C, I, O, D, B, U, R, W/*, GW, AW, PW, TW, CW, IW, OW*/>
 where
     G: Gpio<'int>,
     A: Adc,
@@ -48,6 +77,11 @@ where
     C: Clock,
     I: Input<'int>,
     O: Output<'int>,
+    D: Display,
+    B: Disk,
+    U: Uart,
+    R: Rng,
+    W: Watchdog,
     // GW: 'p + DerefOrOwned<G>,
     // AW: 'p + DerefOrOwned<A>,
     // PW: 'p + DerefOrOwned<P>,
@@ -70,13 +104,18 @@ where
     clock: C,
     input: I,
     output: O,
+    display: D,
+    disk: B,
+    uart: U,
+    rng: R,
+    watchdog: W,
     _marker: PhantomData<&'int ()>,
 }
 
 // TODO: is default a supertrait requirement or just an additional bound here
 // (as in, if all your things implement default, we'll give you a default
 // otherwise no).
-impl<'p, G, A, P, T, C, I, O> Default for PeripheralSet<'p, G, A, P, T, C, I, O/*, G, A, P, T, C, I, O*/>
+impl<'p, G, A, P, T, C, I, O, D, B, U, R, W> Default for PeripheralSet<'p, G, A, P, T, C, I, O, D, B, U, R, W/*, G, A, P, T, C, I, O*/>
 where
     G: Gpio<'p>,
     A: Adc,
@@ -85,6 +124,11 @@ where
     C: Clock,
     I: Input<'p>,
     O: Output<'p>,
+    D: Display,
+    B: Disk,
+    U: Uart,
+    R: Rng,
+    W: Watchdog,
 {
     fn default() -> Self {
         Self {
@@ -95,12 +139,17 @@ where
             clock: C::default(),
             input: I::default(),
             output: O::default(),
+            display: D::default(),
+            disk: B::default(),
+            uart: U::default(),
+            rng: R::default(),
+            watchdog: W::default(),
             _marker: PhantomData,
         }
     }
 }
 
-impl<'p, G, A, P, T, C, I, O/*, GW, AW, PW, TW, CW, IW, OW*/> PeripheralSet<'p, G, A, P, T, C, I, O/*, GW, AW, PW, TW, CW, IW, OW*/>
+impl<'p, G, A, P, T, C, I, O, D, B, U, R, W/*, GW, AW, PW, TW, CW, IW, OW*/> PeripheralSet<'p, G, A, P, T, C, I, O, D, B, U, R, W/*, GW, AW, PW, TW, CW, IW, OW*/>
 where
     G: Gpio<'p>,
     A: Adc,
@@ -109,6 +158,11 @@ where
     C: Clock,
     I: Input<'p>,
     O: Output<'p>,
+    D: Display,
+    B: Disk,
+    U: Uart,
+    R: Rng,
+    W: Watchdog,
     // GW: 'p + DerefOrOwned<G>,
     // AW: 'p + DerefOrOwned<A>,
     // PW: 'p + DerefOrOwned<P>,
@@ -117,7 +171,7 @@ where
     // IW: 'p + DerefOrOwned<I>,
     // OW: 'p + DerefOrOwned<O>,
 {
-    pub fn new(gpio: G, adc: A, pwm: P, timers: T, clock: C, input: I, output: O) -> Self {
+    pub fn new(gpio: G, adc: A, pwm: P, timers: T, clock: C, input: I, output: O, display: D, disk: B, uart: U, rng: R, watchdog: W) -> Self {
         Self {
             gpio,
             adc,
@@ -126,6 +180,11 @@ where
             clock,
             input,
             output,
+            display,
+            disk,
+            uart,
+            rng,
+            watchdog,
             _marker: PhantomData,
         }
     }
@@ -157,6 +216,26 @@ where
     pub fn get_output(&self) -> &O {
         &self.output
     }
+
+    pub fn get_display(&self) -> &D {
+        &self.display
+    }
+
+    pub fn get_disk(&self) -> &B {
+        &self.disk
+    }
+
+    pub fn get_uart(&self) -> &U {
+        &self.uart
+    }
+
+    pub fn get_rng(&self) -> &R {
+        &self.rng
+    }
+
+    pub fn get_watchdog(&self) -> &W {
+        &self.watchdog
+    }
 }
 
 // enum WrapperType {
@@ -235,7 +314,7 @@ macro_rules! borrow_impl {
 #[macro_export]
 macro_rules! peripheral_set_impl {
     ($trait:ty $(| $lifetime:lifetime |)?, { $($rest:tt)* }) => {
-        impl<$($lifetime,)? 'p, G, A, P, T, C, I, O> $trait for $crate::peripherals::PeripheralSet<'p, G, A, P, T, C, I, O/*, G, A, P, T, C, I, O*/>
+        impl<$($lifetime,)? 'p, G, A, P, T, C, I, O, D, B, U, R, W> $trait for $crate::peripherals::PeripheralSet<'p, G, A, P, T, C, I, O, D, B, U, R, W/*, G, A, P, T, C, I, O*/>
         where
             $($lifetime: 'p,)?
             G: $crate::peripherals::gpio::Gpio<'p>,
@@ -245,6 +324,11 @@ macro_rules! peripheral_set_impl {
             C: $crate::peripherals::clock::Clock,
             I: $crate::peripherals::input::Input<'p>,
             O: $crate::peripherals::output::Output<'p>,
+            D: $crate::peripherals::display::Display,
+            B: $crate::peripherals::disk::Disk,
+            U: $crate::peripherals::uart::Uart,
+            R: $crate::peripherals::rng::Rng,
+            W: $crate::peripherals::watchdog::Watchdog,
         { $($rest)* }
     };
 }
@@ -253,7 +337,7 @@ macro_rules! peripheral_set_impl {
 // #[macro_export]
 // macro_rules! peripheral_deref_set_impl {
 //     ($trait:ty $(| $lifetime:lifetime |)?, { $($rest:tt)* }) => {
-//         impl<$($lifetime,)? 'p, G, A, P, T, C, I, O, GInner, AInner, PInner, TInner, CInner, IInner, OInner> $trait for $crate::peripherals::PeripheralSet<'p, G, A, P, T, C, I, O>
+//         impl<$($lifetime,)? 'p, G, A, P, T, C, I, O, GInner, AInner, PInner, TInner, CInner, IInner, OInner> $trait for $crate::peripherals::PeripheralSet<'p, G, A, P, T, C, I, O, D>
 //         where
 //             $($lifetime: 'p,)?
 //             G: 'p + $crate::peripherals::gpio::Gpio<'p>,
@@ -357,7 +441,7 @@ macro_rules! func_sig {
     ($(+($indir:tt))?  $(%($i_im:ident, $i_mut:ident))? $($nom:ident)?, ) => {};
 }
 
-impl<'p, G, A, P, T, C, I, O> Peripherals<'p> for PeripheralSet<'p, G, A, P, T, C, I, O/*, G, A, P, T, C, I, O*/>
+impl<'p, G, A, P, T, C, I, O, D, B, U, R, W> Peripherals<'p> for PeripheralSet<'p, G, A, P, T, C, I, O, D, B, U, R, W/*, G, A, P, T, C, I, O*/>
 where
     G: Gpio<'p>,
     A: Adc,
@@ -366,13 +450,18 @@ where
     C: Clock,
     I: Input<'p>,
     O: Output<'p>,
+    D: Display,
+    B: Disk,
+    U: Uart,
+    R: Rng,
+    W: Watchdog,
 {
     fn init(&mut self) {}
 }
 
 use crate::control::{Snapshot, SnapshotError};
 
-impl<'p, G, A, P, T, C, I, O> Snapshot for PeripheralSet<'p, G, A, P, T, C, I, O>
+impl<'p, G, A, P, T, C, I, O, D, B, U, R, W> Snapshot for PeripheralSet<'p, G, A, P, T, C, I, O, D, B, U, R, W>
 where
     G: Snapshot + Gpio<'p>,
     A: Snapshot + Adc,
@@ -381,6 +470,11 @@ where
     C: Snapshot + Clock,
     I: Snapshot + Input<'p>,
     O: Snapshot + Output<'p>,
+    D: Snapshot + Display,
+    B: Snapshot + Disk,
+    U: Snapshot + Uart,
+    R: Snapshot + Rng,
+    W: Snapshot + Watchdog,
 
     // This shouldn't be needed since, in order to impl Snapshot your Err type has to
     // implement Into<SnapshotError>.
@@ -391,6 +485,11 @@ where
     SnapshotError: From<<C as Snapshot>::Err>,
     SnapshotError: From<<I as Snapshot>::Err>,
     SnapshotError: From<<O as Snapshot>::Err>,
+    SnapshotError: From<<D as Snapshot>::Err>,
+    SnapshotError: From<<B as Snapshot>::Err>,
+    SnapshotError: From<<U as Snapshot>::Err>,
+    SnapshotError: From<<R as Snapshot>::Err>,
+    SnapshotError: From<<W as Snapshot>::Err>,
 {
     type Snap = (
         <G as Snapshot>::Snap,
@@ -400,6 +499,11 @@ where
         <C as Snapshot>::Snap,
         <I as Snapshot>::Snap,
         <O as Snapshot>::Snap,
+        <D as Snapshot>::Snap,
+        <B as Snapshot>::Snap,
+        <U as Snapshot>::Snap,
+        <R as Snapshot>::Snap,
+        <W as Snapshot>::Snap,
     );
 
     type Err = SnapshotError; // TODO: report which thing failed? make it part of the SnapshotError type?
@@ -413,11 +517,16 @@ where
             self.clock.record()?,
             self.input.record()?,
             self.output.record()?,
+            self.display.record()?,
+            self.disk.record()?,
+            self.uart.record()?,
+            self.rng.record()?,
+            self.watchdog.record()?,
         ))
     }
 
     fn restore(&mut self, snap: Self::Snap) -> Result<(), Self::Err> {
-        let (g, a, p, t, c, i, o) = snap;
+        let (g, a, p, t, c, i, o, d, b, u, r, w) = snap;
 
         self.gpio.restore(g)?;
         self.adc.restore(a)?;
@@ -426,6 +535,11 @@ where
         self.clock.restore(c)?;
         self.input.restore(i)?;
         self.output.restore(o)?;
+        self.display.restore(d)?;
+        self.disk.restore(b)?;
+        self.uart.restore(u)?;
+        self.rng.restore(r)?;
+        self.watchdog.restore(w)?;
 
         Ok(())
     }
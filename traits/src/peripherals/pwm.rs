@@ -12,26 +12,31 @@ use serde::{Deserialize, Serialize};
 #[rustfmt::skip]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[derive(DisplayUsingDebug)]
-pub enum PwmPin { P0, P1 }
+pub enum PwmPin {
+    P0, P1,
+    #[cfg(feature = "pwm_pins_4")] P2,
+    #[cfg(feature = "pwm_pins_4")] P3,
+}
 
 // TODO: remove once the derive macro happens...
 impl PwmPin {
-    pub const NUM_PINS: usize = 2; // P0 - P1
+    pub const NUM_PINS: usize = if cfg!(feature = "pwm_pins_4") { 4 } else { 2 }; // P0 - P1 (or P3)
 }
 
 impl From<PwmPin> for usize {
     fn from(pin: PwmPin) -> usize {
-        use PwmPin::*;
-        match pin {
-            P0 => 0,
-            P1 => 1,
-        }
+        pin as usize
     }
 }
 
+#[rustfmt::skip]
 pub const PWM_PINS: PwmPinArr<PwmPin> = {
     use PwmPin::*;
-    PwmPinArr([P0, P1])
+    PwmPinArr([
+        P0, P1,
+        #[cfg(feature = "pwm_pins_4")] P2,
+        #[cfg(feature = "pwm_pins_4")] P3,
+    ])
 }; // TODO: save us, derive macro
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -40,6 +45,55 @@ pub enum PwmState {
     Disabled,
 }
 
+/// A PWM pin's full configuration: whether it's enabled and, if it is, the
+/// period of its wave (in milliseconds) and its duty cycle.
+///
+/// A pin with duty cycle `d` is high for the first `d / 255`ths of each
+/// period and low for the rest (so a duty cycle of 0 is always low and one of
+/// 255 is always high).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PwmConfig {
+    Enabled { period_ms: NonZeroU8, duty_cycle: PwmDutyCycle },
+    Disabled,
+}
+
+impl PwmConfig {
+    pub fn new(state: PwmState, duty_cycle: PwmDutyCycle) -> Self {
+        match state {
+            PwmState::Enabled(period_ms) => PwmConfig::Enabled { period_ms, duty_cycle },
+            PwmState::Disabled => PwmConfig::Disabled,
+        }
+    }
+
+    pub fn state(&self) -> PwmState {
+        match *self {
+            PwmConfig::Enabled { period_ms, .. } => PwmState::Enabled(period_ms),
+            PwmConfig::Disabled => PwmState::Disabled,
+        }
+    }
+
+    /// The length of one period and how much of it the pin is high for, in
+    /// ticks of a clock that runs at `ticks_per_ms`, or `None` if the pin is
+    /// disabled.
+    pub fn period_and_high_time(&self, ticks_per_ms: u64) -> Option<(u64, u64)> {
+        match *self {
+            PwmConfig::Enabled { period_ms, duty_cycle } => {
+                let period = period_ms.get() as u64 * ticks_per_ms;
+                let high = period * duty_cycle as u64 / PwmDutyCycle::max_value() as u64;
+
+                Some((period, high))
+            }
+            PwmConfig::Disabled => None,
+        }
+    }
+}
+
+impl Default for PwmConfig {
+    fn default() -> Self {
+        PwmConfig::Disabled
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PwmPinArr<T>(pub [T; PwmPin::NUM_PINS]);
 
@@ -101,6 +155,33 @@ pub trait Pwm: Default {
 
         duty_cycles
     }
+
+    #[inline]
+    fn get_config(&self, pin: PwmPin) -> PwmConfig {
+        PwmConfig::new(self.get_state(pin), self.get_duty_cycle(pin))
+    }
+    #[inline]
+    fn get_configs(&self) -> PwmPinArr<PwmConfig> {
+        let mut configs = PwmPinArr([PwmConfig::Disabled; PwmPin::NUM_PINS]);
+
+        PWM_PINS
+            .iter()
+            .for_each(|p| configs[*p] = self.get_config(*p));
+
+        configs
+    }
+
+    /// Sets a pin's period and duty cycle at once.
+    ///
+    /// Disabling a pin this way leaves its duty cycle alone.
+    #[inline]
+    fn set_config(&mut self, pin: PwmPin, config: PwmConfig) {
+        if let PwmConfig::Enabled { duty_cycle, .. } = config {
+            self.set_duty_cycle(pin, duty_cycle);
+        }
+
+        self.set_state(pin, config.state());
+    }
 }}
 
 
@@ -0,0 +1,95 @@
+//! [`Rng` peripheral trait](Rng) and associated types.
+
+use crate::peripheral_trait;
+
+use lc3_isa::Word;
+
+use core::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RngError {
+    /// There's no random number generator.
+    NoRng,
+    /// The source of entropy couldn't be read from.
+    EntropyUnavailable,
+}
+
+impl Display for RngError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use RngError::*;
+
+        match self {
+            NoRng => write!(fmt, "Attempted to use the random number generator when there isn't one"),
+            EntropyUnavailable => write!(fmt, "Couldn't get entropy for the random number generator"),
+        }
+    }
+}
+
+peripheral_trait! {rng,
+/// A [random number generator peripheral](Rng) for an LC-3 simulator.
+///
+/// Implementations either produce numbers from some source of entropy or, once
+/// they've been [seeded](Rng::set_seed), produce a sequence that's entirely
+/// determined by the seed (so that programs that use it can be tested).
+///
+/// Implementations that don't actually have a random number generator (i.e.
+/// the [stub](crate::peripherals::stubs::RngStub)) should say so with
+/// [`is_present`](Rng::is_present).
+pub trait Rng: Default {
+    // Must use interior mutability.
+    fn next_word(&self) -> Result<Word, RngError>;
+
+    /// Switches to producing the sequence determined by `seed`; seeding twice
+    /// with the same seed gives the same sequence both times.
+    fn set_seed(&mut self, seed: u64) -> Result<(), RngError>;
+    /// Whether the numbers produced come from a seed (rather than from a
+    /// source of entropy).
+    fn is_seeded(&self) -> bool;
+
+    fn is_present(&self) -> bool {
+        true
+    }
+}}
+
+// TODO: roll this into the macro
+using_std! {
+    use std::sync::{Arc, Mutex, RwLock};
+
+    impl<R: Rng> Rng for Arc<RwLock<R>> {
+        fn next_word(&self) -> Result<Word, RngError> {
+            RwLock::read(self).unwrap().next_word()
+        }
+
+        fn set_seed(&mut self, seed: u64) -> Result<(), RngError> {
+            RwLock::write(self).unwrap().set_seed(seed)
+        }
+
+        fn is_seeded(&self) -> bool {
+            RwLock::read(self).unwrap().is_seeded()
+        }
+
+        fn is_present(&self) -> bool {
+            RwLock::read(self).unwrap().is_present()
+        }
+    }
+
+    impl<R: Rng> Rng for Arc<Mutex<R>> {
+        fn next_word(&self) -> Result<Word, RngError> {
+            Mutex::lock(self).unwrap().next_word()
+        }
+
+        fn set_seed(&mut self, seed: u64) -> Result<(), RngError> {
+            Mutex::lock(self).unwrap().set_seed(seed)
+        }
+
+        fn is_seeded(&self) -> bool {
+            Mutex::lock(self).unwrap().is_seeded()
+        }
+
+        fn is_present(&self) -> bool {
+            Mutex::lock(self).unwrap().is_present()
+        }
+    }
+}
@@ -2,7 +2,7 @@
 //! which the peripherals aren't used (or actual functionality isn't desired).
 
 use lc3_isa::Word;
-use super::{Gpio, Adc, Pwm, Timers, Clock, Input, Output, PeripheralSet};
+use super::{Gpio, Adc, Pwm, Timers, Clock, Input, Output, Display, Disk, Uart, Rng, Watchdog, PeripheralSet};
 use core::sync::atomic::AtomicBool;
 
 pub type PeripheralsStub<'s> = PeripheralSet<
@@ -14,6 +14,11 @@ pub type PeripheralsStub<'s> = PeripheralSet<
     ClockStub,
     InputStub,
     OutputStub,
+    DisplayStub,
+    DiskStub,
+    UartStub,
+    RngStub,
+    WatchdogStub,
 >;
 
 
@@ -113,3 +118,88 @@ impl<'a> Output<'a> for OutputStub {
     fn set_interrupt_enable_bit(&mut self, _bit: bool) { }
     fn interrupts_enabled(&self) -> bool { false }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct DisplayStub;
+
+use super::display::{Color, DisplayError};
+impl Display for DisplayStub {
+    fn set_pixel(&mut self, x: Word, y: Word, _color: Color) -> Result<(), DisplayError> { Err(DisplayError((x, y))) }
+    fn get_pixel(&self, x: Word, y: Word) -> Result<Color, DisplayError> { Err(DisplayError((x, y))) }
+
+    fn fill(&mut self, _color: Color) { }
+    fn is_present(&self) -> bool { false }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct DiskStub;
+
+use super::disk::{DiskError, Sector, SectorIdx};
+impl Disk for DiskStub {
+    fn num_sectors(&self) -> SectorIdx { 0 }
+
+    fn read_sector(&self, _sector: SectorIdx, _buf: &mut Sector) -> Result<(), DiskError> { Err(DiskError::NoDisk) }
+    fn write_sector(&mut self, _sector: SectorIdx, _buf: &Sector) -> Result<(), DiskError> { Err(DiskError::NoDisk) }
+
+    fn is_present(&self) -> bool { false }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct UartStub;
+
+use super::uart::UartError;
+impl Uart for UartStub {
+    fn transmit(&mut self, _byte: u8) -> Result<(), UartError> { Err(UartError::NoUart) }
+    fn receive(&self) -> Result<u8, UartError> { Err(UartError::NoUart) }
+
+    fn rx_count(&self) -> usize { 0 }
+    fn tx_count(&self) -> usize { 0 }
+
+    fn overrun(&self) -> bool { false }
+    fn clear_overrun(&mut self) { }
+
+    fn set_rx_interrupt_enable_bit(&mut self, _bit: bool) { }
+    fn rx_interrupt_enabled(&self) -> bool { false }
+    fn set_tx_interrupt_enable_bit(&mut self, _bit: bool) { }
+    fn tx_interrupt_enabled(&self) -> bool { false }
+
+    fn is_present(&self) -> bool { false }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct RngStub;
+
+use super::rng::RngError;
+impl Rng for RngStub {
+    fn next_word(&self) -> Result<Word, RngError> { Err(RngError::NoRng) }
+
+    fn set_seed(&mut self, _seed: u64) -> Result<(), RngError> { Err(RngError::NoRng) }
+    fn is_seeded(&self) -> bool { false }
+
+    fn is_present(&self) -> bool { false }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct WatchdogStub;
+
+impl Watchdog for WatchdogStub {
+    fn set_timeout(&mut self, _ms: Word) { }
+    fn get_timeout(&self) -> Word { 0 }
+
+    fn kick(&mut self) { }
+    fn remaining(&self) -> Word { 0 }
+    fn expirations(&self) -> u16 { 0 }
+
+    fn set_interrupt_enable_bit(&mut self, _bit: bool) { }
+    fn interrupt_enabled(&self) -> bool { false }
+    fn interrupt_occurred(&self) -> bool { false }
+    fn reset_interrupt_flag(&mut self) { }
+
+    fn caused_reset(&self) -> bool { false }
+    fn set_caused_reset(&mut self, _caused: bool) { }
+
+    fn set_disabled_by_debugger(&mut self, _disabled: bool) { }
+    fn disabled_by_debugger(&self) -> bool { false }
+
+    fn is_present(&self) -> bool { false }
+}
@@ -1,6 +1,7 @@
 //! [`Timers` trait](Timers) and related types.
 
 use crate::peripheral_trait;
+use super::gpio::GpioPin;
 
 use lc3_isa::Word;
 use lc3_macros::DisplayUsingDebug;
@@ -14,25 +15,30 @@ use serde::{Deserialize, Serialize};
 #[rustfmt::skip]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[derive(DisplayUsingDebug)]
-pub enum TimerId { T0, T1, }
+pub enum TimerId {
+    T0, T1,
+    #[cfg(feature = "timers_4")] T2,
+    #[cfg(feature = "timers_4")] T3,
+}
 
 impl TimerId {
-    pub const NUM_TIMERS: usize = 2;
+    pub const NUM_TIMERS: usize = if cfg!(feature = "timers_4") { 4 } else { 2 };
 }
 
 impl From<TimerId> for usize {
     fn from(timer: TimerId) -> usize {
-        use TimerId::*;
-        match timer {
-            T0 => 0,
-            T1 => 1,
-        }
+        timer as usize
     }
 }
 
+#[rustfmt::skip]
 pub const TIMERS: TimerArr<TimerId> = {
     use TimerId::*;
-    TimerArr([T0, T1])
+    TimerArr([
+        T0, T1,
+        #[cfg(feature = "timers_4")] T2,
+        #[cfg(feature = "timers_4")] T3,
+    ])
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -82,6 +88,54 @@ pub enum TimerState {
     WithPeriod(Period)
 }
 
+/// Which edges of a [GPIO pin](GpioPin) a timer [captures](Timers#capture) on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CaptureEdge {
+    Rising,
+    Falling,
+    Both,
+}
+
+impl CaptureEdge {
+    /// Whether a pin going from `prev` to `new` is an edge of this kind.
+    pub fn triggered_by(self, prev: bool, new: bool) -> bool {
+        use CaptureEdge::*;
+
+        match self {
+            Rising => !prev && new,
+            Falling => prev && !new,
+            Both => prev != new,
+        }
+    }
+}
+
+/// Where a timer [captures](Timers#capture) from: a [GPIO pin](GpioPin) and
+/// the edges to capture on. When `interrupt` is set, every capture also raises
+/// the timer's interrupt.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TimerCapture {
+    pub pin: GpioPin,
+    pub edge: CaptureEdge,
+    pub interrupt: bool,
+}
+
+/// What a timer is up to; see [`Timers::get_status`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TimerStatus {
+    pub state: TimerState,
+    /// Milliseconds until the timer next fires (`None` if the timer isn't
+    /// running or if the implementation can't tell).
+    pub remaining: Option<Word>,
+    /// The clock value the timer last [captured](Timers#capture), if any.
+    pub captured: Option<Word>,
+}
+
+impl TimerStatus {
+    pub const fn disabled() -> Self {
+        Self { state: TimerState::Disabled, remaining: None, captured: None }
+    }
+}
+
 peripheral_trait! {timers,
 /// A [Timer peripheral](Timers) for an LC-3 simulator.
 ///
@@ -198,6 +252,34 @@ peripheral_trait! {timers,
 /// [periodically](TimerMode::Repeated) as described in the section on
 /// [Modes](#modes).
 ///
+/// ## Counts
+///
+/// Both modes are really _compare_ modes: a running timer counts from 0 up to
+/// its period and fires when the count reaches the period (starting over at 0,
+/// for [`Repeated`] timers). [`get_remaining`](Timers::get_remaining) gives the
+/// number of milliseconds left until the count runs out: the period minus the
+/// current count. For timers that aren't running it's `None`.
+///
+/// Implementations that can't tell may always return `None` (the default).
+///
+/// ## Capture
+///
+/// Separately from its mode and state, a timer can be set to _capture_ the
+/// [`Clock`] whenever a [GPIO pin](GpioPin) has a particular kind of
+/// [edge](CaptureEdge) (see [`set_capture`](Timers::set_capture)). The
+/// simulator watches the pin and [tells the timer](Timers::capture) about each
+/// edge; the timer then holds on to the clock value (the
+/// [captured value](Timers::get_captured)) until the next edge. Subtracting
+/// two captured values gives the time between two edges (i.e. the length of a
+/// pulse).
+///
+/// If the capture is configured to, each capture also raises the timer's
+/// interrupt.
+///
+/// Setting the capture configuration (even to the current one) clears the
+/// captured value. Implementations that don't support capturing (the default)
+/// ignore the capture configuration.
+///
 /// # Reasoning
 ///
 /// We provide the [`Timers`](Timers) peripheral to enable LC-3 users to
@@ -215,12 +297,11 @@ peripheral_trait! {timers,
 /// complete and usable set of peripherals and helps us get closer to providing
 /// a minimal but realistic pedagogical computer.
 ///
-/// One notable way we differ from many (most?) real hardware timer peripherals
-/// is that we offer no way to read the running 'count' of a timer. This is
-/// intentional (for the sake of simplicity). We do appreciate that there are
-/// use cases that require this (i.e. measuring the time between events or the
-/// duration of an event) and for those situations we offer the
-/// [`Clock` peripheral](super::Clock).
+/// Like many real hardware timer peripherals, timers can also be used to
+/// measure things: the time left on a timer's [count](#counts) can be read and
+/// timers can [capture](#capture) the time at which things happen on GPIO
+/// pins. The latter is more accurate than polling the pin and the
+/// [`Clock` peripheral](super::Clock) from a program.
 ///
 /// [`SingleShot`]: TimerMode::SingleShot
 /// [`Repeated`]: TimerMode::Repeated
@@ -262,6 +343,42 @@ pub trait Timers<'a>: Default {
         matches!(self.get_state(timer), TimerState::WithPeriod(_)) ||
         (self.get_state(timer) == TimerState::Disabled && self.interrupt_occurred(timer))
     }
+
+    /// See [Counts](Timers#counts).
+    #[inline]
+    fn get_remaining(&self, _timer: TimerId) -> Option<Word> { None }
+
+    /// See [Capture](Timers#capture).
+    #[inline]
+    fn set_capture(&mut self, _timer: TimerId, _capture: Option<TimerCapture>) { }
+    #[inline]
+    fn get_capture(&self, _timer: TimerId) -> Option<TimerCapture> { None }
+    /// Called (by the simulator) when the pin a timer captures from has one of
+    /// the edges it's waiting for. `clock` is the current value of the
+    /// [`Clock`](super::Clock).
+    #[inline]
+    fn capture(&mut self, _timer: TimerId, _clock: Word) { }
+    #[inline]
+    fn get_captured(&self, _timer: TimerId) -> Option<Word> { None }
+
+    #[inline]
+    fn get_status(&self, timer: TimerId) -> TimerStatus {
+        TimerStatus {
+            state: self.get_state(timer),
+            remaining: self.get_remaining(timer),
+            captured: self.get_captured(timer),
+        }
+    }
+    #[inline]
+    fn get_statuses(&self) -> TimerArr<TimerStatus> {
+        let mut statuses = TimerArr([TimerStatus::disabled(); TimerId::NUM_TIMERS]);
+
+        TIMERS
+            .iter()
+            .for_each(|t| statuses[*t] = self.get_status(*t));
+
+        statuses
+    }
 }}
 
 // TODO: roll this into the macro
@@ -302,6 +419,25 @@ using_std! {
         fn interrupts_enabled(&self, timer: TimerId) -> bool {
             RwLock::read(self).unwrap().interrupts_enabled(timer)
         }
+        fn get_remaining(&self, timer: TimerId) -> Option<Word> {
+            RwLock::read(self).unwrap().get_remaining(timer)
+        }
+
+        fn set_capture(&mut self, timer: TimerId, capture: Option<TimerCapture>) {
+            RwLock::write(self).unwrap().set_capture(timer, capture)
+        }
+
+        fn get_capture(&self, timer: TimerId) -> Option<TimerCapture> {
+            RwLock::read(self).unwrap().get_capture(timer)
+        }
+
+        fn capture(&mut self, timer: TimerId, clock: Word) {
+            RwLock::write(self).unwrap().capture(timer, clock)
+        }
+
+        fn get_captured(&self, timer: TimerId) -> Option<Word> {
+            RwLock::read(self).unwrap().get_captured(timer)
+        }
     }
 
     impl<'a, T: Timers<'a>> Timers<'a> for Arc<Mutex<T>> {
@@ -336,5 +472,24 @@ using_std! {
         fn interrupts_enabled(&self, timer: TimerId) -> bool {
             Mutex::lock(self).unwrap().interrupts_enabled(timer)
         }
+        fn get_remaining(&self, timer: TimerId) -> Option<Word> {
+            Mutex::lock(self).unwrap().get_remaining(timer)
+        }
+
+        fn set_capture(&mut self, timer: TimerId, capture: Option<TimerCapture>) {
+            Mutex::lock(self).unwrap().set_capture(timer, capture)
+        }
+
+        fn get_capture(&self, timer: TimerId) -> Option<TimerCapture> {
+            Mutex::lock(self).unwrap().get_capture(timer)
+        }
+
+        fn capture(&mut self, timer: TimerId, clock: Word) {
+            Mutex::lock(self).unwrap().capture(timer, clock)
+        }
+
+        fn get_captured(&self, timer: TimerId) -> Option<Word> {
+            Mutex::lock(self).unwrap().get_captured(timer)
+        }
     }
 }
@@ -0,0 +1,169 @@
+//! [`Uart` peripheral trait](Uart) and associated types.
+
+use crate::peripheral_trait;
+
+use core::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+/// How many bytes the transmit and receive FIFOs each hold.
+pub const UART_FIFO_DEPTH: usize = 16;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UartError {
+    /// There's no UART.
+    NoUart,
+    /// The transmit FIFO is full; the byte was not sent.
+    TxFifoFull,
+    /// The receive FIFO is empty; there's nothing to read.
+    RxFifoEmpty,
+}
+
+impl Display for UartError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use UartError::*;
+
+        match self {
+            NoUart => write!(fmt, "Attempted to use the UART when there isn't one"),
+            TxFifoFull => write!(fmt, "Attempted to transmit when the UART's transmit FIFO was full"),
+            RxFifoEmpty => write!(fmt, "Attempted to receive when the UART's receive FIFO was empty"),
+        }
+    }
+}
+
+peripheral_trait! {uart,
+/// A [UART peripheral](Uart) for an LC-3 simulator.
+///
+/// A serial port with a transmit FIFO and a receive FIFO (each
+/// [`UART_FIFO_DEPTH`] bytes deep). Bytes that arrive while the receive FIFO
+/// is full are dropped and the [overrun](Uart::overrun) flag is set; it stays
+/// set until it's [cleared](Uart::clear_overrun).
+///
+/// The interrupts are level triggered: the receive interrupt is pending for as
+/// long as there's something in the receive FIFO and the transmit interrupt is
+/// pending for as long as the transmit FIFO has room. Implementations only
+/// keep track of whether each one is enabled; raising them is the
+/// interpreter's job.
+///
+/// Implementations that don't actually have a UART (i.e. the
+/// [stub](crate::peripherals::stubs::UartStub)) should say so with
+/// [`is_present`](Uart::is_present).
+pub trait Uart: Default {
+    fn transmit(&mut self, byte: u8) -> Result<(), UartError>;
+    // Must use interior mutability.
+    fn receive(&self) -> Result<u8, UartError>;
+
+    /// The number of bytes waiting in the receive FIFO.
+    fn rx_count(&self) -> usize;
+    /// The number of bytes in the transmit FIFO that haven't been sent yet.
+    fn tx_count(&self) -> usize;
+
+    fn overrun(&self) -> bool;
+    fn clear_overrun(&mut self);
+
+    fn set_rx_interrupt_enable_bit(&mut self, bit: bool);
+    fn rx_interrupt_enabled(&self) -> bool;
+    fn set_tx_interrupt_enable_bit(&mut self, bit: bool);
+    fn tx_interrupt_enabled(&self) -> bool;
+
+    fn is_present(&self) -> bool {
+        true
+    }
+}}
+
+// TODO: roll this into the macro
+using_std! {
+    use std::sync::{Arc, Mutex, RwLock};
+
+    impl<U: Uart> Uart for Arc<RwLock<U>> {
+        fn transmit(&mut self, byte: u8) -> Result<(), UartError> {
+            RwLock::write(self).unwrap().transmit(byte)
+        }
+
+        fn receive(&self) -> Result<u8, UartError> {
+            RwLock::read(self).unwrap().receive()
+        }
+
+        fn rx_count(&self) -> usize {
+            RwLock::read(self).unwrap().rx_count()
+        }
+
+        fn tx_count(&self) -> usize {
+            RwLock::read(self).unwrap().tx_count()
+        }
+
+        fn overrun(&self) -> bool {
+            RwLock::read(self).unwrap().overrun()
+        }
+
+        fn clear_overrun(&mut self) {
+            RwLock::write(self).unwrap().clear_overrun()
+        }
+
+        fn set_rx_interrupt_enable_bit(&mut self, bit: bool) {
+            RwLock::write(self).unwrap().set_rx_interrupt_enable_bit(bit)
+        }
+
+        fn rx_interrupt_enabled(&self) -> bool {
+            RwLock::read(self).unwrap().rx_interrupt_enabled()
+        }
+
+        fn set_tx_interrupt_enable_bit(&mut self, bit: bool) {
+            RwLock::write(self).unwrap().set_tx_interrupt_enable_bit(bit)
+        }
+
+        fn tx_interrupt_enabled(&self) -> bool {
+            RwLock::read(self).unwrap().tx_interrupt_enabled()
+        }
+
+        fn is_present(&self) -> bool {
+            RwLock::read(self).unwrap().is_present()
+        }
+    }
+
+    impl<U: Uart> Uart for Arc<Mutex<U>> {
+        fn transmit(&mut self, byte: u8) -> Result<(), UartError> {
+            Mutex::lock(self).unwrap().transmit(byte)
+        }
+
+        fn receive(&self) -> Result<u8, UartError> {
+            Mutex::lock(self).unwrap().receive()
+        }
+
+        fn rx_count(&self) -> usize {
+            Mutex::lock(self).unwrap().rx_count()
+        }
+
+        fn tx_count(&self) -> usize {
+            Mutex::lock(self).unwrap().tx_count()
+        }
+
+        fn overrun(&self) -> bool {
+            Mutex::lock(self).unwrap().overrun()
+        }
+
+        fn clear_overrun(&mut self) {
+            Mutex::lock(self).unwrap().clear_overrun()
+        }
+
+        fn set_rx_interrupt_enable_bit(&mut self, bit: bool) {
+            Mutex::lock(self).unwrap().set_rx_interrupt_enable_bit(bit)
+        }
+
+        fn rx_interrupt_enabled(&self) -> bool {
+            Mutex::lock(self).unwrap().rx_interrupt_enabled()
+        }
+
+        fn set_tx_interrupt_enable_bit(&mut self, bit: bool) {
+            Mutex::lock(self).unwrap().set_tx_interrupt_enable_bit(bit)
+        }
+
+        fn tx_interrupt_enabled(&self) -> bool {
+            Mutex::lock(self).unwrap().tx_interrupt_enabled()
+        }
+
+        fn is_present(&self) -> bool {
+            Mutex::lock(self).unwrap().is_present()
+        }
+    }
+}
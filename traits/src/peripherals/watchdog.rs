@@ -0,0 +1,202 @@
+//! [`Watchdog` peripheral trait](Watchdog) and associated types.
+
+use crate::peripheral_trait;
+
+use lc3_isa::Word;
+
+use core::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WatchdogError {
+    /// There's no watchdog.
+    NoWatchdog,
+}
+
+impl Display for WatchdogError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use WatchdogError::*;
+
+        match self {
+            NoWatchdog => write!(fmt, "Attempted to use the watchdog when there isn't one"),
+        }
+    }
+}
+
+peripheral_trait! {watchdog,
+/// A [watchdog timer peripheral](Watchdog) for an LC-3 simulator.
+///
+/// Once it's given a [timeout](Watchdog::set_timeout), the watchdog has to be
+/// [kicked](Watchdog::kick) at least that often. The first time the timeout
+/// elapses without a kick the watchdog's interrupt (if
+/// [enabled](Watchdog::interrupt_enabled)) goes off, giving the program one
+/// last chance; if the timeout elapses again (or if the interrupt isn't
+/// enabled) the machine is reset. Implementations only keep track of how many
+/// times the timeout has [elapsed](Watchdog::expirations); resetting the
+/// machine is the interpreter's job.
+///
+/// Debuggers can [disable](Watchdog::set_disabled_by_debugger) the watchdog so
+/// that pausing a program doesn't get it reset. While disabled the watchdog
+/// never expires, regardless of how the program has set it up.
+///
+/// Implementations that don't actually have a watchdog (i.e. the
+/// [stub](crate::peripherals::stubs::WatchdogStub)) should say so with
+/// [`is_present`](Watchdog::is_present).
+pub trait Watchdog: Default {
+    /// Sets the timeout, in milliseconds, and kicks the watchdog. A timeout
+    /// of 0 turns the watchdog off.
+    fn set_timeout(&mut self, ms: Word);
+    fn get_timeout(&self) -> Word;
+
+    /// Restarts the countdown and clears the interrupt flag.
+    fn kick(&mut self);
+    /// The number of milliseconds until the timeout next elapses (0 if the
+    /// watchdog is off).
+    fn remaining(&self) -> Word;
+    /// The number of times the timeout has elapsed since the last kick; always
+    /// 0 if the watchdog is off or disabled by the debugger.
+    fn expirations(&self) -> u16;
+
+    fn set_interrupt_enable_bit(&mut self, bit: bool);
+    fn interrupt_enabled(&self) -> bool;
+    /// Whether the timeout has elapsed since the last kick and the interrupt
+    /// hasn't been [handled](Watchdog::reset_interrupt_flag) yet.
+    fn interrupt_occurred(&self) -> bool;
+    fn reset_interrupt_flag(&mut self);
+
+    /// Whether the machine's last reset was caused by the watchdog. This is
+    /// left alone when the machine is reset so that programs can find out
+    /// why they're starting over.
+    fn caused_reset(&self) -> bool;
+    fn set_caused_reset(&mut self, caused: bool);
+
+    /// Re-enabling the watchdog kicks it.
+    fn set_disabled_by_debugger(&mut self, disabled: bool);
+    fn disabled_by_debugger(&self) -> bool;
+
+    fn is_present(&self) -> bool {
+        true
+    }
+}}
+
+// TODO: roll this into the macro
+using_std! {
+    use std::sync::{Arc, Mutex, RwLock};
+
+    impl<W: Watchdog> Watchdog for Arc<RwLock<W>> {
+        fn set_timeout(&mut self, ms: Word) {
+            RwLock::write(self).unwrap().set_timeout(ms)
+        }
+
+        fn get_timeout(&self) -> Word {
+            RwLock::read(self).unwrap().get_timeout()
+        }
+
+        fn kick(&mut self) {
+            RwLock::write(self).unwrap().kick()
+        }
+
+        fn remaining(&self) -> Word {
+            RwLock::read(self).unwrap().remaining()
+        }
+
+        fn expirations(&self) -> u16 {
+            RwLock::read(self).unwrap().expirations()
+        }
+
+        fn set_interrupt_enable_bit(&mut self, bit: bool) {
+            RwLock::write(self).unwrap().set_interrupt_enable_bit(bit)
+        }
+
+        fn interrupt_enabled(&self) -> bool {
+            RwLock::read(self).unwrap().interrupt_enabled()
+        }
+
+        fn interrupt_occurred(&self) -> bool {
+            RwLock::read(self).unwrap().interrupt_occurred()
+        }
+
+        fn reset_interrupt_flag(&mut self) {
+            RwLock::write(self).unwrap().reset_interrupt_flag()
+        }
+
+        fn caused_reset(&self) -> bool {
+            RwLock::read(self).unwrap().caused_reset()
+        }
+
+        fn set_caused_reset(&mut self, caused: bool) {
+            RwLock::write(self).unwrap().set_caused_reset(caused)
+        }
+
+        fn set_disabled_by_debugger(&mut self, disabled: bool) {
+            RwLock::write(self).unwrap().set_disabled_by_debugger(disabled)
+        }
+
+        fn disabled_by_debugger(&self) -> bool {
+            RwLock::read(self).unwrap().disabled_by_debugger()
+        }
+
+        fn is_present(&self) -> bool {
+            RwLock::read(self).unwrap().is_present()
+        }
+    }
+
+    impl<W: Watchdog> Watchdog for Arc<Mutex<W>> {
+        fn set_timeout(&mut self, ms: Word) {
+            Mutex::lock(self).unwrap().set_timeout(ms)
+        }
+
+        fn get_timeout(&self) -> Word {
+            Mutex::lock(self).unwrap().get_timeout()
+        }
+
+        fn kick(&mut self) {
+            Mutex::lock(self).unwrap().kick()
+        }
+
+        fn remaining(&self) -> Word {
+            Mutex::lock(self).unwrap().remaining()
+        }
+
+        fn expirations(&self) -> u16 {
+            Mutex::lock(self).unwrap().expirations()
+        }
+
+        fn set_interrupt_enable_bit(&mut self, bit: bool) {
+            Mutex::lock(self).unwrap().set_interrupt_enable_bit(bit)
+        }
+
+        fn interrupt_enabled(&self) -> bool {
+            Mutex::lock(self).unwrap().interrupt_enabled()
+        }
+
+        fn interrupt_occurred(&self) -> bool {
+            Mutex::lock(self).unwrap().interrupt_occurred()
+        }
+
+        fn reset_interrupt_flag(&mut self) {
+            Mutex::lock(self).unwrap().reset_interrupt_flag()
+        }
+
+        fn caused_reset(&self) -> bool {
+            Mutex::lock(self).unwrap().caused_reset()
+        }
+
+        fn set_caused_reset(&mut self, caused: bool) {
+            Mutex::lock(self).unwrap().set_caused_reset(caused)
+        }
+
+        fn set_disabled_by_debugger(&mut self, disabled: bool) {
+            Mutex::lock(self).unwrap().set_disabled_by_debugger(disabled)
+        }
+
+        fn disabled_by_debugger(&self) -> bool {
+            Mutex::lock(self).unwrap().disabled_by_debugger()
+        }
+
+        fn is_present(&self) -> bool {
+            Mutex::lock(self).unwrap().is_present()
+        }
+    }
+}